@@ -0,0 +1,10 @@
+-- Per-user preferences, one row per user
+CREATE TABLE IF NOT EXISTS user_settings (
+    user_id UUID PRIMARY KEY REFERENCES users(id) ON DELETE CASCADE,
+    timezone VARCHAR(64),
+    currency VARCHAR(3) NOT NULL DEFAULT 'USD',
+    monthly_limit DECIMAL(12, 2) CHECK (monthly_limit > 0),
+    date_format VARCHAR(20) NOT NULL DEFAULT 'MMM D, YYYY',
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
@@ -0,0 +1,10 @@
+-- Trigram similarity for category suggestions. Managed databases may not
+-- allow installing extensions; the suggest endpoint falls back to prefix
+-- matching when pg_trgm is missing, so don't fail the migration over it.
+DO $$
+BEGIN
+    CREATE EXTENSION IF NOT EXISTS pg_trgm;
+EXCEPTION WHEN OTHERS THEN
+    RAISE NOTICE 'pg_trgm unavailable, category suggestions will use prefix matching';
+END
+$$;
@@ -0,0 +1,6 @@
+-- Soft delete: deleted expenses stay in the trash until purged (NULL = live)
+ALTER TABLE expenses
+    ADD COLUMN IF NOT EXISTS deleted_at TIMESTAMPTZ;
+
+CREATE INDEX IF NOT EXISTS idx_expenses_trash ON expenses(user_id, deleted_at)
+    WHERE deleted_at IS NOT NULL;
@@ -0,0 +1,14 @@
+-- Manual position of a category in the user's list (lowest first)
+ALTER TABLE categories
+    ADD COLUMN IF NOT EXISTS sort_order INTEGER NOT NULL DEFAULT 0;
+
+-- Keep the existing alphabetical order as the starting point
+UPDATE categories
+SET sort_order = ranked.position
+FROM (
+    SELECT id, (ROW_NUMBER() OVER (PARTITION BY user_id ORDER BY name) - 1)::INTEGER AS position
+    FROM categories
+) ranked
+WHERE categories.id = ranked.id;
+
+CREATE INDEX IF NOT EXISTS idx_categories_user_sort ON categories(user_id, sort_order);
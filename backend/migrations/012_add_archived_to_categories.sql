@@ -0,0 +1,3 @@
+-- Archived categories keep their expenses but can't be used for new ones
+ALTER TABLE categories
+    ADD COLUMN IF NOT EXISTS archived BOOLEAN NOT NULL DEFAULT FALSE;
@@ -0,0 +1,16 @@
+-- Create expense_history table (append-only snapshot of an expense before each edit)
+CREATE TABLE IF NOT EXISTS expense_history (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    expense_id UUID NOT NULL REFERENCES expenses(id) ON DELETE CASCADE,
+    -- No foreign key: merged categories are deleted but old versions keep their id
+    category_id UUID NOT NULL,
+    amount DECIMAL(12, 2) NOT NULL,
+    description TEXT NOT NULL,
+    expense_date DATE NOT NULL,
+    -- When this version became current (the expense's updated_at at the time)
+    valid_from TIMESTAMPTZ NOT NULL,
+    -- When the edit replaced it
+    replaced_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_expense_history_expense_id ON expense_history(expense_id, replaced_at);
@@ -0,0 +1,22 @@
+-- Create expense_splits table (one receipt allocated across several categories)
+CREATE TABLE IF NOT EXISTS expense_splits (
+    expense_id UUID NOT NULL REFERENCES expenses(id) ON DELETE CASCADE,
+    category_id UUID NOT NULL REFERENCES categories(id),
+    amount DECIMAL(12, 2) NOT NULL CHECK (amount > 0),
+    PRIMARY KEY (expense_id, category_id)
+);
+
+CREATE INDEX idx_expense_splits_category_id ON expense_splits(category_id);
+
+-- Amount attributed to each category: an expense's splits, or the whole
+-- expense under its own category when it has none
+CREATE OR REPLACE VIEW expense_allocations AS
+SELECT
+    expenses.id AS expense_id,
+    expenses.user_id,
+    COALESCE(expense_splits.category_id, expenses.category_id) AS category_id,
+    COALESCE(expense_splits.amount, expenses.amount) AS amount,
+    expenses.expense_date,
+    expenses.deleted_at
+FROM expenses
+LEFT JOIN expense_splits ON expense_splits.expense_id = expenses.id;
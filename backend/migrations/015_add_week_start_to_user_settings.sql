@@ -0,0 +1,4 @@
+-- First day of the week for weekly totals (ISO weeks start on Monday)
+ALTER TABLE user_settings
+    ADD COLUMN IF NOT EXISTS week_start VARCHAR(6) NOT NULL DEFAULT 'monday'
+        CHECK (week_start IN ('monday', 'sunday'));
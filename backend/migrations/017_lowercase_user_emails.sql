@@ -0,0 +1,17 @@
+-- Emails are now trimmed and lowercased before they are stored or looked up,
+-- so existing addresses are brought into the same form.
+--
+-- Accounts that differ only by case (User@Example.com and user@example.com)
+-- can't both be lowercased without breaking the unique constraint, so those are
+-- left untouched and need to be merged or renamed by hand. Find them with:
+--
+--   SELECT LOWER(TRIM(email)), COUNT(*) FROM users
+--   GROUP BY LOWER(TRIM(email)) HAVING COUNT(*) > 1;
+UPDATE users
+SET email = LOWER(TRIM(email))
+WHERE email <> LOWER(TRIM(email))
+  AND NOT EXISTS (
+      SELECT 1 FROM users other
+      WHERE other.id <> users.id
+        AND LOWER(TRIM(other.email)) = LOWER(TRIM(users.email))
+  );
@@ -0,0 +1,5 @@
+-- Optional URL that receives a signed POST for every new expense.
+-- The secret is generated the first time a URL is set and kept afterwards.
+ALTER TABLE user_settings
+    ADD COLUMN IF NOT EXISTS webhook_url TEXT,
+    ADD COLUMN IF NOT EXISTS webhook_secret VARCHAR(64);
@@ -0,0 +1,9 @@
+-- Sort applied to the expense and category lists when a request doesn't ask
+-- for one
+ALTER TABLE user_settings
+    ADD COLUMN IF NOT EXISTS expense_sort_by VARCHAR(11) NOT NULL DEFAULT 'date'
+        CHECK (expense_sort_by IN ('date', 'amount', 'category', 'description')),
+    ADD COLUMN IF NOT EXISTS expense_sort_dir VARCHAR(4) NOT NULL DEFAULT 'desc'
+        CHECK (expense_sort_dir IN ('asc', 'desc')),
+    ADD COLUMN IF NOT EXISTS category_sort VARCHAR(6) NOT NULL DEFAULT 'manual'
+        CHECK (category_sort IN ('manual', 'name', 'usage'));
@@ -0,0 +1,20 @@
+-- Create alerts table (spending thresholds checked by a background task)
+CREATE TABLE IF NOT EXISTS alerts (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    -- 'budget' watches one category's budget, 'monthly_limit' the overall limit
+    alert_type VARCHAR(13) NOT NULL CHECK (alert_type IN ('budget', 'monthly_limit')),
+    category_id UUID REFERENCES categories(id) ON DELETE CASCADE,
+    -- Percentage of the budget or limit that fires the alert
+    threshold_percent INTEGER NOT NULL CHECK (threshold_percent BETWEEN 1 AND 1000),
+    -- Fires at most once per calendar month
+    last_triggered_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    CHECK ((alert_type = 'budget') = (category_id IS NOT NULL))
+);
+
+CREATE INDEX idx_alerts_user_id ON alerts(user_id);
+
+CREATE UNIQUE INDEX idx_alerts_unique ON alerts(
+    user_id, alert_type, COALESCE(category_id, '00000000-0000-0000-0000-000000000000'), threshold_percent
+);
@@ -0,0 +1,16 @@
+-- expenses.amount must be positive. 003 declares the check, but a table created
+-- before it was added kept its old definition (CREATE TABLE IF NOT EXISTS), so
+-- add it here when it is missing.
+--
+-- This fails if such a table already holds amounts <= 0. Find them with:
+--
+--   SELECT id, user_id, amount FROM expenses WHERE amount <= 0;
+DO $$
+BEGIN
+    IF NOT EXISTS (
+        SELECT 1 FROM pg_constraint
+        WHERE conrelid = 'expenses'::regclass AND conname = 'expenses_amount_check'
+    ) THEN
+        ALTER TABLE expenses ADD CONSTRAINT expenses_amount_check CHECK (amount > 0);
+    END IF;
+END $$;
@@ -0,0 +1,7 @@
+-- 023 let deleting a category silently delete the expense templates using it.
+-- Templates now have to be moved first: merging a category moves them, and
+-- deleting a category that templates still use is refused.
+ALTER TABLE expense_templates
+    DROP CONSTRAINT IF EXISTS expense_templates_category_id_fkey,
+    ADD CONSTRAINT expense_templates_category_id_fkey
+        FOREIGN KEY (category_id) REFERENCES categories(id) ON DELETE RESTRICT;
@@ -0,0 +1,160 @@
+//! Spending alerts, checked on a schedule.
+//!
+//! Every `ALERT_CHECK_INTERVAL_SECS` a background task compares each alert's
+//! current-month spending with the category budget or the overall monthly
+//! limit. An alert that has reached its threshold fires once per calendar
+//! month in the user's timezone and is delivered as an `alert.triggered`
+//! webhook.
+//!
+//! Picking the due alerts and marking them fired is a single `UPDATE`, so a
+//! check that overlaps another, or runs on a second instance, can't fire the
+//! same alert twice.
+
+use std::time::Duration;
+
+use chrono::{DateTime, Datelike, NaiveDate, NaiveTime, TimeZone, Utc};
+use rust_decimal::Decimal;
+use serde::Serialize;
+use sqlx::FromRow;
+use tokio::time::MissedTickBehavior;
+use uuid::Uuid;
+
+use crate::{
+    error::AppResult, handlers::settings::parse_timezone, webhook::notify_alert_triggered,
+    AppState,
+};
+
+/// An alert that has just fired, as sent in the `alert.triggered` webhook.
+#[derive(Debug, Serialize, FromRow)]
+pub struct TriggeredAlert {
+    pub id: Uuid,
+    pub alert_type: String,
+    pub category_id: Option<Uuid>,
+    pub category_name: Option<String>,
+    pub threshold_percent: i32,
+    /// Spent this month, in the category for budget alerts
+    pub spent: Decimal,
+    /// The budget or monthly limit the threshold is a share of
+    pub limit_amount: Decimal,
+}
+
+/// Starts the background alert check; does nothing when
+/// `ALERT_CHECK_INTERVAL_SECS` is 0.
+pub fn spawn_checker(state: AppState) {
+    let secs = state.config.alert_check_interval_secs;
+    if secs == 0 {
+        tracing::info!("Spending alerts are disabled (ALERT_CHECK_INTERVAL_SECS=0)");
+        return;
+    }
+
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(Duration::from_secs(secs));
+        // A slow check shouldn't be followed by a burst of catch-up checks
+        interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+        loop {
+            interval.tick().await;
+            if let Err(e) = check_alerts(&state).await {
+                tracing::warn!("Alert check failed: {:?}", e);
+            }
+        }
+    });
+}
+
+/// Fires every alert that has reached its threshold and hasn't fired yet
+/// this month. A failure for one user is logged and the others still run.
+pub async fn check_alerts(state: &AppState) -> AppResult<()> {
+    let users = sqlx::query_as::<_, (Uuid, Option<String>)>(
+        r#"
+        SELECT DISTINCT alerts.user_id, user_settings.timezone
+        FROM alerts
+        LEFT JOIN user_settings ON user_settings.user_id = alerts.user_id
+        "#,
+    )
+    .fetch_all(&state.pool)
+    .await?;
+
+    for (user_id, timezone) in users {
+        let tz = parse_timezone(timezone.as_deref());
+        let today = Utc::now().with_timezone(&tz).date_naive();
+        let month_start =
+            NaiveDate::from_ymd_opt(today.year(), today.month(), 1).expect("Valid date");
+        // Midnight can fall in a DST gap; UTC midnight is close enough then
+        let period_start = tz
+            .from_local_datetime(&month_start.and_time(NaiveTime::MIN))
+            .earliest()
+            .map(|start| start.with_timezone(&Utc))
+            .unwrap_or_else(|| Utc.from_utc_datetime(&month_start.and_time(NaiveTime::MIN)));
+
+        match fire_due_alerts(state, user_id, month_start, period_start).await {
+            Ok(fired) => {
+                for alert in &fired {
+                    tracing::info!("Alert {} fired for user {}", alert.id, user_id);
+                    notify_alert_triggered(state, user_id, alert);
+                }
+            }
+            Err(e) => tracing::warn!("Alert check for user {} failed: {:?}", user_id, e),
+        }
+    }
+
+    Ok(())
+}
+
+/// Marks the user's due alerts as fired and returns them.
+///
+/// An alert is due when spending since `month_start` is at least
+/// `threshold_percent` of its budget or limit, and it last fired before
+/// `period_start` (or never). Alerts whose budget or limit isn't set are skipped.
+async fn fire_due_alerts(
+    state: &AppState,
+    user_id: Uuid,
+    month_start: NaiveDate,
+    period_start: DateTime<Utc>,
+) -> AppResult<Vec<TriggeredAlert>> {
+    // The debounce condition is repeated in the outer WHERE so Postgres
+    // rechecks it against a row another check updated concurrently
+    let fired = sqlx::query_as::<_, TriggeredAlert>(
+        r#"
+        UPDATE alerts
+        SET last_triggered_at = NOW()
+        FROM (
+            SELECT
+                alerts.id,
+                categories.name AS category_name,
+                COALESCE(SUM(expense_allocations.amount), 0) AS spent,
+                CASE
+                    WHEN alerts.alert_type = 'budget' THEN budgets.amount
+                    ELSE user_settings.monthly_limit
+                END AS limit_amount
+            FROM alerts
+            LEFT JOIN categories ON categories.id = alerts.category_id
+            LEFT JOIN budgets ON budgets.category_id = alerts.category_id
+            LEFT JOIN user_settings ON user_settings.user_id = alerts.user_id
+            LEFT JOIN expense_allocations ON expense_allocations.user_id = alerts.user_id
+                AND expense_allocations.deleted_at IS NULL
+                AND expense_allocations.expense_date >= $2
+                AND (
+                    alerts.category_id IS NULL
+                    OR expense_allocations.category_id = alerts.category_id
+                )
+            WHERE alerts.user_id = $1
+                AND (alerts.last_triggered_at IS NULL OR alerts.last_triggered_at < $3)
+            GROUP BY alerts.id, categories.name, budgets.amount, user_settings.monthly_limit
+        ) AS due
+        WHERE alerts.id = due.id
+            AND (alerts.last_triggered_at IS NULL OR alerts.last_triggered_at < $3)
+            AND due.limit_amount IS NOT NULL
+            AND due.spent * 100 >= due.limit_amount * alerts.threshold_percent
+        RETURNING
+            alerts.id, alerts.alert_type, alerts.category_id, due.category_name,
+            alerts.threshold_percent, due.spent, due.limit_amount
+        "#,
+    )
+    .bind(user_id)
+    .bind(month_start)
+    .bind(period_start)
+    .fetch_all(&state.pool)
+    .await?;
+
+    Ok(fired)
+}
@@ -15,6 +15,7 @@
 //!
 //! - **Password Hashing**: Argon2id algorithm with random salts
 //! - **JWT Tokens**: Signed tokens with expiration for stateless authentication
+//!   (HS256 with a shared secret, or RS256 with an RSA key pair)
 //! - **Bearer Authentication**: Standard HTTP Authorization header parsing
 //!
 //! # Example Usage
@@ -23,8 +24,11 @@
 //! // Hash a password during registration
 //! let password_hash = hash_password("user_password")?;
 //!
+//! // Load signing keys once at startup
+//! let jwt_keys = JwtKeys::from_config(&config)?;
+//!
 //! // Create a JWT token after successful login
-//! let token = create_jwt(user_id, &config.jwt_secret, 24)?;
+//! let token = create_jwt(user_id, session_id, &jwt_keys, 24)?;
 //!
 //! // Use AuthUser as a request extractor in handlers
 //! async fn protected_handler(user: AuthUser) -> Response {
@@ -36,9 +40,10 @@ use argon2::{
     password_hash::{rand_core::OsRng, PasswordHash, PasswordHasher, PasswordVerifier, SaltString},
     Argon2,
 };
+use anyhow::Context;
 use axum::{
     async_trait,
-    extract::FromRequestParts,
+    extract::{FromRef, FromRequestParts},
     http::{request::Parts, StatusCode},
     RequestPartsExt,
 };
@@ -47,11 +52,15 @@ use axum_extra::{
     TypedHeader,
 };
 use chrono::{Duration, Utc};
-use jsonwebtoken::{decode, encode, DecodingKey, EncodingKey, Header, Validation};
+use jsonwebtoken::{decode, encode, Algorithm, DecodingKey, EncodingKey, Header, Validation};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
-use crate::error::{AppError, AppResult};
+use crate::{
+    config::{Config, JwtAlgorithm, PasswordPolicy, DEFAULT_JWT_LEEWAY_SECS},
+    error::{AppError, AppResult},
+    AppState,
+};
 
 // ============================================================================
 // JWT Claims
@@ -63,7 +72,10 @@ use crate::error::{AppError, AppResult};
 /// Following the JWT standard (RFC 7519), we use standard claim names:
 ///
 /// - `sub` (subject): The user ID the token is issued for
+/// - `sid` (session ID): The session row the token belongs to, used for revocation
 /// - `exp` (expiration): Unix timestamp when the token expires
+/// - `iss` (issuer) / `aud` (audience): Only present when `JWT_ISSUER` /
+///   `JWT_AUDIENCE` are configured
 ///
 /// # Token Lifecycle
 ///
@@ -77,6 +89,7 @@ use crate::error::{AppError, AppResult};
 /// ```json
 /// {
 ///   "sub": "123e4567-e89b-12d3-a456-426614174000",
+///   "sid": "5f0c2d8e-7a1b-4c3d-9e8f-0a1b2c3d4e5f",
 ///   "exp": 1704067200
 /// }
 /// ```
@@ -86,9 +99,23 @@ pub struct Claims {
     /// Stored as String because JWT standard requires string subjects
     pub sub: String,
 
+    /// Session ID - the `sessions` row this token belongs to
+    /// Deleting that row revokes the token before it expires
+    pub sid: String,
+
     /// Expiration time as Unix timestamp (seconds since epoch)
     /// The token becomes invalid after this time
     pub exp: i64,
+
+    /// Issuer - who signed the token, from `JWT_ISSUER`
+    /// Left out of the payload when not configured
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub iss: Option<String>,
+
+    /// Audience - which service the token is meant for, from `JWT_AUDIENCE`
+    /// Left out of the payload when not configured
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub aud: Option<String>,
 }
 
 impl Claims {
@@ -97,6 +124,7 @@ impl Claims {
     /// # Arguments
     ///
     /// * `user_id` - The UUID of the user this token represents
+    /// * `session_id` - The UUID of the session this token belongs to
     /// * `expiration_hours` - How many hours until the token expires
     ///
     /// # Returns
@@ -106,9 +134,9 @@ impl Claims {
     /// # Example
     ///
     /// ```rust,ignore
-    /// let claims = Claims::new(user_id, 24); // Token expires in 24 hours
+    /// let claims = Claims::new(user_id, session_id, 24); // Token expires in 24 hours
     /// ```
-    pub fn new(user_id: Uuid, expiration_hours: i64) -> Self {
+    pub fn new(user_id: Uuid, session_id: Uuid, expiration_hours: i64) -> Self {
         // Calculate expiration timestamp by adding hours to current time
         let expiration = Utc::now()
             .checked_add_signed(Duration::hours(expiration_hours))
@@ -117,7 +145,10 @@ impl Claims {
 
         Self {
             sub: user_id.to_string(),
+            sid: session_id.to_string(),
             exp: expiration,
+            iss: None,
+            aud: None,
         }
     }
 }
@@ -216,25 +247,175 @@ pub fn verify_password(password: &str, password_hash: &str) -> AppResult<()> {
         .map_err(|_| AppError::Authentication("Invalid credentials".to_string()))
 }
 
+/// Checks a new password against the configured [`PasswordPolicy`].
+///
+/// Every failed rule is reported, so the user can fix them all at once:
+///
+/// ```rust,ignore
+/// validate_password_strength("short", &state.config.password_policy)?;
+/// // Err(Validation("Password must be at least 8 characters; Password must contain a digit"))
+/// ```
+pub fn validate_password_strength(password: &str, policy: &PasswordPolicy) -> AppResult<()> {
+    let mut failures = Vec::new();
+
+    if password.chars().count() < policy.min_length {
+        failures.push(format!("Password must be at least {} characters", policy.min_length));
+    }
+    if policy.require_uppercase && !password.chars().any(|c| c.is_uppercase()) {
+        failures.push("Password must contain an uppercase letter".to_string());
+    }
+    if policy.require_lowercase && !password.chars().any(|c| c.is_lowercase()) {
+        failures.push("Password must contain a lowercase letter".to_string());
+    }
+    if policy.require_digit && !password.chars().any(|c| c.is_ascii_digit()) {
+        failures.push("Password must contain a digit".to_string());
+    }
+    if policy.require_symbol
+        && !password.chars().any(|c| !c.is_alphanumeric() && !c.is_whitespace())
+    {
+        failures.push("Password must contain a symbol".to_string());
+    }
+
+    if failures.is_empty() {
+        Ok(())
+    } else {
+        Err(AppError::Validation(failures.join("; ")))
+    }
+}
+
 // ============================================================================
 // JWT Operations
 // ============================================================================
 
+/// Keys used to sign and verify JWTs.
+///
+/// Built once at startup from [`Config`] and shared through `AppState`, so
+/// key files are read a single time rather than on every request.
+///
+/// - **HS256**: both keys come from the shared `JWT_SECRET`
+/// - **RS256**: tokens are signed with the RSA private key at
+///   `JWT_PRIVATE_KEY_PATH` and verified with the public key at
+///   `JWT_PUBLIC_KEY_PATH` (both PEM encoded)
+///
+/// The optional issuer and audience are written into every token and, when
+/// set, required to match on every request. `leeway` is the clock skew in
+/// seconds tolerated when checking `exp` (and `nbf`, if present).
+#[derive(Clone)]
+pub struct JwtKeys {
+    algorithm: Algorithm,
+    encoding: EncodingKey,
+    decoding: DecodingKey,
+    issuer: Option<String>,
+    audience: Option<String>,
+    leeway: u64,
+}
+
+impl JwtKeys {
+    /// Creates HS256 keys from a shared secret.
+    pub fn hs256(secret: &str) -> Self {
+        Self {
+            algorithm: Algorithm::HS256,
+            encoding: EncodingKey::from_secret(secret.as_bytes()),
+            decoding: DecodingKey::from_secret(secret.as_bytes()),
+            issuer: None,
+            audience: None,
+            leeway: DEFAULT_JWT_LEEWAY_SECS,
+        }
+    }
+
+    /// Creates RS256 keys from PEM-encoded RSA private and public keys.
+    pub fn rs256(private_pem: &[u8], public_pem: &[u8]) -> AppResult<Self> {
+        Ok(Self {
+            algorithm: Algorithm::RS256,
+            encoding: EncodingKey::from_rsa_pem(private_pem).map_err(AppError::Jwt)?,
+            decoding: DecodingKey::from_rsa_pem(public_pem).map_err(AppError::Jwt)?,
+            issuer: None,
+            audience: None,
+            leeway: DEFAULT_JWT_LEEWAY_SECS,
+        })
+    }
+
+    /// Sets the `iss` and `aud` claims to issue and require; `None` skips a claim.
+    pub fn with_claims(self, issuer: Option<String>, audience: Option<String>) -> Self {
+        Self { issuer, audience, ..self }
+    }
+
+    /// Sets how many seconds of clock skew are tolerated on time claims.
+    pub fn with_leeway(self, leeway: u64) -> Self {
+        Self { leeway, ..self }
+    }
+
+    /// Builds the keys for the algorithm selected by `JWT_ALGORITHM`.
+    ///
+    /// Fails at startup if RS256 is selected but a key path is missing or a
+    /// key file cannot be read or parsed.
+    pub fn from_config(config: &Config) -> anyhow::Result<Self> {
+        let keys = match config.jwt_algorithm {
+            JwtAlgorithm::HS256 => Self::hs256(&config.jwt_secret),
+            JwtAlgorithm::RS256 => {
+                let private_path = config
+                    .jwt_private_key_path
+                    .as_deref()
+                    .context("JWT_PRIVATE_KEY_PATH is required when JWT_ALGORITHM=RS256")?;
+                let public_path = config
+                    .jwt_public_key_path
+                    .as_deref()
+                    .context("JWT_PUBLIC_KEY_PATH is required when JWT_ALGORITHM=RS256")?;
+
+                let private_pem = std::fs::read(private_path)
+                    .with_context(|| format!("failed to read JWT private key {}", private_path))?;
+                let public_pem = std::fs::read(public_path)
+                    .with_context(|| format!("failed to read JWT public key {}", public_path))?;
+
+                Self::rs256(&private_pem, &public_pem).context("invalid RSA key for RS256")?
+            }
+        };
+
+        Ok(keys
+            .with_claims(config.jwt_issuer.clone(), config.jwt_audience.clone())
+            .with_leeway(config.jwt_leeway_secs))
+    }
+
+    /// Validation rules for incoming tokens.
+    ///
+    /// A configured issuer or audience must be present and match. Without one,
+    /// the claim isn't checked, so tokens issued before it was set keep working.
+    fn validation(&self) -> Validation {
+        let mut validation = Validation::new(self.algorithm);
+        validation.leeway = self.leeway;
+        let mut required = vec!["exp"];
+        if let Some(issuer) = &self.issuer {
+            validation.set_issuer(&[issuer]);
+            required.push("iss");
+        }
+        match &self.audience {
+            Some(audience) => {
+                validation.set_audience(&[audience]);
+                required.push("aud");
+            }
+            None => validation.validate_aud = false,
+        }
+        validation.set_required_spec_claims(&required);
+        validation
+    }
+}
+
 /// Creates a signed JWT token for a user.
 ///
 /// This function generates a JSON Web Token containing the user's ID and expiration time,
-/// signed with HMAC-SHA256 using the provided secret key.
+/// signed with the algorithm and key held by `keys` (HS256 or RS256).
 ///
 /// # Arguments
 ///
 /// * `user_id` - The UUID of the user this token is for
-/// * `secret` - The secret key used to sign the token (from environment config)
+/// * `session_id` - The UUID of the session row created for this login
+/// * `keys` - The signing keys loaded at startup (see [`JwtKeys`])
 /// * `expiration_hours` - How many hours until the token expires
 ///
 /// # Returns
 ///
 /// * `Ok(String)` - The complete JWT token ready to be sent to the client
-/// * `Err(AppError)` - If token creation fails (e.g., invalid secret)
+/// * `Err(AppError)` - If token creation fails (e.g., invalid key)
 ///
 /// # Token Format
 ///
@@ -247,7 +428,7 @@ pub fn verify_password(password: &str, password_hash: &str) -> AppResult<()> {
 /// # Example
 ///
 /// ```rust,ignore
-/// let token = create_jwt(user.id, "my-secret-key", 24)?;
+/// let token = create_jwt(user.id, session.id, &state.jwt_keys, 24)?;
 /// // Client should send this in: Authorization: Bearer <token>
 /// ```
 ///
@@ -256,17 +437,20 @@ pub fn verify_password(password: &str, password_hash: &str) -> AppResult<()> {
 /// - Secret key should be at least 256 bits (32 bytes) for security
 /// - Token is signed but not encrypted (don't include sensitive data)
 /// - Token should be transmitted over HTTPS only
-pub fn create_jwt(user_id: Uuid, secret: &str, expiration_hours: i64) -> AppResult<String> {
-    // Create claims with user ID and expiration
-    let claims = Claims::new(user_id, expiration_hours);
+pub fn create_jwt(
+    user_id: Uuid,
+    session_id: Uuid,
+    keys: &JwtKeys,
+    expiration_hours: i64,
+) -> AppResult<String> {
+    // Create claims with user ID, session ID and expiration
+    let mut claims = Claims::new(user_id, session_id, expiration_hours);
+    claims.iss = keys.issuer.clone();
+    claims.aud = keys.audience.clone();
 
     // Encode claims into a JWT token
-    // Uses HMAC-SHA256 algorithm by default
-    encode(
-        &Header::default(), // HS256 algorithm
-        &claims,
-        &EncodingKey::from_secret(secret.as_bytes()),
-    )
+    // The header's "alg" must match the key type (HS256 secret or RS256 private key)
+    encode(&Header::new(keys.algorithm), &claims, &keys.encoding)
     .map_err(AppError::Jwt) // Convert JWT error to AppError
 }
 
@@ -278,7 +462,7 @@ pub fn create_jwt(user_id: Uuid, secret: &str, expiration_hours: i64) -> AppResu
 /// # Arguments
 ///
 /// * `token` - The JWT token string to decode
-/// * `secret` - The secret key used to verify the signature
+/// * `keys` - The verification keys loaded at startup (see [`JwtKeys`])
 ///
 /// # Returns
 ///
@@ -288,20 +472,21 @@ pub fn create_jwt(user_id: Uuid, secret: &str, expiration_hours: i64) -> AppResu
 /// # Validation Checks
 ///
 /// 1. Signature validation (token hasn't been tampered with)
-/// 2. Expiration check (token hasn't expired)
+/// 2. Expiration check (token hasn't expired, allowing `JWT_LEEWAY_SECS` of clock skew)
 /// 3. Algorithm verification (prevents algorithm substitution attacks)
+/// 4. Issuer and audience, when `JWT_ISSUER` / `JWT_AUDIENCE` are configured
 ///
 /// # Example
 ///
 /// ```rust,ignore
-/// let claims = decode_jwt(&token, "my-secret-key")?;
+/// let claims = decode_jwt(&token, &state.jwt_keys)?;
 /// let user_id = Uuid::parse_str(&claims.sub)?;
 /// ```
-pub fn decode_jwt(token: &str, secret: &str) -> AppResult<Claims> {
+pub fn decode_jwt(token: &str, keys: &JwtKeys) -> AppResult<Claims> {
     decode::<Claims>(
         token,
-        &DecodingKey::from_secret(secret.as_bytes()),
-        &Validation::default(), // Validates signature, expiration, and algorithm
+        &keys.decoding,
+        &keys.validation(), // Validates signature, expiration, algorithm, issuer and audience
     )
     .map(|data| data.claims) // Extract just the claims from the token data
     .map_err(AppError::Jwt) // Convert JWT error to AppError
@@ -320,8 +505,9 @@ pub fn decode_jwt(token: &str, secret: &str) -> AppResult<Claims> {
 ///
 /// 1. Extracts `Authorization: Bearer <token>` header from request
 /// 2. Validates the JWT token signature and expiration
-/// 3. Extracts user ID from the token claims
-/// 4. Makes user_id available to the handler
+/// 3. Extracts user and session IDs from the token claims
+/// 4. Checks the session has not been revoked
+/// 5. Makes user_id and session_id available to the handler
 ///
 /// # Example Usage in Handlers
 ///
@@ -338,19 +524,21 @@ pub fn decode_jwt(token: &str, secret: &str) -> AppResult<Claims> {
 ///
 /// # Error Responses
 ///
-/// - `401 Unauthorized` - Missing or invalid token
-/// - `500 Internal Server Error` - JWT secret not configured
+/// - `401 Unauthorized` - Missing, invalid or revoked token
+/// - `500 Internal Server Error` - Session lookup failed
 ///
 /// # Rust Concepts Demonstrated
 ///
 /// - **Traits**: Implements `FromRequestParts` trait for custom extraction
 /// - **Async Traits**: Uses `#[async_trait]` macro for async trait methods
-/// - **Generics**: Generic over state type `S` for flexibility
+/// - **Generics**: Generic over any state `S` that `AppState` can be extracted from
 /// - **Error Handling**: Custom rejection type for authentication failures
 pub struct AuthUser {
     /// The authenticated user's UUID
     /// This is guaranteed to be valid if the extractor succeeds
     pub user_id: Uuid,
+    /// The session the request's token belongs to
+    pub session_id: Uuid,
 }
 
 /// Implementation of FromRequestParts trait for AuthUser.
@@ -361,17 +549,21 @@ pub struct AuthUser {
 /// # Trait Bounds
 ///
 /// - `S: Send + Sync` - State must be thread-safe (required for async handlers)
+/// - `AppState: FromRef<S>` - Gives access to the JWT keys and database pool
 ///
 /// # Process Flow
 ///
 /// 1. Extract Authorization header → 401 if missing
-/// 2. Get JWT secret from environment → 500 if not configured
+/// 2. Get JWT keys from application state
 /// 3. Decode and validate token → 401 if invalid/expired
-/// 4. Parse user ID from claims → 401 if invalid UUID
-/// 5. Return AuthUser with validated user_id
+/// 4. Parse user and session IDs from claims → 401 if invalid UUID
+/// 5. Touch the session row → 401 if it was revoked
+/// 6. Record the user ID on the current request span
+/// 7. Return AuthUser with validated user_id and session_id
 #[async_trait]
 impl<S> FromRequestParts<S> for AuthUser
 where
+    AppState: FromRef<S>,
     S: Send + Sync,
 {
     /// Custom rejection type for authentication failures
@@ -383,13 +575,13 @@ where
     /// # Arguments
     ///
     /// * `parts` - The request parts (headers, method, etc.)
-    /// * `_state` - Application state (unused, but required by trait)
+    /// * `state` - Application state holding the JWT keys and database pool
     ///
     /// # Returns
     ///
     /// * `Ok(AuthUser)` - Successfully authenticated user
     /// * `Err((StatusCode, String))` - Authentication failure with status and message
-    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
         // Step 1: Extract Authorization header with Bearer token
         // TypedHeader is an Axum extractor that parses the Authorization header
         let TypedHeader(Authorization(bearer)) = parts
@@ -402,35 +594,302 @@ where
                 )
             })?;
 
-        // Step 2: Get JWT secret from environment variable
-        // This should be configured at startup, but we check again for safety
-        let secret = std::env::var("JWT_SECRET").map_err(|_| {
-            (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                "JWT secret not configured".to_string(),
-            )
-        })?;
+        // Step 2: Get JWT keys from application state
+        // These are loaded once at startup for the configured algorithm
+        let state = AppState::from_ref(state);
+
+        // Steps 3-7: validate the token and its session
+        AuthUser::from_token(&state, bearer.token()).await
+    }
+}
 
+impl AuthUser {
+    /// Checks a bearer token the way the extractor does, for the rare endpoint
+    /// that receives it somewhere other than the `Authorization` header.
+    pub async fn from_token(state: &AppState, token: &str) -> Result<Self, (StatusCode, String)> {
         // Step 3: Decode and validate the JWT token
         // This checks signature, expiration, and extracts claims
-        let claims = decode_jwt(bearer.token(), &secret).map_err(|_| {
+        let claims = decode_jwt(token, &state.jwt_keys).map_err(|_| {
             (
                 StatusCode::UNAUTHORIZED,
                 "Invalid or expired token".to_string(),
             )
         })?;
 
-        // Step 4: Parse user ID from claims.sub (subject)
-        // claims.sub is a String, convert it to UUID
+        // Step 4: Parse user ID from claims.sub (subject) and session ID from claims.sid
+        // Both are Strings, convert them to UUIDs
         let user_id = Uuid::parse_str(&claims.sub).map_err(|_| {
             (
                 StatusCode::UNAUTHORIZED,
                 "Invalid user ID in token".to_string(),
             )
         })?;
+        let session_id = Uuid::parse_str(&claims.sid).map_err(|_| {
+            (
+                StatusCode::UNAUTHORIZED,
+                "Invalid session ID in token".to_string(),
+            )
+        })?;
+
+        AuthUser::from_session(state, user_id, session_id).await
+    }
+
+    /// Checks that the session is still active, for a caller that already
+    /// knows the user and session from something other than a JWT, such as
+    /// a stream ticket.
+    pub async fn from_session(
+        state: &AppState,
+        user_id: Uuid,
+        session_id: Uuid,
+    ) -> Result<Self, (StatusCode, String)> {
+        // Step 5: Make sure the session still exists and record its use
+        // A revoked session has been deleted, so its token stops working immediately.
+        // last_used_at is only written once a minute, so a page making several
+        // requests doesn't write the row for each of them
+        let db_error = |_| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "Failed to verify session".to_string(),
+            )
+        };
+        let touched = sqlx::query(
+            r#"
+            UPDATE sessions SET last_used_at = NOW()
+            WHERE id = $1 AND user_id = $2 AND last_used_at < NOW() - INTERVAL '1 minute'
+            "#,
+        )
+        .bind(session_id)
+        .bind(user_id)
+        .execute(&state.pool)
+        .await
+        .map_err(db_error)?
+        .rows_affected()
+            > 0;
+
+        let active = touched
+            || sqlx::query_scalar::<_, bool>(
+                "SELECT EXISTS(SELECT 1 FROM sessions WHERE id = $1 AND user_id = $2)",
+            )
+            .bind(session_id)
+            .bind(user_id)
+            .fetch_one(&state.pool)
+            .await
+            .map_err(db_error)?;
+
+        if !active {
+            return Err((
+                StatusCode::UNAUTHORIZED,
+                "Session has been revoked".to_string(),
+            ));
+        }
+
+        // Step 6: Attach the user ID to the current request span
+        // Only done after successful validation so 401s never log a user ID
+        tracing::Span::current().record("user_id", tracing::field::display(user_id));
+
+        // Step 7: Return authenticated user
+        // At this point, we have a valid, non-expired, non-revoked token with a valid user ID
+        Ok(AuthUser { user_id, session_id })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn policy() -> PasswordPolicy {
+        PasswordPolicy {
+            min_length: 8,
+            require_uppercase: false,
+            require_lowercase: false,
+            require_digit: false,
+            require_symbol: false,
+        }
+    }
+
+    fn failure(password: &str, policy: &PasswordPolicy) -> String {
+        match validate_password_strength(password, policy) {
+            Err(AppError::Validation(message)) => message,
+            other => panic!("expected a validation error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn default_policy_only_checks_length() {
+        assert!(validate_password_strength("aaaaaaaa", &policy()).is_ok());
+        assert_eq!(failure("aaaaaaa", &policy()), "Password must be at least 8 characters");
+    }
+
+    #[test]
+    fn length_counts_characters_not_bytes() {
+        // Eight characters, sixteen bytes
+        assert!(validate_password_strength("ééééééé", &policy()).is_err());
+        assert!(validate_password_strength("éééééééé", &policy()).is_ok());
+    }
+
+    #[test]
+    fn uppercase_rule() {
+        let policy = PasswordPolicy { require_uppercase: true, ..policy() };
+        assert_eq!(failure("lowercase1", &policy), "Password must contain an uppercase letter");
+        assert!(validate_password_strength("Lowercase1", &policy).is_ok());
+    }
+
+    #[test]
+    fn lowercase_rule() {
+        let policy = PasswordPolicy { require_lowercase: true, ..policy() };
+        assert_eq!(failure("UPPERCASE1", &policy), "Password must contain a lowercase letter");
+        assert!(validate_password_strength("UPPERCASe1", &policy).is_ok());
+    }
+
+    #[test]
+    fn digit_rule() {
+        let policy = PasswordPolicy { require_digit: true, ..policy() };
+        assert_eq!(failure("no-digits-here", &policy), "Password must contain a digit");
+        assert!(validate_password_strength("one-digit-1", &policy).is_ok());
+    }
+
+    #[test]
+    fn symbol_rule_ignores_whitespace() {
+        let policy = PasswordPolicy { require_symbol: true, ..policy() };
+        assert_eq!(failure("with spaces only", &policy), "Password must contain a symbol");
+        assert!(validate_password_strength("with-a-dash", &policy).is_ok());
+    }
+
+    #[test]
+    fn every_failed_rule_is_reported() {
+        let policy = PasswordPolicy {
+            min_length: 12,
+            require_uppercase: true,
+            require_lowercase: true,
+            require_digit: true,
+            require_symbol: true,
+        };
+        assert_eq!(
+            failure("short", &policy),
+            "Password must be at least 12 characters; Password must contain an uppercase letter; \
+             Password must contain a digit; Password must contain a symbol"
+        );
+        assert!(validate_password_strength("Long-enough-1", &policy).is_ok());
+    }
+
+    // Throwaway RSA key pair generated for these tests; never used anywhere else
+    const TEST_PRIVATE_KEY: &[u8] = include_bytes!("../tests/fixtures/jwt_test_private.pem");
+    const TEST_PUBLIC_KEY: &[u8] = include_bytes!("../tests/fixtures/jwt_test_public.pem");
+
+    fn rs256_keys() -> JwtKeys {
+        JwtKeys::rs256(TEST_PRIVATE_KEY, TEST_PUBLIC_KEY).expect("valid test key pair")
+    }
+
+    fn assert_round_trip(keys: &JwtKeys) {
+        let (user_id, session_id) = (Uuid::new_v4(), Uuid::new_v4());
+        let token = create_jwt(user_id, session_id, keys, 1).unwrap();
+
+        let claims = decode_jwt(&token, keys).unwrap();
+        assert_eq!(claims.sub, user_id.to_string());
+        assert_eq!(claims.sid, session_id.to_string());
+    }
+
+    #[test]
+    fn hs256_token_round_trips() {
+        assert_round_trip(&JwtKeys::hs256("test-secret"));
+    }
+
+    #[test]
+    fn rs256_token_round_trips() {
+        let keys = rs256_keys();
+        assert_round_trip(&keys);
+
+        let token = create_jwt(Uuid::new_v4(), Uuid::new_v4(), &keys, 1).unwrap();
+        let header = jsonwebtoken::decode_header(&token).unwrap();
+        assert_eq!(header.alg, Algorithm::RS256);
+    }
+
+    #[test]
+    fn token_signed_with_another_secret_is_rejected() {
+        let token = create_jwt(Uuid::new_v4(), Uuid::new_v4(), &JwtKeys::hs256("one"), 1).unwrap();
+        assert!(decode_jwt(&token, &JwtKeys::hs256("two")).is_err());
+    }
+
+    #[test]
+    fn algorithms_are_not_interchangeable() {
+        let rs256 = rs256_keys();
+        let hs256 = JwtKeys::hs256("test-secret");
+
+        let token = create_jwt(Uuid::new_v4(), Uuid::new_v4(), &rs256, 1).unwrap();
+        assert!(decode_jwt(&token, &hs256).is_err());
+
+        let token = create_jwt(Uuid::new_v4(), Uuid::new_v4(), &hs256, 1).unwrap();
+        assert!(decode_jwt(&token, &rs256).is_err());
+    }
+
+    /// A token for `keys` that expired `seconds_ago`.
+    fn expired_token(keys: &JwtKeys, seconds_ago: i64) -> String {
+        let mut claims = Claims::new(Uuid::new_v4(), Uuid::new_v4(), 0);
+        claims.exp = Utc::now().timestamp() - seconds_ago;
+        encode(&Header::new(keys.algorithm), &claims, &keys.encoding).unwrap()
+    }
+
+    #[test]
+    fn token_just_past_expiry_is_accepted_within_leeway() {
+        let keys = JwtKeys::hs256("test-secret").with_leeway(60);
+        assert!(decode_jwt(&expired_token(&keys, 30), &keys).is_ok());
+    }
+
+    #[test]
+    fn token_expired_longer_than_leeway_is_rejected() {
+        let keys = JwtKeys::hs256("test-secret").with_leeway(60);
+        assert!(decode_jwt(&expired_token(&keys, 120), &keys).is_err());
+    }
+
+    #[test]
+    fn zero_leeway_rejects_any_expired_token() {
+        let keys = JwtKeys::hs256("test-secret").with_leeway(0);
+        assert!(decode_jwt(&expired_token(&keys, 5), &keys).is_err());
+    }
+
+    fn keys_with_claims(issuer: Option<&str>, audience: Option<&str>) -> JwtKeys {
+        JwtKeys::hs256("test-secret")
+            .with_claims(issuer.map(str::to_string), audience.map(str::to_string))
+    }
+
+    fn token_for(keys: &JwtKeys) -> String {
+        create_jwt(Uuid::new_v4(), Uuid::new_v4(), keys, 1).unwrap()
+    }
+
+    #[test]
+    fn configured_issuer_and_audience_round_trip() {
+        let keys = keys_with_claims(Some("expense-tracker"), Some("expense-api"));
+        let claims = decode_jwt(&token_for(&keys), &keys).unwrap();
+        assert_eq!(claims.iss.as_deref(), Some("expense-tracker"));
+        assert_eq!(claims.aud.as_deref(), Some("expense-api"));
+    }
+
+    #[test]
+    fn token_for_another_audience_is_rejected() {
+        let ours = keys_with_claims(Some("expense-tracker"), Some("expense-api"));
+        let theirs = keys_with_claims(Some("expense-tracker"), Some("billing-api"));
+        assert!(decode_jwt(&token_for(&theirs), &ours).is_err());
+    }
+
+    #[test]
+    fn token_from_another_issuer_is_rejected() {
+        let ours = keys_with_claims(Some("expense-tracker"), None);
+        let theirs = keys_with_claims(Some("someone-else"), None);
+        assert!(decode_jwt(&token_for(&theirs), &ours).is_err());
+    }
+
+    #[test]
+    fn configured_claims_are_required() {
+        let token = token_for(&keys_with_claims(None, None));
+        assert!(decode_jwt(&token, &keys_with_claims(None, Some("expense-api"))).is_err());
+        assert!(decode_jwt(&token, &keys_with_claims(Some("expense-tracker"), None)).is_err());
+    }
 
-        // Step 5: Return authenticated user
-        // At this point, we have a valid, non-expired token with a valid user ID
-        Ok(AuthUser { user_id })
+    #[test]
+    fn claims_are_not_checked_when_unconfigured() {
+        let plain = keys_with_claims(None, None);
+        let tagged = keys_with_claims(Some("expense-tracker"), Some("expense-api"));
+        assert!(decode_jwt(&token_for(&plain), &plain).is_ok());
+        assert!(decode_jwt(&token_for(&tagged), &plain).is_ok());
     }
 }
@@ -0,0 +1,54 @@
+//! Optional in-memory cache of each user's category list.
+//!
+//! Enabled with `CATEGORY_CACHE_ENABLED`. Category handlers drop a user's
+//! entry after every change, so reads within one process always see their
+//! own writes. The cache is per process: with several instances behind a load
+//! balancer, a change made through one instance isn't seen by the others until
+//! their entry is replaced, so keep it off for multi-instance deployments.
+
+use std::{collections::HashMap, sync::RwLock};
+
+use uuid::Uuid;
+
+use crate::models::Category;
+
+#[derive(Default)]
+struct Entries {
+    lists: HashMap<Uuid, Vec<Category>>,
+    /// Bumped on every invalidation, so a list read from the database before a
+    /// change can't be stored after it.
+    generation: u64,
+}
+
+#[derive(Default)]
+pub struct CategoryCache {
+    entries: RwLock<Entries>,
+}
+
+impl CategoryCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The cached list for `user_id`, and otherwise the generation to pass to
+    /// [`insert`](Self::insert) once the list has been loaded.
+    pub fn get(&self, user_id: Uuid) -> Result<Vec<Category>, u64> {
+        let entries = self.entries.read().expect("category cache lock poisoned");
+        entries.lists.get(&user_id).cloned().ok_or(entries.generation)
+    }
+
+    /// Stores a freshly loaded list, unless something was invalidated since
+    /// `generation` was handed out.
+    pub fn insert(&self, user_id: Uuid, generation: u64, categories: Vec<Category>) {
+        let mut entries = self.entries.write().expect("category cache lock poisoned");
+        if entries.generation == generation {
+            entries.lists.insert(user_id, categories);
+        }
+    }
+
+    pub fn invalidate(&self, user_id: Uuid) {
+        let mut entries = self.entries.write().expect("category cache lock poisoned");
+        entries.lists.remove(&user_id);
+        entries.generation += 1;
+    }
+}
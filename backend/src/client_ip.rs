@@ -0,0 +1,190 @@
+//! The address of the client behind any trusted reverse proxies.
+//!
+//! In the docker-compose setup every request reaches the backend through
+//! nginx, so the socket peer is always nginx. Rate limits, sessions and the
+//! audit log need the real client, which nginx passes in `X-Forwarded-For`.
+//! That header is only believed when the peer is listed in `TRUSTED_PROXIES`;
+//! otherwise anyone could pick their own address by sending it.
+//!
+//! [`resolve_client_ip`] runs once per request and stores the result as a
+//! [`ClientIp`] extension, which handlers and route layers then extract.
+
+use std::net::{IpAddr, SocketAddr};
+
+use axum::{
+    async_trait,
+    extract::{ConnectInfo, FromRequestParts, Request, State},
+    http::{request::Parts, HeaderMap},
+    middleware::Next,
+    response::Response,
+};
+use ipnet::IpNet;
+
+use crate::{error::AppError, AppState};
+
+const FORWARDED_FOR: &str = "x-forwarded-for";
+const REAL_IP: &str = "x-real-ip";
+
+/// The client address for the current request.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ClientIp(pub IpAddr);
+
+/// Works out the client address from the socket `peer` and the proxy headers.
+///
+/// When `peer` is a trusted proxy, `X-Forwarded-For` is read from the right
+/// (the entry the nearest proxy added) past any further trusted proxies; the
+/// first untrusted entry is the client. Entries further left were sent by the
+/// client itself and are ignored. Without `X-Forwarded-For`, `X-Real-IP` is
+/// used. An untrusted peer is always the client.
+pub fn client_ip(peer: IpAddr, headers: &HeaderMap, trusted_proxies: &[IpNet]) -> IpAddr {
+    let trusted = |ip: &IpAddr| trusted_proxies.iter().any(|net| net.contains(ip));
+    if !trusted(&peer) {
+        return peer;
+    }
+
+    let forwarded: Vec<&str> = headers
+        .get_all(FORWARDED_FOR)
+        .iter()
+        .filter_map(|v| v.to_str().ok())
+        .flat_map(|v| v.split(','))
+        .map(str::trim)
+        .filter(|entry| !entry.is_empty())
+        .collect();
+
+    if forwarded.is_empty() {
+        return headers
+            .get(REAL_IP)
+            .and_then(|v| v.to_str().ok())
+            .and_then(parse_ip)
+            .unwrap_or(peer);
+    }
+
+    let mut client = peer;
+    for entry in forwarded.iter().rev() {
+        // Garbage can only come from the client; stop at the last good hop
+        let Some(ip) = parse_ip(entry) else { break };
+        client = ip;
+        if !trusted(&ip) {
+            break;
+        }
+    }
+    client
+}
+
+/// Parses `1.2.3.4`, `2001:db8::1` or either with a port.
+fn parse_ip(value: &str) -> Option<IpAddr> {
+    let value = value.trim();
+    value
+        .parse::<IpAddr>()
+        .or_else(|_| value.parse::<SocketAddr>().map(|addr| addr.ip()))
+        .ok()
+}
+
+/// Middleware storing the [`ClientIp`] of each request.
+pub async fn resolve_client_ip(
+    State(state): State<AppState>,
+    ConnectInfo(peer): ConnectInfo<SocketAddr>,
+    mut req: Request,
+    next: Next,
+) -> Response {
+    let ip = client_ip(peer.ip(), req.headers(), &state.config.trusted_proxies);
+    req.extensions_mut().insert(ClientIp(ip));
+    next.run(req).await
+}
+
+#[async_trait]
+impl<S: Send + Sync> FromRequestParts<S> for ClientIp {
+    type Rejection = AppError;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        parts.extensions.get::<ClientIp>().copied().ok_or_else(|| {
+            AppError::Internal(anyhow::anyhow!("resolve_client_ip layer is missing"))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::HeaderValue;
+
+    const NGINX: &str = "172.28.0.10";
+    const CLIENT: &str = "198.51.100.4";
+
+    fn ip(value: &str) -> IpAddr {
+        value.parse().unwrap()
+    }
+
+    fn trusted() -> Vec<IpNet> {
+        vec!["172.28.0.10/32".parse().unwrap(), "10.0.0.0/8".parse().unwrap()]
+    }
+
+    fn with_headers(pairs: &[(&'static str, &str)]) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        for (name, value) in pairs {
+            headers.append(*name, HeaderValue::from_str(value).unwrap());
+        }
+        headers
+    }
+
+    #[test]
+    fn untrusted_peer_is_the_client_whatever_it_sends() {
+        let headers = with_headers(&[(FORWARDED_FOR, "1.2.3.4"), (REAL_IP, "1.2.3.4")]);
+        assert_eq!(client_ip(ip(CLIENT), &headers, &trusted()), ip(CLIENT));
+        assert_eq!(client_ip(ip(NGINX), &headers, &[]), ip(NGINX));
+    }
+
+    #[test]
+    fn trusted_peer_passes_on_the_forwarded_client() {
+        let headers = with_headers(&[(FORWARDED_FOR, CLIENT)]);
+        assert_eq!(client_ip(ip(NGINX), &headers, &trusted()), ip(CLIENT));
+    }
+
+    #[test]
+    fn spoofed_entries_left_of_the_client_are_ignored() {
+        // The client sent "1.2.3.4" itself; nginx appended the real address
+        let headers = with_headers(&[(FORWARDED_FOR, "1.2.3.4, 198.51.100.4")]);
+        assert_eq!(client_ip(ip(NGINX), &headers, &trusted()), ip(CLIENT));
+    }
+
+    #[test]
+    fn chained_trusted_proxies_are_skipped() {
+        let headers = with_headers(&[
+            (FORWARDED_FOR, "198.51.100.4, 10.1.2.3"),
+            (FORWARDED_FOR, "10.0.0.5"),
+        ]);
+        assert_eq!(client_ip(ip(NGINX), &headers, &trusted()), ip(CLIENT));
+    }
+
+    #[test]
+    fn all_trusted_hops_fall_back_to_the_leftmost() {
+        let headers = with_headers(&[(FORWARDED_FOR, "10.0.0.7, 10.0.0.5")]);
+        assert_eq!(client_ip(ip(NGINX), &headers, &trusted()), ip("10.0.0.7"));
+    }
+
+    #[test]
+    fn garbage_stops_at_the_last_good_hop() {
+        let headers = with_headers(&[(FORWARDED_FOR, "unknown, 10.0.0.5")]);
+        assert_eq!(client_ip(ip(NGINX), &headers, &trusted()), ip("10.0.0.5"));
+
+        let headers = with_headers(&[(FORWARDED_FOR, "unknown")]);
+        assert_eq!(client_ip(ip(NGINX), &headers, &trusted()), ip(NGINX));
+    }
+
+    #[test]
+    fn real_ip_is_used_without_forwarded_for() {
+        let headers = with_headers(&[(REAL_IP, CLIENT)]);
+        assert_eq!(client_ip(ip(NGINX), &headers, &trusted()), ip(CLIENT));
+
+        assert_eq!(client_ip(ip(NGINX), &HeaderMap::new(), &trusted()), ip(NGINX));
+    }
+
+    #[test]
+    fn addresses_with_ports_and_ipv6() {
+        let headers = with_headers(&[(FORWARDED_FOR, "[2001:db8::1]:4711")]);
+        assert_eq!(client_ip(ip(NGINX), &headers, &trusted()), ip("2001:db8::1"));
+
+        let headers = with_headers(&[(FORWARDED_FOR, "198.51.100.4:4711")]);
+        assert_eq!(client_ip(ip(NGINX), &headers, &trusted()), ip(CLIENT));
+    }
+}
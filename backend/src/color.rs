@@ -0,0 +1,88 @@
+//! WCAG 2 contrast math for category colors.
+//!
+//! Category colors are picked by users, so labels drawn on them need either
+//! black or white text depending on how light the color is.
+
+pub const BLACK: &str = "#000000";
+pub const WHITE: &str = "#FFFFFF";
+
+/// Text color for labels on a `#RRGGBB` background: black or white, whichever
+/// contrasts more. Anything that isn't a 6-digit hex code gets black.
+pub fn text_color_for(hex: &str) -> &'static str {
+    match relative_luminance(hex) {
+        Some(luminance) if contrast_ratio(luminance, 1.0) > contrast_ratio(luminance, 0.0) => WHITE,
+        _ => BLACK,
+    }
+}
+
+/// Relative luminance of a `#RRGGBB` color, from 0 (black) to 1 (white).
+pub fn relative_luminance(hex: &str) -> Option<f64> {
+    let digits = hex
+        .strip_prefix('#')
+        .filter(|d| d.len() == 6 && d.bytes().all(|b| b.is_ascii_hexdigit()))?;
+
+    // sRGB channel to linear light
+    let channel = |i: usize| {
+        let value = f64::from(u8::from_str_radix(&digits[i..i + 2], 16).expect("hex digits"));
+        let srgb = value / 255.0;
+        if srgb <= 0.03928 {
+            srgb / 12.92
+        } else {
+            ((srgb + 0.055) / 1.055).powf(2.4)
+        }
+    };
+
+    Some(0.2126 * channel(0) + 0.7152 * channel(2) + 0.0722 * channel(4))
+}
+
+/// Contrast ratio between two relative luminances, from 1 (none) to 21
+/// (black on white). WCAG AA asks for at least 4.5 for normal text.
+pub fn contrast_ratio(a: f64, b: f64) -> f64 {
+    let (lighter, darker) = if a > b { (a, b) } else { (b, a) };
+    (lighter + 0.05) / (darker + 0.05)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn black_background_gets_white_text() {
+        assert_eq!(text_color_for("#000000"), WHITE);
+    }
+
+    #[test]
+    fn white_background_gets_black_text() {
+        assert_eq!(text_color_for("#FFFFFF"), BLACK);
+        assert_eq!(text_color_for("#ffffff"), BLACK);
+    }
+
+    #[test]
+    fn mid_grey_sits_on_the_black_side() {
+        // #808080 has a luminance of about 0.22; the two ratios cross near 0.18
+        assert_eq!(text_color_for("#808080"), BLACK);
+        assert_eq!(text_color_for("#595959"), WHITE);
+    }
+
+    #[test]
+    fn saturated_colors() {
+        assert_eq!(text_color_for("#FFEAA7"), BLACK);
+        assert_eq!(text_color_for("#0000FF"), WHITE);
+    }
+
+    #[test]
+    fn invalid_hex_falls_back_to_black() {
+        for hex in ["", "#", "#FFF", "FFFFFF", "#GGGGGG", "#0000000", "#ÿÿÿ"] {
+            assert_eq!(relative_luminance(hex), None, "{}", hex);
+            assert_eq!(text_color_for(hex), BLACK, "{}", hex);
+        }
+    }
+
+    #[test]
+    fn luminance_and_contrast_extremes() {
+        assert_eq!(relative_luminance("#000000"), Some(0.0));
+        assert!((relative_luminance("#FFFFFF").unwrap() - 1.0).abs() < 1e-9);
+        assert!((contrast_ratio(1.0, 0.0) - 21.0).abs() < 1e-9);
+        assert_eq!(contrast_ratio(0.5, 0.5), 1.0);
+    }
+}
@@ -1,33 +1,372 @@
+use anyhow::Context;
+use chrono::NaiveDate;
+use ipnet::IpNet;
+use rust_decimal::Decimal;
 use serde::Deserialize;
-use std::env;
+use std::{env, net::IpAddr, str::FromStr};
+
+/// Seconds of clock skew tolerated on JWT expiry unless `JWT_LEEWAY_SECS` says otherwise.
+pub const DEFAULT_JWT_LEEWAY_SECS: u64 = 60;
+
+/// Algorithm used to sign and verify JWTs.
+///
+/// `HS256` signs with the shared `JWT_SECRET`. `RS256` signs with an RSA
+/// private key so other services can verify tokens with only the public key.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+pub enum JwtAlgorithm {
+    #[default]
+    HS256,
+    RS256,
+}
+
+impl FromStr for JwtAlgorithm {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_uppercase().as_str() {
+            "HS256" => Ok(Self::HS256),
+            "RS256" => Ok(Self::RS256),
+            other => Err(format!("unsupported JWT algorithm: {}", other)),
+        }
+    }
+}
+
+/// Rules a new password must satisfy.
+///
+/// The default only requires 8 characters, matching what existing accounts
+/// were created with; the character-class rules are opt-in.
+#[derive(Debug, Clone, Deserialize)]
+pub struct PasswordPolicy {
+    pub min_length: usize,
+    pub require_uppercase: bool,
+    pub require_lowercase: bool,
+    pub require_digit: bool,
+    pub require_symbol: bool,
+}
+
+impl PasswordPolicy {
+    fn from_env() -> Self {
+        let flag = |name: &str| {
+            env::var(name)
+                .unwrap_or_else(|_| "false".to_string())
+                .parse()
+                .unwrap_or(false)
+        };
+
+        Self {
+            min_length: env::var("PASSWORD_MIN_LENGTH")
+                .unwrap_or_else(|_| "8".to_string())
+                .parse()
+                .unwrap_or(8),
+            require_uppercase: flag("PASSWORD_REQUIRE_UPPERCASE"),
+            require_lowercase: flag("PASSWORD_REQUIRE_LOWERCASE"),
+            require_digit: flag("PASSWORD_REQUIRE_DIGIT"),
+            require_symbol: flag("PASSWORD_REQUIRE_SYMBOL"),
+        }
+    }
+}
+
+/// Requests allowed per client address within a fixed window.
+///
+/// A `max_requests` of 0 turns the limit off.
+#[derive(Debug, Clone, Deserialize)]
+pub struct RateLimit {
+    pub max_requests: u32,
+    pub window_secs: u64,
+}
+
+impl RateLimit {
+    /// Reads `{prefix}_RATE_LIMIT` and `{prefix}_RATE_LIMIT_WINDOW_SECS`.
+    fn from_env(prefix: &str, default_max: u32, default_window_secs: u64) -> Self {
+        Self {
+            max_requests: env::var(format!("{}_RATE_LIMIT", prefix))
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(default_max),
+            window_secs: env::var(format!("{}_RATE_LIMIT_WINDOW_SECS", prefix))
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(default_window_secs),
+        }
+    }
+}
 
 #[derive(Debug, Clone, Deserialize)]
 pub struct Config {
     pub database_url: String,
     pub jwt_secret: String,
+    pub jwt_algorithm: JwtAlgorithm,
+    pub jwt_private_key_path: Option<String>,
+    pub jwt_public_key_path: Option<String>,
+    pub jwt_issuer: Option<String>,
+    pub jwt_audience: Option<String>,
     pub jwt_expiration_hours: i64,
+    pub jwt_remember_hours: i64,
+    pub jwt_leeway_secs: u64,
     pub server_host: String,
     pub server_port: u16,
+    pub metrics_enabled: bool,
+    pub api_docs_enabled: bool,
+    pub attachments_dir: String,
+    pub max_attachment_bytes: usize,
+    pub max_body_bytes: usize,
+    pub allow_future_expense_dates: bool,
+    pub min_expense_date: NaiveDate,
+    pub max_expense_amount: Decimal,
+    pub large_expense_multiple: Decimal,
+    pub trash_retention_days: i64,
+    pub default_page_size: i64,
+    pub max_page_size: i64,
+    pub max_categories_per_user: i64,
+    pub max_batch_size: usize,
+    pub category_cache_enabled: bool,
+    pub alert_check_interval_secs: u64,
+    pub require_https: bool,
+    pub db_connect_retries: u32,
+    pub db_connect_backoff_secs: u64,
+    pub password_policy: PasswordPolicy,
+    pub login_rate_limit: RateLimit,
+    pub register_rate_limit: RateLimit,
+    pub trusted_proxies: Vec<IpNet>,
+    pub webhook_allow_private_networks: bool,
+    pub cors_allowed_origins: Vec<String>,
+    pub cors_allow_credentials: bool,
+    pub cors_max_age_secs: u64,
 }
 
 impl Config {
-    pub fn from_env() -> Result<Self, env::VarError> {
+    /// Reads the configuration from the environment.
+    ///
+    /// Fails if a required variable is missing, `JWT_ALGORITHM` isn't a
+    /// supported algorithm (a typo there must not quietly fall back to HS256)
+    /// or `TRUSTED_PROXIES` lists something that isn't an address or network.
+    pub fn from_env() -> anyhow::Result<Self> {
+        let max_page_size = env::var("MAX_PAGE_SIZE")
+            .unwrap_or_else(|_| "200".to_string())
+            .parse()
+            .unwrap_or(200)
+            .max(1);
+
         Ok(Self {
-            database_url: env::var("DATABASE_URL")?,
-            jwt_secret: env::var("JWT_SECRET")?,
+            database_url: env::var("DATABASE_URL").context("DATABASE_URL must be set")?,
+            jwt_secret: env::var("JWT_SECRET").context("JWT_SECRET must be set")?,
+            jwt_algorithm: env::var("JWT_ALGORITHM")
+                .unwrap_or_else(|_| "HS256".to_string())
+                .parse()
+                .map_err(anyhow::Error::msg)
+                .context("invalid JWT_ALGORITHM")?,
+            jwt_private_key_path: env::var("JWT_PRIVATE_KEY_PATH").ok(),
+            jwt_public_key_path: env::var("JWT_PUBLIC_KEY_PATH").ok(),
+            // Unset or empty leaves the claim out of tokens and unchecked
+            jwt_issuer: env::var("JWT_ISSUER").ok().filter(|v| !v.is_empty()),
+            jwt_audience: env::var("JWT_AUDIENCE").ok().filter(|v| !v.is_empty()),
             jwt_expiration_hours: env::var("JWT_EXPIRATION_HOURS")
                 .unwrap_or_else(|_| "24".to_string())
                 .parse()
                 .unwrap_or(24),
-            server_host: env::var("SERVER_HOST").unwrap_or_else(|_| "0.0.0.0".to_string()),
+            jwt_remember_hours: env::var("JWT_REMEMBER_HOURS")
+                .unwrap_or_else(|_| "720".to_string())
+                .parse()
+                .unwrap_or(720),
+            jwt_leeway_secs: env::var("JWT_LEEWAY_SECS")
+                .ok()
+                .and_then(|secs| secs.parse().ok())
+                .unwrap_or(DEFAULT_JWT_LEEWAY_SECS),
+            // SERVER_HOST/SERVER_PORT win over the shorter HOST/PORT. Defaults to
+            // loopback so a bare `cargo run` isn't reachable from the network.
+            server_host: env::var("SERVER_HOST")
+                .or_else(|_| env::var("HOST"))
+                .unwrap_or_else(|_| "127.0.0.1".to_string()),
             server_port: env::var("SERVER_PORT")
+                .or_else(|_| env::var("PORT"))
                 .unwrap_or_else(|_| "3000".to_string())
                 .parse()
                 .unwrap_or(3000),
+            metrics_enabled: env::var("METRICS_ENABLED")
+                .unwrap_or_else(|_| "false".to_string())
+                .parse()
+                .unwrap_or(false),
+            api_docs_enabled: env::var("API_DOCS_ENABLED")
+                .unwrap_or_else(|_| "false".to_string())
+                .parse()
+                .unwrap_or(false),
+            attachments_dir: env::var("ATTACHMENTS_DIR")
+                .unwrap_or_else(|_| "./attachments".to_string()),
+            max_attachment_bytes: env::var("MAX_ATTACHMENT_BYTES")
+                .unwrap_or_else(|_| "5242880".to_string())
+                .parse()
+                .unwrap_or(5 * 1024 * 1024),
+            max_body_bytes: env::var("MAX_BODY_BYTES")
+                .unwrap_or_else(|_| "262144".to_string())
+                .parse()
+                .unwrap_or(256 * 1024),
+            allow_future_expense_dates: env::var("ALLOW_FUTURE_EXPENSE_DATES")
+                .unwrap_or_else(|_| "true".to_string())
+                .parse()
+                .unwrap_or(true),
+            min_expense_date: env::var("MIN_EXPENSE_DATE")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(NaiveDate::from_ymd_opt(1970, 1, 1).expect("Valid date")),
+            max_expense_amount: env::var("MAX_EXPENSE_AMOUNT")
+                .unwrap_or_else(|_| "1000000".to_string())
+                .parse()
+                .unwrap_or(Decimal::from(1_000_000)),
+            // 0 turns the large-expense warning off
+            large_expense_multiple: env::var("LARGE_EXPENSE_MULTIPLE")
+                .unwrap_or_else(|_| "3".to_string())
+                .parse()
+                .unwrap_or(Decimal::from(3)),
+            trash_retention_days: env::var("TRASH_RETENTION_DAYS")
+                .unwrap_or_else(|_| "30".to_string())
+                .parse()
+                .unwrap_or(30),
+            // Clamped so a request without `limit` is always valid
+            default_page_size: env::var("DEFAULT_PAGE_SIZE")
+                .unwrap_or_else(|_| "50".to_string())
+                .parse()
+                .unwrap_or(50)
+                .clamp(1, max_page_size),
+            max_page_size,
+            max_categories_per_user: env::var("MAX_CATEGORIES_PER_USER")
+                .unwrap_or_else(|_| "100".to_string())
+                .parse()
+                .unwrap_or(100),
+            max_batch_size: env::var("MAX_BATCH_SIZE")
+                .unwrap_or_else(|_| "500".to_string())
+                .parse()
+                .unwrap_or(500),
+            category_cache_enabled: env::var("CATEGORY_CACHE_ENABLED")
+                .unwrap_or_else(|_| "false".to_string())
+                .parse()
+                .unwrap_or(false),
+            // 0 turns the alert checker off
+            alert_check_interval_secs: env::var("ALERT_CHECK_INTERVAL_SECS")
+                .unwrap_or_else(|_| "300".to_string())
+                .parse()
+                .unwrap_or(300),
+            require_https: env::var("REQUIRE_HTTPS")
+                .unwrap_or_else(|_| "false".to_string())
+                .parse()
+                .unwrap_or(false),
+            db_connect_retries: env::var("DB_CONNECT_RETRIES")
+                .unwrap_or_else(|_| "5".to_string())
+                .parse()
+                .unwrap_or(5),
+            db_connect_backoff_secs: env::var("DB_CONNECT_BACKOFF_SECS")
+                .unwrap_or_else(|_| "2".to_string())
+                .parse()
+                .unwrap_or(2),
+            password_policy: PasswordPolicy::from_env(),
+            // Registration is stricter: legitimate users sign up once
+            login_rate_limit: RateLimit::from_env("LOGIN", 10, 60),
+            register_rate_limit: RateLimit::from_env("REGISTER", 5, 3600),
+            trusted_proxies: parse_trusted_proxies(
+                &env::var("TRUSTED_PROXIES").unwrap_or_default(),
+            )
+            .context("invalid TRUSTED_PROXIES")?,
+            // Lets webhooks reach receivers on the local network
+            webhook_allow_private_networks: env::var("WEBHOOK_ALLOW_PRIVATE_NETWORKS")
+                .unwrap_or_else(|_| "false".to_string())
+                .parse()
+                .unwrap_or(false),
+            // Comma-separated, e.g. `https://app.example.com,http://localhost:8080`
+            cors_allowed_origins: env::var("CORS_ALLOWED_ORIGINS")
+                .unwrap_or_else(|_| "*".to_string())
+                .split(',')
+                .map(|origin| origin.trim().to_string())
+                .filter(|origin| !origin.is_empty())
+                .collect(),
+            cors_allow_credentials: env::var("CORS_ALLOW_CREDENTIALS")
+                .unwrap_or_else(|_| "false".to_string())
+                .parse()
+                .unwrap_or(false),
+            cors_max_age_secs: env::var("CORS_MAX_AGE_SECS")
+                .unwrap_or_else(|_| "0".to_string())
+                .parse()
+                .unwrap_or(0),
         })
     }
 
+    /// True when `CORS_ALLOWED_ORIGINS` is `*` (or lists it).
+    pub fn cors_allows_any_origin(&self) -> bool {
+        self.cors_allowed_origins.iter().any(|origin| origin == "*")
+    }
+
+    /// Address to bind, e.g. `127.0.0.1:3000`.
     pub fn server_address(&self) -> String {
-        format!("{}:{}", self.server_host, self.server_port)
+        join_host_port(&self.server_host, self.server_port)
+    }
+}
+
+/// `host:port`, with an IPv6 address in brackets (`[::1]:3000`) so its colons
+/// aren't read as the port separator.
+fn join_host_port(host: &str, port: u16) -> String {
+    if host.parse::<std::net::Ipv6Addr>().is_ok() {
+        format!("[{}]:{}", host, port)
+    } else {
+        format!("{}:{}", host, port)
+    }
+}
+
+/// Parses a comma-separated list of addresses and CIDR networks, e.g.
+/// `172.28.0.10,10.0.0.0/8`. A bare address trusts just that address.
+fn parse_trusted_proxies(value: &str) -> anyhow::Result<Vec<IpNet>> {
+    value
+        .split(',')
+        .map(str::trim)
+        .filter(|entry| !entry.is_empty())
+        .map(|entry| {
+            entry
+                .parse::<IpNet>()
+                .or_else(|_| entry.parse::<IpAddr>().map(IpNet::from))
+                .with_context(|| format!("not an IP address or network: {}", entry))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn jwt_algorithm_parses_case_insensitively() {
+        assert_eq!("HS256".parse(), Ok(JwtAlgorithm::HS256));
+        assert_eq!("rs256".parse(), Ok(JwtAlgorithm::RS256));
+    }
+
+    #[test]
+    fn unknown_jwt_algorithm_is_an_error() {
+        assert_eq!(
+            "RS265".parse::<JwtAlgorithm>(),
+            Err("unsupported JWT algorithm: RS265".to_string())
+        );
+        assert!("".parse::<JwtAlgorithm>().is_err());
+    }
+
+    #[test]
+    fn server_address_joins_host_and_port() {
+        assert_eq!(join_host_port("127.0.0.1", 3000), "127.0.0.1:3000");
+        assert_eq!(join_host_port("0.0.0.0", 8080), "0.0.0.0:8080");
+        assert_eq!(join_host_port("localhost", 3000), "localhost:3000");
+        assert_eq!(join_host_port("::", 3000), "[::]:3000");
+        assert_eq!(join_host_port("::1", 3000), "[::1]:3000");
+
+        // Every form binds
+        for host in ["127.0.0.1", "::1"] {
+            let address = join_host_port(host, 3000);
+            assert!(address.parse::<std::net::SocketAddr>().is_ok(), "{}", address);
+        }
+    }
+
+    #[test]
+    fn trusted_proxies_accept_addresses_and_networks() {
+        let proxies = parse_trusted_proxies(" 172.28.0.10, 10.0.0.0/8,,::1 ").unwrap();
+        let proxies: Vec<String> = proxies.iter().map(ToString::to_string).collect();
+        assert_eq!(proxies, ["172.28.0.10/32", "10.0.0.0/8", "::1/128"]);
+
+        assert!(parse_trusted_proxies("").unwrap().is_empty());
+        assert!(parse_trusted_proxies("nginx").is_err());
+        assert!(parse_trusted_proxies("10.0.0.0/33").is_err());
     }
 }
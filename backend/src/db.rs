@@ -3,16 +3,42 @@ use std::time::Duration;
 
 use crate::error::AppResult;
 
-pub async fn create_pool(database_url: &str) -> AppResult<PgPool> {
-    let pool = PgPoolOptions::new()
-        .max_connections(5)
-        .acquire_timeout(Duration::from_secs(3))
-        .connect(database_url)
-        .await?;
+/// Connects to Postgres, retrying up to `retries` more times with `backoff`
+/// between attempts so the server survives starting before the database
+/// (e.g. under docker-compose). Returns the last error once retries run out.
+pub async fn create_pool(database_url: &str, retries: u32, backoff: Duration) -> AppResult<PgPool> {
+    let mut attempt = 0;
 
-    tracing::info!("Database connection pool established");
+    loop {
+        attempt += 1;
 
-    Ok(pool)
+        let result = PgPoolOptions::new()
+            .max_connections(5)
+            .acquire_timeout(Duration::from_secs(3))
+            .connect(database_url)
+            .await;
+
+        match result {
+            Ok(pool) => {
+                tracing::info!("Database connection pool established");
+                return Ok(pool);
+            }
+            Err(e) if attempt <= retries => {
+                tracing::warn!(
+                    "Database connection attempt {}/{} failed: {}; retrying in {:?}",
+                    attempt,
+                    retries + 1,
+                    e,
+                    backoff
+                );
+                tokio::time::sleep(backoff).await;
+            }
+            Err(e) => {
+                tracing::error!("Giving up on the database after {} attempts", attempt);
+                return Err(e.into());
+            }
+        }
+    }
 }
 
 pub async fn run_migrations(pool: &PgPool) -> AppResult<()> {
@@ -22,3 +48,23 @@ pub async fn run_migrations(pool: &PgPool) -> AppResult<()> {
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Instant;
+
+    #[tokio::test]
+    async fn gives_up_after_the_configured_retries() {
+        // Nothing listens on port 1; each attempt fails at the acquire timeout
+        let backoff = Duration::from_millis(500);
+        let started = Instant::now();
+
+        let result = create_pool("postgres://postgres@127.0.0.1:1/none", 1, backoff).await;
+
+        assert!(result.is_err());
+        // Two attempts with the backoff between them
+        let elapsed = started.elapsed();
+        assert!(elapsed >= backoff, "gave up after {:?}", elapsed);
+    }
+}
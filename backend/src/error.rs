@@ -1,5 +1,5 @@
 use axum::{
-    http::StatusCode,
+    http::{header, HeaderValue, StatusCode},
     response::{IntoResponse, Response},
     Json,
 };
@@ -26,6 +26,18 @@ pub enum AppError {
     #[error("Validation error: {0}")]
     Validation(String),
 
+    #[error("Conflict: {0}")]
+    Conflict(String),
+
+    #[error("Method not allowed")]
+    MethodNotAllowed,
+
+    #[error("Too many requests, retry after {retry_after_secs}s")]
+    TooManyRequests { retry_after_secs: u64 },
+
+    #[error("Payload too large: {0}")]
+    PayloadTooLarge(String),
+
     #[error("Internal server error")]
     Internal(#[from] anyhow::Error),
 
@@ -38,6 +50,11 @@ pub enum AppError {
 
 impl IntoResponse for AppError {
     fn into_response(self) -> Response {
+        let retry_after = match self {
+            AppError::TooManyRequests { retry_after_secs } => Some(retry_after_secs),
+            _ => None,
+        };
+
         let (status, error_message) = match self {
             AppError::Database(ref e) => {
                 tracing::error!("Database error: {:?}", e);
@@ -51,6 +68,12 @@ impl IntoResponse for AppError {
             AppError::Unauthorized => (StatusCode::UNAUTHORIZED, "Unauthorized"),
             AppError::NotFound(ref msg) => (StatusCode::NOT_FOUND, msg.as_str()),
             AppError::Validation(ref msg) => (StatusCode::BAD_REQUEST, msg.as_str()),
+            AppError::Conflict(ref msg) => (StatusCode::CONFLICT, msg.as_str()),
+            AppError::MethodNotAllowed => (StatusCode::METHOD_NOT_ALLOWED, "Method not allowed"),
+            AppError::TooManyRequests { .. } => {
+                (StatusCode::TOO_MANY_REQUESTS, "Too many requests, please try again later")
+            }
+            AppError::PayloadTooLarge(ref msg) => (StatusCode::PAYLOAD_TOO_LARGE, msg.as_str()),
             AppError::Internal(ref e) => {
                 tracing::error!("Internal error: {:?}", e);
                 (StatusCode::INTERNAL_SERVER_ERROR, "Internal server error")
@@ -72,8 +95,95 @@ impl IntoResponse for AppError {
             "error": error_message,
         }));
 
-        (status, body).into_response()
+        let mut response = (status, body).into_response();
+        if let Some(secs) = retry_after {
+            response.headers_mut().insert(header::RETRY_AFTER, HeaderValue::from(secs));
+        }
+        response
     }
 }
 
 pub type AppResult<T> = Result<T, AppError>;
+
+/// Router fallback, so unknown paths get the same `{ "error": ... }` body as
+/// every other error instead of axum's empty 404.
+pub async fn route_not_found() -> AppError {
+    AppError::NotFound("Route not found".to_string())
+}
+
+/// Gives axum's empty `405 Method Not Allowed` the JSON error body.
+///
+/// Axum answers a known path with the wrong method itself, before any handler
+/// runs, so this rewrites the response instead. The `Allow` header is kept.
+///
+/// ```rust,ignore
+/// router.layer(middleware::map_response(json_method_not_allowed))
+/// ```
+pub async fn json_method_not_allowed(response: Response) -> Response {
+    if response.status() != StatusCode::METHOD_NOT_ALLOWED {
+        return response;
+    }
+
+    let (mut parts, _) = response.into_parts();
+    let (json_parts, body) = AppError::MethodNotAllowed.into_response().into_parts();
+    parts.headers.extend(json_parts.headers);
+    Response::from_parts(parts, body)
+}
+
+/// Postgres SQLSTATE for `unique_violation`.
+const UNIQUE_VIOLATION: &str = "23505";
+/// Postgres SQLSTATE for `check_violation`.
+const CHECK_VIOLATION: &str = "23514";
+
+/// Converts a unique-constraint violation into a `400` validation error.
+///
+/// Handlers insert first and let the database enforce uniqueness instead of
+/// checking with `SELECT EXISTS` beforehand, which would race with concurrent
+/// requests. Any other error is passed through as [`AppError::Database`].
+///
+/// ```rust,ignore
+/// sqlx::query_as::<_, Category>("INSERT ...")
+///     .fetch_one(&state.pool)
+///     .await
+///     .map_err(map_unique_violation)?;
+/// ```
+pub fn map_unique_violation(err: sqlx::Error) -> AppError {
+    let constraint = match err.as_database_error() {
+        Some(db_err) if db_err.code().as_deref() == Some(UNIQUE_VIOLATION) => {
+            db_err.constraint().unwrap_or_default().to_string()
+        }
+        _ => return AppError::Database(err),
+    };
+
+    let message = match constraint.as_str() {
+        "users_email_key" => "Email already registered",
+        "categories_user_id_name_key" => "Category name already exists",
+        "idx_alerts_unique" => "An identical alert already exists",
+        "expense_templates_user_id_name_key" => "Template name already exists",
+        _ => "A record with the same value already exists",
+    };
+
+    AppError::Validation(message.to_string())
+}
+
+/// Converts a `CHECK` constraint violation into a `400` validation error.
+///
+/// Request validation should reject these values first; this is the backstop
+/// for anything that gets past it, so the client sees a readable message
+/// instead of a `500`. Any other error is passed through as
+/// [`AppError::Database`].
+pub fn map_check_violation(err: sqlx::Error) -> AppError {
+    let constraint = match err.as_database_error() {
+        Some(db_err) if db_err.code().as_deref() == Some(CHECK_VIOLATION) => {
+            db_err.constraint().unwrap_or_default().to_string()
+        }
+        _ => return AppError::Database(err),
+    };
+
+    let message = match constraint.as_str() {
+        "expenses_amount_check" => "Amount must be greater than 0",
+        _ => "A value is outside its allowed range",
+    };
+
+    AppError::Validation(message.to_string())
+}
@@ -0,0 +1,146 @@
+//! Live notifications of expense changes for open dashboards.
+//!
+//! Handlers that change expenses publish an [`ExpenseEvent`] after their
+//! transaction commits, and `GET /api/events` streams a user's events to them
+//! as server-sent events. Each user gets their own broadcast channel, created
+//! on their first subscription and dropped once nobody is listening, so one
+//! user's events never reach another.
+//!
+//! The browser's `EventSource` can't send an `Authorization` header, so a
+//! browser first trades its JWT for a stream ticket: a random string that
+//! opens one stream within [`TICKET_TTL`] and is useless afterwards. That way
+//! no long-lived credential ends up in URLs, logs or the browser history.
+//!
+//! Like the category cache, this is per process: with several instances
+//! behind a load balancer, a client only hears about changes made through the
+//! instance it is connected to, and a ticket only works on the instance that
+//! issued it.
+
+use std::{
+    collections::HashMap,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use rand::RngCore;
+use serde::Serialize;
+use tokio::sync::broadcast;
+use uuid::Uuid;
+
+/// Events a slow subscriber may fall behind by before it misses some; it is
+/// then told to reload everything instead.
+const CHANNEL_CAPACITY: usize = 64;
+
+/// How long a stream ticket can be redeemed for.
+pub const TICKET_TTL: Duration = Duration::from_secs(30);
+
+/// What happened to the user's expenses.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum ExpenseEventKind {
+    #[serde(rename = "expense.created")]
+    Created,
+    #[serde(rename = "expense.updated")]
+    Updated,
+    #[serde(rename = "expense.deleted")]
+    Deleted,
+    #[serde(rename = "expense.restored")]
+    Restored,
+    /// Several expenses changed at once, e.g. a bulk recategorize
+    #[serde(rename = "expenses.changed")]
+    Changed,
+}
+
+/// Body of one server-sent event.
+///
+/// # Example
+/// ```json
+/// {
+///   "type": "expense.created",
+///   "expense_id": "123e4567-e89b-12d3-a456-426614174000"
+/// }
+/// ```
+#[derive(Debug, Clone, Serialize)]
+pub struct ExpenseEvent {
+    #[serde(rename = "type")]
+    pub kind: ExpenseEventKind,
+    /// The expense concerned; `null` for `expenses.changed`
+    pub expense_id: Option<Uuid>,
+}
+
+impl ExpenseEvent {
+    pub fn single(kind: ExpenseEventKind, expense_id: Uuid) -> Self {
+        Self {
+            kind,
+            expense_id: Some(expense_id),
+        }
+    }
+
+    pub fn changed() -> Self {
+        Self {
+            kind: ExpenseEventKind::Changed,
+            expense_id: None,
+        }
+    }
+}
+
+/// Who a stream ticket was issued to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TicketHolder {
+    pub user_id: Uuid,
+    pub session_id: Uuid,
+}
+
+#[derive(Default)]
+pub struct EventHub {
+    channels: Mutex<HashMap<Uuid, broadcast::Sender<ExpenseEvent>>>,
+    tickets: Mutex<HashMap<String, (TicketHolder, Instant)>>,
+}
+
+impl EventHub {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Receives every event published for `user_id` from now on.
+    pub fn subscribe(&self, user_id: Uuid) -> broadcast::Receiver<ExpenseEvent> {
+        let mut channels = self.channels.lock().expect("event hub lock poisoned");
+        // Channels whose subscribers have all disconnected
+        channels.retain(|_, sender| sender.receiver_count() > 0);
+        channels
+            .entry(user_id)
+            .or_insert_with(|| broadcast::channel(CHANNEL_CAPACITY).0)
+            .subscribe()
+    }
+
+    /// A new single-use ticket for opening one stream as `holder`.
+    pub fn issue_ticket(&self, holder: TicketHolder) -> String {
+        let mut bytes = [0u8; 32];
+        rand::thread_rng().fill_bytes(&mut bytes);
+        let ticket = hex::encode(bytes);
+
+        let now = Instant::now();
+        let mut tickets = self.tickets.lock().expect("event hub lock poisoned");
+        // Tickets that were never redeemed
+        tickets.retain(|_, (_, expires_at)| *expires_at > now);
+        tickets.insert(ticket.clone(), (holder, now + TICKET_TTL));
+        ticket
+    }
+
+    /// Uses up `ticket`, returning who it was issued to unless it is unknown,
+    /// already used or expired.
+    pub fn redeem_ticket(&self, ticket: &str) -> Option<TicketHolder> {
+        let mut tickets = self.tickets.lock().expect("event hub lock poisoned");
+        let (holder, expires_at) = tickets.remove(ticket)?;
+        (expires_at > Instant::now()).then_some(holder)
+    }
+
+    /// Sends `event` to the user's open streams, if they have any.
+    pub fn publish(&self, user_id: Uuid, event: ExpenseEvent) {
+        let mut channels = self.channels.lock().expect("event hub lock poisoned");
+        if let Some(sender) = channels.get(&user_id) {
+            if sender.send(event).is_err() {
+                channels.remove(&user_id);
+            }
+        }
+    }
+}
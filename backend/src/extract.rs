@@ -0,0 +1,51 @@
+//! Extractors that reject with [`AppError`], so a malformed request gets the
+//! same `{ "error": ... }` body as every other error.
+
+use std::error::Error;
+
+use axum::{
+    extract::{rejection::JsonRejection, FromRequest},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
+use serde::Serialize;
+
+use crate::error::AppError;
+
+/// Drop-in replacement for [`axum::Json`], as both extractor and response.
+///
+/// A body that isn't valid JSON, or doesn't match the expected type, is a
+/// `400` naming the offending field, e.g. `Invalid request body:
+/// amount: invalid type: string "ten", expected a Decimal at line 1 column 16`.
+#[derive(Debug, Clone, Copy, Default, FromRequest)]
+#[from_request(via(axum::Json), rejection(AppError))]
+pub struct Json<T>(pub T);
+
+impl<T: Serialize> IntoResponse for Json<T> {
+    fn into_response(self) -> Response {
+        axum::Json(self.0).into_response()
+    }
+}
+
+impl From<JsonRejection> for AppError {
+    fn from(rejection: JsonRejection) -> Self {
+        match rejection {
+            // The source is serde's message prefixed with the field path
+            JsonRejection::JsonDataError(err) => AppError::Validation(format!(
+                "Invalid request body: {}",
+                err.source().map(ToString::to_string).unwrap_or_else(|| err.body_text())
+            )),
+            JsonRejection::JsonSyntaxError(err) => AppError::Validation(format!(
+                "Malformed JSON: {}",
+                err.source().map(ToString::to_string).unwrap_or_else(|| err.body_text())
+            )),
+            JsonRejection::MissingJsonContentType(_) => AppError::Validation(
+                "Expected request with `Content-Type: application/json`".to_string(),
+            ),
+            rejection if rejection.status() == StatusCode::PAYLOAD_TOO_LARGE => {
+                AppError::PayloadTooLarge(rejection.body_text())
+            }
+            rejection => AppError::Validation(rejection.body_text()),
+        }
+    }
+}
@@ -0,0 +1,182 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+};
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::{
+    auth::AuthUser,
+    error::{map_unique_violation, AppError, AppResult},
+    extract::Json,
+    models::{Alert, CreateAlert, UpdateAlert, ALERT_TYPES},
+    AppState,
+};
+
+#[utoipa::path(
+    get,
+    path = "/api/alerts",
+    tag = "alerts",
+    responses(
+        (status = 200, description = "All alerts of the user", body = [Alert]),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn list_alerts(
+    State(state): State<AppState>,
+    user: AuthUser,
+) -> AppResult<Json<Vec<Alert>>> {
+    let alerts = sqlx::query_as::<_, Alert>(
+        "SELECT * FROM alerts WHERE user_id = $1 ORDER BY created_at"
+    )
+    .bind(user.user_id)
+    .fetch_all(&state.pool)
+    .await?;
+
+    Ok(Json(alerts))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/alerts",
+    tag = "alerts",
+    request_body = CreateAlert,
+    responses(
+        (status = 201, description = "Alert created", body = Alert),
+        (status = 400, description = "Validation failed or an identical alert exists"),
+        (status = 404, description = "Category not found"),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn create_alert(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Json(payload): Json<CreateAlert>,
+) -> AppResult<(StatusCode, Json<Alert>)> {
+    payload.validate().map_err(|e| AppError::Validation(e.to_string()))?;
+
+    if !ALERT_TYPES.contains(&payload.alert_type.as_str()) {
+        return Err(AppError::Validation(format!(
+            "Alert type must be one of: {}",
+            ALERT_TYPES.join(", ")
+        )));
+    }
+
+    match (payload.alert_type.as_str(), payload.category_id) {
+        ("budget", None) => {
+            return Err(AppError::Validation(
+                "category_id is required for budget alerts".to_string(),
+            ));
+        }
+        ("budget", Some(category_id)) => {
+            let category_exists = sqlx::query_scalar::<_, bool>(
+                "SELECT EXISTS(SELECT 1 FROM categories WHERE id = $1 AND user_id = $2)"
+            )
+            .bind(category_id)
+            .bind(user.user_id)
+            .fetch_one(&state.pool)
+            .await?;
+
+            if !category_exists {
+                return Err(AppError::NotFound("Category not found".to_string()));
+            }
+        }
+        (_, Some(_)) => {
+            return Err(AppError::Validation(
+                "category_id is only allowed for budget alerts".to_string(),
+            ));
+        }
+        (_, None) => {}
+    }
+
+    let alert = sqlx::query_as::<_, Alert>(
+        r#"
+        INSERT INTO alerts (user_id, alert_type, category_id, threshold_percent)
+        VALUES ($1, $2, $3, $4)
+        RETURNING *
+        "#,
+    )
+    .bind(user.user_id)
+    .bind(&payload.alert_type)
+    .bind(payload.category_id)
+    .bind(payload.threshold_percent)
+    .fetch_one(&state.pool)
+    .await
+    .map_err(map_unique_violation)?;
+
+    Ok((StatusCode::CREATED, Json(alert)))
+}
+
+#[utoipa::path(
+    put,
+    path = "/api/alerts/{id}",
+    tag = "alerts",
+    params(("id" = Uuid, Path, description = "Alert ID")),
+    request_body = UpdateAlert,
+    responses(
+        (status = 200, description = "Alert updated", body = Alert),
+        (status = 400, description = "Validation failed or an identical alert exists"),
+        (status = 404, description = "Alert not found"),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn update_alert(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path(id): Path<Uuid>,
+    Json(payload): Json<UpdateAlert>,
+) -> AppResult<Json<Alert>> {
+    payload.validate().map_err(|e| AppError::Validation(e.to_string()))?;
+
+    // A new threshold may not have been reached yet this month, so it can fire again
+    let alert = sqlx::query_as::<_, Alert>(
+        r#"
+        UPDATE alerts
+        SET threshold_percent = $3,
+            last_triggered_at = CASE
+                WHEN threshold_percent = $3 THEN last_triggered_at
+                ELSE NULL
+            END
+        WHERE id = $1 AND user_id = $2
+        RETURNING *
+        "#,
+    )
+    .bind(id)
+    .bind(user.user_id)
+    .bind(payload.threshold_percent)
+    .fetch_optional(&state.pool)
+    .await
+    .map_err(map_unique_violation)?
+    .ok_or_else(|| AppError::NotFound("Alert not found".to_string()))?;
+
+    Ok(Json(alert))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/alerts/{id}",
+    tag = "alerts",
+    params(("id" = Uuid, Path, description = "Alert ID")),
+    responses(
+        (status = 204, description = "Alert removed"),
+        (status = 404, description = "Alert not found"),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn delete_alert(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path(id): Path<Uuid>,
+) -> AppResult<StatusCode> {
+    let result = sqlx::query("DELETE FROM alerts WHERE id = $1 AND user_id = $2")
+        .bind(id)
+        .bind(user.user_id)
+        .execute(&state.pool)
+        .await?;
+
+    if result.rows_affected() == 0 {
+        return Err(AppError::NotFound("Alert not found".to_string()));
+    }
+
+    Ok(StatusCode::NO_CONTENT)
+}
@@ -0,0 +1,210 @@
+use axum::{
+    extract::{multipart::MultipartError, Multipart, Path, State},
+    http::{header, StatusCode},
+    response::IntoResponse,
+};
+use uuid::Uuid;
+
+use crate::{
+    auth::AuthUser,
+    error::{AppError, AppResult},
+    extract::Json,
+    models::Attachment,
+    storage, AppState,
+};
+
+const ALLOWED_CONTENT_TYPES: &[&str] = &["image/jpeg", "image/png", "application/pdf"];
+
+/// Extra room on top of the file size limit for multipart boundaries and headers.
+pub const MULTIPART_OVERHEAD_BYTES: usize = 16 * 1024;
+
+/// Longest filename `attachments.filename` (`VARCHAR(255)`) can hold, in characters.
+const MAX_FILENAME_CHARS: usize = 255;
+
+pub async fn upload_attachment(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path(expense_id): Path<Uuid>,
+    mut multipart: Multipart,
+) -> AppResult<(StatusCode, Json<Attachment>)> {
+    let expense_exists = sqlx::query_scalar::<_, bool>(
+        "SELECT EXISTS(SELECT 1 FROM expenses WHERE id = $1 AND user_id = $2 AND deleted_at IS NULL)"
+    )
+    .bind(expense_id)
+    .bind(user.user_id)
+    .fetch_one(&state.pool)
+    .await?;
+
+    if !expense_exists {
+        return Err(AppError::NotFound("Expense not found".to_string()));
+    }
+
+    let mut field = multipart
+        .next_field()
+        .await
+        .map_err(multipart_error)?
+        .ok_or_else(|| AppError::Validation("No file provided".to_string()))?;
+
+    let filename = field
+        .file_name()
+        .filter(|name| !name.is_empty())
+        .unwrap_or("attachment")
+        .to_string();
+    if filename.chars().count() > MAX_FILENAME_CHARS {
+        return Err(AppError::Validation(format!(
+            "Filename can be at most {} characters",
+            MAX_FILENAME_CHARS
+        )));
+    }
+
+    let content_type = field.content_type().unwrap_or_default().to_string();
+    if !ALLOWED_CONTENT_TYPES.contains(&content_type.as_str()) {
+        return Err(AppError::Validation(format!(
+            "Unsupported file type; allowed types are {}",
+            ALLOWED_CONTENT_TYPES.join(", ")
+        )));
+    }
+
+    let max_bytes = state.config.max_attachment_bytes;
+    let mut data = Vec::new();
+    while let Some(chunk) = field.chunk().await.map_err(multipart_error)? {
+        if data.len() + chunk.len() > max_bytes {
+            return Err(AppError::PayloadTooLarge(format!(
+                "Attachment exceeds the maximum size of {} bytes",
+                max_bytes
+            )));
+        }
+        data.extend_from_slice(&chunk);
+    }
+
+    if data.is_empty() {
+        return Err(AppError::Validation("Attachment is empty".to_string()));
+    }
+
+    let storage_key = format!("{}/{}", user.user_id, Uuid::new_v4());
+
+    // The row is only committed once the file is stored, so a failed write
+    // leaves no attachment pointing at a missing file and vice versa
+    let mut tx = state.pool.begin().await?;
+
+    let attachment = sqlx::query_as::<_, Attachment>(
+        r#"
+        INSERT INTO attachments (expense_id, filename, content_type, size, storage_key)
+        VALUES ($1, $2, $3, $4, $5)
+        RETURNING *
+        "#,
+    )
+    .bind(expense_id)
+    .bind(&filename)
+    .bind(&content_type)
+    .bind(data.len() as i64)
+    .bind(&storage_key)
+    .fetch_one(&mut *tx)
+    .await?;
+
+    state.storage.put(&storage_key, &data).await?;
+    if let Err(e) = tx.commit().await {
+        storage::delete_all(state.storage.as_ref(), &[storage_key]).await;
+        return Err(e.into());
+    }
+
+    Ok((StatusCode::CREATED, Json(attachment)))
+}
+
+pub async fn download_attachment(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path((expense_id, attachment_id)): Path<(Uuid, Uuid)>,
+) -> AppResult<impl IntoResponse> {
+    let attachment = sqlx::query_as::<_, Attachment>(
+        r#"
+        SELECT attachments.*
+        FROM attachments
+        JOIN expenses ON attachments.expense_id = expenses.id
+        WHERE attachments.id = $1 AND attachments.expense_id = $2 AND expenses.user_id = $3
+          AND expenses.deleted_at IS NULL
+        "#,
+    )
+    .bind(attachment_id)
+    .bind(expense_id)
+    .bind(user.user_id)
+    .fetch_optional(&state.pool)
+    .await?
+    .ok_or_else(|| AppError::NotFound("Attachment not found".to_string()))?;
+
+    let data = state.storage.get(&attachment.storage_key).await?;
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, attachment.content_type),
+            (header::CONTENT_DISPOSITION, content_disposition(&attachment.filename)),
+        ],
+        data,
+    ))
+}
+
+/// `Content-Disposition` value offering `filename` for download.
+///
+/// The plain `filename` parameter gets an ASCII-only copy with quotes,
+/// backslashes and control characters replaced, for old clients; the exact
+/// name goes in `filename*` as percent-encoded UTF-8 (RFC 6266).
+fn content_disposition(filename: &str) -> String {
+    let ascii: String = filename
+        .chars()
+        .map(|c| match c {
+            '"' | '\\' => '_',
+            c if c.is_ascii() && !c.is_ascii_control() => c,
+            _ => '_',
+        })
+        .collect();
+
+    let mut encoded = String::new();
+    for byte in filename.bytes() {
+        // attr-char from RFC 5987; everything else is escaped
+        if byte.is_ascii_alphanumeric() || b"!#$&+-.^_`|~".contains(&byte) {
+            encoded.push(byte as char);
+        } else {
+            encoded.push_str(&format!("%{:02X}", byte));
+        }
+    }
+
+    format!("attachment; filename=\"{}\"; filename*=UTF-8''{}", ascii, encoded)
+}
+
+fn multipart_error(e: MultipartError) -> AppError {
+    if e.status() == StatusCode::PAYLOAD_TOO_LARGE {
+        AppError::PayloadTooLarge("Attachment is too large".to_string())
+    } else {
+        AppError::Validation(format!("Invalid multipart body: {}", e.body_text()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn plain_names_are_kept() {
+        assert_eq!(
+            content_disposition("receipt.pdf"),
+            "attachment; filename=\"receipt.pdf\"; filename*=UTF-8''receipt.pdf"
+        );
+    }
+
+    #[test]
+    fn non_ascii_names_are_percent_encoded() {
+        assert_eq!(
+            content_disposition("Quittung Müller.png"),
+            "attachment; filename=\"Quittung M_ller.png\"; \
+             filename*=UTF-8''Quittung%20M%C3%BCller.png"
+        );
+    }
+
+    #[test]
+    fn quotes_and_control_characters_cannot_break_the_header() {
+        let value = content_disposition("a\"b\\c\r\nSet-Cookie: x=1.png");
+        assert!(value.starts_with("attachment; filename=\"a_b_c__Set-Cookie: x=1.png\"; "));
+        assert!(value.ends_with("filename*=UTF-8''a%22b%5Cc%0D%0ASet-Cookie%3A%20x%3D1.png"));
+        assert!(header::HeaderValue::from_str(&value).is_ok());
+    }
+}
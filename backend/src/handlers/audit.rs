@@ -0,0 +1,130 @@
+use axum::{
+    extract::{Query, State},
+    http::{header, HeaderMap},
+};
+use sqlx::PgExecutor;
+use std::net::IpAddr;
+use uuid::Uuid;
+
+use crate::{
+    auth::AuthUser,
+    error::{AppError, AppResult},
+    extract::Json,
+    models::{AuditEvent, AuditLogQuery, PaginatedAuditEvents},
+    AppState,
+};
+
+/// Kinds of security events written to `audit_log`.
+#[derive(Debug, Clone, Copy)]
+pub enum AuditEventType {
+    Login,
+    LoginFailed,
+    SessionRevoked,
+    OtherSessionsRevoked,
+    EmailChanged,
+}
+
+impl AuditEventType {
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Login => "login",
+            Self::LoginFailed => "login_failed",
+            Self::SessionRevoked => "session_revoked",
+            Self::OtherSessionsRevoked => "other_sessions_revoked",
+            Self::EmailChanged => "email_changed",
+        }
+    }
+}
+
+/// Appends a security event to the audit log.
+///
+/// `user_id` is `None` for a failed login whose email matches no account;
+/// `email` is the address that was tried for a failed login, or the previous
+/// address for an email change. The client address (see
+/// [`ClientIp`](crate::client_ip::ClientIp)) and User-Agent are recorded as
+/// for sessions. Pass a transaction to record the event together with the
+/// change it describes.
+pub async fn record_audit_event(
+    executor: impl PgExecutor<'_>,
+    user_id: Option<Uuid>,
+    event_type: AuditEventType,
+    email: Option<&str>,
+    headers: &HeaderMap,
+    ip: IpAddr,
+) -> AppResult<()> {
+    let user_agent = headers.get(header::USER_AGENT).and_then(|v| v.to_str().ok());
+
+    sqlx::query(
+        r#"
+        INSERT INTO audit_log (user_id, event_type, email, ip_address, user_agent)
+        VALUES ($1, $2, $3, $4, $5)
+        "#,
+    )
+    .bind(user_id)
+    .bind(event_type.as_str())
+    .bind(email)
+    .bind(ip.to_string())
+    .bind(user_agent)
+    .execute(executor)
+    .await?;
+
+    Ok(())
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/users/me/audit",
+    tag = "users",
+    params(AuditLogQuery),
+    responses(
+        (status = 200, description = "Security events for the current user, newest first", body = PaginatedAuditEvents),
+        (status = 400, description = "Invalid limit or offset"),
+        (status = 401, description = "Missing or invalid token"),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn list_audit_events(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Query(query): Query<AuditLogQuery>,
+) -> AppResult<Json<PaginatedAuditEvents>> {
+    let limit = query.limit.unwrap_or(state.config.default_page_size);
+    if !(1..=state.config.max_page_size).contains(&limit) {
+        return Err(AppError::Validation(format!(
+            "limit must be between 1 and {}",
+            state.config.max_page_size
+        )));
+    }
+
+    let offset = query.offset.unwrap_or(0);
+    if offset < 0 {
+        return Err(AppError::Validation("offset must not be negative".to_string()));
+    }
+
+    let items = sqlx::query_as::<_, AuditEvent>(
+        r#"
+        SELECT id, event_type, email, ip_address, user_agent, created_at
+        FROM audit_log
+        WHERE user_id = $1
+        ORDER BY created_at DESC, id
+        LIMIT $2 OFFSET $3
+        "#,
+    )
+    .bind(user.user_id)
+    .bind(limit)
+    .bind(offset)
+    .fetch_all(&state.pool)
+    .await?;
+
+    let total = sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM audit_log WHERE user_id = $1")
+        .bind(user.user_id)
+        .fetch_one(&state.pool)
+        .await?;
+
+    Ok(Json(PaginatedAuditEvents {
+        items,
+        total,
+        limit,
+        offset,
+    }))
+}
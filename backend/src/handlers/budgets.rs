@@ -0,0 +1,191 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+};
+use chrono::{Datelike, NaiveDate};
+use rust_decimal::Decimal;
+use std::collections::HashMap;
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::{
+    auth::AuthUser,
+    error::{AppError, AppResult},
+    extract::Json,
+    handlers::{settings::user_today, summaries::days_in_month},
+    models::{Budget, BurndownDay, SetBudget},
+    AppState,
+};
+
+#[utoipa::path(
+    get,
+    path = "/api/budgets",
+    tag = "budgets",
+    responses(
+        (status = 200, description = "All budgets of the user", body = [Budget]),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn list_budgets(
+    State(state): State<AppState>,
+    user: AuthUser,
+) -> AppResult<Json<Vec<Budget>>> {
+    let budgets = sqlx::query_as::<_, Budget>(
+        "SELECT * FROM budgets WHERE user_id = $1 ORDER BY created_at"
+    )
+    .bind(user.user_id)
+    .fetch_all(&state.pool)
+    .await?;
+
+    Ok(Json(budgets))
+}
+
+#[utoipa::path(
+    put,
+    path = "/api/budgets/{category_id}",
+    tag = "budgets",
+    params(("category_id" = Uuid, Path, description = "Category ID")),
+    request_body = SetBudget,
+    responses(
+        (status = 200, description = "Budget created or updated", body = Budget),
+        (status = 400, description = "Validation failed"),
+        (status = 404, description = "Category not found"),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn set_budget(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path(category_id): Path<Uuid>,
+    Json(payload): Json<SetBudget>,
+) -> AppResult<Json<Budget>> {
+    payload.validate().map_err(|e| AppError::Validation(e.to_string()))?;
+
+    let category_exists = sqlx::query_scalar::<_, bool>(
+        "SELECT EXISTS(SELECT 1 FROM categories WHERE id = $1 AND user_id = $2)"
+    )
+    .bind(category_id)
+    .bind(user.user_id)
+    .fetch_one(&state.pool)
+    .await?;
+
+    if !category_exists {
+        return Err(AppError::NotFound("Category not found".to_string()));
+    }
+
+    let amount = Decimal::try_from(payload.amount)
+        .map_err(|_| AppError::Validation("Invalid amount".to_string()))?;
+
+    let budget = sqlx::query_as::<_, Budget>(
+        r#"
+        INSERT INTO budgets (user_id, category_id, amount)
+        VALUES ($1, $2, $3)
+        ON CONFLICT (category_id)
+        DO UPDATE SET amount = EXCLUDED.amount, updated_at = NOW()
+        RETURNING *
+        "#,
+    )
+    .bind(user.user_id)
+    .bind(category_id)
+    .bind(amount)
+    .fetch_one(&state.pool)
+    .await?;
+
+    Ok(Json(budget))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/budgets/{category_id}",
+    tag = "budgets",
+    params(("category_id" = Uuid, Path, description = "Category ID")),
+    responses(
+        (status = 204, description = "Budget removed"),
+        (status = 404, description = "Budget not found"),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn delete_budget(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path(category_id): Path<Uuid>,
+) -> AppResult<StatusCode> {
+    let result = sqlx::query("DELETE FROM budgets WHERE category_id = $1 AND user_id = $2")
+        .bind(category_id)
+        .bind(user.user_id)
+        .execute(&state.pool)
+        .await?;
+
+    if result.rows_affected() == 0 {
+        return Err(AppError::NotFound("Budget not found".to_string()));
+    }
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/budgets/{category_id}/burndown",
+    tag = "budgets",
+    params(("category_id" = Uuid, Path, description = "Category ID")),
+    responses(
+        (status = 200, description = "Cumulative spend against the ideal pace for each day of the month so far", body = [BurndownDay]),
+        (status = 404, description = "Budget not found"),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn get_budget_burndown(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path(category_id): Path<Uuid>,
+) -> AppResult<Json<Vec<BurndownDay>>> {
+    let budget = sqlx::query_scalar::<_, Decimal>(
+        "SELECT amount FROM budgets WHERE category_id = $1 AND user_id = $2"
+    )
+    .bind(category_id)
+    .bind(user.user_id)
+    .fetch_optional(&state.pool)
+    .await?
+    .ok_or_else(|| AppError::NotFound("Budget not found".to_string()))?;
+
+    let today = user_today(&state.pool, user.user_id).await?;
+    let start_of_month = NaiveDate::from_ymd_opt(today.year(), today.month(), 1)
+        .expect("Valid date");
+
+    // Allocations, so a split expense only counts its part in this category
+    let daily: HashMap<NaiveDate, Decimal> = sqlx::query_as::<_, (NaiveDate, Decimal)>(
+        r#"
+        SELECT expense_date, SUM(amount)
+        FROM expense_allocations
+        WHERE user_id = $1 AND category_id = $2 AND deleted_at IS NULL
+            AND expense_date >= $3 AND expense_date <= $4
+        GROUP BY expense_date
+        "#,
+    )
+    .bind(user.user_id)
+    .bind(category_id)
+    .bind(start_of_month)
+    .bind(today)
+    .fetch_all(&state.pool)
+    .await?
+    .into_iter()
+    .collect();
+
+    let days_in_month = Decimal::from(days_in_month(today.year(), today.month()));
+    // Scale 2 so days before the first expense read "0.00" like the rest
+    let mut cumulative_spent = Decimal::new(0, 2);
+    let burndown = start_of_month
+        .iter_days()
+        .take_while(|day| *day <= today)
+        .map(|day| {
+            cumulative_spent += daily.get(&day).copied().unwrap_or_default();
+            BurndownDay {
+                day,
+                cumulative_spent,
+                ideal_pace: (budget * Decimal::from(day.day()) / days_in_month).round_dp(2),
+            }
+        })
+        .collect();
+
+    Ok(Json(burndown))
+}
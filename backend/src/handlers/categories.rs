@@ -1,70 +1,348 @@
 use axum::{
-    extract::{Path, State},
+    extract::{Path, Query, State},
     http::StatusCode,
-    Json,
 };
+use chrono::{Datelike, Duration, NaiveDate};
+use rust_decimal::Decimal;
+use sqlx::{FromRow, Postgres, QueryBuilder, Row, Transaction};
+use std::collections::{HashMap, HashSet};
 use uuid::Uuid;
 use validator::Validate;
 
 use crate::{
     auth::AuthUser,
-    error::{AppError, AppResult},
-    models::{Category, CreateCategory, UpdateCategory},
+    color::{contrast_ratio, relative_luminance, text_color_for, WHITE},
+    error::{map_unique_violation, AppError, AppResult},
+    extract::Json,
+    handlers::{
+        check_batch_size,
+        events::publish_expenses_changed,
+        settings::{user_sort_preferences, user_today},
+    },
+    models::{
+        Category, CategoryContrast, CategoryHistoryQuery, CategoryListQuery,
+        CategoryMonthlyTotal, CategorySort, CategoryTemplate, CreateCategory, CreateCategoryQuery,
+        ImportCategoriesResult, MergeCategory, PaginatedCategories, PaletteQuery,
+        RecentCategoriesQuery, ReorderCategories, SuggestCategoriesQuery, UpdateCategory,
+    },
     AppState,
 };
 
+/// Number of categories returned by the suggest endpoint.
+const SUGGESTION_LIMIT: i64 = 5;
+
+/// Minimum `pg_trgm` similarity for a category to be suggested.
+const MIN_SIMILARITY: f32 = 0.1;
+
+/// Postgres error code for a call to a function that doesn't exist.
+const UNDEFINED_FUNCTION: &str = "42883";
+
+/// Suggested category colors: mid-tone hues that stay distinct from each other
+/// and readable behind white text.
+const PALETTE: &[&str] = &[
+    "#4E79A7", "#F28E2B", "#E15759", "#76B7B2", "#59A14F", "#EDC948",
+    "#B07AA1", "#FF9DA7", "#9C755F", "#5F9ED1", "#C85200", "#6F63B5",
+];
+
+#[utoipa::path(
+    post,
+    path = "/api/categories",
+    tag = "categories",
+    params(CreateCategoryQuery),
+    request_body = CreateCategory,
+    responses(
+        (status = 201, description = "Category created", body = Category),
+        (status = 200, description = "Upsert: a category with this name already existed and is returned unchanged", body = Category),
+        (status = 400, description = "Validation failed, name already exists or category limit reached"),
+    ),
+    security(("bearer_auth" = []))
+)]
 pub async fn create_category(
     State(state): State<AppState>,
     user: AuthUser,
-    Json(payload): Json<CreateCategory>,
+    Query(query): Query<CreateCategoryQuery>,
+    Json(mut payload): Json<CreateCategory>,
 ) -> AppResult<(StatusCode, Json<Category>)> {
+    payload.trim();
     payload.validate().map_err(|e| AppError::Validation(e.to_string()))?;
 
-    let name_exists = sqlx::query_scalar::<_, bool>(
-        "SELECT EXISTS(SELECT 1 FROM categories WHERE user_id = $1 AND name = $2)"
-    )
-    .bind(user.user_id)
-    .bind(&payload.name)
-    .fetch_one(&state.pool)
-    .await?;
+    // With upsert, a name clash becomes a no-op update so RETURNING still
+    // yields the existing row; xmax is 0 only for a freshly inserted row.
+    let on_conflict = if query.upsert.unwrap_or(false) {
+        "ON CONFLICT (user_id, name) DO UPDATE SET name = EXCLUDED.name"
+    } else {
+        ""
+    };
 
-    if name_exists {
-        return Err(AppError::Validation(
-            "Category name already exists".to_string(),
-        ));
-    }
+    let mut tx = state.pool.begin().await?;
 
-    let category = sqlx::query_as::<_, Category>(
+    let row = sqlx::query(&format!(
         r#"
-        INSERT INTO categories (user_id, name, color, icon)
-        VALUES ($1, $2, $3, $4)
-        RETURNING *
+        INSERT INTO categories (user_id, name, color, icon, sort_order)
+        VALUES (
+            $1, $2, $3, $4,
+            COALESCE($5, (SELECT COALESCE(MAX(sort_order) + 1, 0) FROM categories WHERE user_id = $1))
+        )
+        {on_conflict}
+        RETURNING *, (xmax = 0) AS inserted
         "#,
-    )
+    ))
     .bind(user.user_id)
     .bind(&payload.name)
     .bind(&payload.color)
     .bind(&payload.icon)
-    .fetch_one(&state.pool)
-    .await?;
+    .bind(payload.sort_order)
+    .fetch_one(&mut *tx)
+    .await
+    .map_err(map_unique_violation)?;
 
-    Ok((StatusCode::CREATED, Json(category)))
+    let category = Category::from_row(&row)?;
+    let inserted: bool = row.try_get("inserted")?;
+    // Checked after the insert so an upsert hitting an existing name still
+    // succeeds at the limit; returning early rolls the insert back.
+    if inserted {
+        check_category_limit(&state, &mut tx, user.user_id).await?;
+    }
+    tx.commit().await?;
+    if inserted {
+        invalidate_cache(&state, user.user_id);
+    }
+    let status = if inserted { StatusCode::CREATED } else { StatusCode::OK };
+
+    Ok((status, Json(category)))
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/categories",
+    tag = "categories",
+    params(CategoryListQuery),
+    responses(
+        (status = 200, description = "The user's categories matching the search, one page of them when `limit` is given", body = PaginatedCategories),
+        (status = 400, description = "Unknown sort, invalid limit or offset"),
+    ),
+    security(("bearer_auth" = []))
+)]
 pub async fn list_categories(
     State(state): State<AppState>,
     user: AuthUser,
-) -> AppResult<Json<Vec<Category>>> {
+    Query(query): Query<CategoryListQuery>,
+) -> AppResult<Json<PaginatedCategories>> {
+    if let Some(limit) = query.limit {
+        if !(1..=state.config.max_page_size).contains(&limit) {
+            return Err(AppError::Validation(format!(
+                "limit must be between 1 and {}",
+                state.config.max_page_size
+            )));
+        }
+    }
+
+    let offset = query.offset.unwrap_or(0);
+    if offset < 0 {
+        return Err(AppError::Validation("offset must not be negative".to_string()));
+    }
+
+    let sort = match query.sort {
+        Some(sort) => sort,
+        None => user_sort_preferences(&state.pool, user.user_id).await?.category_sort,
+    };
+    let search = query.search.as_deref().map(str::trim).unwrap_or_default();
+
+    let mut page_query = QueryBuilder::<Postgres>::new(
+        "SELECT categories.*, COUNT(*) OVER() AS total FROM categories",
+    );
+    if matches!(sort, CategorySort::Usage) {
+        page_query
+            .push(
+                r#"
+                LEFT JOIN (
+                    SELECT category_id, COUNT(*) AS uses
+                    FROM expense_allocations
+                    WHERE user_id = "#,
+            )
+            .push_bind(user.user_id)
+            .push(
+                r#" AND deleted_at IS NULL
+                    GROUP BY category_id
+                ) AS usage ON usage.category_id = categories.id"#,
+            );
+    }
+    push_category_filters(&mut page_query, user.user_id, search);
+
+    // Ties keep the manual order
+    page_query.push(match sort {
+        CategorySort::Manual => " ORDER BY categories.sort_order, categories.name",
+        CategorySort::Name => {
+            " ORDER BY LOWER(categories.name), categories.sort_order, categories.name"
+        }
+        CategorySort::Usage => {
+            " ORDER BY COALESCE(usage.uses, 0) DESC, categories.sort_order, categories.name"
+        }
+    });
+    if let Some(limit) = query.limit {
+        page_query.push(" LIMIT ").push_bind(limit);
+    }
+    page_query.push(" OFFSET ").push_bind(offset);
+
+    let rows = page_query.build_query_as::<CategoryRow>().fetch_all(&state.pool).await?;
+    let total = match rows.first() {
+        Some(row) => row.total,
+        // Past the last match the window count has no row to ride on
+        None if offset > 0 => {
+            let mut count_query =
+                QueryBuilder::<Postgres>::new("SELECT COUNT(*) FROM categories");
+            push_category_filters(&mut count_query, user.user_id, search);
+            count_query.build_query_scalar::<i64>().fetch_one(&state.pool).await?
+        }
+        None => 0,
+    };
+
+    let items: Vec<Category> = rows.into_iter().map(|row| row.category).collect();
+    // Without a limit every match is on this one page
+    let limit = query.limit.unwrap_or(items.len().max(1) as i64);
+
+    Ok(Json(PaginatedCategories::new(items, total, limit, offset)))
+}
+
+/// A category plus the number of categories matching the list query.
+#[derive(FromRow)]
+struct CategoryRow {
+    #[sqlx(flatten)]
+    category: Category,
+    total: i64,
+}
+
+/// Restricts a category query to the user's categories whose name contains
+/// `search`, ignoring case. An empty `search` matches every name.
+fn push_category_filters(builder: &mut QueryBuilder<'_, Postgres>, user_id: Uuid, search: &str) {
+    builder.push(" WHERE categories.user_id = ").push_bind(user_id);
+    if !search.is_empty() {
+        builder
+            .push(" AND categories.name ILIKE ")
+            .push_bind(format!("%{}%", escape_like(search)));
+    }
+}
+
+/// The user's categories in `sort` order, or their `category_sort` setting
+/// when no order is given. Used by the dashboard, which reads them from the
+/// category cache.
+pub async fn fetch_sorted_categories(
+    state: &AppState,
+    user_id: Uuid,
+    sort: Option<CategorySort>,
+) -> AppResult<Vec<Category>> {
+    let sort = match sort {
+        Some(sort) => sort,
+        None => user_sort_preferences(&state.pool, user_id).await?.category_sort,
+    };
+    let mut categories = fetch_categories(state, user_id).await?;
+
+    // Both sorts are stable, so ties keep the manual order
+    match sort {
+        CategorySort::Manual => {}
+        CategorySort::Name => {
+            categories.sort_by_cached_key(|category| category.name.to_lowercase());
+        }
+        CategorySort::Usage => {
+            let usage: HashMap<Uuid, i64> = sqlx::query_as::<_, (Uuid, i64)>(
+                r#"
+                SELECT category_id, COUNT(*)
+                FROM expense_allocations
+                WHERE user_id = $1 AND deleted_at IS NULL
+                GROUP BY category_id
+                "#,
+            )
+            .bind(user_id)
+            .fetch_all(&state.pool)
+            .await?
+            .into_iter()
+            .collect();
+
+            categories.sort_by_key(|category| {
+                std::cmp::Reverse(usage.get(&category.id).copied().unwrap_or(0))
+            });
+        }
+    }
+
+    Ok(categories)
+}
+
+/// All of the user's categories in their manual order, ties alphabetically.
+/// Served from the category cache when it is enabled.
+pub async fn fetch_categories(state: &AppState, user_id: Uuid) -> AppResult<Vec<Category>> {
+    let generation = match &state.category_cache {
+        Some(cache) => match cache.get(user_id) {
+            Ok(categories) => return Ok(categories),
+            Err(generation) => Some(generation),
+        },
+        None => None,
+    };
+
     let categories = sqlx::query_as::<_, Category>(
-        "SELECT * FROM categories WHERE user_id = $1 ORDER BY name"
+        "SELECT * FROM categories WHERE user_id = $1 ORDER BY sort_order, name"
     )
-    .bind(user.user_id)
+    .bind(user_id)
     .fetch_all(&state.pool)
     .await?;
 
-    Ok(Json(categories))
+    if let (Some(cache), Some(generation)) = (&state.category_cache, generation) {
+        cache.insert(user_id, generation, categories.clone());
+    }
+
+    Ok(categories)
 }
 
+/// Fails once the user has more active categories than `MAX_CATEGORIES_PER_USER`.
+///
+/// Call inside the transaction that added the category, after adding it. The
+/// user row lock makes concurrent additions count one after the other.
+async fn check_category_limit(
+    state: &AppState,
+    tx: &mut Transaction<'_, Postgres>,
+    user_id: Uuid,
+) -> AppResult<()> {
+    sqlx::query("SELECT id FROM users WHERE id = $1 FOR UPDATE")
+        .bind(user_id)
+        .execute(&mut **tx)
+        .await?;
+
+    let active = sqlx::query_scalar::<_, i64>(
+        "SELECT COUNT(*) FROM categories WHERE user_id = $1 AND NOT archived"
+    )
+    .bind(user_id)
+    .fetch_one(&mut **tx)
+    .await?;
+
+    let max = state.config.max_categories_per_user;
+    if active > max {
+        return Err(AppError::Validation(format!(
+            "Category limit reached: at most {} active categories; archive or delete one first",
+            max
+        )));
+    }
+
+    Ok(())
+}
+
+/// Drops the user's cached category list; call after any change to their
+/// categories has been committed.
+fn invalidate_cache(state: &AppState, user_id: Uuid) {
+    if let Some(cache) = &state.category_cache {
+        cache.invalidate(user_id);
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/categories/{id}",
+    tag = "categories",
+    params(("id" = Uuid, Path, description = "Category ID")),
+    responses(
+        (status = 200, description = "Category found", body = Category),
+        (status = 404, description = "Category not found"),
+    ),
+    security(("bearer_auth" = []))
+)]
 pub async fn get_category(
     State(state): State<AppState>,
     user: AuthUser,
@@ -82,84 +360,93 @@ pub async fn get_category(
     Ok(Json(category))
 }
 
+#[utoipa::path(
+    put,
+    path = "/api/categories/{id}",
+    tag = "categories",
+    params(("id" = Uuid, Path, description = "Category ID")),
+    request_body = UpdateCategory,
+    responses(
+        (status = 200, description = "Category updated", body = Category),
+        (status = 400, description = "Validation failed or name already exists"),
+        (status = 404, description = "Category not found"),
+    ),
+    security(("bearer_auth" = []))
+)]
 pub async fn update_category(
     State(state): State<AppState>,
     user: AuthUser,
     Path(id): Path<Uuid>,
-    Json(payload): Json<UpdateCategory>,
+    Json(mut payload): Json<UpdateCategory>,
 ) -> AppResult<Json<Category>> {
+    payload.trim();
     payload.validate().map_err(|e| AppError::Validation(e.to_string()))?;
 
-    let category_exists = sqlx::query_scalar::<_, bool>(
-        "SELECT EXISTS(SELECT 1 FROM categories WHERE id = $1 AND user_id = $2)"
-    )
-    .bind(id)
-    .bind(user.user_id)
-    .fetch_one(&state.pool)
-    .await?;
-
-    if !category_exists {
-        return Err(AppError::NotFound("Category not found".to_string()));
-    }
-
-    if let Some(ref name) = payload.name {
-        let name_exists = sqlx::query_scalar::<_, bool>(
-            "SELECT EXISTS(SELECT 1 FROM categories WHERE user_id = $1 AND name = $2 AND id != $3)"
-        )
-        .bind(user.user_id)
-        .bind(name)
-        .bind(id)
-        .fetch_one(&state.pool)
-        .await?;
-
-        if name_exists {
-            return Err(AppError::Validation(
-                "Category name already exists".to_string(),
-            ));
-        }
+    if payload.name.is_none()
+        && payload.color.is_none()
+        && payload.icon.is_none()
+        && payload.sort_order.is_none()
+    {
+        return Err(AppError::Validation("No fields to update".to_string()));
     }
 
-    let mut sql = String::from("UPDATE categories SET ");
-    let mut updates = Vec::new();
+    let mut query = QueryBuilder::<Postgres>::new("UPDATE categories SET ");
+    let mut set = query.separated(", ");
 
     if let Some(name) = &payload.name {
-        updates.push(format!("name = '{}'", name.replace("'", "''")));
+        set.push("name = ").push_bind_unseparated(name);
     }
 
+    // `Some(None)` is an explicit null in the request and clears the column
     if let Some(color) = &payload.color {
-        updates.push(format!("color = '{}'", color.replace("'", "''")));
+        set.push("color = ").push_bind_unseparated(color);
     }
 
     if let Some(icon) = &payload.icon {
-        updates.push(format!("icon = '{}'", icon.replace("'", "''")));
+        set.push("icon = ").push_bind_unseparated(icon);
     }
 
-    if updates.is_empty() {
-        return Err(AppError::Validation("No fields to update".to_string()));
+    if let Some(sort_order) = payload.sort_order {
+        set.push("sort_order = ").push_bind_unseparated(sort_order);
     }
 
-    sql.push_str(&updates.join(", "));
-    sql.push_str(&format!(" WHERE id = '{}' AND user_id = '{}'", id, user.user_id));
+    query.push(" WHERE id = ").push_bind(id);
+    query.push(" AND user_id = ").push_bind(user.user_id);
+    query.push(" RETURNING *");
 
-    sqlx::query(&sql).execute(&state.pool).await?;
+    let category = query
+        .build_query_as::<Category>()
+        .fetch_optional(&state.pool)
+        .await
+        .map_err(map_unique_violation)?
+        .ok_or_else(|| AppError::NotFound("Category not found".to_string()))?;
+    invalidate_cache(&state, user.user_id);
 
-    let updated_category = sqlx::query_as::<_, Category>(
-        "SELECT * FROM categories WHERE id = $1"
-    )
-    .bind(id)
-    .fetch_one(&state.pool)
-    .await?;
-
-    Ok(Json(updated_category))
+    Ok(Json(category))
 }
 
+#[utoipa::path(
+    delete,
+    path = "/api/categories/{id}",
+    tag = "categories",
+    params(("id" = Uuid, Path, description = "Category ID")),
+    responses(
+        (status = 204, description = "Category deleted"),
+        (status = 400, description = "Category still has expenses or templates"),
+        (status = 404, description = "Category not found"),
+    ),
+    security(("bearer_auth" = []))
+)]
 pub async fn delete_category(
     State(state): State<AppState>,
     user: AuthUser,
     Path(id): Path<Uuid>,
 ) -> AppResult<StatusCode> {
     let has_expenses = sqlx::query_scalar::<_, bool>(
-        "SELECT EXISTS(SELECT 1 FROM expenses WHERE category_id = $1)"
+        r#"
+        SELECT EXISTS(SELECT 1 FROM expenses WHERE category_id = $1)
+            OR EXISTS(SELECT 1 FROM expense_splits WHERE category_id = $1)
+        "#,
     )
     .bind(id)
     .fetch_one(&state.pool)
@@ -167,7 +454,23 @@ pub async fn delete_category(
 
     if has_expenses {
         return Err(AppError::Validation(
-            "Cannot delete category with existing expenses".to_string(),
+            "Cannot delete category with existing expenses (including any in the trash); \
+             archive it instead"
+                .to_string(),
+        ));
+    }
+
+    let has_templates = sqlx::query_scalar::<_, bool>(
+        "SELECT EXISTS(SELECT 1 FROM expense_templates WHERE category_id = $1)",
+    )
+    .bind(id)
+    .fetch_one(&state.pool)
+    .await?;
+
+    if has_templates {
+        return Err(AppError::Validation(
+            "Cannot delete category used by expense templates; merge it or change the templates"
+                .to_string(),
         ));
     }
 
@@ -180,6 +483,667 @@ pub async fn delete_category(
     if result.rows_affected() == 0 {
         return Err(AppError::NotFound("Category not found".to_string()));
     }
+    invalidate_cache(&state, user.user_id);
 
     Ok(StatusCode::NO_CONTENT)
 }
+
+#[utoipa::path(
+    post,
+    path = "/api/categories/{id}/merge",
+    tag = "categories",
+    params(("id" = Uuid, Path, description = "Category to merge away")),
+    request_body = MergeCategory,
+    responses(
+        (status = 200, description = "Expenses moved; returns the kept category", body = Category),
+        (status = 400, description = "Cannot merge a category into itself"),
+        (status = 404, description = "Category not found"),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn merge_category(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path(id): Path<Uuid>,
+    Json(payload): Json<MergeCategory>,
+) -> AppResult<Json<Category>> {
+    if id == payload.into {
+        return Err(AppError::Validation(
+            "Cannot merge a category into itself".to_string(),
+        ));
+    }
+
+    let mut tx = state.pool.begin().await?;
+
+    // Locks both rows so a concurrent rename or delete can't interleave
+    let owned = sqlx::query_scalar::<_, i64>(
+        r#"
+        SELECT COUNT(*) FROM (
+            SELECT id FROM categories
+            WHERE id IN ($1, $2) AND user_id = $3
+            FOR UPDATE
+        ) owned
+        "#,
+    )
+    .bind(id)
+    .bind(payload.into)
+    .bind(user.user_id)
+    .fetch_one(&mut *tx)
+    .await?;
+
+    if owned != 2 {
+        return Err(AppError::NotFound("Category not found".to_string()));
+    }
+
+    sqlx::query("UPDATE expenses SET category_id = $1, updated_at = NOW() WHERE category_id = $2")
+        .bind(payload.into)
+        .bind(id)
+        .execute(&mut *tx)
+        .await?;
+
+    // A receipt split across both categories ends up with one combined part
+    sqlx::query(
+        r#"
+        INSERT INTO expense_splits (expense_id, category_id, amount)
+        SELECT expense_id, $1, amount FROM expense_splits WHERE category_id = $2
+        ON CONFLICT (expense_id, category_id)
+            DO UPDATE SET amount = expense_splits.amount + EXCLUDED.amount
+        "#,
+    )
+    .bind(payload.into)
+    .bind(id)
+    .execute(&mut *tx)
+    .await?;
+
+    sqlx::query("DELETE FROM expense_splits WHERE category_id = $1")
+        .bind(id)
+        .execute(&mut *tx)
+        .await?;
+
+    sqlx::query(
+        "UPDATE expense_templates SET category_id = $1, updated_at = NOW() WHERE category_id = $2",
+    )
+    .bind(payload.into)
+    .bind(id)
+    .execute(&mut *tx)
+    .await?;
+
+    // The target's own budget wins; the source's is only moved when the
+    // target has none, and is otherwise deleted with the category
+    sqlx::query(
+        r#"
+        UPDATE budgets SET category_id = $1, updated_at = NOW()
+        WHERE category_id = $2
+          AND NOT EXISTS (SELECT 1 FROM budgets WHERE category_id = $1)
+        "#,
+    )
+    .bind(payload.into)
+    .bind(id)
+    .execute(&mut *tx)
+    .await?;
+
+    // Likewise an alert the target already has at the same threshold is kept
+    sqlx::query(
+        r#"
+        UPDATE alerts SET category_id = $1
+        WHERE category_id = $2
+          AND NOT EXISTS (
+              SELECT 1 FROM alerts existing
+              WHERE existing.category_id = $1
+                AND existing.alert_type = alerts.alert_type
+                AND existing.threshold_percent = alerts.threshold_percent
+          )
+        "#,
+    )
+    .bind(payload.into)
+    .bind(id)
+    .execute(&mut *tx)
+    .await?;
+
+    sqlx::query("DELETE FROM categories WHERE id = $1")
+        .bind(id)
+        .execute(&mut *tx)
+        .await?;
+
+    let category = sqlx::query_as::<_, Category>("SELECT * FROM categories WHERE id = $1")
+        .bind(payload.into)
+        .fetch_one(&mut *tx)
+        .await?;
+
+    tx.commit().await?;
+    invalidate_cache(&state, user.user_id);
+    // The source category's expenses now belong to the target
+    publish_expenses_changed(&state, user.user_id);
+
+    Ok(Json(category))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/categories/{id}/archive",
+    tag = "categories",
+    params(("id" = Uuid, Path, description = "Category ID")),
+    responses(
+        (status = 200, description = "Category archived", body = Category),
+        (status = 404, description = "Category not found"),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn archive_category(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path(id): Path<Uuid>,
+) -> AppResult<Json<Category>> {
+    set_archived(&state, user.user_id, id, true).await.map(Json)
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/categories/{id}/unarchive",
+    tag = "categories",
+    params(("id" = Uuid, Path, description = "Category ID")),
+    responses(
+        (status = 200, description = "Category unarchived", body = Category),
+        (status = 400, description = "Category limit reached"),
+        (status = 404, description = "Category not found"),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn unarchive_category(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path(id): Path<Uuid>,
+) -> AppResult<Json<Category>> {
+    set_archived(&state, user.user_id, id, false).await.map(Json)
+}
+
+/// Shared by archive and unarchive; setting the current value again is a no-op.
+async fn set_archived(
+    state: &AppState,
+    user_id: Uuid,
+    id: Uuid,
+    archived: bool,
+) -> AppResult<Category> {
+    let mut tx = state.pool.begin().await?;
+
+    let category = sqlx::query_as::<_, Category>(
+        "UPDATE categories SET archived = $1 WHERE id = $2 AND user_id = $3 RETURNING *"
+    )
+    .bind(archived)
+    .bind(id)
+    .bind(user_id)
+    .fetch_optional(&mut *tx)
+    .await?
+    .ok_or_else(|| AppError::NotFound("Category not found".to_string()))?;
+    // Archived categories don't count, so unarchiving one must fit under the limit
+    if !archived {
+        check_category_limit(state, &mut tx, user_id).await?;
+    }
+    tx.commit().await?;
+    invalidate_cache(state, user_id);
+
+    Ok(category)
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/categories/reorder",
+    tag = "categories",
+    request_body = ReorderCategories,
+    responses(
+        (status = 200, description = "Categories in their new order", body = [Category]),
+        (status = 400, description = "ids doesn't list every category exactly once"),
+        (status = 404, description = "An id isn't one of the user's categories"),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn reorder_categories(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Json(payload): Json<ReorderCategories>,
+) -> AppResult<Json<Vec<Category>>> {
+    check_batch_size(&state, "ids", payload.ids.len())?;
+
+    let unique: HashSet<Uuid> = payload.ids.iter().copied().collect();
+    if unique.len() != payload.ids.len() {
+        return Err(AppError::Validation("ids must not contain duplicates".to_string()));
+    }
+
+    let mut tx = state.pool.begin().await?;
+
+    // Locks the user's categories so a concurrent create can't miss its position
+    let owned = sqlx::query_scalar::<_, Uuid>(
+        "SELECT id FROM categories WHERE user_id = $1 FOR UPDATE"
+    )
+    .bind(user.user_id)
+    .fetch_all(&mut *tx)
+    .await?;
+    let owned: HashSet<Uuid> = owned.into_iter().collect();
+
+    let unknown: Vec<Uuid> = payload.ids.iter().copied().filter(|id| !owned.contains(id)).collect();
+    match unknown.as_slice() {
+        [] => {}
+        [id] => return Err(AppError::NotFound(format!("Category not found: {}", id))),
+        ids => {
+            return Err(AppError::NotFound(format!(
+                "Categories not found: {}",
+                ids.iter().map(Uuid::to_string).collect::<Vec<_>>().join(", ")
+            )))
+        }
+    }
+
+    if owned.len() != payload.ids.len() {
+        return Err(AppError::Validation(
+            "ids must list every category exactly once".to_string(),
+        ));
+    }
+
+    sqlx::query(
+        r#"
+        UPDATE categories
+        SET sort_order = (ordered.position - 1)::INTEGER
+        FROM UNNEST($1::UUID[]) WITH ORDINALITY AS ordered(id, position)
+        WHERE categories.id = ordered.id AND categories.user_id = $2
+        "#,
+    )
+    .bind(&payload.ids)
+    .bind(user.user_id)
+    .execute(&mut *tx)
+    .await?;
+
+    tx.commit().await?;
+    invalidate_cache(&state, user.user_id);
+
+    let categories = fetch_categories(&state, user.user_id).await?;
+
+    Ok(Json(categories))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/categories/{id}/history",
+    tag = "categories",
+    params(("id" = Uuid, Path, description = "Category ID"), CategoryHistoryQuery),
+    responses(
+        (status = 200, description = "Monthly totals for the category, oldest first", body = [CategoryMonthlyTotal]),
+        (status = 400, description = "Invalid number of months"),
+        (status = 404, description = "Category not found"),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn get_category_history(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path(id): Path<Uuid>,
+    Query(query): Query<CategoryHistoryQuery>,
+) -> AppResult<Json<Vec<CategoryMonthlyTotal>>> {
+    let months = query.months.unwrap_or(6);
+    if !(1..=60).contains(&months) {
+        return Err(AppError::Validation("months must be between 1 and 60".to_string()));
+    }
+
+    let category_exists = sqlx::query_scalar::<_, bool>(
+        "SELECT EXISTS(SELECT 1 FROM categories WHERE id = $1 AND user_id = $2)"
+    )
+    .bind(id)
+    .bind(user.user_id)
+    .fetch_one(&state.pool)
+    .await?;
+
+    if !category_exists {
+        return Err(AppError::NotFound("Category not found".to_string()));
+    }
+
+    // Months are counted as year * 12 + (month - 1) so stepping back is plain subtraction
+    let today = user_today(&state.pool, user.user_id).await?;
+    let current_index = today.year() * 12 + today.month0() as i32;
+    let first_index = current_index - (months as i32 - 1);
+    let start_date = NaiveDate::from_ymd_opt(first_index / 12, first_index as u32 % 12 + 1, 1)
+        .expect("Valid date");
+
+    let totals = sqlx::query_as::<_, (i32, i32, Decimal, i64)>(
+        r#"
+        SELECT
+            EXTRACT(YEAR FROM expense_date)::INTEGER as year,
+            EXTRACT(MONTH FROM expense_date)::INTEGER as month,
+            SUM(amount) as total_amount,
+            COUNT(*)::BIGINT as expense_count
+        FROM expense_allocations
+        WHERE user_id = $1 AND category_id = $2 AND expense_date >= $3
+          AND deleted_at IS NULL
+        GROUP BY year, month
+        "#,
+    )
+    .bind(user.user_id)
+    .bind(id)
+    .bind(start_date)
+    .fetch_all(&state.pool)
+    .await?;
+
+    let mut history: Vec<CategoryMonthlyTotal> = (first_index..=current_index)
+        .map(|index| CategoryMonthlyTotal {
+            year: index / 12,
+            month: (index % 12 + 1) as u32,
+            total_amount: Decimal::ZERO,
+            expense_count: 0,
+        })
+        .collect();
+
+    for (year, month, total_amount, expense_count) in totals {
+        let index = year * 12 + (month - 1);
+        if let Some(entry) = history.get_mut((index - first_index) as usize) {
+            entry.total_amount = total_amount;
+            entry.expense_count = expense_count;
+        }
+    }
+
+    Ok(Json(history))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/categories/recent",
+    tag = "categories",
+    params(RecentCategoriesQuery),
+    responses(
+        (status = 200, description = "Categories used most in the last 30 days, most used first", body = [Category]),
+        (status = 400, description = "Invalid limit"),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn list_recent_categories(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Query(query): Query<RecentCategoriesQuery>,
+) -> AppResult<Json<Vec<Category>>> {
+    let limit = query.limit.unwrap_or(5);
+    if !(1..=20).contains(&limit) {
+        return Err(AppError::Validation("limit must be between 1 and 20".to_string()));
+    }
+
+    let since = user_today(&state.pool, user.user_id).await? - Duration::days(30);
+
+    // Split parts count as uses; ties go to the category used most recently
+    let categories = sqlx::query_as::<_, Category>(
+        r#"
+        SELECT categories.*
+        FROM categories
+        JOIN expense_allocations ON expense_allocations.category_id = categories.id
+        WHERE categories.user_id = $1
+          AND NOT categories.archived
+          AND expense_allocations.expense_date >= $2
+          AND expense_allocations.deleted_at IS NULL
+        GROUP BY categories.id
+        ORDER BY COUNT(*) DESC, MAX(expense_allocations.expense_date) DESC, categories.name
+        LIMIT $3
+        "#,
+    )
+    .bind(user.user_id)
+    .bind(since)
+    .bind(limit)
+    .fetch_all(&state.pool)
+    .await?;
+
+    Ok(Json(categories))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/categories/palette",
+    tag = "categories",
+    params(PaletteQuery),
+    responses(
+        (status = 200, description = "Suggested hex colors for new categories", body = [String]),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn get_category_palette(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Query(query): Query<PaletteQuery>,
+) -> AppResult<Json<Vec<String>>> {
+    let mut palette: Vec<String> = PALETTE.iter().map(|c| c.to_string()).collect();
+    if !query.exclude_used.unwrap_or(false) {
+        return Ok(Json(palette));
+    }
+
+    let used: HashSet<String> = sqlx::query_scalar::<_, String>(
+        "SELECT UPPER(color) FROM categories WHERE user_id = $1 AND color IS NOT NULL"
+    )
+    .bind(user.user_id)
+    .fetch_all(&state.pool)
+    .await?
+    .into_iter()
+    .collect();
+
+    // Once every color is taken, reuse the full palette rather than offer nothing
+    let unused: Vec<String> = palette.iter().filter(|c| !used.contains(*c)).cloned().collect();
+    if !unused.is_empty() {
+        palette = unused;
+    }
+
+    Ok(Json(palette))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/categories/contrast",
+    tag = "categories",
+    responses(
+        (status = 200, description = "Readable text color for each category's color", body = [CategoryContrast]),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn get_category_contrast(
+    State(state): State<AppState>,
+    user: AuthUser,
+) -> AppResult<Json<Vec<CategoryContrast>>> {
+    let categories = fetch_categories(&state, user.user_id).await?;
+
+    let contrast = categories
+        .into_iter()
+        .map(|category| {
+            let color = category.color;
+            let text = color.as_deref().and_then(|hex| {
+                let luminance = relative_luminance(hex)?;
+                let text_color = text_color_for(hex);
+                let text_luminance = if text_color == WHITE { 1.0 } else { 0.0 };
+                let ratio = contrast_ratio(luminance, text_luminance);
+                Some((text_color.to_string(), (ratio * 100.0).round() / 100.0))
+            });
+            let (text_color, contrast_ratio) = text.unzip();
+
+            CategoryContrast {
+                category_id: category.id,
+                color,
+                text_color,
+                contrast_ratio,
+            }
+        })
+        .collect();
+
+    Ok(Json(contrast))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/categories/export",
+    tag = "categories",
+    responses(
+        (status = 200, description = "Name, color and icon of each active category, in list order", body = [CategoryTemplate]),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn export_categories(
+    State(state): State<AppState>,
+    user: AuthUser,
+) -> AppResult<Json<Vec<CategoryTemplate>>> {
+    let templates = sqlx::query_as::<_, CategoryTemplate>(
+        r#"
+        SELECT name, color, icon
+        FROM categories
+        WHERE user_id = $1 AND NOT archived
+        ORDER BY sort_order, name
+        "#,
+    )
+    .bind(user.user_id)
+    .fetch_all(&state.pool)
+    .await?;
+
+    Ok(Json(templates))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/categories/import",
+    tag = "categories",
+    request_body = [CategoryTemplate],
+    responses(
+        (status = 200, description = "How many categories were created and how many already existed", body = ImportCategoriesResult),
+        (status = 400, description = "An entry failed validation or the category limit would be exceeded"),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn import_categories(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Json(mut templates): Json<Vec<CategoryTemplate>>,
+) -> AppResult<Json<ImportCategoriesResult>> {
+    check_batch_size(&state, "Import", templates.len())?;
+
+    for (i, template) in templates.iter_mut().enumerate() {
+        template.trim();
+        template
+            .validate()
+            .map_err(|e| AppError::Validation(format!("Entry {}: {}", i, e)))?;
+    }
+
+    let mut names = Vec::with_capacity(templates.len());
+    let mut colors = Vec::with_capacity(templates.len());
+    let mut icons = Vec::with_capacity(templates.len());
+    for template in &templates {
+        names.push(template.name.as_str());
+        colors.push(template.color.as_deref());
+        icons.push(template.icon.as_deref());
+    }
+
+    let mut tx = state.pool.begin().await?;
+
+    // Names the user already has (archived ones included) and repeats of an
+    // earlier entry are skipped, so importing the same set twice creates nothing
+    // the second time. New categories go after the existing ones, in the order
+    // given and without gaps.
+    let created = sqlx::query(
+        r#"
+        WITH entry AS (
+            SELECT DISTINCT ON (name) name, color, icon, position
+            FROM unnest($2::TEXT[], $3::TEXT[], $4::TEXT[])
+                WITH ORDINALITY AS entry(name, color, icon, position)
+            WHERE name NOT IN (SELECT name FROM categories WHERE user_id = $1)
+            ORDER BY name, position
+        )
+        INSERT INTO categories (user_id, name, color, icon, sort_order)
+        SELECT
+            $1, name, color, icon,
+            (SELECT COALESCE(MAX(sort_order) + 1, 0) FROM categories WHERE user_id = $1)
+                + ROW_NUMBER() OVER (ORDER BY position) - 1
+        FROM entry
+        ON CONFLICT (user_id, name) DO NOTHING
+        "#,
+    )
+    .bind(user.user_id)
+    .bind(&names)
+    .bind(&colors)
+    .bind(&icons)
+    .execute(&mut *tx)
+    .await?
+    .rows_affected() as i64;
+
+    if created > 0 {
+        check_category_limit(&state, &mut tx, user.user_id).await?;
+    }
+    tx.commit().await?;
+    if created > 0 {
+        invalidate_cache(&state, user.user_id);
+    }
+
+    Ok(Json(ImportCategoriesResult {
+        created,
+        skipped: templates.len() as i64 - created,
+    }))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/categories/suggest",
+    tag = "categories",
+    params(SuggestCategoriesQuery),
+    responses(
+        (status = 200, description = "Up to 5 categories closest to the query, best match first", body = [Category]),
+        (status = 400, description = "Empty query"),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn suggest_categories(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Query(query): Query<SuggestCategoriesQuery>,
+) -> AppResult<Json<Vec<Category>>> {
+    let q = query.q.trim();
+    if q.is_empty() {
+        return Err(AppError::Validation("q must not be empty".to_string()));
+    }
+
+    let prefix = format!("{}%", escape_like(q));
+
+    // Prefix matches are kept even when short queries score below the threshold
+    let ranked = sqlx::query_as::<_, Category>(
+        r#"
+        SELECT *
+        FROM categories
+        WHERE user_id = $1 AND NOT archived
+          AND (similarity(name, $2) >= $3 OR name ILIKE $4)
+        ORDER BY similarity(name, $2) DESC, name
+        LIMIT $5
+        "#,
+    )
+    .bind(user.user_id)
+    .bind(q)
+    .bind(MIN_SIMILARITY)
+    .bind(&prefix)
+    .bind(SUGGESTION_LIMIT)
+    .fetch_all(&state.pool)
+    .await;
+
+    let categories = match ranked {
+        Ok(categories) => categories,
+        // `similarity` is missing when the pg_trgm migration couldn't install the extension
+        Err(err)
+            if err.as_database_error().and_then(|e| e.code()).as_deref()
+                == Some(UNDEFINED_FUNCTION) =>
+        {
+            sqlx::query_as::<_, Category>(
+                r#"
+                SELECT *
+                FROM categories
+                WHERE user_id = $1 AND NOT archived AND name ILIKE $2
+                ORDER BY name
+                LIMIT $3
+                "#,
+            )
+            .bind(user.user_id)
+            .bind(&prefix)
+            .bind(SUGGESTION_LIMIT)
+            .fetch_all(&state.pool)
+            .await?
+        }
+        Err(err) => return Err(err.into()),
+    };
+
+    Ok(Json(categories))
+}
+
+/// Escapes `LIKE` wildcards so user input only ever matches literally.
+pub fn escape_like(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('%', "\\%").replace('_', "\\_")
+}
@@ -0,0 +1,46 @@
+use axum::extract::{Query, State};
+
+use crate::{
+    auth::AuthUser,
+    error::AppResult,
+    extract::Json,
+    handlers::{
+        categories::fetch_sorted_categories,
+        expenses::fetch_expense_page,
+        summaries::{fetch_category_summary, fetch_monthly_summary},
+    },
+    models::{DashboardResponse, ExpenseQuery},
+    AppState,
+};
+
+#[utoipa::path(
+    get,
+    path = "/api/dashboard",
+    tag = "summaries",
+    params(ExpenseQuery),
+    responses(
+        (status = 200, description = "Categories, a page of expenses and summaries in one response", body = DashboardResponse),
+        (status = 400, description = "Invalid limit or offset"),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn get_dashboard(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Query(query): Query<ExpenseQuery>,
+) -> AppResult<Json<DashboardResponse>> {
+    // Run concurrently; the first error fails the whole response
+    let (categories, expenses, monthly_summary, category_summary) = tokio::try_join!(
+        fetch_sorted_categories(&state, user.user_id, None),
+        fetch_expense_page(&state, user.user_id, &query),
+        fetch_monthly_summary(&state.pool, user.user_id),
+        fetch_category_summary(&state.pool, user.user_id),
+    )?;
+
+    Ok(Json(DashboardResponse {
+        categories,
+        expenses,
+        monthly_summary,
+        category_summary,
+    }))
+}
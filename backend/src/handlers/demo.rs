@@ -0,0 +1,255 @@
+use axum::extract::{Query, State};
+use chrono::{Datelike, Duration, Months, NaiveDate};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use rust_decimal::Decimal;
+use uuid::Uuid;
+
+use crate::{
+    auth::AuthUser,
+    error::{AppError, AppResult},
+    extract::Json,
+    handlers::{events::publish_expenses_changed, settings::user_today},
+    models::{SeedDemoQuery, SeedDemoResult},
+    AppState,
+};
+
+/// Whole months of history generated before the current month.
+const DEMO_MONTHS: u32 = 2;
+
+/// Description and amount range (in cents) for one kind of demo expense.
+type DemoExpense = (&'static str, i64, i64);
+
+const FOOD: &[DemoExpense] = &[
+    ("Coffee", 300, 650),
+    ("Lunch", 900, 1800),
+    ("Groceries", 2500, 9000),
+    ("Dinner out", 2500, 6500),
+    ("Bakery", 400, 1200),
+];
+const TRANSPORT: &[DemoExpense] = &[
+    ("Bus ticket", 250, 300),
+    ("Fuel", 3000, 6500),
+    ("Taxi", 1200, 3500),
+    ("Parking", 300, 1500),
+];
+const SHOPPING: &[DemoExpense] = &[
+    ("Clothes", 2000, 8000),
+    ("Household supplies", 800, 3000),
+    ("Books", 1000, 3500),
+    ("Electronics accessory", 1500, 6000),
+];
+const ENTERTAINMENT: &[DemoExpense] = &[
+    ("Cinema", 1000, 2500),
+    ("Streaming subscription", 999, 1599),
+    ("Concert tickets", 4000, 12000),
+    ("Board game night", 500, 2000),
+];
+const BILLS: &[DemoExpense] = &[
+    ("Electricity bill", 4000, 12000),
+    ("Internet", 3999, 6999),
+    ("Phone plan", 1500, 4500),
+    ("Water bill", 2000, 5000),
+];
+const HEALTH: &[DemoExpense] = &[
+    ("Pharmacy", 500, 3000),
+    ("Doctor visit", 2500, 9000),
+    ("Gym membership", 2500, 5000),
+];
+const GENERAL: &[DemoExpense] = &[
+    ("Gift", 1500, 6000),
+    ("Haircut", 1500, 4000),
+    ("Miscellaneous", 200, 2500),
+];
+
+/// Realistic expenses for a category, picked by keywords in its name so the
+/// default categories (and similarly named custom ones) get fitting entries.
+fn demo_expenses_for(category_name: &str) -> &'static [DemoExpense] {
+    let name = category_name.to_lowercase();
+    let has = |keywords: &[&str]| keywords.iter().any(|k| name.contains(k));
+
+    if has(&["food", "dining", "grocer", "restaurant"]) {
+        FOOD
+    } else if has(&["transport", "car", "travel", "fuel"]) {
+        TRANSPORT
+    } else if has(&["shop", "clothes"]) {
+        SHOPPING
+    } else if has(&["entertain", "fun", "hobby"]) {
+        ENTERTAINMENT
+    } else if has(&["bill", "utilit", "rent"]) {
+        BILLS
+    } else if has(&["health", "medical", "fitness"]) {
+        HEALTH
+    } else {
+        GENERAL
+    }
+}
+
+/// Generates demo expenses from the first day `DEMO_MONTHS` months back up to
+/// `today`, as (category, amount, description, date).
+///
+/// The same `seed` and inputs always give the same expenses.
+fn generate_demo_expenses(
+    categories: &[(Uuid, String)],
+    today: NaiveDate,
+    seed: u64,
+) -> Vec<(Uuid, Decimal, String, NaiveDate)> {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let start = today
+        .with_day(1)
+        .and_then(|d| d.checked_sub_months(Months::new(DEMO_MONTHS)))
+        .expect("Valid date");
+
+    let mut expenses = Vec::new();
+    let mut date = start;
+    while date <= today {
+        // Mostly one or two a day, with the occasional quiet day
+        for _ in 0..rng.gen_range(0..=3) {
+            let (category_id, name) = &categories[rng.gen_range(0..categories.len())];
+            let choices = demo_expenses_for(name);
+            let (description, min_cents, max_cents) = choices[rng.gen_range(0..choices.len())];
+            let amount = Decimal::new(rng.gen_range(min_cents..=max_cents), 2);
+            expenses.push((*category_id, amount, description.to_string(), date));
+        }
+        date += Duration::days(1);
+    }
+
+    expenses
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/users/me/seed-demo",
+    tag = "users",
+    params(SeedDemoQuery),
+    responses(
+        (status = 200, description = "Number of demo expenses created (0 if the user already had expenses)", body = SeedDemoResult),
+        (status = 400, description = "The user has no active categories"),
+        (status = 401, description = "Missing or invalid token"),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn seed_demo_data(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Query(query): Query<SeedDemoQuery>,
+) -> AppResult<Json<SeedDemoResult>> {
+    let today = user_today(&state.pool, user.user_id).await?;
+
+    let mut tx = state.pool.begin().await?;
+
+    // Serializes concurrent requests, so a double-click can't seed twice
+    sqlx::query("SELECT id FROM users WHERE id = $1 FOR UPDATE")
+        .bind(user.user_id)
+        .execute(&mut *tx)
+        .await?;
+
+    // Expenses in the trash count too: they can still be restored
+    let has_expenses = sqlx::query_scalar::<_, bool>(
+        "SELECT EXISTS (SELECT 1 FROM expenses WHERE user_id = $1)"
+    )
+    .bind(user.user_id)
+    .fetch_one(&mut *tx)
+    .await?;
+    if has_expenses {
+        return Ok(Json(SeedDemoResult { created: 0 }));
+    }
+
+    let categories = sqlx::query_as::<_, (Uuid, String)>(
+        r#"
+        SELECT id, name FROM categories
+        WHERE user_id = $1 AND NOT archived
+        ORDER BY sort_order, name
+        "#,
+    )
+    .bind(user.user_id)
+    .fetch_all(&mut *tx)
+    .await?;
+    if categories.is_empty() {
+        return Err(AppError::Validation(
+            "Create a category before loading demo data".to_string(),
+        ));
+    }
+
+    let seed = query.seed.unwrap_or_else(rand::random);
+    let expenses = generate_demo_expenses(&categories, today, seed);
+
+    let mut category_ids = Vec::with_capacity(expenses.len());
+    let mut amounts = Vec::with_capacity(expenses.len());
+    let mut descriptions = Vec::with_capacity(expenses.len());
+    let mut dates = Vec::with_capacity(expenses.len());
+    for (category_id, amount, description, date) in expenses {
+        category_ids.push(category_id);
+        amounts.push(amount);
+        descriptions.push(description);
+        dates.push(date);
+    }
+
+    let created = sqlx::query(
+        r#"
+        INSERT INTO expenses (user_id, category_id, amount, description, expense_date)
+        SELECT $1, category_id, amount, description, expense_date
+        FROM UNNEST($2::UUID[], $3::DECIMAL[], $4::TEXT[], $5::DATE[])
+            AS demo(category_id, amount, description, expense_date)
+        "#,
+    )
+    .bind(user.user_id)
+    .bind(&category_ids)
+    .bind(&amounts)
+    .bind(&descriptions)
+    .bind(&dates)
+    .execute(&mut *tx)
+    .await?
+    .rows_affected();
+
+    tx.commit().await?;
+    if created > 0 {
+        publish_expenses_changed(&state, user.user_id);
+    }
+
+    Ok(Json(SeedDemoResult {
+        created: created as i64,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn categories() -> Vec<(Uuid, String)> {
+        ["Food & Dining", "Transportation", "Other"]
+            .iter()
+            .map(|name| (Uuid::new_v4(), name.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn same_seed_gives_the_same_expenses() {
+        let categories = categories();
+        let today = NaiveDate::from_ymd_opt(2024, 3, 15).unwrap();
+
+        let first = generate_demo_expenses(&categories, today, 42);
+        assert!(!first.is_empty());
+        assert_eq!(first, generate_demo_expenses(&categories, today, 42));
+        assert_ne!(first, generate_demo_expenses(&categories, today, 43));
+    }
+
+    #[test]
+    fn expenses_span_the_demo_months_up_to_today() {
+        let categories = categories();
+        let today = NaiveDate::from_ymd_opt(2024, 3, 15).unwrap();
+        let start = NaiveDate::from_ymd_opt(2024, 1, 1).unwrap();
+
+        for (category_id, amount, _, date) in generate_demo_expenses(&categories, today, 7) {
+            assert!(start <= date && date <= today, "{}", date);
+            assert!(amount > Decimal::ZERO);
+            assert!(categories.iter().any(|(id, _)| *id == category_id));
+        }
+    }
+
+    #[test]
+    fn categories_get_expenses_matching_their_name() {
+        assert_eq!(demo_expenses_for("Food & Dining"), FOOD);
+        assert_eq!(demo_expenses_for("Bills & Utilities"), BILLS);
+        assert_eq!(demo_expenses_for("Pets"), GENERAL);
+    }
+}
@@ -0,0 +1,168 @@
+use std::{convert::Infallible, time::Duration};
+
+use axum::{
+    extract::{Query, State},
+    http::StatusCode,
+    response::sse::{Event, KeepAlive, Sse},
+};
+use axum_extra::{
+    headers::{authorization::Bearer, Authorization},
+    TypedHeader,
+};
+use futures_util::{stream, Stream, StreamExt};
+use sqlx::PgPool;
+use tokio::sync::broadcast::{error::RecvError, Receiver};
+use uuid::Uuid;
+
+use crate::{
+    auth::AuthUser,
+    events::{ExpenseEvent, ExpenseEventKind, TicketHolder, TICKET_TTL},
+    extract::Json,
+    models::{EventsQuery, StreamTicket},
+    AppState,
+};
+
+/// How long the browser waits before reconnecting a dropped stream.
+const RECONNECT_DELAY: Duration = Duration::from_secs(5);
+
+/// How often an open stream checks that its session hasn't been revoked.
+const SESSION_CHECK_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Everything the stream needs between events.
+struct Subscription {
+    receiver: Receiver<ExpenseEvent>,
+    pool: PgPool,
+    user: AuthUser,
+    session_check: tokio::time::Interval,
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/events/ticket",
+    tag = "expenses",
+    responses(
+        (status = 200, description = "Single-use ticket for opening the event stream", body = StreamTicket),
+        (status = 401, description = "Missing, invalid or revoked token"),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn issue_stream_ticket(
+    State(state): State<AppState>,
+    user: AuthUser,
+) -> Json<StreamTicket> {
+    let ticket = state.events.issue_ticket(TicketHolder {
+        user_id: user.user_id,
+        session_id: user.session_id,
+    });
+    Json(StreamTicket {
+        ticket,
+        expires_in: TICKET_TTL.as_secs(),
+    })
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/events",
+    tag = "expenses",
+    params(EventsQuery),
+    responses(
+        (status = 200, description = "Server-sent event stream of changes to the user's expenses", content_type = "text/event-stream"),
+        (status = 401, description = "Missing, invalid or revoked token, or an unknown, used or expired ticket"),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn stream_events(
+    State(state): State<AppState>,
+    bearer: Option<TypedHeader<Authorization<Bearer>>>,
+    Query(query): Query<EventsQuery>,
+) -> Result<Sse<impl Stream<Item = Result<Event, Infallible>>>, (StatusCode, String)> {
+    // EventSource can't set headers, so browsers pass a ticket in the query
+    let user = match (&bearer, &query.ticket) {
+        (Some(TypedHeader(Authorization(bearer))), _) => {
+            AuthUser::from_token(&state, bearer.token()).await?
+        }
+        (None, Some(ticket)) => {
+            let holder = state.events.redeem_ticket(ticket).ok_or_else(|| {
+                (
+                    StatusCode::UNAUTHORIZED,
+                    "Invalid or expired stream ticket".to_string(),
+                )
+            })?;
+            AuthUser::from_session(&state, holder.user_id, holder.session_id).await?
+        }
+        (None, None) => {
+            return Err((
+                StatusCode::UNAUTHORIZED,
+                "Missing authorization header".to_string(),
+            ))
+        }
+    };
+
+    let subscription = Subscription {
+        receiver: state.events.subscribe(user.user_id),
+        pool: state.pool.clone(),
+        user,
+        session_check: tokio::time::interval_at(
+            tokio::time::Instant::now() + SESSION_CHECK_INTERVAL,
+            SESSION_CHECK_INTERVAL,
+        ),
+    };
+
+    let ready = Event::default().retry(RECONNECT_DELAY).comment("connected");
+    let events = stream::once(async move { Ok(ready) })
+        .chain(stream::unfold(subscription, next_event).map(Ok));
+
+    Ok(Sse::new(events).keep_alive(KeepAlive::default()))
+}
+
+/// Waits for the user's next event. Ends the stream once the session is
+/// revoked, so a logged-out device stops hearing about changes.
+async fn next_event(mut subscription: Subscription) -> Option<(Event, Subscription)> {
+    loop {
+        tokio::select! {
+            received = subscription.receiver.recv() => {
+                let event = match received {
+                    Ok(event) => event,
+                    // Events were dropped; the client has to reload everything anyway
+                    Err(RecvError::Lagged(_)) => ExpenseEvent::changed(),
+                    Err(RecvError::Closed) => return None,
+                };
+                return Some((to_sse(&event), subscription));
+            }
+            _ = subscription.session_check.tick() => {
+                let user = &subscription.user;
+                if !session_exists(&subscription.pool, user.user_id, user.session_id).await {
+                    return None;
+                }
+            }
+        }
+    }
+}
+
+/// A database error counts as still active; the next check will try again.
+async fn session_exists(pool: &PgPool, user_id: Uuid, session_id: Uuid) -> bool {
+    sqlx::query_scalar::<_, bool>(
+        "SELECT EXISTS (SELECT 1 FROM sessions WHERE id = $1 AND user_id = $2)"
+    )
+    .bind(session_id)
+    .bind(user_id)
+    .fetch_one(pool)
+    .await
+    .unwrap_or(true)
+}
+
+fn to_sse(event: &ExpenseEvent) -> Event {
+    Event::default()
+        .json_data(event)
+        .unwrap_or_else(|_| Event::default().data(r#"{"type":"expenses.changed"}"#))
+}
+
+/// Publishes a change to one expense; call after the change is committed.
+pub fn publish_expense_event(state: &AppState, user_id: Uuid, kind: ExpenseEventKind, id: Uuid) {
+    state.events.publish(user_id, ExpenseEvent::single(kind, id));
+}
+
+/// Publishes a change to several expenses at once.
+pub fn publish_expenses_changed(state: &AppState, user_id: Uuid) {
+    state.events.publish(user_id, ExpenseEvent::changed());
+}
@@ -1,46 +1,103 @@
 use axum::{
     extract::{Path, Query, State},
-    http::StatusCode,
-    Json,
+    http::{HeaderMap, StatusCode},
 };
+use chrono::{Datelike, NaiveDate, Utc};
 use rust_decimal::Decimal;
+use sqlx::{PgPool, Postgres, QueryBuilder};
 use uuid::Uuid;
 use validator::Validate;
 
 use crate::{
     auth::AuthUser,
-    error::{AppError, AppResult},
-    models::{CreateExpense, ExpenseQuery, ExpenseWithCategory, UpdateExpense},
+    error::{map_check_violation, AppError, AppResult},
+    events::ExpenseEventKind,
+    extract::Json,
+    handlers::{
+        categories::escape_like,
+        check_batch_size,
+        events::{publish_expense_event, publish_expenses_changed},
+        settings::{user_sort_preferences, user_today},
+        summaries::days_in_month,
+    },
+    models::{
+        CloneMonth, CreateExpense, CreatedExpense, DescriptionQuery, ExpenseQuery, ExpenseSplit,
+        ExpenseTemplate, ExpenseVersion, ExpenseWithCategory, FromTemplateQuery, PaginatedExpenses,
+        QuickExpense, RecategorizeExpenses, RecategorizeResult, TrashedExpense, UpdateExpense,
+    },
+    storage,
+    webhook::notify_expense_created,
     AppState,
 };
 
-pub async fn create_expense(
-    State(state): State<AppState>,
-    user: AuthUser,
-    Json(payload): Json<CreateExpense>,
-) -> AppResult<(StatusCode, Json<ExpenseWithCategory>)> {
-    payload.validate().map_err(|e| AppError::Validation(e.to_string()))?;
+/// Header a client sets on `POST /api/expenses` so retries of the same submit
+/// return the originally created expense instead of inserting a duplicate.
+const IDEMPOTENCY_KEY_HEADER: &str = "idempotency-key";
+
+/// Days of past expenses averaged for the large-expense warning.
+const LARGE_EXPENSE_WINDOW_DAYS: i32 = 90;
+/// Fewest past expenses in the window before their average is trusted.
+const LARGE_EXPENSE_MIN_SAMPLE: i64 = 5;
 
-    let category_exists = sqlx::query_scalar::<_, bool>(
-        "SELECT EXISTS(SELECT 1 FROM categories WHERE id = $1 AND user_id = $2)"
+/// Reads and checks the optional `Idempotency-Key` header.
+fn idempotency_key(headers: &HeaderMap) -> AppResult<Option<String>> {
+    let Some(value) = headers.get(IDEMPOTENCY_KEY_HEADER) else {
+        return Ok(None);
+    };
+
+    match value.to_str() {
+        Ok(key) if !key.is_empty() && key.len() <= 255 => Ok(Some(key.to_string())),
+        _ => Err(AppError::Validation(
+            "Idempotency-Key must be 1-255 visible ASCII characters".to_string(),
+        )),
+    }
+}
+
+/// Looks up the expense created by an earlier request with the same key.
+/// Keys older than 24 hours are ignored.
+///
+/// Fails with [`AppError::Conflict`] when that expense has since been moved
+/// to the trash: replaying it would hand back an expense the user deleted,
+/// and creating a new one would undo the deletion.
+async fn find_idempotent_expense(
+    pool: &PgPool,
+    user_id: Uuid,
+    key: &str,
+) -> AppResult<Option<ExpenseWithCategory>> {
+    let expense_id = sqlx::query_scalar::<_, Uuid>(
+        r#"
+        SELECT expense_id FROM idempotency_keys
+        WHERE user_id = $1 AND key = $2 AND created_at > NOW() - INTERVAL '24 hours'
+        "#,
     )
-    .bind(payload.category_id)
-    .bind(user.user_id)
-    .fetch_one(&state.pool)
+    .bind(user_id)
+    .bind(key)
+    .fetch_optional(pool)
     .await?;
 
-    if !category_exists {
-        return Err(AppError::NotFound("Category not found".to_string()));
+    let Some(id) = expense_id else {
+        return Ok(None);
+    };
+    // Purging removes the key along with the expense, so a missing live
+    // expense is one in the trash
+    match fetch_expense(pool, id, user_id).await? {
+        Some(expense) => Ok(Some(expense)),
+        None => Err(AppError::Conflict(
+            "The expense created with this Idempotency-Key has been deleted; restore it from \
+             the trash or use a new key"
+                .to_string(),
+        )),
     }
+}
 
-    let amount = Decimal::try_from(payload.amount)
-        .map_err(|_| AppError::Validation("Invalid amount".to_string()))?;
-
+async fn fetch_expense(
+    pool: &PgPool,
+    id: Uuid,
+    user_id: Uuid,
+) -> AppResult<Option<ExpenseWithCategory>> {
     let expense = sqlx::query_as::<_, ExpenseWithCategory>(
         r#"
-        INSERT INTO expenses (user_id, category_id, amount, description, expense_date)
-        VALUES ($1, $2, $3, $4, $5)
-        RETURNING
+        SELECT
             expenses.id,
             expenses.user_id,
             expenses.category_id,
@@ -54,7 +111,424 @@ pub async fn create_expense(
             expenses.updated_at
         FROM expenses
         JOIN categories ON expenses.category_id = categories.id
-        WHERE expenses.id = expenses.id
+        WHERE expenses.id = $1 AND expenses.user_id = $2 AND expenses.deleted_at IS NULL
+        "#,
+    )
+    .bind(id)
+    .bind(user_id)
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(expense)
+}
+
+/// Rejects dates before `MIN_EXPENSE_DATE`, which are almost always typos or
+/// bad imports, and dates after today (UTC) when
+/// `ALLOW_FUTURE_EXPENSE_DATES=false`.
+fn check_expense_date(state: &AppState, expense_date: NaiveDate) -> AppResult<()> {
+    if expense_date < state.config.min_expense_date {
+        return Err(AppError::Validation(format!(
+            "Expense date cannot be before {}",
+            state.config.min_expense_date
+        )));
+    }
+    if !state.config.allow_future_expense_dates && expense_date > Utc::now().date_naive() {
+        return Err(AppError::Validation(
+            "Expense date cannot be in the future".to_string(),
+        ));
+    }
+
+    Ok(())
+}
+
+/// Rejects amounts above `MAX_EXPENSE_AMOUNT` (the maximum itself is allowed),
+/// catching typos such as an extra zero or two.
+pub fn check_expense_amount(state: &AppState, amount: Decimal) -> AppResult<()> {
+    if amount > state.config.max_expense_amount {
+        return Err(AppError::Validation(format!(
+            "Amount cannot be more than {}",
+            state.config.max_expense_amount
+        )));
+    }
+
+    Ok(())
+}
+
+/// Whether `amount` is more than `multiple` times `average`. A `multiple` of 0
+/// (or below) turns the check off.
+fn exceeds_soft_limit(amount: Decimal, average: Decimal, multiple: Decimal) -> bool {
+    multiple > Decimal::ZERO && amount > average * multiple
+}
+
+/// Warns when `amount` is far above what the user usually spends, which is
+/// often a typo (an extra zero) but may be a real big purchase, so the expense
+/// is still saved. Needs [`LARGE_EXPENSE_MIN_SAMPLE`] recent expenses to
+/// compare against.
+async fn large_expense_warning(
+    state: &AppState,
+    user_id: Uuid,
+    amount: Decimal,
+) -> AppResult<Option<String>> {
+    let multiple = state.config.large_expense_multiple;
+    if multiple <= Decimal::ZERO {
+        return Ok(None);
+    }
+
+    let (count, average) = sqlx::query_as::<_, (i64, Option<Decimal>)>(
+        r#"
+        SELECT COUNT(*), AVG(amount)
+        FROM expenses
+        WHERE user_id = $1
+          AND deleted_at IS NULL
+          AND expense_date > CURRENT_DATE - $2
+        "#,
+    )
+    .bind(user_id)
+    .bind(LARGE_EXPENSE_WINDOW_DAYS)
+    .fetch_one(&state.pool)
+    .await?;
+
+    let Some(average) = average.filter(|_| count >= LARGE_EXPENSE_MIN_SAMPLE) else {
+        return Ok(None);
+    };
+    if !exceeds_soft_limit(amount, average, multiple) {
+        return Ok(None);
+    }
+
+    Ok(Some(format!(
+        "Amount is more than {}x your average expense of {} over the last {} days",
+        multiple.normalize(),
+        average.round_dp(2),
+        LARGE_EXPENSE_WINDOW_DAYS
+    )))
+}
+
+/// Converts a request amount to a `Decimal`, rejecting anything with more
+/// than two decimal places rather than silently rounding it (e.g. `42.123`).
+///
+/// The sign isn't checked here; request validation and [`parse_splits`]
+/// reject zero and negative amounts with their own messages.
+pub fn parse_amount(amount: f64) -> AppResult<Decimal> {
+    let decimal = Decimal::try_from(amount)
+        .map_err(|_| AppError::Validation("Invalid amount".to_string()))?;
+
+    // `try_from` picks the shortest decimal for the f64, so 42.1 has scale 1
+    if decimal.normalize().scale() > 2 {
+        return Err(AppError::Validation(
+            "Amount must have at most two decimal places".to_string(),
+        ));
+    }
+
+    Ok(decimal)
+}
+
+/// Checks that `splits` name each category once, have valid positive amounts
+/// and add up exactly to `total`. Returns the parts as (category, amount).
+fn parse_splits(splits: &[ExpenseSplit], total: Decimal) -> AppResult<Vec<(Uuid, Decimal)>> {
+    let mut parts: Vec<(Uuid, Decimal)> = Vec::with_capacity(splits.len());
+    for split in splits {
+        if parts.iter().any(|(category_id, _)| *category_id == split.category_id) {
+            return Err(AppError::Validation(
+                "Each category can appear in splits only once".to_string(),
+            ));
+        }
+
+        let amount = parse_amount(split.amount)?;
+        if amount <= Decimal::ZERO {
+            return Err(AppError::Validation(
+                "Split amounts must be greater than 0".to_string(),
+            ));
+        }
+        parts.push((split.category_id, amount));
+    }
+
+    let sum: Decimal = parts.iter().map(|(_, amount)| *amount).sum();
+    if !parts.is_empty() && sum != total {
+        return Err(AppError::Validation(format!(
+            "Split amounts add up to {} but the expense amount is {}",
+            sum, total
+        )));
+    }
+
+    Ok(parts)
+}
+
+/// Parses a `YYYY-MM` month into its first day.
+fn parse_month(value: &str, field: &str) -> AppResult<NaiveDate> {
+    NaiveDate::parse_from_str(&format!("{}-01", value.trim()), "%Y-%m-%d")
+        .map_err(|_| AppError::Validation(format!("{} must be a month like 2024-01", field)))
+}
+
+/// The pieces of a quick-add line such as `12.50 lunch #food 2024-01-15`.
+#[derive(Debug, PartialEq)]
+struct QuickExpenseParts {
+    amount: Decimal,
+    description: String,
+    /// Category name as typed after `#`, still to be matched to a category
+    category: String,
+    /// `None` means today
+    expense_date: Option<NaiveDate>,
+}
+
+/// Splits a quick-add line into amount, description, `#category` and date.
+///
+/// Tokens can come in any order: the first number is the amount, a
+/// `YYYY-MM-DD` token is the date, a `#` token is the category and every
+/// other word goes into the description. Errors name the offending token.
+fn parse_quick_expense(text: &str) -> AppResult<QuickExpenseParts> {
+    let mut amount = None;
+    let mut category = None;
+    let mut expense_date = None;
+    let mut words = Vec::new();
+
+    for token in text.split_whitespace() {
+        if let Some(name) = token.strip_prefix('#') {
+            if name.is_empty() {
+                return Err(AppError::Validation(
+                    "'#' must be followed by a category name".to_string(),
+                ));
+            }
+            if category.is_some() {
+                return Err(AppError::Validation(format!(
+                    "Only one #category is allowed, got '{}'",
+                    token
+                )));
+            }
+            category = Some(name.to_string());
+        } else if looks_like_date(token) {
+            let date = NaiveDate::parse_from_str(token, "%Y-%m-%d")
+                .map_err(|_| AppError::Validation(format!("Invalid date '{}'", token)))?;
+            if expense_date.is_some() {
+                return Err(AppError::Validation(format!(
+                    "Only one date is allowed, got '{}'",
+                    token
+                )));
+            }
+            expense_date = Some(date);
+        } else if let (None, Ok(value)) = (amount, token.parse::<Decimal>()) {
+            if value <= Decimal::ZERO {
+                return Err(AppError::Validation(format!(
+                    "Amount '{}' must be greater than 0",
+                    token
+                )));
+            }
+            if value.normalize().scale() > 2 {
+                return Err(AppError::Validation(format!(
+                    "Amount '{}' must have at most two decimal places",
+                    token
+                )));
+            }
+            amount = Some(value);
+        } else {
+            words.push(token);
+        }
+    }
+
+    let amount = amount
+        .ok_or_else(|| AppError::Validation("No amount found, e.g. 12.50".to_string()))?;
+    let category = category
+        .ok_or_else(|| AppError::Validation("No category found, add one like #food".to_string()))?;
+    let description = words.join(" ");
+    if description.is_empty() {
+        return Err(AppError::Validation("No description found".to_string()));
+    }
+    if description.chars().count() > 500 {
+        return Err(AppError::Validation(
+            "Description must be 1-500 characters".to_string(),
+        ));
+    }
+
+    Ok(QuickExpenseParts {
+        amount,
+        description,
+        category,
+        expense_date,
+    })
+}
+
+/// `YYYY-MM-DD` shape, valid date or not, so `2024-13-01` is reported as a
+/// bad date rather than ending up in the description.
+fn looks_like_date(token: &str) -> bool {
+    token.len() == 10
+        && token.char_indices().all(|(i, c)| match i {
+            4 | 7 => c == '-',
+            _ => c.is_ascii_digit(),
+        })
+}
+
+/// Finds the category a quick-add `#name` refers to among `(id, name)` pairs.
+///
+/// Case, spaces and punctuation are ignored, so `#food` matches "Food" and
+/// `#billsutilities` matches "Bills & Utilities". Without an exact match, a
+/// single category starting with the name is used (`#ent` for
+/// "Entertainment"); more than one is ambiguous.
+fn match_quick_category(categories: &[(Uuid, String)], name: &str) -> AppResult<Uuid> {
+    let normalize = |s: &str| -> String {
+        s.chars()
+            .filter(|c| c.is_alphanumeric())
+            .flat_map(char::to_lowercase)
+            .collect()
+    };
+    let wanted = normalize(name);
+
+    if let Some((id, _)) = categories.iter().find(|(_, n)| normalize(n) == wanted) {
+        return Ok(*id);
+    }
+
+    let prefixed: Vec<&(Uuid, String)> = categories
+        .iter()
+        .filter(|(_, n)| !wanted.is_empty() && normalize(n).starts_with(&wanted))
+        .collect();
+    match prefixed.as_slice() {
+        [(id, _)] => Ok(*id),
+        [] => Err(AppError::Validation(format!("No category matches '#{}'", name))),
+        several => Err(AppError::Validation(format!(
+            "'#{}' matches several categories: {}",
+            name,
+            several.iter().map(|(_, n)| n.as_str()).collect::<Vec<_>>().join(", ")
+        ))),
+    }
+}
+
+/// Appends the `WHERE` clause for every filter set in `query`, binding each
+/// value as it goes so placeholders and bindings can't get out of step.
+///
+/// Every query over a user's expenses (page, count, export, ...) should use
+/// this so they all agree on which rows match. Expects `expenses` joined with
+/// `categories` in the `FROM` clause.
+fn push_expense_filters(
+    builder: &mut QueryBuilder<'_, Postgres>,
+    user_id: Uuid,
+    query: &ExpenseQuery,
+) {
+    builder.push(" WHERE expenses.user_id = ").push_bind(user_id);
+    builder.push(" AND expenses.deleted_at IS NULL");
+
+    if let Some(start_date) = query.start_date {
+        builder.push(" AND expenses.expense_date >= ").push_bind(start_date);
+    }
+
+    if let Some(end_date) = query.end_date {
+        builder.push(" AND expenses.expense_date <= ").push_bind(end_date);
+    }
+
+    let category_ids = query.category_filter();
+    if !category_ids.is_empty() {
+        builder.push(" AND expenses.category_id = ANY(").push_bind(category_ids).push(")");
+    }
+}
+
+/// Checks in one query that every id in `category_ids` is a category owned by
+/// `user_id`, so an expense can never reference another user's category.
+/// Returns [`AppError::NotFound`] naming each offending id, or
+/// [`AppError::Validation`] naming any archived category.
+///
+/// Any endpoint that writes expenses, single or bulk, must call this first.
+pub async fn verify_categories_owned(
+    pool: &PgPool,
+    user_id: Uuid,
+    category_ids: &[Uuid],
+) -> AppResult<()> {
+    let owned = sqlx::query_as::<_, (Uuid, String, bool)>(
+        "SELECT id, name, archived FROM categories WHERE user_id = $1 AND id = ANY($2)"
+    )
+    .bind(user_id)
+    .bind(category_ids)
+    .fetch_all(pool)
+    .await?;
+
+    let mut missing: Vec<Uuid> = Vec::new();
+    for id in category_ids {
+        if !owned.iter().any(|(owned_id, ..)| owned_id == id) && !missing.contains(id) {
+            missing.push(*id);
+        }
+    }
+
+    match missing.as_slice() {
+        [] => {}
+        [id] => return Err(AppError::NotFound(format!("Category not found: {}", id))),
+        ids => {
+            return Err(AppError::NotFound(format!(
+                "Categories not found: {}",
+                ids.iter().map(Uuid::to_string).collect::<Vec<_>>().join(", ")
+            )))
+        }
+    }
+
+    let archived: Vec<&str> = owned
+        .iter()
+        .filter(|(.., archived)| *archived)
+        .map(|(_, name, _)| name.as_str())
+        .collect();
+
+    match archived.as_slice() {
+        [] => Ok(()),
+        [name] => Err(AppError::Validation(format!("Category is archived: {}", name))),
+        names => Err(AppError::Validation(format!(
+            "Categories are archived: {}",
+            names.join(", ")
+        ))),
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/expenses",
+    tag = "expenses",
+    request_body = CreateExpense,
+    params(
+        ("Idempotency-Key" = Option<String>, Header,
+            description = "Repeat a key within 24 hours to get the original expense back instead of a duplicate"),
+    ),
+    responses(
+        (status = 201, description = "Expense created (or previously created with this Idempotency-Key), with any warnings", body = CreatedExpense),
+        (status = 400, description = "Validation failed"),
+        (status = 404, description = "Category not found"),
+        (status = 409, description = "The expense created with this Idempotency-Key is in the trash"),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn create_expense(
+    State(state): State<AppState>,
+    user: AuthUser,
+    headers: HeaderMap,
+    Json(mut payload): Json<CreateExpense>,
+) -> AppResult<(StatusCode, Json<CreatedExpense>)> {
+    payload.trim();
+    payload.validate().map_err(|e| AppError::Validation(e.to_string()))?;
+    check_batch_size(&state, "splits", payload.splits.as_ref().map_or(0, Vec::len))?;
+    check_expense_date(&state, payload.expense_date)?;
+
+    let idempotency_key = idempotency_key(&headers)?;
+    if let Some(key) = &idempotency_key {
+        // A retry already had its warnings with the first response
+        if let Some(expense) = find_idempotent_expense(&state.pool, user.user_id, key).await? {
+            let created = CreatedExpense { expense, warnings: Vec::new() };
+            return Ok((StatusCode::CREATED, Json(created)));
+        }
+    }
+
+    let amount = parse_amount(payload.amount)?;
+    check_expense_amount(&state, amount)?;
+    let splits = parse_splits(payload.splits.as_deref().unwrap_or_default(), amount)?;
+
+    let mut category_ids = vec![payload.category_id];
+    category_ids.extend(splits.iter().map(|(category_id, _)| *category_id));
+    verify_categories_owned(&state.pool, user.user_id, &category_ids).await?;
+
+    // Averaged before the insert so the new expense doesn't dilute it
+    let warnings: Vec<String> = large_expense_warning(&state, user.user_id, amount)
+        .await?
+        .into_iter()
+        .collect();
+
+    let mut tx = state.pool.begin().await?;
+
+    let expense_id = sqlx::query_scalar::<_, Uuid>(
+        r#"
+        INSERT INTO expenses (user_id, category_id, amount, description, expense_date)
+        VALUES ($1, $2, $3, $4, $5)
+        RETURNING id
         "#,
     )
     .bind(user.user_id)
@@ -62,18 +536,236 @@ pub async fn create_expense(
     .bind(amount)
     .bind(&payload.description)
     .bind(payload.expense_date)
+    .fetch_one(&mut *tx)
+    .await
+    .map_err(map_check_violation)?;
+
+    if !splits.is_empty() {
+        let (split_categories, split_amounts): (Vec<Uuid>, Vec<Decimal>) =
+            splits.into_iter().unzip();
+        sqlx::query(
+            r#"
+            INSERT INTO expense_splits (expense_id, category_id, amount)
+            SELECT $1, category_id, amount
+            FROM UNNEST($2::UUID[], $3::DECIMAL[]) AS parts(category_id, amount)
+            "#,
+        )
+        .bind(expense_id)
+        .bind(&split_categories)
+        .bind(&split_amounts)
+        .execute(&mut *tx)
+        .await?;
+    }
+
+    if let Some(key) = &idempotency_key {
+        sqlx::query(
+            "DELETE FROM idempotency_keys WHERE user_id = $1 AND created_at <= NOW() - INTERVAL '24 hours'"
+        )
+        .bind(user.user_id)
+        .execute(&mut *tx)
+        .await?;
+
+        // Blocks on a concurrent request holding the same key until it commits
+        let recorded = sqlx::query(
+            r#"
+            INSERT INTO idempotency_keys (user_id, key, expense_id)
+            VALUES ($1, $2, $3)
+            ON CONFLICT (user_id, key) DO NOTHING
+            "#,
+        )
+        .bind(user.user_id)
+        .bind(key)
+        .bind(expense_id)
+        .execute(&mut *tx)
+        .await?
+        .rows_affected()
+            > 0;
+
+        if !recorded {
+            // Lost the race: discard our insert and answer with the winner's expense
+            tx.rollback().await?;
+            let expense = find_idempotent_expense(&state.pool, user.user_id, key)
+                .await?
+                .ok_or_else(|| AppError::NotFound("Expense not found".to_string()))?;
+            let created = CreatedExpense { expense, warnings: Vec::new() };
+            return Ok((StatusCode::CREATED, Json(created)));
+        }
+    }
+
+    tx.commit().await?;
+
+    let expense = fetch_expense(&state.pool, expense_id, user.user_id)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Expense not found".to_string()))?;
+    notify_expense_created(&state, user.user_id, &expense);
+    publish_expense_event(&state, user.user_id, ExpenseEventKind::Created, expense.id);
+
+    Ok((StatusCode::CREATED, Json(CreatedExpense { expense, warnings })))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/expenses/quick",
+    tag = "expenses",
+    request_body = QuickExpense,
+    responses(
+        (status = 201, description = "Expense created from the parsed text", body = ExpenseWithCategory),
+        (status = 400, description = "A token couldn't be parsed or the #category matched no single category"),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn create_quick_expense(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Json(payload): Json<QuickExpense>,
+) -> AppResult<(StatusCode, Json<ExpenseWithCategory>)> {
+    payload.validate().map_err(|e| AppError::Validation(e.to_string()))?;
+    let parts = parse_quick_expense(&payload.text)?;
+    check_expense_amount(&state, parts.amount)?;
+
+    let expense_date = match parts.expense_date {
+        Some(date) => date,
+        None => user_today(&state.pool, user.user_id).await?,
+    };
+    check_expense_date(&state, expense_date)?;
+
+    let categories = sqlx::query_as::<_, (Uuid, String)>(
+        "SELECT id, name FROM categories WHERE user_id = $1 AND NOT archived ORDER BY name"
+    )
+    .bind(user.user_id)
+    .fetch_all(&state.pool)
+    .await?;
+    let category_id = match_quick_category(&categories, &parts.category)?;
+
+    let expense_id = sqlx::query_scalar::<_, Uuid>(
+        r#"
+        INSERT INTO expenses (user_id, category_id, amount, description, expense_date)
+        VALUES ($1, $2, $3, $4, $5)
+        RETURNING id
+        "#,
+    )
+    .bind(user.user_id)
+    .bind(category_id)
+    .bind(parts.amount)
+    .bind(&parts.description)
+    .bind(expense_date)
+    .fetch_one(&state.pool)
+    .await?;
+
+    let expense = fetch_expense(&state.pool, expense_id, user.user_id)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Expense not found".to_string()))?;
+    notify_expense_created(&state, user.user_id, &expense);
+    publish_expense_event(&state, user.user_id, ExpenseEventKind::Created, expense.id);
+
+    Ok((StatusCode::CREATED, Json(expense)))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/expenses/from-template/{id}",
+    tag = "expenses",
+    params(
+        ("id" = Uuid, Path, description = "Template ID"),
+        FromTemplateQuery,
+    ),
+    responses(
+        (status = 201, description = "Expense created from the template", body = ExpenseWithCategory),
+        (status = 400, description = "Invalid date, or the template's category is archived"),
+        (status = 404, description = "Template not found"),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn create_expense_from_template(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path(id): Path<Uuid>,
+    Query(query): Query<FromTemplateQuery>,
+) -> AppResult<(StatusCode, Json<ExpenseWithCategory>)> {
+    let template = sqlx::query_as::<_, ExpenseTemplate>(
+        "SELECT * FROM expense_templates WHERE id = $1 AND user_id = $2"
+    )
+    .bind(id)
+    .bind(user.user_id)
+    .fetch_optional(&state.pool)
+    .await?
+    .ok_or_else(|| AppError::NotFound("Template not found".to_string()))?;
+
+    let expense_date = match query.expense_date {
+        Some(date) => date,
+        None => user_today(&state.pool, user.user_id).await?,
+    };
+    check_expense_date(&state, expense_date)?;
+    // The limit or the category may have changed since the template was saved
+    check_expense_amount(&state, template.amount)?;
+    verify_categories_owned(&state.pool, user.user_id, &[template.category_id]).await?;
+
+    let expense_id = sqlx::query_scalar::<_, Uuid>(
+        r#"
+        INSERT INTO expenses (user_id, category_id, amount, description, expense_date)
+        VALUES ($1, $2, $3, $4, $5)
+        RETURNING id
+        "#,
+    )
+    .bind(user.user_id)
+    .bind(template.category_id)
+    .bind(template.amount)
+    .bind(&template.description)
+    .bind(expense_date)
     .fetch_one(&state.pool)
     .await?;
 
+    let expense = fetch_expense(&state.pool, expense_id, user.user_id)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Expense not found".to_string()))?;
+    notify_expense_created(&state, user.user_id, &expense);
+    publish_expense_event(&state, user.user_id, ExpenseEventKind::Created, expense.id);
+
     Ok((StatusCode::CREATED, Json(expense)))
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/expenses",
+    tag = "expenses",
+    params(ExpenseQuery),
+    responses(
+        (status = 200, description = "A page of matching expenses", body = PaginatedExpenses),
+        (status = 400, description = "Invalid limit or offset"),
+    ),
+    security(("bearer_auth" = []))
+)]
 pub async fn list_expenses(
     State(state): State<AppState>,
     user: AuthUser,
     Query(query): Query<ExpenseQuery>,
-) -> AppResult<Json<Vec<ExpenseWithCategory>>> {
-    let mut sql = String::from(
+) -> AppResult<Json<PaginatedExpenses>> {
+    let page = fetch_expense_page(&state, user.user_id, &query).await?;
+
+    Ok(Json(page))
+}
+
+/// One page of the user's expenses matching `query`, plus the total count.
+/// Shared by the expense list and the dashboard.
+pub async fn fetch_expense_page(
+    state: &AppState,
+    user_id: Uuid,
+    query: &ExpenseQuery,
+) -> AppResult<PaginatedExpenses> {
+    let limit = query.limit.unwrap_or(state.config.default_page_size);
+    if !(1..=state.config.max_page_size).contains(&limit) {
+        return Err(AppError::Validation(format!(
+            "limit must be between 1 and {}",
+            state.config.max_page_size
+        )));
+    }
+
+    let offset = query.offset.unwrap_or(0);
+    if offset < 0 {
+        return Err(AppError::Validation("offset must not be negative".to_string()));
+    }
+
+    let mut page_query = QueryBuilder::<Postgres>::new(
         r#"
         SELECT
             expenses.id,
@@ -89,54 +781,196 @@ pub async fn list_expenses(
             expenses.updated_at
         FROM expenses
         JOIN categories ON expenses.category_id = categories.id
-        WHERE expenses.user_id = $1
         "#,
     );
+    push_expense_filters(&mut page_query, user_id, query);
+
+    // Whichever of sort_by/sort_dir is missing comes from the user's settings.
+    // Ties fall back to newest first, then id, so pages don't overlap or skip rows
+    let (sort_by, sort_dir) = match (query.sort_by, query.sort_dir) {
+        (Some(sort_by), Some(sort_dir)) => (sort_by, sort_dir),
+        (sort_by, sort_dir) => {
+            let preferences = user_sort_preferences(&state.pool, user_id).await?;
+            (
+                sort_by.unwrap_or(preferences.expense_sort_by),
+                sort_dir.unwrap_or(preferences.expense_sort_dir),
+            )
+        }
+    };
+    page_query.push(format!(
+        " ORDER BY {} {}, expenses.expense_date DESC, expenses.created_at DESC, expenses.id",
+        sort_by.column(),
+        sort_dir.as_sql()
+    ));
+    page_query.push(" LIMIT ").push_bind(limit);
+    page_query.push(" OFFSET ").push_bind(offset);
+
+    let mut count_query = QueryBuilder::<Postgres>::new(
+        "SELECT COUNT(*) FROM expenses JOIN categories ON expenses.category_id = categories.id",
+    );
+    push_expense_filters(&mut count_query, user_id, query);
+
+    let items = page_query
+        .build_query_as::<ExpenseWithCategory>()
+        .fetch_all(&state.pool)
+        .await?;
+    let total = count_query
+        .build_query_scalar::<i64>()
+        .fetch_one(&state.pool)
+        .await?;
+
+    Ok(PaginatedExpenses::new(items, total, limit, offset))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/expenses/{id}",
+    tag = "expenses",
+    params(("id" = Uuid, Path, description = "Expense ID")),
+    responses(
+        (status = 200, description = "Expense found", body = ExpenseWithCategory),
+        (status = 404, description = "Expense not found"),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn get_expense(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path(id): Path<Uuid>,
+) -> AppResult<Json<ExpenseWithCategory>> {
+    let expense = fetch_expense(&state.pool, id, user.user_id)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Expense not found".to_string()))?;
 
-    let mut param_count = 1;
+    Ok(Json(expense))
+}
 
-    if query.start_date.is_some() {
-        param_count += 1;
-        sql.push_str(&format!(" AND expenses.expense_date >= ${}", param_count));
+#[utoipa::path(
+    put,
+    path = "/api/expenses/{id}",
+    tag = "expenses",
+    params(("id" = Uuid, Path, description = "Expense ID")),
+    request_body = UpdateExpense,
+    responses(
+        (status = 200, description = "Expense updated", body = ExpenseWithCategory),
+        (status = 400, description = "Validation failed"),
+        (status = 404, description = "Expense or category not found"),
+        (status = 409, description = "Expense was modified since `expected_updated_at`"),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn update_expense(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path(id): Path<Uuid>,
+    Json(mut payload): Json<UpdateExpense>,
+) -> AppResult<Json<ExpenseWithCategory>> {
+    payload.trim();
+    payload.validate().map_err(|e| AppError::Validation(e.to_string()))?;
+    if let Some(expense_date) = payload.expense_date {
+        check_expense_date(&state, expense_date)?;
     }
 
-    if query.end_date.is_some() {
-        param_count += 1;
-        sql.push_str(&format!(" AND expenses.expense_date <= ${}", param_count));
+    let expense_exists = sqlx::query_scalar::<_, bool>(
+        "SELECT EXISTS(SELECT 1 FROM expenses WHERE id = $1 AND user_id = $2 AND deleted_at IS NULL)"
+    )
+    .bind(id)
+    .bind(user.user_id)
+    .fetch_one(&state.pool)
+    .await?;
+
+    if !expense_exists {
+        return Err(AppError::NotFound("Expense not found".to_string()));
     }
 
-    if query.category_id.is_some() {
-        param_count += 1;
-        sql.push_str(&format!(" AND expenses.category_id = ${}", param_count));
+    if let Some(category_id) = payload.category_id {
+        verify_categories_owned(&state.pool, user.user_id, &[category_id]).await?;
     }
 
-    sql.push_str(" ORDER BY expenses.expense_date DESC, expenses.created_at DESC");
+    let amount = payload.amount.map(parse_amount).transpose()?;
+    if let Some(amount) = amount {
+        check_expense_amount(&state, amount)?;
+    }
 
-    let mut query_builder = sqlx::query_as::<_, ExpenseWithCategory>(&sql).bind(user.user_id);
+    let mut query = QueryBuilder::<Postgres>::new("UPDATE expenses SET updated_at = NOW()");
 
-    if let Some(start_date) = query.start_date {
-        query_builder = query_builder.bind(start_date);
+    if let Some(category_id) = payload.category_id {
+        query.push(", category_id = ").push_bind(category_id);
     }
 
-    if let Some(end_date) = query.end_date {
-        query_builder = query_builder.bind(end_date);
+    if let Some(amount) = amount {
+        query.push(", amount = ").push_bind(amount);
     }
 
-    if let Some(category_id) = query.category_id {
-        query_builder = query_builder.bind(category_id);
+    if let Some(description) = &payload.description {
+        query.push(", description = ").push_bind(description);
     }
 
-    let expenses = query_builder.fetch_all(&state.pool).await?;
+    if let Some(expense_date) = payload.expense_date {
+        query.push(", expense_date = ").push_bind(expense_date);
+    }
 
-    Ok(Json(expenses))
-}
+    query.push(" WHERE id = ").push_bind(id);
+    query.push(" AND user_id = ").push_bind(user.user_id);
+    query.push(" AND deleted_at IS NULL");
 
-pub async fn get_expense(
-    State(state): State<AppState>,
-    user: AuthUser,
-    Path(id): Path<Uuid>,
-) -> AppResult<Json<ExpenseWithCategory>> {
-    let expense = sqlx::query_as::<_, ExpenseWithCategory>(
+    // Checked in the same statement so a concurrent write can't slip in between
+    if let Some(expected) = payload.expected_updated_at {
+        query.push(" AND updated_at = ").push_bind(expected);
+    }
+
+    let mut tx = state.pool.begin().await?;
+
+    // Snapshot of the version being replaced; the row lock keeps it exact
+    sqlx::query(
+        r#"
+        INSERT INTO expense_history (expense_id, category_id, amount, description, expense_date, valid_from)
+        SELECT id, category_id, amount, description, expense_date, updated_at
+        FROM expenses
+        WHERE id = $1 AND user_id = $2
+        FOR UPDATE
+        "#,
+    )
+    .bind(id)
+    .bind(user.user_id)
+    .execute(&mut *tx)
+    .await?;
+
+    let result = query.build().execute(&mut *tx).await.map_err(map_check_violation)?;
+
+    // The expense exists (checked above), so no match means it was modified.
+    // Returning drops the transaction, which also discards the snapshot.
+    if result.rows_affected() == 0 {
+        return Err(AppError::Conflict(
+            "Expense was modified by another request; reload and try again".to_string(),
+        ));
+    }
+
+    // A split expense's parts must keep adding up to its amount
+    if payload.amount.is_some() {
+        let splits_mismatch = sqlx::query_scalar::<_, bool>(
+            r#"
+            SELECT COALESCE(SUM(expense_splits.amount) <> MIN(expenses.amount), false)
+            FROM expenses
+            JOIN expense_splits ON expense_splits.expense_id = expenses.id
+            WHERE expenses.id = $1
+            "#,
+        )
+        .bind(id)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        if splits_mismatch {
+            return Err(AppError::Validation(
+                "Expense is split across categories; its amount can't change without new splits"
+                    .to_string(),
+            ));
+        }
+    }
+
+    tx.commit().await?;
+
+    let updated_expense = sqlx::query_as::<_, ExpenseWithCategory>(
         r#"
         SELECT
             expenses.id,
@@ -152,28 +986,35 @@ pub async fn get_expense(
             expenses.updated_at
         FROM expenses
         JOIN categories ON expenses.category_id = categories.id
-        WHERE expenses.id = $1 AND expenses.user_id = $2
+        WHERE expenses.id = $1
         "#,
     )
     .bind(id)
-    .bind(user.user_id)
-    .fetch_optional(&state.pool)
-    .await?
-    .ok_or_else(|| AppError::NotFound("Expense not found".to_string()))?;
+    .fetch_one(&state.pool)
+    .await?;
+    publish_expense_event(&state, user.user_id, ExpenseEventKind::Updated, id);
 
-    Ok(Json(expense))
+    Ok(Json(updated_expense))
 }
 
-pub async fn update_expense(
+#[utoipa::path(
+    get,
+    path = "/api/expenses/{id}/history",
+    tag = "expenses",
+    params(("id" = Uuid, Path, description = "Expense ID")),
+    responses(
+        (status = 200, description = "Previous versions of the expense, newest first", body = [ExpenseVersion]),
+        (status = 404, description = "Expense not found"),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn get_expense_history(
     State(state): State<AppState>,
     user: AuthUser,
     Path(id): Path<Uuid>,
-    Json(payload): Json<UpdateExpense>,
-) -> AppResult<Json<ExpenseWithCategory>> {
-    payload.validate().map_err(|e| AppError::Validation(e.to_string()))?;
-
+) -> AppResult<Json<Vec<ExpenseVersion>>> {
     let expense_exists = sqlx::query_scalar::<_, bool>(
-        "SELECT EXISTS(SELECT 1 FROM expenses WHERE id = $1 AND user_id = $2)"
+        "SELECT EXISTS(SELECT 1 FROM expenses WHERE id = $1 AND user_id = $2 AND deleted_at IS NULL)"
     )
     .bind(id)
     .bind(user.user_id)
@@ -184,54 +1025,445 @@ pub async fn update_expense(
         return Err(AppError::NotFound("Expense not found".to_string()));
     }
 
-    if let Some(category_id) = payload.category_id {
-        let category_exists = sqlx::query_scalar::<_, bool>(
-            "SELECT EXISTS(SELECT 1 FROM categories WHERE id = $1 AND user_id = $2)"
+    let versions = sqlx::query_as::<_, ExpenseVersion>(
+        r#"
+        SELECT
+            expense_history.id,
+            expense_history.category_id,
+            categories.name as category_name,
+            expense_history.amount,
+            expense_history.description,
+            expense_history.expense_date,
+            expense_history.valid_from,
+            expense_history.replaced_at
+        FROM expense_history
+        LEFT JOIN categories ON expense_history.category_id = categories.id
+        WHERE expense_history.expense_id = $1
+        ORDER BY expense_history.replaced_at DESC, expense_history.id
+        "#,
+    )
+    .bind(id)
+    .fetch_all(&state.pool)
+    .await?;
+
+    Ok(Json(versions))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/expenses/{id}",
+    tag = "expenses",
+    params(("id" = Uuid, Path, description = "Expense ID")),
+    responses(
+        (status = 204, description = "Expense moved to the trash"),
+        (status = 404, description = "Expense not found"),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn delete_expense(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path(id): Path<Uuid>,
+) -> AppResult<StatusCode> {
+    purge_expired_trash(&state, user.user_id).await?;
+
+    let result = sqlx::query(
+        "UPDATE expenses SET deleted_at = NOW() WHERE id = $1 AND user_id = $2 AND deleted_at IS NULL"
+    )
+    .bind(id)
+    .bind(user.user_id)
+    .execute(&state.pool)
+    .await?;
+
+    if result.rows_affected() == 0 {
+        return Err(AppError::NotFound("Expense not found".to_string()));
+    }
+    publish_expense_event(&state, user.user_id, ExpenseEventKind::Deleted, id);
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Hard-deletes the user's trashed expenses older than `TRASH_RETENTION_DAYS`.
+/// Run before the trash is read or added to, so nothing expired is ever shown.
+async fn purge_expired_trash(state: &AppState, user_id: Uuid) -> AppResult<()> {
+    // Attachment rows go with their expense (ON DELETE CASCADE); the SELECT
+    // still sees them, so their files can be removed once the delete commits
+    let storage_keys = sqlx::query_scalar::<_, String>(
+        r#"
+        WITH purged AS (
+            DELETE FROM expenses
+            WHERE user_id = $1 AND deleted_at <= NOW() - make_interval(days => $2)
+            RETURNING id
         )
-        .bind(category_id)
-        .bind(user.user_id)
-        .fetch_one(&state.pool)
-        .await?;
+        SELECT attachments.storage_key
+        FROM attachments
+        JOIN purged ON attachments.expense_id = purged.id
+        "#,
+    )
+    .bind(user_id)
+    .bind(state.config.trash_retention_days as i32)
+    .fetch_all(&state.pool)
+    .await?;
 
-        if !category_exists {
-            return Err(AppError::NotFound("Category not found".to_string()));
-        }
+    storage::delete_all(state.storage.as_ref(), &storage_keys).await;
+
+    Ok(())
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/expenses/trash",
+    tag = "expenses",
+    responses(
+        (status = 200, description = "Deleted expenses that can still be restored, most recently deleted first", body = [TrashedExpense]),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn list_trash(
+    State(state): State<AppState>,
+    user: AuthUser,
+) -> AppResult<Json<Vec<TrashedExpense>>> {
+    purge_expired_trash(&state, user.user_id).await?;
+
+    let trash = sqlx::query_as::<_, TrashedExpense>(
+        r#"
+        SELECT
+            expenses.id,
+            expenses.category_id,
+            categories.name as category_name,
+            categories.color as category_color,
+            categories.icon as category_icon,
+            expenses.amount,
+            expenses.description,
+            expenses.expense_date,
+            expenses.deleted_at,
+            purge.at as purge_at,
+            GREATEST(CEIL(EXTRACT(EPOCH FROM purge.at - NOW()) / 86400), 0)::INTEGER
+                as days_until_purge
+        FROM expenses
+        JOIN categories ON expenses.category_id = categories.id
+        CROSS JOIN LATERAL (
+            SELECT expenses.deleted_at + make_interval(days => $2) as at
+        ) purge
+        WHERE expenses.user_id = $1 AND expenses.deleted_at IS NOT NULL
+        ORDER BY expenses.deleted_at DESC, expenses.id
+        "#,
+    )
+    .bind(user.user_id)
+    .bind(state.config.trash_retention_days as i32)
+    .fetch_all(&state.pool)
+    .await?;
+
+    Ok(Json(trash))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/expenses/{id}/restore",
+    tag = "expenses",
+    params(("id" = Uuid, Path, description = "Expense ID")),
+    responses(
+        (status = 200, description = "Expense restored from the trash", body = ExpenseWithCategory),
+        (status = 404, description = "Expense not in the trash"),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn restore_expense(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path(id): Path<Uuid>,
+) -> AppResult<Json<ExpenseWithCategory>> {
+    purge_expired_trash(&state, user.user_id).await?;
+
+    let result = sqlx::query(
+        r#"
+        UPDATE expenses SET deleted_at = NULL, updated_at = NOW()
+        WHERE id = $1 AND user_id = $2 AND deleted_at IS NOT NULL
+        "#,
+    )
+    .bind(id)
+    .bind(user.user_id)
+    .execute(&state.pool)
+    .await?;
+
+    if result.rows_affected() == 0 {
+        return Err(AppError::NotFound("Expense not in the trash".to_string()));
     }
+    publish_expense_event(&state, user.user_id, ExpenseEventKind::Restored, id);
 
-    let mut sql = String::from("UPDATE expenses SET updated_at = NOW()");
-    let mut update_fields = Vec::new();
+    let expense = fetch_expense(&state.pool, id, user.user_id)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Expense not found".to_string()))?;
 
-    if let Some(category_id) = payload.category_id {
-        update_fields.push(format!("category_id = '{}'", category_id));
+    Ok(Json(expense))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/expenses/{id}/purge",
+    tag = "expenses",
+    params(("id" = Uuid, Path, description = "Expense ID")),
+    responses(
+        (status = 204, description = "Expense permanently deleted"),
+        (status = 404, description = "Expense not in the trash"),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn purge_expense(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path(id): Path<Uuid>,
+) -> AppResult<StatusCode> {
+    // Only trashed expenses, so a live one can't skip the trash by mistake.
+    // One row per attachment (or a single row without one) of the purged
+    // expense, read before the cascade removes them.
+    let purged = sqlx::query_as::<_, (Uuid, Option<String>)>(
+        r#"
+        WITH purged AS (
+            DELETE FROM expenses
+            WHERE id = $1 AND user_id = $2 AND deleted_at IS NOT NULL
+            RETURNING id
+        )
+        SELECT purged.id, attachments.storage_key
+        FROM purged
+        LEFT JOIN attachments ON attachments.expense_id = purged.id
+        "#,
+    )
+    .bind(id)
+    .bind(user.user_id)
+    .fetch_all(&state.pool)
+    .await?;
+
+    if purged.is_empty() {
+        return Err(AppError::NotFound("Expense not in the trash".to_string()));
     }
 
-    if let Some(amount) = payload.amount {
-        let decimal_amount = Decimal::try_from(amount)
-            .map_err(|_| AppError::Validation("Invalid amount".to_string()))?;
-        update_fields.push(format!("amount = {}", decimal_amount));
+    let storage_keys: Vec<String> = purged.into_iter().filter_map(|(_, key)| key).collect();
+    storage::delete_all(state.storage.as_ref(), &storage_keys).await;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/expenses/recategorize",
+    tag = "expenses",
+    request_body = RecategorizeExpenses,
+    responses(
+        (status = 200, description = "Matching expenses moved to the target category", body = RecategorizeResult),
+        (status = 400, description = "Invalid filter or the target category is archived"),
+        (status = 404, description = "Target category not found"),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn recategorize_expenses(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Json(payload): Json<RecategorizeExpenses>,
+) -> AppResult<Json<RecategorizeResult>> {
+    check_batch_size(&state, "filter.category_ids", payload.filter.category_ids.len())?;
+    verify_categories_owned(&state.pool, user.user_id, &[payload.to_category]).await?;
+
+    // Snapshot each moved expense into its history, like a single edit
+    // would, then move them all and hand back their old categories
+    let mut query = QueryBuilder::<Postgres>::new(
+        r#"
+        WITH matched AS (
+            SELECT
+                expenses.id,
+                expenses.category_id,
+                expenses.amount,
+                expenses.description,
+                expenses.expense_date,
+                expenses.updated_at
+            FROM expenses
+            JOIN categories ON expenses.category_id = categories.id
+        "#,
+    );
+    push_expense_filters(&mut query, user.user_id, &payload.filter);
+    query
+        .push(" AND expenses.category_id <> ")
+        .push_bind(payload.to_category)
+        .push(
+            r#"
+            FOR UPDATE OF expenses
+        ), snapshot AS (
+            INSERT INTO expense_history (expense_id, category_id, amount, description, expense_date, valid_from)
+            SELECT id, category_id, amount, description, expense_date, updated_at
+            FROM matched
+        )
+        UPDATE expenses
+        SET category_id = "#,
+        )
+        .push_bind(payload.to_category)
+        .push(
+            r#", updated_at = NOW()
+        FROM matched
+        WHERE expenses.id = matched.id
+        RETURNING expenses.id, matched.category_id
+        "#,
+        );
+
+    let mut tx = state.pool.begin().await?;
+    let moved: Vec<(Uuid, Uuid)> = query.build_query_as().fetch_all(&mut *tx).await?;
+    let (ids, old_categories): (Vec<Uuid>, Vec<Uuid>) = moved.into_iter().unzip();
+
+    // The split part booked under the old category follows the expense,
+    // adding to any part already under the target
+    sqlx::query(
+        r#"
+        INSERT INTO expense_splits (expense_id, category_id, amount)
+        SELECT expense_splits.expense_id, $3, expense_splits.amount
+        FROM expense_splits
+        JOIN UNNEST($1::UUID[], $2::UUID[]) AS moved(expense_id, category_id)
+            ON expense_splits.expense_id = moved.expense_id
+            AND expense_splits.category_id = moved.category_id
+        ON CONFLICT (expense_id, category_id)
+        DO UPDATE SET amount = expense_splits.amount + EXCLUDED.amount
+        "#,
+    )
+    .bind(&ids)
+    .bind(&old_categories)
+    .bind(payload.to_category)
+    .execute(&mut *tx)
+    .await?;
+
+    sqlx::query(
+        r#"
+        DELETE FROM expense_splits
+        USING UNNEST($1::UUID[], $2::UUID[]) AS moved(expense_id, category_id)
+        WHERE expense_splits.expense_id = moved.expense_id
+            AND expense_splits.category_id = moved.category_id
+        "#,
+    )
+    .bind(&ids)
+    .bind(&old_categories)
+    .execute(&mut *tx)
+    .await?;
+
+    tx.commit().await?;
+
+    if !ids.is_empty() {
+        publish_expenses_changed(&state, user.user_id);
     }
 
-    if let Some(description) = &payload.description {
-        update_fields.push(format!("description = '{}'", description.replace("'", "''")));
+    Ok(Json(RecategorizeResult {
+        updated: ids.len() as i64,
+    }))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/expenses/clone-month",
+    tag = "expenses",
+    request_body = CloneMonth,
+    responses(
+        (status = 201, description = "Copies created in the target month, oldest first", body = [ExpenseWithCategory]),
+        (status = 400, description = "Invalid month or a copy would be dated in the future"),
+        (status = 404, description = "A source expense's category no longer exists"),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn clone_month(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Json(payload): Json<CloneMonth>,
+) -> AppResult<(StatusCode, Json<Vec<ExpenseWithCategory>>)> {
+    let from = parse_month(&payload.from_month, "from_month")?;
+    let to = parse_month(&payload.to_month, "to_month")?;
+    if from == to {
+        return Err(AppError::Validation(
+            "from_month and to_month must be different".to_string(),
+        ));
     }
 
-    if let Some(expense_date) = payload.expense_date {
-        update_fields.push(format!("expense_date = '{}'", expense_date));
+    let from_end = from.with_day(days_in_month(from.year(), from.month())).expect("Valid date");
+    let to_last_day = days_in_month(to.year(), to.month());
+
+    let sources = sqlx::query_as::<_, (Uuid, Uuid, Decimal, String, NaiveDate)>(
+        r#"
+        SELECT id, category_id, amount, description, expense_date
+        FROM expenses
+        WHERE user_id = $1 AND expense_date >= $2 AND expense_date <= $3
+            AND deleted_at IS NULL
+        ORDER BY expense_date, created_at
+        "#,
+    )
+    .bind(user.user_id)
+    .bind(from)
+    .bind(from_end)
+    .fetch_all(&state.pool)
+    .await?;
+
+    if sources.is_empty() {
+        return Ok((StatusCode::CREATED, Json(Vec::new())));
     }
 
-    if !update_fields.is_empty() {
-        sql.push_str(", ");
-        sql.push_str(&update_fields.join(", "));
+    let source_ids: Vec<Uuid> = sources.iter().map(|(id, ..)| *id).collect();
+    let category_ids: Vec<Uuid> = sources.iter().map(|(_, id, ..)| *id).collect();
+
+    // The split parts are copied too, so their categories must still be usable
+    let split_category_ids = sqlx::query_scalar::<_, Uuid>(
+        "SELECT DISTINCT category_id FROM expense_splits WHERE expense_id = ANY($1)"
+    )
+    .bind(&source_ids)
+    .fetch_all(&state.pool)
+    .await?;
+    let mut checked_ids = category_ids.clone();
+    checked_ids.extend(split_category_ids);
+    verify_categories_owned(&state.pool, user.user_id, &checked_ids).await?;
+
+    // Same day of month in the target month, or its last day if that's shorter
+    let mut dates = Vec::with_capacity(sources.len());
+    for (.., date) in &sources {
+        let shifted = to.with_day(date.day().min(to_last_day)).expect("Valid date");
+        check_expense_date(&state, shifted)?;
+        dates.push(shifted);
     }
 
-    sql.push_str(&format!(
-        " WHERE id = '{}' AND user_id = '{}'",
-        id, user.user_id
-    ));
+    // Chosen here so each copy's splits can be matched to it below
+    let ids: Vec<Uuid> = sources.iter().map(|_| Uuid::new_v4()).collect();
+    let amounts: Vec<Decimal> = sources.iter().map(|(_, _, amount, ..)| *amount).collect();
+    let descriptions: Vec<String> = sources.iter().map(|(.., d, _)| d.clone()).collect();
 
-    sqlx::query(&sql).execute(&state.pool).await?;
+    let mut tx = state.pool.begin().await?;
 
-    let updated_expense = sqlx::query_as::<_, ExpenseWithCategory>(
+    sqlx::query(
+        r#"
+        INSERT INTO expenses (id, user_id, category_id, amount, description, expense_date)
+        SELECT id, $1, category_id, amount, description, expense_date
+        FROM UNNEST($2::UUID[], $3::UUID[], $4::DECIMAL[], $5::TEXT[], $6::DATE[])
+            AS copies(id, category_id, amount, description, expense_date)
+        "#,
+    )
+    .bind(user.user_id)
+    .bind(&ids)
+    .bind(&category_ids)
+    .bind(&amounts)
+    .bind(&descriptions)
+    .bind(&dates)
+    .execute(&mut *tx)
+    .await?;
+
+    // A split receipt is cloned with its parts, or it would count only
+    // towards its main category
+    sqlx::query(
+        r#"
+        INSERT INTO expense_splits (expense_id, category_id, amount)
+        SELECT copies.id, expense_splits.category_id, expense_splits.amount
+        FROM UNNEST($1::UUID[], $2::UUID[]) AS copies(source_id, id)
+        JOIN expense_splits ON expense_splits.expense_id = copies.source_id
+        "#,
+    )
+    .bind(&source_ids)
+    .bind(&ids)
+    .execute(&mut *tx)
+    .await?;
+
+    tx.commit().await?;
+    publish_expenses_changed(&state, user.user_id);
+
+    let expenses = sqlx::query_as::<_, ExpenseWithCategory>(
         r#"
         SELECT
             expenses.id,
@@ -247,30 +1479,192 @@ pub async fn update_expense(
             expenses.updated_at
         FROM expenses
         JOIN categories ON expenses.category_id = categories.id
-        WHERE expenses.id = $1
+        WHERE expenses.id = ANY($1) AND expenses.user_id = $2
+        ORDER BY expenses.expense_date, expenses.id
         "#,
     )
-    .bind(id)
-    .fetch_one(&state.pool)
+    .bind(&ids)
+    .bind(user.user_id)
+    .fetch_all(&state.pool)
     .await?;
 
-    Ok(Json(updated_expense))
+    Ok((StatusCode::CREATED, Json(expenses)))
 }
 
-pub async fn delete_expense(
+#[utoipa::path(
+    get,
+    path = "/api/expenses/descriptions",
+    tag = "expenses",
+    params(DescriptionQuery),
+    responses(
+        (status = 200, description = "Distinct past descriptions, most used first", body = [String]),
+        (status = 400, description = "Invalid limit"),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn list_descriptions(
     State(state): State<AppState>,
     user: AuthUser,
-    Path(id): Path<Uuid>,
-) -> AppResult<StatusCode> {
-    let result = sqlx::query("DELETE FROM expenses WHERE id = $1 AND user_id = $2")
-        .bind(id)
-        .bind(user.user_id)
-        .execute(&state.pool)
-        .await?;
+    Query(query): Query<DescriptionQuery>,
+) -> AppResult<Json<Vec<String>>> {
+    let limit = query.limit.unwrap_or(10);
+    if !(1..=50).contains(&limit) {
+        return Err(AppError::Validation("limit must be between 1 and 50".to_string()));
+    }
 
-    if result.rows_affected() == 0 {
-        return Err(AppError::NotFound("Expense not found".to_string()));
+    let prefix = format!("{}%", escape_like(query.q.as_deref().unwrap_or("").trim()));
+
+    // Ties go to the description used most recently
+    let descriptions = sqlx::query_scalar::<_, String>(
+        r#"
+        SELECT description
+        FROM expenses
+        WHERE user_id = $1 AND description ILIKE $2 AND deleted_at IS NULL
+        GROUP BY description
+        ORDER BY COUNT(*) DESC, MAX(expense_date) DESC, description
+        LIMIT $3
+        "#,
+    )
+    .bind(user.user_id)
+    .bind(prefix)
+    .bind(limit)
+    .fetch_all(&state.pool)
+    .await?;
+
+    Ok(Json(descriptions))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn validation_error<T: std::fmt::Debug>(result: AppResult<T>) -> String {
+        match result {
+            Err(AppError::Validation(message)) => message,
+            other => panic!("expected a validation error, got {:?}", other),
+        }
     }
 
-    Ok(StatusCode::NO_CONTENT)
+    #[test]
+    fn parse_amount_keeps_up_to_two_decimals() {
+        assert_eq!(parse_amount(42.0).unwrap(), Decimal::new(42, 0));
+        assert_eq!(parse_amount(42.1).unwrap(), Decimal::new(421, 1));
+        assert_eq!(parse_amount(42.12).unwrap(), Decimal::new(4212, 2));
+        assert_eq!(parse_amount(0.01).unwrap(), Decimal::new(1, 2));
+    }
+
+    #[test]
+    fn parse_amount_rejects_a_scale_above_two() {
+        for amount in [42.123, 0.001, 19.999, -1.005] {
+            assert_eq!(
+                validation_error(parse_amount(amount)),
+                "Amount must have at most two decimal places",
+                "{}",
+                amount
+            );
+        }
+    }
+
+    #[test]
+    fn parse_amount_leaves_the_sign_to_the_callers() {
+        assert_eq!(parse_amount(0.0).unwrap(), Decimal::ZERO);
+        assert_eq!(parse_amount(-5.25).unwrap(), Decimal::new(-525, 2));
+    }
+
+    #[test]
+    fn parse_amount_rejects_non_finite_values() {
+        for amount in [f64::NAN, f64::INFINITY, f64::NEG_INFINITY] {
+            assert_eq!(validation_error(parse_amount(amount)), "Invalid amount");
+        }
+    }
+
+    #[test]
+    fn quick_expense_tokens_in_any_order() {
+        let expected = QuickExpenseParts {
+            amount: Decimal::new(1250, 2),
+            description: "lunch with team".to_string(),
+            category: "food".to_string(),
+            expense_date: NaiveDate::from_ymd_opt(2024, 1, 15),
+        };
+
+        assert_eq!(
+            parse_quick_expense("12.50 lunch with team #food 2024-01-15").unwrap(),
+            expected
+        );
+        assert_eq!(
+            parse_quick_expense("#food 2024-01-15 lunch with 12.50 team").unwrap(),
+            expected
+        );
+    }
+
+    #[test]
+    fn quick_expense_date_defaults_to_today() {
+        let parts = parse_quick_expense("  3 coffee   #Food ").unwrap();
+        assert_eq!(parts.amount, Decimal::new(3, 0));
+        assert_eq!(parts.description, "coffee");
+        assert_eq!(parts.category, "Food");
+        assert_eq!(parts.expense_date, None);
+    }
+
+    #[test]
+    fn quick_expense_later_numbers_belong_to_the_description() {
+        let parts = parse_quick_expense("20 pack of 6 eggs #groceries").unwrap();
+        assert_eq!(parts.amount, Decimal::new(20, 0));
+        assert_eq!(parts.description, "pack of 6 eggs");
+    }
+
+    #[test]
+    fn quick_expense_errors_name_the_problem() {
+        let cases = [
+            ("lunch #food", "No amount found, e.g. 12.50"),
+            ("12.50 lunch", "No category found, add one like #food"),
+            ("12.50 #food", "No description found"),
+            ("12.50 lunch # food", "'#' must be followed by a category name"),
+            ("12.50 lunch #food #fun", "Only one #category is allowed, got '#fun'"),
+            ("12.50 lunch #food 2024-13-01", "Invalid date '2024-13-01'"),
+            (
+                "12.50 lunch #food 2024-01-15 2024-01-16",
+                "Only one date is allowed, got '2024-01-16'",
+            ),
+            ("0 lunch #food", "Amount '0' must be greater than 0"),
+            ("-4 lunch #food", "Amount '-4' must be greater than 0"),
+            ("4.567 lunch #food", "Amount '4.567' must have at most two decimal places"),
+        ];
+
+        for (text, message) in cases {
+            assert_eq!(validation_error(parse_quick_expense(text)), message, "{}", text);
+        }
+    }
+
+    #[test]
+    fn quick_category_matches_loosely() {
+        let ids: Vec<Uuid> = (0..4).map(|_| Uuid::new_v4()).collect();
+        let categories = vec![
+            (ids[0], "Food & Dining".to_string()),
+            (ids[1], "Bills & Utilities".to_string()),
+            (ids[2], "Entertainment".to_string()),
+            (ids[3], "Education".to_string()),
+        ];
+
+        assert_eq!(match_quick_category(&categories, "FOODDINING").unwrap(), ids[0]);
+        assert_eq!(match_quick_category(&categories, "billsutilities").unwrap(), ids[1]);
+        assert_eq!(match_quick_category(&categories, "ent").unwrap(), ids[2]);
+        assert_eq!(
+            validation_error(match_quick_category(&categories, "e")),
+            "'#e' matches several categories: Entertainment, Education"
+        );
+        assert_eq!(
+            validation_error(match_quick_category(&categories, "travel")),
+            "No category matches '#travel'"
+        );
+    }
+
+    #[test]
+    fn quick_expense_description_is_capped() {
+        let long = format!("1 {} #food", "a".repeat(501));
+        assert_eq!(
+            validation_error(parse_quick_expense(&long)),
+            "Description must be 1-500 characters"
+        );
+    }
 }
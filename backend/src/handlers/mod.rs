@@ -1,4 +1,61 @@
+pub mod alerts;
+pub mod attachments;
+pub mod audit;
+pub mod budgets;
 pub mod categories;
+pub mod dashboard;
+pub mod demo;
+pub mod events;
 pub mod expenses;
+pub mod reports;
+pub mod sessions;
+pub mod settings;
 pub mod summaries;
+pub mod templates;
 pub mod users;
+
+use crate::{
+    error::{AppError, AppResult},
+    AppState,
+};
+
+/// Rejects a request array (ids, imported entries, splits, ...) longer than
+/// `MAX_BATCH_SIZE`, so one oversized batch can't tie up a connection.
+///
+/// Bulk endpoints call this for each array field before any database work.
+/// `field` names the array in the error message.
+pub fn check_batch_size(state: &AppState, field: &str, len: usize) -> AppResult<()> {
+    check_len(state.config.max_batch_size, field, len)
+}
+
+fn check_len(max: usize, field: &str, len: usize) -> AppResult<()> {
+    if len > max {
+        return Err(AppError::Validation(format!(
+            "{} can have at most {} entries, got {}",
+            field, max, len
+        )));
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn batch_at_the_limit_is_allowed() {
+        assert!(check_len(500, "ids", 0).is_ok());
+        assert!(check_len(500, "ids", 500).is_ok());
+    }
+
+    #[test]
+    fn batch_over_the_limit_names_the_field() {
+        match check_len(500, "ids", 501) {
+            Err(AppError::Validation(message)) => {
+                assert_eq!(message, "ids can have at most 500 entries, got 501")
+            }
+            other => panic!("expected a validation error, got {:?}", other),
+        }
+    }
+}
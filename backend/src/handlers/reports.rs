@@ -0,0 +1,325 @@
+use axum::{
+    extract::{Query, State},
+    http::header,
+    response::IntoResponse,
+};
+use chrono::{Datelike, NaiveDate};
+use printpdf::{
+    BuiltinFont, Color, IndirectFontRef, Mm, PdfDocument, PdfDocumentReference,
+    PdfLayerReference, Rect, Rgb,
+};
+use rust_decimal::Decimal;
+
+use crate::{
+    auth::AuthUser,
+    error::{AppError, AppResult},
+    handlers::{settings::user_today, summaries::days_in_month},
+    models::MonthlyReportQuery,
+    AppState,
+};
+
+/// A4 portrait, in millimetres.
+const PAGE_WIDTH: f32 = 210.0;
+const PAGE_HEIGHT: f32 = 297.0;
+const MARGIN: f32 = 20.0;
+const LINE_HEIGHT: f32 = 6.0;
+/// Longest description printed before it is cut off with "...".
+const MAX_DESCRIPTION_CHARS: usize = 34;
+
+#[utoipa::path(
+    get,
+    path = "/api/reports/monthly.pdf",
+    tag = "reports",
+    params(MonthlyReportQuery),
+    responses(
+        (status = 200, description = "Spending report for the month as a PDF", content_type = "application/pdf", body = Vec<u8>),
+        (status = 400, description = "Invalid year or month"),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn export_monthly_report_pdf(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Query(query): Query<MonthlyReportQuery>,
+) -> AppResult<impl IntoResponse> {
+    let today = user_today(&state.pool, user.user_id).await?;
+    let year = query.year.unwrap_or(today.year());
+    let month = query.month.unwrap_or(today.month());
+    if !(1..=12).contains(&month) {
+        return Err(AppError::Validation("month must be between 1 and 12".to_string()));
+    }
+    let Some(start_date) = NaiveDate::from_ymd_opt(year, month, 1) else {
+        return Err(AppError::Validation("Invalid year".to_string()));
+    };
+    let end_date = start_date.with_day(days_in_month(year, month)).expect("Valid date");
+
+    let currency = sqlx::query_scalar::<_, String>(
+        "SELECT currency FROM user_settings WHERE user_id = $1"
+    )
+    .bind(user.user_id)
+    .fetch_optional(&state.pool)
+    .await?
+    .unwrap_or_else(|| "USD".to_string());
+
+    // Splits count toward their own categories, as in the category summaries
+    let categories = sqlx::query_as::<_, (String, Option<String>, Decimal, i64)>(
+        r#"
+        SELECT
+            categories.name,
+            categories.color,
+            SUM(expense_allocations.amount),
+            COUNT(expense_allocations.expense_id)::BIGINT
+        FROM expense_allocations
+        JOIN categories ON expense_allocations.category_id = categories.id
+        WHERE categories.user_id = $1
+          AND expense_allocations.expense_date >= $2
+          AND expense_allocations.expense_date <= $3
+          AND expense_allocations.deleted_at IS NULL
+        GROUP BY categories.id, categories.name, categories.color
+        ORDER BY SUM(expense_allocations.amount) DESC, categories.name
+        "#,
+    )
+    .bind(user.user_id)
+    .bind(start_date)
+    .bind(end_date)
+    .fetch_all(&state.pool)
+    .await?;
+
+    let expenses = sqlx::query_as::<_, (NaiveDate, String, String, Option<String>, Decimal)>(
+        r#"
+        SELECT
+            expenses.expense_date,
+            expenses.description,
+            categories.name,
+            categories.color,
+            expenses.amount
+        FROM expenses
+        JOIN categories ON expenses.category_id = categories.id
+        WHERE expenses.user_id = $1
+          AND expenses.expense_date >= $2
+          AND expenses.expense_date <= $3
+          AND expenses.deleted_at IS NULL
+        ORDER BY expenses.expense_date, expenses.created_at
+        "#,
+    )
+    .bind(user.user_id)
+    .bind(start_date)
+    .bind(end_date)
+    .fetch_all(&state.pool)
+    .await?;
+
+    let report = MonthlyReport {
+        title: format!("Expense Report - {}", start_date.format("%B %Y")),
+        currency,
+        categories,
+        expenses,
+    };
+    // The document isn't Send, so it is built only after the last await
+    let pdf = report
+        .render()
+        .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to render PDF report: {}", e)))?;
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, "application/pdf".to_string()),
+            (
+                header::CONTENT_DISPOSITION,
+                format!("attachment; filename=\"expenses-{}-{:02}.pdf\"", year, month),
+            ),
+        ],
+        pdf,
+    ))
+}
+
+struct MonthlyReport {
+    title: String,
+    currency: String,
+    /// Name, color, total and number of expenses per category, highest first
+    categories: Vec<(String, Option<String>, Decimal, i64)>,
+    /// Date, description, category name, category color and amount, oldest first
+    expenses: Vec<(NaiveDate, String, String, Option<String>, Decimal)>,
+}
+
+impl MonthlyReport {
+    fn render(&self) -> Result<Vec<u8>, printpdf::Error> {
+        let mut pdf = ReportWriter::new(&self.title)?;
+        let total: Decimal = self.expenses.iter().map(|(_, _, _, _, amount)| *amount).sum();
+
+        pdf.text(&self.title, 18.0, MARGIN, true);
+        pdf.advance(LINE_HEIGHT * 1.5);
+        pdf.text(
+            &format!(
+                "Total: {} across {} expense{}",
+                self.money(total),
+                self.expenses.len(),
+                if self.expenses.len() == 1 { "" } else { "s" }
+            ),
+            12.0,
+            MARGIN,
+            false,
+        );
+        pdf.advance(LINE_HEIGHT * 2.0);
+
+        if self.expenses.is_empty() {
+            pdf.text("No expenses were recorded in this month.", 11.0, MARGIN, false);
+            return pdf.finish();
+        }
+
+        pdf.heading("Spending by Category");
+        pdf.row(
+            &[(MARGIN + 6.0, "Category"), (110.0, "Expenses"), (140.0, "Amount"), (175.0, "Share")],
+            true,
+        );
+        for (name, color, amount, count) in &self.categories {
+            pdf.ensure_space(LINE_HEIGHT);
+            pdf.swatch(MARGIN, color.as_deref());
+            let share = if total.is_zero() {
+                Decimal::ZERO
+            } else {
+                (*amount / total * Decimal::from(100)).round_dp(1)
+            };
+            pdf.row(
+                &[
+                    (MARGIN + 6.0, name),
+                    (110.0, &count.to_string()),
+                    (140.0, &self.money(*amount)),
+                    (175.0, &format!("{}%", share)),
+                ],
+                false,
+            );
+        }
+        pdf.advance(LINE_HEIGHT);
+
+        pdf.heading("Expenses");
+        pdf.row(
+            &[
+                (MARGIN, "Date"),
+                (MARGIN + 26.0, "Description"),
+                (118.0, "Category"),
+                (160.0, "Amount"),
+            ],
+            true,
+        );
+        for (date, description, category, color, amount) in &self.expenses {
+            pdf.ensure_space(LINE_HEIGHT);
+            pdf.swatch(112.0, color.as_deref());
+            pdf.row(
+                &[
+                    (MARGIN, &date.format("%Y-%m-%d").to_string()),
+                    (MARGIN + 26.0, &truncate(description, MAX_DESCRIPTION_CHARS)),
+                    (118.0, &truncate(category, 20)),
+                    (160.0, &self.money(*amount)),
+                ],
+                false,
+            );
+        }
+
+        pdf.finish()
+    }
+
+    fn money(&self, amount: Decimal) -> String {
+        format!("{} {:.2}", self.currency, amount)
+    }
+}
+
+/// Cuts `text` to at most `max` characters, marking the cut with "...".
+fn truncate(text: &str, max: usize) -> String {
+    if text.chars().count() <= max {
+        text.to_string()
+    } else {
+        format!("{}...", text.chars().take(max - 3).collect::<String>())
+    }
+}
+
+/// `#RRGGBB` as a PDF color; anything else falls back to grey.
+fn parse_color(hex: Option<&str>) -> Color {
+    let channels = hex
+        .and_then(|h| h.strip_prefix('#'))
+        .filter(|h| h.len() == 6)
+        .and_then(|h| {
+            let channel = |i: usize| u8::from_str_radix(&h[i..i + 2], 16).ok();
+            Some((channel(0)?, channel(2)?, channel(4)?))
+        })
+        .unwrap_or((0x99, 0x99, 0x99));
+
+    Color::Rgb(Rgb::new(
+        channels.0 as f32 / 255.0,
+        channels.1 as f32 / 255.0,
+        channels.2 as f32 / 255.0,
+        None,
+    ))
+}
+
+/// Lays text out top to bottom, starting a new page when one fills up.
+///
+/// The built-in Helvetica fonts only cover Latin-1, so characters outside it
+/// (such as emoji icons) are left out of the PDF.
+struct ReportWriter {
+    doc: PdfDocumentReference,
+    layer: PdfLayerReference,
+    regular: IndirectFontRef,
+    bold: IndirectFontRef,
+    /// Baseline of the next line, from the bottom of the page
+    y: f32,
+}
+
+impl ReportWriter {
+    fn new(title: &str) -> Result<Self, printpdf::Error> {
+        let (doc, page, layer) =
+            PdfDocument::new(title, Mm(PAGE_WIDTH), Mm(PAGE_HEIGHT), "Layer 1");
+        let regular = doc.add_builtin_font(BuiltinFont::Helvetica)?;
+        let bold = doc.add_builtin_font(BuiltinFont::HelveticaBold)?;
+        let layer = doc.get_page(page).get_layer(layer);
+
+        Ok(Self {
+            doc,
+            layer,
+            regular,
+            bold,
+            y: PAGE_HEIGHT - MARGIN,
+        })
+    }
+
+    fn text(&self, text: &str, size: f32, x: f32, bold: bool) {
+        let font = if bold { &self.bold } else { &self.regular };
+        self.layer.use_text(text, size, Mm(x), Mm(self.y), font);
+    }
+
+    fn heading(&mut self, text: &str) {
+        self.ensure_space(LINE_HEIGHT * 3.0);
+        self.text(text, 14.0, MARGIN, true);
+        self.advance(LINE_HEIGHT * 1.5);
+    }
+
+    /// One line of table cells, each at its own x position.
+    fn row(&mut self, cells: &[(f32, &str)], bold: bool) {
+        self.ensure_space(LINE_HEIGHT);
+        for (x, text) in cells {
+            self.text(text, 10.0, *x, bold);
+        }
+        self.advance(LINE_HEIGHT);
+    }
+
+    /// Small filled square in the category's color, on the current line.
+    fn swatch(&self, x: f32, color: Option<&str>) {
+        self.layer.set_fill_color(parse_color(color));
+        self.layer.add_rect(Rect::new(Mm(x), Mm(self.y - 0.5), Mm(x + 3.5), Mm(self.y + 3.0)));
+        self.layer.set_fill_color(Color::Rgb(Rgb::new(0.0, 0.0, 0.0, None)));
+    }
+
+    fn advance(&mut self, height: f32) {
+        self.y -= height;
+    }
+
+    fn ensure_space(&mut self, height: f32) {
+        if self.y - height < MARGIN {
+            let (page, layer) = self.doc.add_page(Mm(PAGE_WIDTH), Mm(PAGE_HEIGHT), "Layer 1");
+            self.layer = self.doc.get_page(page).get_layer(layer);
+            self.y = PAGE_HEIGHT - MARGIN;
+        }
+    }
+
+    fn finish(self) -> Result<Vec<u8>, printpdf::Error> {
+        self.doc.save_to_bytes()
+    }
+}
@@ -0,0 +1,165 @@
+use axum::{
+    extract::{Path, State},
+    http::{header, HeaderMap, StatusCode},
+};
+use chrono::{Duration, Utc};
+use std::net::IpAddr;
+use uuid::Uuid;
+
+use crate::{
+    auth::{create_jwt, AuthUser},
+    client_ip::ClientIp,
+    error::{AppError, AppResult},
+    extract::Json,
+    handlers::audit::{record_audit_event, AuditEventType},
+    models::Session,
+    AppState,
+};
+
+/// Records a new session for `user_id` and issues a token bound to it.
+///
+/// Called on login and registration. The User-Agent header and client address
+/// (see [`ClientIp`](crate::client_ip::ClientIp)) are stored so the user can
+/// tell their devices apart when listing sessions. Expired sessions of every
+/// user are deleted on the way.
+pub async fn start_session(
+    state: &AppState,
+    user_id: Uuid,
+    headers: &HeaderMap,
+    ip: IpAddr,
+    expiration_hours: i64,
+) -> AppResult<String> {
+    let user_agent = headers
+        .get(header::USER_AGENT)
+        .and_then(|v| v.to_str().ok())
+        .map(str::to_string);
+    let expires_at = Utc::now() + Duration::hours(expiration_hours);
+
+    // Expired tokens are rejected anyway; logins are a cheap moment to drop their rows
+    sqlx::query("DELETE FROM sessions WHERE expires_at <= NOW()")
+        .execute(&state.pool)
+        .await?;
+
+    let session_id = sqlx::query_scalar::<_, Uuid>(
+        r#"
+        INSERT INTO sessions (user_id, user_agent, ip_address, expires_at)
+        VALUES ($1, $2, $3, $4)
+        RETURNING id
+        "#,
+    )
+    .bind(user_id)
+    .bind(user_agent)
+    .bind(ip.to_string())
+    .bind(expires_at)
+    .fetch_one(&state.pool)
+    .await?;
+
+    create_jwt(user_id, session_id, &state.jwt_keys, expiration_hours)
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/users/me/sessions",
+    tag = "users",
+    responses(
+        (status = 200, description = "Active sessions, most recently used first", body = [Session]),
+        (status = 401, description = "Missing or invalid token"),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn list_sessions(
+    State(state): State<AppState>,
+    user: AuthUser,
+) -> AppResult<Json<Vec<Session>>> {
+    let sessions = sqlx::query_as::<_, Session>(
+        r#"
+        SELECT id, user_agent, ip_address, created_at, last_used_at, expires_at,
+               id = $2 AS current
+        FROM sessions
+        WHERE user_id = $1 AND expires_at > NOW()
+        ORDER BY last_used_at DESC
+        "#,
+    )
+    .bind(user.user_id)
+    .bind(user.session_id)
+    .fetch_all(&state.pool)
+    .await?;
+
+    Ok(Json(sessions))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/users/me/sessions/{id}",
+    tag = "users",
+    params(("id" = Uuid, Path, description = "Session ID")),
+    responses(
+        (status = 204, description = "Session revoked"),
+        (status = 404, description = "Session not found"),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn revoke_session(
+    State(state): State<AppState>,
+    ClientIp(ip): ClientIp,
+    headers: HeaderMap,
+    user: AuthUser,
+    Path(id): Path<Uuid>,
+) -> AppResult<StatusCode> {
+    let result = sqlx::query("DELETE FROM sessions WHERE id = $1 AND user_id = $2")
+        .bind(id)
+        .bind(user.user_id)
+        .execute(&state.pool)
+        .await?;
+
+    if result.rows_affected() == 0 {
+        return Err(AppError::NotFound("Session not found".to_string()));
+    }
+
+    record_audit_event(
+        &state.pool,
+        Some(user.user_id),
+        AuditEventType::SessionRevoked,
+        None,
+        &headers,
+        ip,
+    )
+    .await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/users/me/sessions",
+    tag = "users",
+    responses(
+        (status = 204, description = "All other sessions revoked"),
+        (status = 401, description = "Missing or invalid token"),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn revoke_other_sessions(
+    State(state): State<AppState>,
+    ClientIp(ip): ClientIp,
+    headers: HeaderMap,
+    user: AuthUser,
+) -> AppResult<StatusCode> {
+    sqlx::query("DELETE FROM sessions WHERE user_id = $1 AND id <> $2")
+        .bind(user.user_id)
+        .bind(user.session_id)
+        .execute(&state.pool)
+        .await?;
+
+    record_audit_event(
+        &state.pool,
+        Some(user.user_id),
+        AuditEventType::OtherSessionsRevoked,
+        None,
+        &headers,
+        ip,
+    )
+    .await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
@@ -0,0 +1,321 @@
+use axum::extract::State;
+use chrono::{DateTime, NaiveDate, Utc};
+use chrono_tz::Tz;
+use rust_decimal::Decimal;
+use serde::de::{value, DeserializeOwned, IntoDeserializer};
+use sqlx::{PgConnection, PgPool};
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::{
+    auth::AuthUser,
+    error::{AppError, AppResult},
+    extract::Json,
+    models::{
+        CategorySort, ExpenseSortField, SortDirection, UpdateUserSettings, UserSettings,
+        CATEGORY_SORTS, DATE_FORMATS, EXPENSE_SORT_FIELDS, SORT_DIRECTIONS, WEEK_STARTS,
+    },
+    webhook::{self, generate_secret},
+    AppState,
+};
+
+/// Inserts the default settings row for a freshly registered user.
+///
+/// Runs inside the registration transaction so every user has exactly one row.
+pub async fn create_default_settings(conn: &mut PgConnection, user_id: Uuid) -> AppResult<()> {
+    sqlx::query("INSERT INTO user_settings (user_id) VALUES ($1) ON CONFLICT DO NOTHING")
+        .bind(user_id)
+        .execute(conn)
+        .await?;
+
+    Ok(())
+}
+
+/// The current date in the user's timezone, falling back to UTC when none is
+/// set (or the stored name no longer parses).
+///
+/// Use this instead of `Utc::now()` wherever "today" or "this month" decides
+/// which expenses count, since `expense_date` is the user's local date.
+pub async fn user_today(pool: &PgPool, user_id: Uuid) -> AppResult<NaiveDate> {
+    let timezone = sqlx::query_scalar::<_, Option<String>>(
+        "SELECT timezone FROM user_settings WHERE user_id = $1"
+    )
+    .bind(user_id)
+    .fetch_optional(pool)
+    .await?
+    .flatten();
+
+    Ok(local_date(Utc::now(), timezone.as_deref()))
+}
+
+/// The date `now` falls on in `timezone` (a stored setting, see [`parse_timezone`]).
+pub fn local_date(now: DateTime<Utc>, timezone: Option<&str>) -> NaiveDate {
+    now.with_timezone(&parse_timezone(timezone)).date_naive()
+}
+
+/// A stored `timezone` setting, UTC when unset or no longer valid.
+pub fn parse_timezone(timezone: Option<&str>) -> Tz {
+    timezone.and_then(|tz| tz.parse::<Tz>().ok()).unwrap_or(Tz::UTC)
+}
+
+/// Sorts applied to lists when a request doesn't ask for one.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SortPreferences {
+    pub expense_sort_by: ExpenseSortField,
+    pub expense_sort_dir: SortDirection,
+    pub category_sort: CategorySort,
+}
+
+/// The user's default sorts, falling back to the built-in ones when the
+/// settings row is missing.
+pub async fn user_sort_preferences(pool: &PgPool, user_id: Uuid) -> AppResult<SortPreferences> {
+    let stored = sqlx::query_as::<_, (String, String, String)>(
+        "SELECT expense_sort_by, expense_sort_dir, category_sort FROM user_settings WHERE user_id = $1"
+    )
+    .bind(user_id)
+    .fetch_optional(pool)
+    .await?;
+
+    let Some((expense_sort_by, expense_sort_dir, category_sort)) = stored else {
+        return Ok(SortPreferences::default());
+    };
+
+    Ok(SortPreferences {
+        expense_sort_by: parse_setting(&expense_sort_by),
+        expense_sort_dir: parse_setting(&expense_sort_dir),
+        category_sort: parse_setting(&category_sort),
+    })
+}
+
+/// Days `date_trunc('week', ...)` input is shifted by so weeks start on the
+/// user's `week_start`: 0 for Monday (also when unset), 1 for Sunday.
+pub async fn user_week_offset(pool: &PgPool, user_id: Uuid) -> AppResult<i32> {
+    let week_start = sqlx::query_scalar::<_, String>(
+        "SELECT week_start FROM user_settings WHERE user_id = $1"
+    )
+    .bind(user_id)
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(if week_start.as_deref() == Some("sunday") { 1 } else { 0 })
+}
+
+/// Reads a stored setting through the same names the query string accepts.
+/// The column CHECKs keep values valid, so the default is only a safety net.
+fn parse_setting<T: DeserializeOwned + Default>(stored: &str) -> T {
+    T::deserialize(IntoDeserializer::<value::Error>::into_deserializer(stored))
+        .unwrap_or_default()
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/users/me/settings",
+    tag = "users",
+    responses(
+        (status = 200, description = "Current user's settings", body = UserSettings),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 404, description = "Settings row missing"),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn get_settings(
+    State(state): State<AppState>,
+    user: AuthUser,
+) -> AppResult<Json<UserSettings>> {
+    let settings = sqlx::query_as::<_, UserSettings>(
+        r#"
+        SELECT
+            timezone, currency, monthly_limit, date_format, week_start, expense_sort_by,
+            expense_sort_dir, category_sort, webhook_url, webhook_secret, updated_at
+        FROM user_settings
+        WHERE user_id = $1
+        "#,
+    )
+    .bind(user.user_id)
+    .fetch_optional(&state.pool)
+    .await?
+    .ok_or_else(|| AppError::NotFound("Settings not found".to_string()))?;
+
+    Ok(Json(settings))
+}
+
+#[utoipa::path(
+    put,
+    path = "/api/users/me/settings",
+    tag = "users",
+    request_body = UpdateUserSettings,
+    responses(
+        (status = 200, description = "Settings updated", body = UserSettings),
+        (status = 400, description = "Validation error"),
+        (status = 401, description = "Missing or invalid token"),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn update_settings(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Json(payload): Json<UpdateUserSettings>,
+) -> AppResult<Json<UserSettings>> {
+    payload
+        .validate()
+        .map_err(|e| AppError::Validation(e.to_string()))?;
+
+    if payload.timezone.is_none()
+        && payload.currency.is_none()
+        && payload.monthly_limit.is_none()
+        && payload.date_format.is_none()
+        && payload.week_start.is_none()
+        && payload.expense_sort_by.is_none()
+        && payload.expense_sort_dir.is_none()
+        && payload.category_sort.is_none()
+        && payload.webhook_url.is_none()
+    {
+        return Err(AppError::Validation("No fields to update".to_string()));
+    }
+
+    if let Some(timezone) = payload.timezone.as_deref().filter(|tz| !tz.is_empty()) {
+        if timezone.parse::<Tz>().is_err() {
+            return Err(AppError::Validation(format!("Unknown timezone: {}", timezone)));
+        }
+    }
+
+    if let Some(format) = payload.date_format.as_deref() {
+        if !DATE_FORMATS.contains(&format) {
+            return Err(AppError::Validation(format!(
+                "Date format must be one of: {}",
+                DATE_FORMATS.join(", ")
+            )));
+        }
+    }
+
+    if let Some(week_start) = payload.week_start.as_deref() {
+        if !WEEK_STARTS.contains(&week_start) {
+            return Err(AppError::Validation(format!(
+                "Week start must be one of: {}",
+                WEEK_STARTS.join(", ")
+            )));
+        }
+    }
+
+    if let Some(sort_by) = payload.expense_sort_by.as_deref() {
+        if !EXPENSE_SORT_FIELDS.contains(&sort_by) {
+            return Err(AppError::Validation(format!(
+                "Expense sort must be one of: {}",
+                EXPENSE_SORT_FIELDS.join(", ")
+            )));
+        }
+    }
+
+    if let Some(sort_dir) = payload.expense_sort_dir.as_deref() {
+        if !SORT_DIRECTIONS.contains(&sort_dir) {
+            return Err(AppError::Validation(format!(
+                "Expense sort direction must be one of: {}",
+                SORT_DIRECTIONS.join(", ")
+            )));
+        }
+    }
+
+    if let Some(category_sort) = payload.category_sort.as_deref() {
+        if !CATEGORY_SORTS.contains(&category_sort) {
+            return Err(AppError::Validation(format!(
+                "Category sort must be one of: {}",
+                CATEGORY_SORTS.join(", ")
+            )));
+        }
+    }
+
+    if let Some(url) = payload.webhook_url.as_deref().filter(|url| !url.is_empty()) {
+        webhook::check_destination(url, state.config.webhook_allow_private_networks)
+            .await
+            .map_err(|e| AppError::Validation(e.to_string()))?;
+    }
+
+    let monthly_limit = payload
+        .monthly_limit
+        .map(Decimal::try_from)
+        .transpose()
+        .map_err(|_| AppError::Validation("Invalid monthly limit".to_string()))?;
+
+    // Omitting monthly_limit, timezone or webhook_url keeps it; sending 0 or ""
+    // removes it. The webhook secret is only generated once, so setting a new
+    // URL doesn't invalidate what receivers verify against.
+    let settings = sqlx::query_as::<_, UserSettings>(
+        r#"
+        UPDATE user_settings
+        SET timezone = CASE
+                WHEN $2::VARCHAR IS NULL THEN timezone
+                ELSE NULLIF($2::VARCHAR, '')
+            END,
+            currency = COALESCE($3, currency),
+            monthly_limit = CASE
+                WHEN $4::DECIMAL IS NULL THEN monthly_limit
+                WHEN $4::DECIMAL = 0 THEN NULL
+                ELSE $4::DECIMAL
+            END,
+            date_format = COALESCE($5, date_format),
+            week_start = COALESCE($6, week_start),
+            expense_sort_by = COALESCE($7, expense_sort_by),
+            expense_sort_dir = COALESCE($8, expense_sort_dir),
+            category_sort = COALESCE($9, category_sort),
+            webhook_url = CASE
+                WHEN $10::TEXT IS NULL THEN webhook_url
+                ELSE NULLIF($10::TEXT, '')
+            END,
+            webhook_secret = CASE
+                WHEN NULLIF($10::TEXT, '') IS NULL THEN webhook_secret
+                ELSE COALESCE(webhook_secret, $11)
+            END,
+            updated_at = NOW()
+        WHERE user_id = $1
+        RETURNING
+            timezone, currency, monthly_limit, date_format, week_start, expense_sort_by,
+            expense_sort_dir, category_sort, webhook_url, webhook_secret, updated_at
+        "#,
+    )
+    .bind(user.user_id)
+    .bind(&payload.timezone)
+    .bind(&payload.currency)
+    .bind(monthly_limit)
+    .bind(&payload.date_format)
+    .bind(&payload.week_start)
+    .bind(&payload.expense_sort_by)
+    .bind(&payload.expense_sort_dir)
+    .bind(&payload.category_sort)
+    .bind(&payload.webhook_url)
+    .bind(generate_secret())
+    .fetch_optional(&state.pool)
+    .await?
+    .ok_or_else(|| AppError::NotFound("Settings not found".to_string()))?;
+
+    Ok(Json(settings))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Datelike, TimeZone};
+
+    #[test]
+    fn kolkata_is_already_in_the_next_month_shortly_before_utc_midnight() {
+        // 00:15 on March 1st in Asia/Kolkata (UTC+05:30)
+        let now = Utc.with_ymd_and_hms(2024, 2, 29, 18, 45, 0).unwrap();
+
+        let local = local_date(now, Some("Asia/Kolkata"));
+        assert_eq!(local, NaiveDate::from_ymd_opt(2024, 3, 1).unwrap());
+        assert_eq!((local.year(), local.month()), (2024, 3));
+
+        // Without a timezone it's still February
+        assert_eq!(local_date(now, None), NaiveDate::from_ymd_opt(2024, 2, 29).unwrap());
+        assert_eq!(local_date(now, Some("Not/AZone")), local_date(now, None));
+    }
+
+    #[test]
+    fn kolkata_is_still_in_the_previous_month_until_its_own_midnight() {
+        // 23:59 on February 29th in Asia/Kolkata
+        let now = Utc.with_ymd_and_hms(2024, 2, 29, 18, 29, 0).unwrap();
+        assert_eq!(
+            local_date(now, Some("Asia/Kolkata")),
+            NaiveDate::from_ymd_opt(2024, 2, 29).unwrap()
+        );
+    }
+}
@@ -1,17 +1,34 @@
-use axum::{extract::State, Json};
+use axum::{
+    extract::{Query, State},
+    http::header,
+    response::IntoResponse,
+};
 use chrono::{Datelike, NaiveDate};
+use rust_decimal::Decimal;
+use sqlx::PgPool;
+use std::collections::HashMap;
+use uuid::Uuid;
 
 use crate::{
     auth::AuthUser,
-    error::AppResult,
-    models::{CategorySummary, MonthlySummary},
+    error::{AppError, AppResult},
+    extract::Json,
+    handlers::settings::{user_today, user_week_offset},
+    models::{
+        CategoryDelta, CategorySummary, CategoryYearQuery, CompareRanges, DateRange, DayExtremes,
+        DayExtremesQuery, DayTotal, HeatmapQuery, MonthlySummary, RangeComparison, RangeTotals,
+        SpendingProjection, SpendingStreaks, TimeseriesBucket, TimeseriesInterval, TimeseriesQuery,
+        YearlyComparison, YearlyComparisonQuery,
+    },
     AppState,
 };
 
-pub async fn get_monthly_summary(
-    State(state): State<AppState>,
-    user: AuthUser,
-) -> AppResult<Json<Vec<MonthlySummary>>> {
+/// Most buckets a time series may have, about three years of days.
+const MAX_TIMESERIES_BUCKETS: i64 = 1100;
+
+/// Totals for the user's 12 most recent months with expenses, newest first.
+/// Shared by the JSON and CSV endpoints and the dashboard.
+pub async fn fetch_monthly_summary(pool: &PgPool, user_id: Uuid) -> AppResult<Vec<MonthlySummary>> {
     let summaries = sqlx::query_as::<_, MonthlySummary>(
         r#"
         SELECT
@@ -20,12 +37,203 @@ pub async fn get_monthly_summary(
             SUM(amount) as total_amount,
             COUNT(*)::BIGINT as expense_count
         FROM expenses
-        WHERE user_id = $1
+        WHERE user_id = $1 AND deleted_at IS NULL
         GROUP BY month, year
         ORDER BY year DESC, MIN(expense_date) DESC
         LIMIT 12
         "#,
     )
+    .bind(user_id)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(summaries)
+}
+
+/// Current-month (in the user's timezone) totals for every category of the user, highest first.
+/// Shared by the JSON and CSV endpoints and the dashboard.
+pub async fn fetch_category_summary(pool: &PgPool, user_id: Uuid) -> AppResult<Vec<CategorySummary>> {
+    let now = user_today(pool, user_id).await?;
+    let start_of_month = NaiveDate::from_ymd_opt(now.year(), now.month(), 1)
+        .expect("Valid date");
+
+    let summaries = sqlx::query_as::<_, CategorySummary>(
+        r#"
+        SELECT
+            categories.id as category_id,
+            categories.name as category_name,
+            categories.color as category_color,
+            categories.icon as category_icon,
+            COALESCE(SUM(expense_allocations.amount), 0) as total_amount,
+            COUNT(expense_allocations.expense_id)::BIGINT as expense_count,
+            budgets.amount as budget_amount,
+            (budgets.amount IS NOT NULL AND COALESCE(SUM(expense_allocations.amount), 0) > budgets.amount) as over_budget
+        FROM categories
+        LEFT JOIN expense_allocations ON categories.id = expense_allocations.category_id
+            AND expense_allocations.expense_date >= $2
+            AND expense_allocations.deleted_at IS NULL
+        LEFT JOIN budgets ON budgets.category_id = categories.id
+        WHERE categories.user_id = $1
+        GROUP BY categories.id, categories.name, categories.color, categories.icon, budgets.amount
+        ORDER BY total_amount DESC
+        "#,
+    )
+    .bind(user_id)
+    .bind(start_of_month)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(summaries)
+}
+
+/// Quotes a CSV field if it contains a delimiter, quote or line break.
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// Wraps CSV text in a response the browser saves as `filename`.
+fn csv_response(filename: &str, body: String) -> impl IntoResponse {
+    (
+        [
+            (header::CONTENT_TYPE, "text/csv; charset=utf-8".to_string()),
+            (
+                header::CONTENT_DISPOSITION,
+                format!("attachment; filename=\"{}\"", filename),
+            ),
+        ],
+        body,
+    )
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/summaries/monthly",
+    tag = "summaries",
+    responses(
+        (status = 200, description = "Totals for the last 12 months", body = [MonthlySummary]),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn get_monthly_summary(
+    State(state): State<AppState>,
+    user: AuthUser,
+) -> AppResult<Json<Vec<MonthlySummary>>> {
+    Ok(Json(fetch_monthly_summary(&state.pool, user.user_id).await?))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/summaries/monthly.csv",
+    tag = "summaries",
+    responses(
+        (status = 200, description = "Totals for the last 12 months as CSV", content_type = "text/csv", body = String),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn export_monthly_summary_csv(
+    State(state): State<AppState>,
+    user: AuthUser,
+) -> AppResult<impl IntoResponse> {
+    let summaries = fetch_monthly_summary(&state.pool, user.user_id).await?;
+
+    let mut csv = String::from("year,month,total_amount,expense_count\n");
+    for summary in summaries {
+        csv.push_str(&format!(
+            "{},{},{},{}\n",
+            summary.year,
+            csv_field(summary.month.trim()),
+            summary.total_amount,
+            summary.expense_count
+        ));
+    }
+
+    Ok(csv_response("monthly-summary.csv", csv))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/summaries/categories",
+    tag = "summaries",
+    responses(
+        (status = 200, description = "Current month totals per category", body = [CategorySummary]),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn get_category_summary(
+    State(state): State<AppState>,
+    user: AuthUser,
+) -> AppResult<Json<Vec<CategorySummary>>> {
+    Ok(Json(fetch_category_summary(&state.pool, user.user_id).await?))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/summaries/categories.csv",
+    tag = "summaries",
+    responses(
+        (status = 200, description = "Current month totals per category as CSV", content_type = "text/csv", body = String),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn export_category_summary_csv(
+    State(state): State<AppState>,
+    user: AuthUser,
+) -> AppResult<impl IntoResponse> {
+    let summaries = fetch_category_summary(&state.pool, user.user_id).await?;
+
+    let mut csv =
+        String::from("category_name,total_amount,expense_count,budget_amount,over_budget\n");
+    for summary in summaries {
+        csv.push_str(&format!(
+            "{},{},{},{},{}\n",
+            csv_field(&summary.category_name),
+            summary.total_amount,
+            summary.expense_count,
+            summary.budget_amount.map(|b| b.to_string()).unwrap_or_default(),
+            summary.over_budget
+        ));
+    }
+
+    Ok(csv_response("category-summary.csv", csv))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/summaries/categories/all-time",
+    tag = "summaries",
+    responses(
+        (status = 200, description = "Totals per category across all expenses", body = [CategorySummary]),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn get_all_time_category_summary(
+    State(state): State<AppState>,
+    user: AuthUser,
+) -> AppResult<Json<Vec<CategorySummary>>> {
+    // Budgets are monthly, so they don't apply to an all-time total
+    let summaries = sqlx::query_as::<_, CategorySummary>(
+        r#"
+        SELECT
+            categories.id as category_id,
+            categories.name as category_name,
+            categories.color as category_color,
+            categories.icon as category_icon,
+            COALESCE(SUM(expense_allocations.amount), 0) as total_amount,
+            COUNT(expense_allocations.expense_id)::BIGINT as expense_count,
+            NULL::DECIMAL as budget_amount,
+            false as over_budget
+        FROM categories
+        LEFT JOIN expense_allocations ON categories.id = expense_allocations.category_id
+            AND expense_allocations.deleted_at IS NULL
+        WHERE categories.user_id = $1
+        GROUP BY categories.id, categories.name, categories.color, categories.icon
+        ORDER BY total_amount DESC
+        "#,
+    )
     .bind(user.user_id)
     .fetch_all(&state.pool)
     .await?;
@@ -33,14 +241,36 @@ pub async fn get_monthly_summary(
     Ok(Json(summaries))
 }
 
-pub async fn get_category_summary(
+#[utoipa::path(
+    get,
+    path = "/api/summaries/categories/yearly",
+    tag = "summaries",
+    params(CategoryYearQuery),
+    responses(
+        (status = 200, description = "Totals per category for one calendar year", body = [CategorySummary]),
+        (status = 400, description = "Invalid year"),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn get_yearly_category_summary(
     State(state): State<AppState>,
     user: AuthUser,
+    Query(query): Query<CategoryYearQuery>,
 ) -> AppResult<Json<Vec<CategorySummary>>> {
-    let now = chrono::Utc::now().naive_utc().date();
-    let start_of_month = NaiveDate::from_ymd_opt(now.year(), now.month(), 1)
-        .expect("Valid date");
+    let year = match query.year {
+        Some(year) => year,
+        None => user_today(&state.pool, user.user_id).await?.year(),
+    };
+
+    let (Some(start_date), Some(end_date)) = (
+        NaiveDate::from_ymd_opt(year, 1, 1),
+        NaiveDate::from_ymd_opt(year, 12, 31),
+    ) else {
+        return Err(AppError::Validation("Invalid year".to_string()));
+    };
 
+    // The date range is part of the join so categories without expenses in
+    // the year still get a zero row; budgets are monthly, so they don't apply
     let summaries = sqlx::query_as::<_, CategorySummary>(
         r#"
         SELECT
@@ -48,20 +278,577 @@ pub async fn get_category_summary(
             categories.name as category_name,
             categories.color as category_color,
             categories.icon as category_icon,
-            COALESCE(SUM(expenses.amount), 0) as total_amount,
-            COUNT(expenses.id)::BIGINT as expense_count
+            COALESCE(SUM(expense_allocations.amount), 0) as total_amount,
+            COUNT(expense_allocations.expense_id)::BIGINT as expense_count,
+            NULL::DECIMAL as budget_amount,
+            false as over_budget
         FROM categories
-        LEFT JOIN expenses ON categories.id = expenses.category_id
-            AND expenses.expense_date >= $2
+        LEFT JOIN expense_allocations ON categories.id = expense_allocations.category_id
+            AND expense_allocations.expense_date >= $2
+            AND expense_allocations.expense_date <= $3
+            AND expense_allocations.deleted_at IS NULL
         WHERE categories.user_id = $1
         GROUP BY categories.id, categories.name, categories.color, categories.icon
         ORDER BY total_amount DESC
         "#,
     )
     .bind(user.user_id)
-    .bind(start_of_month)
+    .bind(start_date)
+    .bind(end_date)
     .fetch_all(&state.pool)
     .await?;
 
     Ok(Json(summaries))
 }
+
+#[utoipa::path(
+    get,
+    path = "/api/summaries/projection",
+    tag = "summaries",
+    responses(
+        (status = 200, description = "Projected month-end spend", body = SpendingProjection),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn get_spending_projection(
+    State(state): State<AppState>,
+    user: AuthUser,
+) -> AppResult<Json<SpendingProjection>> {
+    let today = user_today(&state.pool, user.user_id).await?;
+    let start_of_month = NaiveDate::from_ymd_opt(today.year(), today.month(), 1)
+        .expect("Valid date");
+
+    let spent_so_far = sqlx::query_scalar::<_, Decimal>(
+        r#"
+        SELECT COALESCE(SUM(amount), 0)
+        FROM expenses
+        WHERE user_id = $1 AND expense_date >= $2 AND expense_date <= $3
+            AND deleted_at IS NULL
+        "#,
+    )
+    .bind(user.user_id)
+    .bind(start_of_month)
+    .bind(today)
+    .fetch_one(&state.pool)
+    .await?;
+
+    // Today counts as elapsed, so this is at least 1
+    let days_elapsed = today.day();
+    let days_in_month = days_in_month(today.year(), today.month());
+
+    let projected_total =
+        (spent_so_far / Decimal::from(days_elapsed) * Decimal::from(days_in_month)).round_dp(2);
+
+    Ok(Json(SpendingProjection {
+        spent_so_far,
+        projected_total,
+        days_elapsed,
+        days_in_month,
+    }))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/summaries/streaks",
+    tag = "summaries",
+    responses(
+        (status = 200, description = "Current and longest runs of days with expenses", body = SpendingStreaks),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn get_spending_streaks(
+    State(state): State<AppState>,
+    user: AuthUser,
+) -> AppResult<Json<SpendingStreaks>> {
+    let today = user_today(&state.pool, user.user_id).await?;
+    let start_of_month = NaiveDate::from_ymd_opt(today.year(), today.month(), 1)
+        .expect("Valid date");
+
+    // Gaps and islands: consecutive dates share the same date minus row number
+    let islands = sqlx::query_as::<_, (NaiveDate, NaiveDate)>(
+        r#"
+        WITH days AS (
+            SELECT DISTINCT expense_date
+            FROM expenses
+            WHERE user_id = $1 AND expense_date <= $2 AND deleted_at IS NULL
+        )
+        SELECT MIN(expense_date), MAX(expense_date)
+        FROM (
+            SELECT expense_date,
+                   expense_date - (ROW_NUMBER() OVER (ORDER BY expense_date))::INT AS island
+            FROM days
+        ) numbered
+        GROUP BY island
+        "#,
+    )
+    .bind(user.user_id)
+    .bind(today)
+    .fetch_all(&state.pool)
+    .await?;
+
+    let yesterday = today.pred_opt().expect("Valid date");
+    let current_streak_days = islands
+        .iter()
+        .find(|(_, end)| *end == today || *end == yesterday)
+        .map(|(start, end)| (*end - *start).num_days() + 1)
+        .unwrap_or(0);
+
+    // Streaks that started last month only count their days in this month
+    let longest_streak_days = islands
+        .iter()
+        .filter(|(_, end)| *end >= start_of_month)
+        .map(|(start, end)| (*end - (*start).max(start_of_month)).num_days() + 1)
+        .max()
+        .unwrap_or(0);
+
+    Ok(Json(SpendingStreaks {
+        current_streak_days,
+        longest_streak_days,
+    }))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/summaries/yearly-comparison",
+    tag = "summaries",
+    params(YearlyComparisonQuery),
+    responses(
+        (status = 200, description = "Monthly totals next to the previous year", body = [YearlyComparison]),
+        (status = 400, description = "Invalid year"),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn get_yearly_comparison(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Query(query): Query<YearlyComparisonQuery>,
+) -> AppResult<Json<Vec<YearlyComparison>>> {
+    let year = match query.year {
+        Some(year) => year,
+        None => user_today(&state.pool, user.user_id).await?.year(),
+    };
+
+    let (Some(start_date), Some(end_date)) = (
+        year.checked_sub(1).and_then(|previous| NaiveDate::from_ymd_opt(previous, 1, 1)),
+        NaiveDate::from_ymd_opt(year, 12, 31),
+    ) else {
+        return Err(AppError::Validation("Invalid year".to_string()));
+    };
+
+    let totals = sqlx::query_as::<_, (i32, i32, Decimal)>(
+        r#"
+        SELECT
+            EXTRACT(YEAR FROM expense_date)::INTEGER as year,
+            EXTRACT(MONTH FROM expense_date)::INTEGER as month,
+            SUM(amount) as total_amount
+        FROM expenses
+        WHERE user_id = $1 AND expense_date >= $2 AND expense_date <= $3
+            AND deleted_at IS NULL
+        GROUP BY year, month
+        "#,
+    )
+    .bind(user.user_id)
+    .bind(start_date)
+    .bind(end_date)
+    .fetch_all(&state.pool)
+    .await?;
+
+    let mut comparison: Vec<YearlyComparison> = (1..=12)
+        .map(|month| YearlyComparison {
+            month,
+            current_total: Decimal::ZERO,
+            previous_total: Decimal::ZERO,
+        })
+        .collect();
+
+    for (total_year, month, total_amount) in totals {
+        let entry = &mut comparison[(month - 1) as usize];
+        if total_year == year {
+            entry.current_total = total_amount;
+        } else {
+            entry.previous_total = total_amount;
+        }
+    }
+
+    Ok(Json(comparison))
+}
+
+/// Category id, name, color and icon, then total and number of expenses. The
+/// id and descriptive fields are `None` on the row totalling the whole range.
+type RangeRow = (
+    Option<Uuid>,
+    Option<String>,
+    Option<String>,
+    Option<String>,
+    Option<Decimal>,
+    i64,
+);
+
+/// Per-category totals within `range`, plus one grand-total row. Split expenses
+/// count toward each of their categories but only once in the grand total.
+async fn fetch_range_totals(
+    pool: &PgPool,
+    user_id: Uuid,
+    range: DateRange,
+) -> AppResult<Vec<RangeRow>> {
+    let rows = sqlx::query_as::<_, RangeRow>(
+        r#"
+        SELECT
+            categories.id,
+            categories.name,
+            categories.color,
+            categories.icon,
+            SUM(expense_allocations.amount),
+            COUNT(DISTINCT expense_allocations.expense_id)::BIGINT
+        FROM expense_allocations
+        JOIN categories ON expense_allocations.category_id = categories.id
+        WHERE categories.user_id = $1
+          AND expense_allocations.expense_date >= $2
+          AND expense_allocations.expense_date <= $3
+          AND expense_allocations.deleted_at IS NULL
+        GROUP BY GROUPING SETS (
+            (categories.id, categories.name, categories.color, categories.icon),
+            ()
+        )
+        "#,
+    )
+    .bind(user_id)
+    .bind(range.start)
+    .bind(range.end)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows)
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/summaries/compare",
+    tag = "summaries",
+    request_body = CompareRanges,
+    responses(
+        (status = 200, description = "Totals and per-category changes between the two ranges", body = RangeComparison),
+        (status = 400, description = "A range ends before it starts"),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn compare_ranges(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Json(payload): Json<CompareRanges>,
+) -> AppResult<Json<RangeComparison>> {
+    for (name, range) in [("a", payload.a), ("b", payload.b)] {
+        if range.start > range.end {
+            return Err(AppError::Validation(format!(
+                "{}.start must not be after {}.end",
+                name, name
+            )));
+        }
+    }
+
+    let (a_rows, b_rows) = tokio::try_join!(
+        fetch_range_totals(&state.pool, user.user_id, payload.a),
+        fetch_range_totals(&state.pool, user.user_id, payload.b),
+    )?;
+
+    let mut a = RangeTotals {
+        start: payload.a.start,
+        end: payload.a.end,
+        total_amount: Decimal::ZERO,
+        expense_count: 0,
+    };
+    let mut b = RangeTotals {
+        start: payload.b.start,
+        end: payload.b.end,
+        total_amount: Decimal::ZERO,
+        expense_count: 0,
+    };
+    let mut categories: HashMap<Uuid, CategoryDelta> = HashMap::new();
+
+    let rows = a_rows
+        .into_iter()
+        .map(|row| (true, row))
+        .chain(b_rows.into_iter().map(|row| (false, row)));
+    for (in_a, (category_id, name, color, icon, total, count)) in rows {
+        let total = total.unwrap_or(Decimal::ZERO);
+        let Some(category_id) = category_id else {
+            let range = if in_a { &mut a } else { &mut b };
+            range.total_amount = total;
+            range.expense_count = count;
+            continue;
+        };
+
+        let entry = categories.entry(category_id).or_insert_with(|| CategoryDelta {
+            category_id,
+            category_name: name.unwrap_or_default(),
+            category_color: color,
+            category_icon: icon,
+            a_total: Decimal::ZERO,
+            b_total: Decimal::ZERO,
+            delta: Decimal::ZERO,
+            a_count: 0,
+            b_count: 0,
+        });
+        if in_a {
+            entry.a_total = total;
+            entry.a_count = count;
+        } else {
+            entry.b_total = total;
+            entry.b_count = count;
+        }
+    }
+
+    let mut categories: Vec<CategoryDelta> = categories
+        .into_values()
+        .map(|mut category| {
+            category.delta = category.b_total - category.a_total;
+            category
+        })
+        .collect();
+    categories.sort_by(|x, y| {
+        y.delta.abs().cmp(&x.delta.abs()).then_with(|| x.category_name.cmp(&y.category_name))
+    });
+
+    Ok(Json(RangeComparison {
+        total_delta: b.total_amount - a.total_amount,
+        a,
+        b,
+        categories,
+    }))
+}
+
+/// The day in `range` with the highest (`highest`) or lowest total spend.
+/// Days without expenses don't count; ties go to the most recent day.
+async fn fetch_day_extreme(
+    pool: &PgPool,
+    user_id: Uuid,
+    range: DateRange,
+    highest: bool,
+) -> AppResult<Option<DayTotal>> {
+    let direction = if highest { "DESC" } else { "ASC" };
+    let day = sqlx::query_as::<_, DayTotal>(&format!(
+        r#"
+        SELECT expense_date AS date, SUM(amount) AS total_amount
+        FROM expenses
+        WHERE user_id = $1 AND expense_date >= $2 AND expense_date <= $3
+            AND deleted_at IS NULL
+        GROUP BY expense_date
+        HAVING SUM(amount) > 0
+        ORDER BY SUM(amount) {}, expense_date DESC
+        LIMIT 1
+        "#,
+        direction
+    ))
+    .bind(user_id)
+    .bind(range.start)
+    .bind(range.end)
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(day)
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/summaries/day-extremes",
+    tag = "summaries",
+    params(DayExtremesQuery),
+    responses(
+        (status = 200, description = "Most and least expensive days in the range", body = DayExtremes),
+        (status = 400, description = "start_date is after end_date"),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn get_day_extremes(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Query(query): Query<DayExtremesQuery>,
+) -> AppResult<Json<DayExtremes>> {
+    let (start_date, end_date) = match (query.start_date, query.end_date) {
+        (Some(start), Some(end)) => (start, end),
+        (start, end) => {
+            let today = user_today(&state.pool, user.user_id).await?;
+            let start_of_month = today.with_day(1).expect("Valid date");
+            (start.unwrap_or(start_of_month), end.unwrap_or(today))
+        }
+    };
+    if start_date > end_date {
+        return Err(AppError::Validation(
+            "start_date must not be after end_date".to_string(),
+        ));
+    }
+
+    let range = DateRange {
+        start: start_date,
+        end: end_date,
+    };
+    let (most_expensive, cheapest) = tokio::try_join!(
+        fetch_day_extreme(&state.pool, user.user_id, range, true),
+        fetch_day_extreme(&state.pool, user.user_id, range, false),
+    )?;
+
+    Ok(Json(DayExtremes {
+        start_date,
+        end_date,
+        most_expensive,
+        cheapest,
+    }))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/summaries/heatmap",
+    tag = "summaries",
+    params(HeatmapQuery),
+    responses(
+        (status = 200, description = "Spending per day of the year, oldest first", body = [DayTotal]),
+        (status = 400, description = "Invalid year"),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn get_spending_heatmap(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Query(query): Query<HeatmapQuery>,
+) -> AppResult<Json<Vec<DayTotal>>> {
+    let year = match query.year {
+        Some(year) => year,
+        None => user_today(&state.pool, user.user_id).await?.year(),
+    };
+
+    let (Some(start_date), Some(end_date)) = (
+        NaiveDate::from_ymd_opt(year, 1, 1),
+        NaiveDate::from_ymd_opt(year, 12, 31),
+    ) else {
+        return Err(AppError::Validation("Invalid year".to_string()));
+    };
+
+    let days = sqlx::query_as::<_, DayTotal>(
+        r#"
+        SELECT expense_date as date, SUM(amount) as total_amount
+        FROM expenses
+        WHERE user_id = $1 AND expense_date >= $2 AND expense_date <= $3
+            AND deleted_at IS NULL
+        GROUP BY expense_date
+        ORDER BY expense_date
+        "#,
+    )
+    .bind(user.user_id)
+    .bind(start_date)
+    .bind(end_date)
+    .fetch_all(&state.pool)
+    .await?;
+
+    if !query.fill.unwrap_or(false) {
+        return Ok(Json(days));
+    }
+
+    // 365 or 366 entries; walking the calendar takes care of leap years
+    let totals: HashMap<NaiveDate, Decimal> =
+        days.into_iter().map(|day| (day.date, day.total_amount)).collect();
+    let filled = start_date
+        .iter_days()
+        .take_while(|date| *date <= end_date)
+        .map(|date| DayTotal {
+            date,
+            total_amount: totals.get(&date).copied().unwrap_or(Decimal::ZERO),
+        })
+        .collect();
+
+    Ok(Json(filled))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/summaries/timeseries",
+    tag = "summaries",
+    params(TimeseriesQuery),
+    responses(
+        (status = 200, description = "Spending per bucket, oldest first, empty buckets included", body = [TimeseriesBucket]),
+        (status = 400, description = "Unknown interval, start_date after end_date or too many buckets"),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn get_spending_timeseries(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Query(query): Query<TimeseriesQuery>,
+) -> AppResult<Json<Vec<TimeseriesBucket>>> {
+    if query.start_date > query.end_date {
+        return Err(AppError::Validation(
+            "start_date must not be after end_date".to_string(),
+        ));
+    }
+
+    let days = (query.end_date - query.start_date).num_days() + 1;
+    let buckets = match query.interval {
+        TimeseriesInterval::Day => days,
+        TimeseriesInterval::Week => days / 7 + 2,
+        TimeseriesInterval::Month => {
+            i64::from(query.end_date.year() - query.start_date.year()) * 12
+                + i64::from(query.end_date.month())
+                - i64::from(query.start_date.month())
+                + 1
+        }
+    };
+    if buckets > MAX_TIMESERIES_BUCKETS {
+        return Err(AppError::Validation(format!(
+            "Range is too long for this interval (at most {} buckets)",
+            MAX_TIMESERIES_BUCKETS
+        )));
+    }
+
+    // date_trunc weeks start on Monday; shifting each date forward a day
+    // and the result back again makes them start on Sunday
+    let offset = match query.interval {
+        TimeseriesInterval::Week => user_week_offset(&state.pool, user.user_id).await?,
+        _ => 0,
+    };
+
+    // The buckets come from generate_series so empty ones are still returned
+    let series = sqlx::query_as::<_, TimeseriesBucket>(
+        r#"
+        SELECT
+            buckets.bucket_start::DATE AS bucket_start,
+            COALESCE(SUM(expenses.amount), 0) AS total_amount,
+            COUNT(expenses.id) AS expense_count
+        FROM generate_series(
+            date_trunc($4, $2::TIMESTAMP + make_interval(days => $5))
+                - make_interval(days => $5),
+            $3::TIMESTAMP,
+            ('1 ' || $4)::INTERVAL
+        ) AS buckets(bucket_start)
+        LEFT JOIN expenses
+            ON expenses.user_id = $1
+            AND expenses.expense_date >= $2 AND expenses.expense_date <= $3
+            AND expenses.deleted_at IS NULL
+            AND date_trunc($4, expenses.expense_date::TIMESTAMP + make_interval(days => $5))
+                - make_interval(days => $5) = buckets.bucket_start
+        GROUP BY buckets.bucket_start
+        ORDER BY buckets.bucket_start
+        "#,
+    )
+    .bind(user.user_id)
+    .bind(query.start_date)
+    .bind(query.end_date)
+    .bind(query.interval.as_sql())
+    .bind(offset)
+    .fetch_all(&state.pool)
+    .await?;
+
+    Ok(Json(series))
+}
+
+pub fn days_in_month(year: i32, month: u32) -> u32 {
+    let (next_year, next_month) = if month == 12 {
+        (year + 1, 1)
+    } else {
+        (year, month + 1)
+    };
+
+    NaiveDate::from_ymd_opt(next_year, next_month, 1)
+        .expect("Valid date")
+        .pred_opt()
+        .expect("Valid date")
+        .day()
+}
@@ -0,0 +1,166 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+};
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::{
+    auth::AuthUser,
+    error::{map_unique_violation, AppError, AppResult},
+    extract::Json,
+    handlers::expenses::{check_expense_amount, parse_amount, verify_categories_owned},
+    models::{CreateExpenseTemplate, ExpenseTemplate, UpdateExpenseTemplate},
+    AppState,
+};
+
+#[utoipa::path(
+    get,
+    path = "/api/expense-templates",
+    tag = "templates",
+    responses(
+        (status = 200, description = "All expense templates of the user, by name", body = [ExpenseTemplate]),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn list_templates(
+    State(state): State<AppState>,
+    user: AuthUser,
+) -> AppResult<Json<Vec<ExpenseTemplate>>> {
+    let templates = sqlx::query_as::<_, ExpenseTemplate>(
+        "SELECT * FROM expense_templates WHERE user_id = $1 ORDER BY name"
+    )
+    .bind(user.user_id)
+    .fetch_all(&state.pool)
+    .await?;
+
+    Ok(Json(templates))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/expense-templates",
+    tag = "templates",
+    request_body = CreateExpenseTemplate,
+    responses(
+        (status = 201, description = "Template created", body = ExpenseTemplate),
+        (status = 400, description = "Validation failed or the name is taken"),
+        (status = 404, description = "Category not found"),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn create_template(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Json(mut payload): Json<CreateExpenseTemplate>,
+) -> AppResult<(StatusCode, Json<ExpenseTemplate>)> {
+    payload.trim();
+    payload.validate().map_err(|e| AppError::Validation(e.to_string()))?;
+
+    let amount = parse_amount(payload.amount)?;
+    check_expense_amount(&state, amount)?;
+    verify_categories_owned(&state.pool, user.user_id, &[payload.category_id]).await?;
+
+    let template = sqlx::query_as::<_, ExpenseTemplate>(
+        r#"
+        INSERT INTO expense_templates (user_id, name, category_id, amount, description)
+        VALUES ($1, $2, $3, $4, $5)
+        RETURNING *
+        "#,
+    )
+    .bind(user.user_id)
+    .bind(&payload.name)
+    .bind(payload.category_id)
+    .bind(amount)
+    .bind(&payload.description)
+    .fetch_one(&state.pool)
+    .await
+    .map_err(map_unique_violation)?;
+
+    Ok((StatusCode::CREATED, Json(template)))
+}
+
+#[utoipa::path(
+    put,
+    path = "/api/expense-templates/{id}",
+    tag = "templates",
+    params(("id" = Uuid, Path, description = "Template ID")),
+    request_body = UpdateExpenseTemplate,
+    responses(
+        (status = 200, description = "Template updated", body = ExpenseTemplate),
+        (status = 400, description = "Validation failed or the name is taken"),
+        (status = 404, description = "Template or category not found"),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn update_template(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path(id): Path<Uuid>,
+    Json(mut payload): Json<UpdateExpenseTemplate>,
+) -> AppResult<Json<ExpenseTemplate>> {
+    payload.trim();
+    payload.validate().map_err(|e| AppError::Validation(e.to_string()))?;
+
+    let amount = payload.amount.map(parse_amount).transpose()?;
+    if let Some(amount) = amount {
+        check_expense_amount(&state, amount)?;
+    }
+    if let Some(category_id) = payload.category_id {
+        verify_categories_owned(&state.pool, user.user_id, &[category_id]).await?;
+    }
+
+    let template = sqlx::query_as::<_, ExpenseTemplate>(
+        r#"
+        UPDATE expense_templates
+        SET name = COALESCE($3, name),
+            category_id = COALESCE($4, category_id),
+            amount = COALESCE($5, amount),
+            description = COALESCE($6, description),
+            updated_at = NOW()
+        WHERE id = $1 AND user_id = $2
+        RETURNING *
+        "#,
+    )
+    .bind(id)
+    .bind(user.user_id)
+    .bind(&payload.name)
+    .bind(payload.category_id)
+    .bind(amount)
+    .bind(&payload.description)
+    .fetch_optional(&state.pool)
+    .await
+    .map_err(map_unique_violation)?
+    .ok_or_else(|| AppError::NotFound("Template not found".to_string()))?;
+
+    Ok(Json(template))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/expense-templates/{id}",
+    tag = "templates",
+    params(("id" = Uuid, Path, description = "Template ID")),
+    responses(
+        (status = 204, description = "Template removed; expenses created from it are kept"),
+        (status = 404, description = "Template not found"),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn delete_template(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path(id): Path<Uuid>,
+) -> AppResult<StatusCode> {
+    let result = sqlx::query("DELETE FROM expense_templates WHERE id = $1 AND user_id = $2")
+        .bind(id)
+        .bind(user.user_id)
+        .execute(&state.pool)
+        .await?;
+
+    if result.rows_affected() == 0 {
+        return Err(AppError::NotFound("Template not found".to_string()));
+    }
+
+    Ok(StatusCode::NO_CONTENT)
+}
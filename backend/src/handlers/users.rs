@@ -1,32 +1,87 @@
-use axum::{extract::State, http::StatusCode, Json};
+use axum::{
+    extract::State,
+    http::{HeaderMap, StatusCode},
+};
+use sqlx::PgConnection;
+use uuid::Uuid;
 use validator::Validate;
 
 use crate::{
-    auth::{create_jwt, hash_password, verify_password},
-    error::{AppError, AppResult},
-    models::{AuthResponse, CreateUser, LoginRequest, User, UserResponse},
+    auth::{hash_password, validate_password_strength, verify_password},
+    client_ip::ClientIp,
+    error::{map_unique_violation, AppError, AppResult},
+    extract::Json,
+    handlers::{
+        audit::{record_audit_event, AuditEventType},
+        sessions::start_session,
+        settings::create_default_settings,
+    },
+    models::{
+        AuthResponse, CreateUser, IntegrityReport, LoginRequest, UpdateUser, User, UserResponse,
+    },
     AppState,
 };
 
+/// Starter categories (name, color, icon) every new user gets, matching the
+/// set backfilled for existing users by the categories migration.
+const DEFAULT_CATEGORIES: &[(&str, &str, &str)] = &[
+    ("Food & Dining", "#FF6B6B", "🍔"),
+    ("Transportation", "#4ECDC4", "🚗"),
+    ("Shopping", "#45B7D1", "🛍️"),
+    ("Entertainment", "#96CEB4", "🎬"),
+    ("Bills & Utilities", "#FFEAA7", "💡"),
+    ("Healthcare", "#DFE6E9", "🏥"),
+    ("Other", "#B2BEC3", "📦"),
+];
+
+/// Inserts [`DEFAULT_CATEGORIES`] for a newly created user.
+///
+/// Takes a connection rather than the pool so it can run inside the
+/// registration transaction.
+pub async fn seed_default_categories(conn: &mut PgConnection, user_id: Uuid) -> AppResult<()> {
+    for (sort_order, (name, color, icon)) in DEFAULT_CATEGORIES.iter().enumerate() {
+        sqlx::query(
+            r#"
+            INSERT INTO categories (user_id, name, color, icon, sort_order)
+            VALUES ($1, $2, $3, $4, $5)
+            "#,
+        )
+        .bind(user_id)
+        .bind(name)
+        .bind(color)
+        .bind(icon)
+        .bind(sort_order as i32)
+        .execute(&mut *conn)
+        .await?;
+    }
+
+    Ok(())
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/auth/register",
+    tag = "auth",
+    request_body = CreateUser,
+    responses(
+        (status = 201, description = "User registered", body = AuthResponse),
+        (status = 400, description = "Validation failed or email already registered"),
+    )
+)]
 pub async fn register(
     State(state): State<AppState>,
-    Json(payload): Json<CreateUser>,
+    ClientIp(ip): ClientIp,
+    headers: HeaderMap,
+    Json(mut payload): Json<CreateUser>,
 ) -> AppResult<(StatusCode, Json<AuthResponse>)> {
+    payload.normalize();
     payload.validate().map_err(|e| AppError::Validation(e.to_string()))?;
-
-    let email_exists = sqlx::query_scalar::<_, bool>(
-        "SELECT EXISTS(SELECT 1 FROM users WHERE email = $1)"
-    )
-    .bind(&payload.email)
-    .fetch_one(&state.pool)
-    .await?;
-
-    if email_exists {
-        return Err(AppError::Validation("Email already registered".to_string()));
-    }
+    validate_password_strength(&payload.password, &state.config.password_policy)?;
 
     let password_hash = hash_password(&payload.password)?;
 
+    let mut tx = state.pool.begin().await?;
+
     let user = sqlx::query_as::<_, User>(
         r#"
         INSERT INTO users (email, password_hash, full_name)
@@ -37,14 +92,23 @@ pub async fn register(
     .bind(&payload.email)
     .bind(&password_hash)
     .bind(&payload.full_name)
-    .fetch_one(&state.pool)
-    .await?;
+    .fetch_one(&mut *tx)
+    .await
+    .map_err(map_unique_violation)?;
+
+    seed_default_categories(&mut tx, user.id).await?;
+    create_default_settings(&mut tx, user.id).await?;
 
-    let token = create_jwt(
+    tx.commit().await?;
+
+    let token = start_session(
+        &state,
         user.id,
-        &state.config.jwt_secret,
+        &headers,
+        ip,
         state.config.jwt_expiration_hours,
-    )?;
+    )
+    .await?;
 
     let response = AuthResponse {
         token,
@@ -54,25 +118,57 @@ pub async fn register(
     Ok((StatusCode::CREATED, Json(response)))
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/auth/login",
+    tag = "auth",
+    request_body = LoginRequest,
+    responses(
+        (status = 200, description = "Login successful", body = AuthResponse),
+        (status = 401, description = "Invalid credentials"),
+    )
+)]
 pub async fn login(
     State(state): State<AppState>,
-    Json(payload): Json<LoginRequest>,
+    ClientIp(ip): ClientIp,
+    headers: HeaderMap,
+    Json(mut payload): Json<LoginRequest>,
 ) -> AppResult<Json<AuthResponse>> {
+    payload.normalize();
     payload.validate().map_err(|e| AppError::Validation(e.to_string()))?;
 
     let user = sqlx::query_as::<_, User>("SELECT * FROM users WHERE email = $1")
         .bind(&payload.email)
         .fetch_optional(&state.pool)
-        .await?
-        .ok_or_else(|| AppError::Authentication("Invalid credentials".to_string()))?;
+        .await?;
+
+    // Unknown email and wrong password get the same response; only the audit
+    // row tells them apart, by whether it belongs to an account
+    let user = match user {
+        Some(user) if verify_password(&payload.password, &user.password_hash).is_ok() => user,
+        user => {
+            record_audit_event(
+                &state.pool,
+                user.map(|u| u.id),
+                AuditEventType::LoginFailed,
+                Some(&payload.email),
+                &headers,
+                ip,
+            )
+            .await?;
+            return Err(AppError::Authentication("Invalid credentials".to_string()));
+        }
+    };
 
-    verify_password(&payload.password, &user.password_hash)?;
+    let expiration_hours = if payload.remember_me {
+        state.config.jwt_remember_hours
+    } else {
+        state.config.jwt_expiration_hours
+    };
 
-    let token = create_jwt(
-        user.id,
-        &state.config.jwt_secret,
-        state.config.jwt_expiration_hours,
-    )?;
+    let token = start_session(&state, user.id, &headers, ip, expiration_hours).await?;
+    record_audit_event(&state.pool, Some(user.id), AuditEventType::Login, None, &headers, ip)
+        .await?;
 
     let response = AuthResponse {
         token,
@@ -82,6 +178,17 @@ pub async fn login(
     Ok(Json(response))
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/users/me",
+    tag = "users",
+    responses(
+        (status = 200, description = "Current user", body = UserResponse),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 404, description = "User not found"),
+    ),
+    security(("bearer_auth" = []))
+)]
 pub async fn get_current_user(
     State(state): State<AppState>,
     user: crate::auth::AuthUser,
@@ -94,3 +201,127 @@ pub async fn get_current_user(
 
     Ok(Json(user.into()))
 }
+
+#[utoipa::path(
+    put,
+    path = "/api/users/me",
+    tag = "users",
+    request_body = UpdateUser,
+    responses(
+        (status = 200, description = "Profile updated", body = UserResponse),
+        (status = 400, description = "Validation failed, email already registered, or the current password is missing or wrong for an email change"),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 404, description = "User not found"),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn update_current_user(
+    State(state): State<AppState>,
+    user: crate::auth::AuthUser,
+    ClientIp(ip): ClientIp,
+    headers: HeaderMap,
+    Json(mut payload): Json<UpdateUser>,
+) -> AppResult<Json<UserResponse>> {
+    payload.normalize();
+    payload.validate().map_err(|e| AppError::Validation(e.to_string()))?;
+
+    if payload.full_name.is_none() && payload.email.is_none() {
+        return Err(AppError::Validation("No fields to update".to_string()));
+    }
+
+    let mut tx = state.pool.begin().await?;
+
+    let current = sqlx::query_as::<_, User>("SELECT * FROM users WHERE id = $1 FOR UPDATE")
+        .bind(user.user_id)
+        .fetch_optional(&mut *tx)
+        .await?
+        .ok_or_else(|| AppError::NotFound("User not found".to_string()))?;
+
+    // Whoever holds a stolen token could otherwise take over the account by
+    // pointing its login at their own address
+    let email_changed = payload.email.as_ref().is_some_and(|email| *email != current.email);
+    if email_changed {
+        let password = payload.current_password.as_deref().ok_or_else(|| {
+            AppError::Validation("current_password is required to change the email".to_string())
+        })?;
+        // A 400 rather than a 401, which would log the client out
+        verify_password(password, &current.password_hash)
+            .map_err(|_| AppError::Validation("Current password is incorrect".to_string()))?;
+    }
+
+    let updated_user = sqlx::query_as::<_, User>(
+        r#"
+        UPDATE users
+        SET full_name = COALESCE($2, full_name),
+            email = COALESCE($3, email),
+            updated_at = NOW()
+        WHERE id = $1
+        RETURNING *
+        "#,
+    )
+    .bind(user.user_id)
+    .bind(&payload.full_name)
+    .bind(&payload.email)
+    .fetch_one(&mut *tx)
+    .await
+    .map_err(map_unique_violation)?;
+
+    if email_changed {
+        record_audit_event(
+            &mut *tx,
+            Some(user.user_id),
+            AuditEventType::EmailChanged,
+            Some(&current.email),
+            &headers,
+            ip,
+        )
+        .await?;
+    }
+
+    tx.commit().await?;
+
+    Ok(Json(updated_user.into()))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/users/me/integrity",
+    tag = "users",
+    responses(
+        (status = 200, description = "Data consistency counts for the current user", body = IntegrityReport),
+        (status = 401, description = "Missing or invalid token"),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn get_integrity_report(
+    State(state): State<AppState>,
+    user: crate::auth::AuthUser,
+) -> AppResult<Json<IntegrityReport>> {
+    // Allocations rather than expenses.category_id, so a category that only
+    // receives split parts counts as used and its split parts are checked too
+    let report = sqlx::query_as::<_, IntegrityReport>(
+        r#"
+        SELECT
+            (SELECT COUNT(DISTINCT expense_allocations.expense_id) FROM expense_allocations
+             WHERE expense_allocations.user_id = $1
+               AND NOT EXISTS (
+                   SELECT 1 FROM categories
+                   WHERE categories.id = expense_allocations.category_id
+                     AND categories.user_id = $1
+               )) as orphaned_expenses,
+            (SELECT COUNT(*) FROM expenses
+             WHERE user_id = $1 AND amount <= 0) as non_positive_amounts,
+            (SELECT COUNT(*) FROM categories
+             WHERE categories.user_id = $1
+               AND NOT EXISTS (
+                   SELECT 1 FROM expense_allocations
+                   WHERE expense_allocations.category_id = categories.id
+               )) as empty_categories
+        "#,
+    )
+    .bind(user.user_id)
+    .fetch_one(&state.pool)
+    .await?;
+
+    Ok(Json(report))
+}
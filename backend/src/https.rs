@@ -0,0 +1,75 @@
+//! Optional HTTPS check for the endpoints that issue tokens.
+//!
+//! Behind a TLS-terminating proxy the backend only ever sees plain HTTP, so it
+//! relies on the proxy's `X-Forwarded-Proto` header. With `REQUIRE_HTTPS` on,
+//! login and registration answer `400 Bad Request` unless that header says
+//! `https`, so a misconfigured deployment fails loudly instead of sending
+//! passwords and tokens in the clear. Off by default for local development.
+
+use axum::{
+    extract::Request,
+    http::HeaderMap,
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+
+use crate::error::AppError;
+
+const FORWARDED_PROTO: &str = "x-forwarded-proto";
+
+/// Whether the client connected over HTTPS according to the proxy.
+///
+/// Proxy chains may append to the header (`https, http`); the first entry is
+/// the protocol the client used. A missing header counts as plain HTTP.
+fn forwarded_https(headers: &HeaderMap) -> bool {
+    headers
+        .get(FORWARDED_PROTO)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.split(',').next())
+        .is_some_and(|proto| proto.trim().eq_ignore_ascii_case("https"))
+}
+
+/// Middleware rejecting requests that didn't reach the proxy over HTTPS.
+///
+/// ```rust,ignore
+/// auth_routes.route_layer(middleware::from_fn(require_https))
+/// ```
+pub async fn require_https(req: Request, next: Next) -> Response {
+    if forwarded_https(req.headers()) {
+        next.run(req).await
+    } else {
+        AppError::Validation("HTTPS is required for this endpoint".to_string()).into_response()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::HeaderValue;
+
+    fn with_proto(value: &'static str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(FORWARDED_PROTO, HeaderValue::from_static(value));
+        headers
+    }
+
+    #[test]
+    fn https_is_recognised_in_any_case() {
+        assert!(forwarded_https(&with_proto("https")));
+        assert!(forwarded_https(&with_proto("HTTPS")));
+        assert!(forwarded_https(&with_proto(" https ")));
+    }
+
+    #[test]
+    fn plain_or_missing_proto_is_not_https() {
+        assert!(!forwarded_https(&with_proto("http")));
+        assert!(!forwarded_https(&with_proto("")));
+        assert!(!forwarded_https(&HeaderMap::new()));
+    }
+
+    #[test]
+    fn first_hop_decides_in_a_proxy_chain() {
+        assert!(forwarded_https(&with_proto("https, http")));
+        assert!(!forwarded_https(&with_proto("http, https")));
+    }
+}
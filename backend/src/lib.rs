@@ -0,0 +1,108 @@
+//! Expense tracker REST API.
+//!
+//! The server binary in `main.rs` only reads the configuration, connects to
+//! the database and serves [`routes::create_router`]; everything else lives
+//! here so integration tests can build the same app against a test database.
+
+pub mod alerts;
+pub mod auth;
+pub mod category_cache;
+pub mod client_ip;
+pub mod color;
+pub mod config;
+pub mod db;
+pub mod error;
+pub mod events;
+pub mod extract;
+pub mod handlers;
+pub mod https;
+pub mod metrics;
+pub mod models;
+pub mod openapi;
+pub mod rate_limit;
+pub mod routes;
+pub mod storage;
+pub mod webhook;
+
+use anyhow::{bail, Context};
+use axum::{
+    body::Body,
+    http::{HeaderValue, Request},
+};
+use metrics_exporter_prometheus::PrometheusHandle;
+use sqlx::PgPool;
+use std::{sync::Arc, time::Duration};
+use tower_http::cors::{AllowHeaders, AllowMethods, Any, CorsLayer};
+
+use crate::{
+    auth::JwtKeys, category_cache::CategoryCache, config::Config, events::EventHub,
+    storage::Storage,
+};
+
+#[derive(Clone)]
+pub struct AppState {
+    pub pool: PgPool,
+    pub config: Arc<Config>,
+    pub jwt_keys: Arc<JwtKeys>,
+    pub metrics: Option<PrometheusHandle>,
+    pub storage: Arc<dyn Storage>,
+    pub category_cache: Option<Arc<CategoryCache>>,
+    pub webhook_client: reqwest::Client,
+    pub events: Arc<EventHub>,
+}
+
+/// Builds the CORS layer from the `CORS_*` settings.
+///
+/// Browsers reject credentialed responses that allow any origin, so that
+/// combination is a startup error rather than a silently broken frontend.
+pub fn cors_layer(config: &Config) -> anyhow::Result<CorsLayer> {
+    let mut cors = CorsLayer::new();
+
+    if config.cors_allow_credentials {
+        if config.cors_allows_any_origin() {
+            bail!("CORS_ALLOW_CREDENTIALS=true requires CORS_ALLOWED_ORIGINS to list origins, not *");
+        }
+        // Wildcards aren't allowed with credentials either, so echo the request back
+        cors = cors
+            .allow_credentials(true)
+            .allow_methods(AllowMethods::mirror_request())
+            .allow_headers(AllowHeaders::mirror_request());
+    } else {
+        cors = cors.allow_methods(Any).allow_headers(Any);
+    }
+
+    if config.cors_allows_any_origin() {
+        cors = cors.allow_origin(Any);
+    } else {
+        let origins = config
+            .cors_allowed_origins
+            .iter()
+            .map(|origin| {
+                origin
+                    .parse::<HeaderValue>()
+                    .with_context(|| format!("invalid origin in CORS_ALLOWED_ORIGINS: {}", origin))
+            })
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        cors = cors.allow_origin(origins);
+    }
+
+    if config.cors_max_age_secs > 0 {
+        cors = cors.max_age(Duration::from_secs(config.cors_max_age_secs));
+    }
+
+    Ok(cors)
+}
+
+/// Opens a span per request so handler logs can be correlated.
+///
+/// `user_id` starts out empty and is filled in by the [`auth::AuthUser`]
+/// extractor once the token has been validated, so unauthenticated or
+/// rejected requests never carry a user id.
+pub fn make_request_span(request: &Request<Body>) -> tracing::Span {
+    tracing::info_span!(
+        "request",
+        method = %request.method(),
+        path = %request.uri().path(),
+        user_id = tracing::field::Empty,
+    )
+}
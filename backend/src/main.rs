@@ -1,23 +1,18 @@
-mod auth;
-mod config;
-mod db;
-mod error;
-mod handlers;
-mod models;
-mod routes;
-
-use sqlx::PgPool;
-use std::sync::Arc;
-use tower_http::cors::{Any, CorsLayer};
+use std::{net::SocketAddr, sync::Arc, time::Duration};
+use tower_http::trace::TraceLayer;
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 
-use crate::config::Config;
-
-#[derive(Clone)]
-pub struct AppState {
-    pub pool: PgPool,
-    pub config: Arc<Config>,
-}
+use expense_tracker_backend::{
+    alerts,
+    auth::JwtKeys,
+    category_cache::CategoryCache,
+    config::Config,
+    cors_layer, db,
+    events::EventHub,
+    make_request_span, metrics, routes,
+    storage::LocalStorage,
+    webhook, AppState,
+};
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
@@ -33,27 +28,59 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         .init();
 
     let config = Config::from_env()?;
-    let pool = db::create_pool(&config.database_url).await?;
+    let jwt_keys = JwtKeys::from_config(&config)?;
+    let cors = cors_layer(&config)?;
+    let pool = db::create_pool(
+        &config.database_url,
+        config.db_connect_retries,
+        Duration::from_secs(config.db_connect_backoff_secs),
+    )
+    .await?;
 
     db::run_migrations(&pool).await?;
 
+    let metrics = if config.metrics_enabled {
+        tracing::info!("Prometheus metrics enabled at /metrics");
+        Some(metrics::install_recorder()?)
+    } else {
+        None
+    };
+
     let state = AppState {
         pool,
         config: Arc::new(config.clone()),
+        jwt_keys: Arc::new(jwt_keys),
+        metrics,
+        storage: Arc::new(LocalStorage::new(&config.attachments_dir)),
+        category_cache: config.category_cache_enabled.then(|| Arc::new(CategoryCache::new())),
+        webhook_client: webhook::http_client(&config)?,
+        events: Arc::new(EventHub::new()),
     };
 
-    let cors = CorsLayer::new()
-        .allow_origin(Any)
-        .allow_methods(Any)
-        .allow_headers(Any);
+    alerts::spawn_checker(state.clone());
+
+    let app = routes::create_router(state)
+        .layer(TraceLayer::new_for_http().make_span_with(make_request_span))
+        .layer(cors);
 
-    let app = routes::create_router(state).layer(cors);
+    let all_interfaces = matches!(config.server_host.as_str(), "0.0.0.0" | "::");
+    if all_interfaces && config.cors_allows_any_origin() {
+        // CORS allows any origin, so this exposes the API to every site a user
+        // on the network visits
+        tracing::warn!(
+            "Binding to all interfaces ({}) with CORS allowing any origin; \
+             set SERVER_HOST=127.0.0.1 or CORS_ALLOWED_ORIGINS unless the API must be \
+             reachable from any site",
+            config.server_host
+        );
+    }
 
     let listener = tokio::net::TcpListener::bind(&config.server_address()).await?;
 
     tracing::info!("Server listening on {}", config.server_address());
 
-    axum::serve(listener, app).await?;
+    // Connect info lets handlers see the client address (recorded on sessions)
+    axum::serve(listener, app.into_make_service_with_connect_info::<SocketAddr>()).await?;
 
     Ok(())
 }
@@ -0,0 +1,82 @@
+//! Prometheus metrics for monitoring.
+//!
+//! When `METRICS_ENABLED` is set, a global [`metrics`] recorder is installed at
+//! startup and every request passes through [`track_metrics`], which records:
+//!
+//! - `http_requests_total` - counter labelled by method, route and status
+//! - `http_request_duration_seconds` - histogram labelled the same way
+//!
+//! Database pool gauges (`db_pool_connections`, `db_pool_idle_connections`)
+//! are sampled whenever `GET /metrics` is scraped, so they always reflect the
+//! pool state at scrape time.
+
+use std::time::Instant;
+
+use axum::{
+    extract::{MatchedPath, Request, State},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use metrics_exporter_prometheus::{Matcher, PrometheusBuilder, PrometheusHandle};
+
+use crate::{error::AppError, AppState};
+
+/// Histogram buckets (in seconds) used for request latencies.
+const REQUEST_DURATION_BUCKETS: &[f64] = &[
+    0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0,
+];
+
+/// Installs the global Prometheus recorder and returns a handle for rendering.
+///
+/// Must be called at most once per process; a second call fails because the
+/// `metrics` facade only accepts a single global recorder.
+pub fn install_recorder() -> anyhow::Result<PrometheusHandle> {
+    let handle = PrometheusBuilder::new()
+        .set_buckets_for_metric(
+            Matcher::Full("http_request_duration_seconds".to_string()),
+            REQUEST_DURATION_BUCKETS,
+        )?
+        .install_recorder()?;
+
+    Ok(handle)
+}
+
+/// Middleware that records request counts and latencies per route.
+///
+/// The matched route template (e.g. `/api/expenses/:id`) is used as the
+/// `path` label rather than the raw URI, which keeps label cardinality bounded.
+pub async fn track_metrics(req: Request, next: Next) -> Response {
+    let start = Instant::now();
+    let method = req.method().to_string();
+    let path = req
+        .extensions()
+        .get::<MatchedPath>()
+        .map(|matched| matched.as_str().to_string())
+        .unwrap_or_else(|| req.uri().path().to_string());
+
+    let response = next.run(req).await;
+
+    let labels = [
+        ("method", method),
+        ("path", path),
+        ("status", response.status().as_u16().to_string()),
+    ];
+
+    metrics::counter!("http_requests_total", &labels).increment(1);
+    metrics::histogram!("http_request_duration_seconds", &labels)
+        .record(start.elapsed().as_secs_f64());
+
+    response
+}
+
+/// Renders all collected metrics in the Prometheus text exposition format.
+pub async fn metrics_handler(State(state): State<AppState>) -> Response {
+    let Some(handle) = state.metrics else {
+        return AppError::NotFound("Metrics are disabled".to_string()).into_response();
+    };
+
+    metrics::gauge!("db_pool_connections").set(state.pool.size() as f64);
+    metrics::gauge!("db_pool_idle_connections").set(state.pool.num_idle() as f64);
+
+    handle.render().into_response()
+}
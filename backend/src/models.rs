@@ -12,9 +12,12 @@
 //! - **Type Safety**: NewType pattern with UUIDs and specific types
 
 use chrono::{DateTime, NaiveDate, Utc};
+use once_cell::sync::Lazy;
+use regex::Regex;
 use rust_decimal::Decimal;
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize};
 use sqlx::FromRow;
+use utoipa::{IntoParams, ToSchema};
 use uuid::Uuid;
 use validator::Validate;
 
@@ -70,8 +73,9 @@ pub struct User {
 /// Each field has validation rules that are checked before processing.
 ///
 /// # Validation Rules
-/// - `email`: Must be a valid email format
-/// - `password`: Minimum 8 characters
+/// - `email`: Must be a valid email format; stored trimmed and lowercased
+/// - `password`: Checked against the configured password policy in the handler
+///   (by default, minimum 8 characters)
 /// - `full_name`: At least 1 character (non-empty)
 ///
 /// # Example
@@ -82,15 +86,15 @@ pub struct User {
 ///   "full_name": "John Doe"
 /// }
 /// ```
-#[derive(Debug, Deserialize, Validate)]
+#[derive(Debug, Deserialize, Validate, ToSchema)]
 pub struct CreateUser {
     /// User's email address (validated for proper email format)
     #[validate(email(message = "Invalid email address"))]
     pub email: String,
 
-    /// Plain text password (will be hashed with Argon2 before storage)
-    /// Minimum 8 characters for basic security
-    #[validate(length(min = 8, message = "Password must be at least 8 characters"))]
+    /// Plain text password (will be hashed with Argon2 before storage).
+    /// Strength rules come from `Config::password_policy`, so they're checked
+    /// by `validate_password_strength` rather than here.
     pub password: String,
 
     /// User's full name (cannot be empty)
@@ -98,6 +102,20 @@ pub struct CreateUser {
     pub full_name: String,
 }
 
+impl CreateUser {
+    /// Normalizes the email with [`normalize_email`]. Call before `validate()`.
+    pub fn normalize(&mut self) {
+        self.email = normalize_email(&self.email);
+    }
+}
+
+/// Trims and lowercases an email so `" User@Example.com"` and
+/// `"user@example.com"` are the same account. Every email that is stored or
+/// looked up must go through this.
+pub fn normalize_email(email: &str) -> String {
+    email.trim().to_lowercase()
+}
+
 /// Request body for user login.
 ///
 /// Simpler than CreateUser as we only need credentials.
@@ -107,10 +125,11 @@ pub struct CreateUser {
 /// ```json
 /// {
 ///   "email": "user@example.com",
-///   "password": "securepassword123"
+///   "password": "securepassword123",
+///   "remember_me": true
 /// }
 /// ```
-#[derive(Debug, Deserialize, Validate)]
+#[derive(Debug, Deserialize, Validate, ToSchema)]
 pub struct LoginRequest {
     /// User's email address
     #[validate(email(message = "Invalid email address"))]
@@ -118,6 +137,61 @@ pub struct LoginRequest {
 
     /// Plain text password (will be verified against stored hash)
     pub password: String,
+
+    /// Issue a longer-lived token (`JWT_REMEMBER_HOURS` instead of
+    /// `JWT_EXPIRATION_HOURS`). Defaults to false when omitted.
+    #[serde(default)]
+    pub remember_me: bool,
+}
+
+impl LoginRequest {
+    /// Normalizes the email with [`normalize_email`]. Call before `validate()`.
+    pub fn normalize(&mut self) {
+        self.email = normalize_email(&self.email);
+    }
+}
+
+/// Request body for updating the current user's profile.
+///
+/// All fields are optional - only provided fields will be updated.
+/// A new email must still be unique across all users, and changing it needs
+/// the current password, since the email is what logs in.
+///
+/// # Example (only updating name)
+/// ```json
+/// {
+///   "full_name": "Jane Doe"
+/// }
+/// ```
+///
+/// # Example (changing the email)
+/// ```json
+/// {
+///   "email": "jane@example.org",
+///   "current_password": "SecurePass123"
+/// }
+/// ```
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct UpdateUser {
+    /// New display name (optional, cannot be empty)
+    #[validate(length(min = 1, max = 255, message = "Full name must be 1-255 characters"))]
+    pub full_name: Option<String>,
+
+    /// New email address (optional, validated for proper email format)
+    #[validate(email(message = "Invalid email address"))]
+    pub email: Option<String>,
+
+    /// The account's password (required when `email` changes)
+    pub current_password: Option<String>,
+}
+
+impl UpdateUser {
+    /// Normalizes the email with [`normalize_email`]. Call before `validate()`.
+    pub fn normalize(&mut self) {
+        if let Some(email) = &mut self.email {
+            *email = normalize_email(email);
+        }
+    }
 }
 
 /// Response returned after successful authentication.
@@ -137,7 +211,7 @@ pub struct LoginRequest {
 ///   }
 /// }
 /// ```
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
 pub struct AuthResponse {
     /// JWT token for authentication (include in Authorization header)
     pub token: String,
@@ -151,7 +225,7 @@ pub struct AuthResponse {
 /// sensitive information like password hashes.
 ///
 /// Demonstrates the **From trait** for type conversion.
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
 pub struct UserResponse {
     /// User's unique identifier
     pub id: Uuid,
@@ -178,10 +252,304 @@ impl From<User> for UserResponse {
     }
 }
 
+/// Date display formats a user can choose from.
+pub const DATE_FORMATS: &[&str] = &["MMM D, YYYY", "YYYY-MM-DD", "DD/MM/YYYY", "MM/DD/YYYY"];
+
+/// Days a week can start on for weekly totals.
+pub const WEEK_STARTS: &[&str] = &["monday", "sunday"];
+
+/// Columns a user can sort their expenses by default; see [`ExpenseSortField`].
+pub const EXPENSE_SORT_FIELDS: &[&str] = &["date", "amount", "category", "description"];
+
+/// Directions for the default expense sort; see [`SortDirection`].
+pub const SORT_DIRECTIONS: &[&str] = &["asc", "desc"];
+
+/// Orders a user can list their categories in by default; see [`CategorySort`].
+pub const CATEGORY_SORTS: &[&str] = &["manual", "name", "usage"];
+
+/// ISO 4217 style currency code, e.g. `USD`.
+pub static CURRENCY_CODE_REGEX: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^[A-Z]{3}$").expect("valid currency code regex"));
+
+/// Per-user preferences, stored one row per user in `user_settings`.
+///
+/// A row with the defaults is created at registration.
+///
+/// # Example Response
+/// ```json
+/// {
+///   "timezone": "Asia/Kolkata",
+///   "currency": "INR",
+///   "monthly_limit": "50000.00",
+///   "date_format": "DD/MM/YYYY",
+///   "week_start": "monday",
+///   "expense_sort_by": "amount",
+///   "expense_sort_dir": "desc",
+///   "category_sort": "usage",
+///   "webhook_url": "https://hooks.example.com/expenses",
+///   "webhook_secret": "9f86d081884c7d659a2feaa0c55ad015a3bf4f1b2b0b822cd15d6c15b0f00a08",
+///   "updated_at": "2024-01-15T10:30:00Z"
+/// }
+/// ```
+#[derive(Debug, Serialize, FromRow, ToSchema)]
+pub struct UserSettings {
+    /// IANA timezone for "today" and month boundaries; UTC when unset
+    pub timezone: Option<String>,
+    /// Currency code amounts are displayed in
+    pub currency: String,
+    /// Overall monthly spending limit across all categories, if set
+    pub monthly_limit: Option<Decimal>,
+    /// One of [`DATE_FORMATS`]
+    pub date_format: String,
+    /// One of [`WEEK_STARTS`]
+    pub week_start: String,
+    /// Expense sort column when a request has no `sort_by`; one of [`EXPENSE_SORT_FIELDS`]
+    pub expense_sort_by: String,
+    /// Expense sort direction when a request has no `sort_dir`; one of [`SORT_DIRECTIONS`]
+    pub expense_sort_dir: String,
+    /// Category order when a request has no `sort`; one of [`CATEGORY_SORTS`]
+    pub category_sort: String,
+    /// URL that receives a POST for every new expense, if set
+    pub webhook_url: Option<String>,
+    /// Key the webhook bodies are signed with; created when a URL is first set
+    pub webhook_secret: Option<String>,
+    /// When the settings were last changed
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Request body for updating settings.
+///
+/// All fields are optional - only provided fields are changed. Send a
+/// `monthly_limit` of `0` to remove the limit, an empty `timezone` to go
+/// back to UTC and an empty `webhook_url` to stop webhook deliveries.
+///
+/// # Example
+/// ```json
+/// {
+///   "currency": "EUR",
+///   "monthly_limit": 2000.00
+/// }
+/// ```
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct UpdateUserSettings {
+    /// IANA timezone name, e.g. "Europe/Berlin" (optional, empty string clears it)
+    pub timezone: Option<String>,
+
+    /// Three-letter currency code (optional)
+    #[validate(regex(
+        path = "CURRENCY_CODE_REGEX",
+        message = "Currency must be a 3-letter code like USD"
+    ))]
+    pub currency: Option<String>,
+
+    /// Overall monthly spending limit (optional, 0 clears it)
+    #[validate(range(min = 0.0, message = "Monthly limit cannot be negative"))]
+    pub monthly_limit: Option<f64>,
+
+    /// One of [`DATE_FORMATS`] (optional)
+    pub date_format: Option<String>,
+
+    /// One of [`WEEK_STARTS`] (optional)
+    pub week_start: Option<String>,
+
+    /// One of [`EXPENSE_SORT_FIELDS`] (optional)
+    pub expense_sort_by: Option<String>,
+
+    /// One of [`SORT_DIRECTIONS`] (optional)
+    pub expense_sort_dir: Option<String>,
+
+    /// One of [`CATEGORY_SORTS`] (optional)
+    pub category_sort: Option<String>,
+
+    /// `http` or `https` URL for new-expense webhooks (optional, empty string clears it)
+    #[validate(length(max = 2048, message = "Webhook URL must be at most 2048 characters"))]
+    pub webhook_url: Option<String>,
+}
+
+/// Consistency checks over the current user's data.
+///
+/// All counts should be zero except `empty_categories`, which is
+/// informational (unused categories are harmless but may be worth cleaning up
+/// after an import).
+///
+/// # Example Response
+/// ```json
+/// {
+///   "orphaned_expenses": 0,
+///   "non_positive_amounts": 0,
+///   "empty_categories": 2
+/// }
+/// ```
+#[derive(Debug, Serialize, FromRow, ToSchema)]
+pub struct IntegrityReport {
+    /// Expenses with a category or split category that is missing or isn't owned by the user
+    pub orphaned_expenses: i64,
+    /// Expenses with an amount of zero or less
+    pub non_positive_amounts: i64,
+    /// Categories without any expenses or split parts (trashed expenses count)
+    pub empty_categories: i64,
+}
+
+/// A logged-in device, i.e. one issued access token.
+///
+/// A session is created on every login/registration and its ID is embedded in
+/// the token's `sid` claim. Deleting the row revokes the token immediately,
+/// even though the JWT itself has not yet expired.
+///
+/// # Database Schema
+/// ```sql
+/// CREATE TABLE sessions (
+///     id UUID PRIMARY KEY,
+///     user_id UUID NOT NULL REFERENCES users(id),
+///     user_agent TEXT,
+///     ip_address VARCHAR(45),
+///     created_at TIMESTAMPTZ NOT NULL,
+///     last_used_at TIMESTAMPTZ NOT NULL,
+///     expires_at TIMESTAMPTZ NOT NULL
+/// );
+/// ```
+///
+/// # Example Response
+/// ```json
+/// {
+///   "id": "5f0c2d8e-7a1b-4c3d-9e8f-0a1b2c3d4e5f",
+///   "user_agent": "Mozilla/5.0 (X11; Linux x86_64) ...",
+///   "ip_address": "203.0.113.7",
+///   "created_at": "2024-01-15T10:30:00Z",
+///   "last_used_at": "2024-01-15T12:05:00Z",
+///   "expires_at": "2024-01-16T10:30:00Z",
+///   "current": true
+/// }
+/// ```
+#[derive(Debug, Clone, FromRow, Serialize, ToSchema)]
+pub struct Session {
+    /// Session identifier (the token's `sid` claim)
+    pub id: Uuid,
+    /// User-Agent header sent when the session was created
+    pub user_agent: Option<String>,
+    /// Client IP address the session was created from
+    pub ip_address: Option<String>,
+    /// When the user logged in
+    pub created_at: DateTime<Utc>,
+    /// Last authenticated request made with this session, to within a minute
+    pub last_used_at: DateTime<Utc>,
+    /// When the session's token expires
+    pub expires_at: DateTime<Utc>,
+    /// Whether this is the session making the request
+    pub current: bool,
+}
+
+/// Options for generating demo expenses.
+///
+/// # Example URL
+/// ```text
+/// POST /api/users/me/seed-demo
+/// POST /api/users/me/seed-demo?seed=42
+/// ```
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct SeedDemoQuery {
+    /// Fixes the random generator so the same expenses are produced every time
+    pub seed: Option<u64>,
+}
+
+/// Result of generating demo expenses.
+///
+/// # Example Response
+/// ```json
+/// {
+///   "created": 143
+/// }
+/// ```
+#[derive(Debug, Serialize, ToSchema)]
+pub struct SeedDemoResult {
+    /// Number of expenses created; 0 when the user already had expenses
+    pub created: i64,
+}
+
+/// A security event recorded against the user's account.
+///
+/// Event types:
+///
+/// - `login`: A successful login
+/// - `login_failed`: A login with a wrong password for this account's email
+/// - `session_revoked`: One session was revoked
+/// - `other_sessions_revoked`: Every session but the current one was revoked
+/// - `email_changed`: The account's email was changed; `email` is the old one
+///
+/// # Example Response
+/// ```json
+/// {
+///   "id": "7c9e6679-7425-40de-944b-e07fc1f90ae7",
+///   "event_type": "login_failed",
+///   "email": "user@example.com",
+///   "ip_address": "203.0.113.7",
+///   "user_agent": "Mozilla/5.0 (X11; Linux x86_64) ...",
+///   "created_at": "2024-01-15T10:30:00Z"
+/// }
+/// ```
+#[derive(Debug, Clone, FromRow, Serialize, ToSchema)]
+pub struct AuditEvent {
+    pub id: Uuid,
+    /// What happened (see the list above)
+    pub event_type: String,
+    /// Email that was tried, for failed logins, or the previous email, for
+    /// an email change
+    pub email: Option<String>,
+    /// Client IP address the request came from
+    pub ip_address: Option<String>,
+    /// User-Agent header sent with the request
+    pub user_agent: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// One page of audit events plus the total number recorded.
+///
+/// # Example Response
+/// ```json
+/// {
+///   "items": [ ... ],
+///   "total": 12,
+///   "limit": 50,
+///   "offset": 0
+/// }
+/// ```
+#[derive(Debug, Serialize, ToSchema)]
+pub struct PaginatedAuditEvents {
+    /// Events on this page, newest first
+    pub items: Vec<AuditEvent>,
+    /// Number of events across all pages
+    pub total: i64,
+    /// Page size used
+    pub limit: i64,
+    /// Number of events skipped before this page
+    pub offset: i64,
+}
+
+/// Pagination for the audit log.
+///
+/// # Example URL
+/// ```text
+/// GET /api/users/me/audit?limit=20&offset=20
+/// ```
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct AuditLogQuery {
+    /// Maximum number of events to return (1 to `MAX_PAGE_SIZE`, default `DEFAULT_PAGE_SIZE`)
+    pub limit: Option<i64>,
+    /// Number of events to skip (default 0)
+    pub offset: Option<i64>,
+}
+
 // ============================================================================
 // Category Models
 // ============================================================================
 
+/// Matches a 6-digit hex color code such as `#4ECDC4`.
+///
+/// Category colors are rendered directly into inline styles on the frontend,
+/// so anything other than a plain hex code is rejected.
+pub static HEX_COLOR_REGEX: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^#[0-9A-Fa-f]{6}$").expect("valid hex color regex"));
+
 /// Represents an expense category in the database.
 ///
 /// Categories help users organize their expenses (e.g., Food, Transportation, Entertainment).
@@ -196,10 +564,12 @@ impl From<User> for UserResponse {
 ///     color VARCHAR(7),  -- Hex color code like "#FF6B6B"
 ///     icon VARCHAR(50),  -- Emoji or icon identifier like "🍔"
 ///     created_at TIMESTAMPTZ NOT NULL,
+///     sort_order INTEGER NOT NULL DEFAULT 0,
+///     archived BOOLEAN NOT NULL DEFAULT FALSE,
 ///     UNIQUE(user_id, name)
 /// );
 /// ```
-#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, ToSchema)]
 pub struct Category {
     /// Unique identifier for the category
     pub id: Uuid,
@@ -213,6 +583,10 @@ pub struct Category {
     pub icon: Option<String>,
     /// Timestamp when the category was created
     pub created_at: DateTime<Utc>,
+    /// Position in the user's category list, lowest first
+    pub sort_order: i32,
+    /// Hidden from pickers and closed to new expenses, but kept for history
+    pub archived: bool,
 }
 
 /// Request body for creating a new category.
@@ -225,21 +599,47 @@ pub struct Category {
 ///   "icon": "🛒"
 /// }
 /// ```
-#[derive(Debug, Deserialize, Validate)]
+#[derive(Debug, Deserialize, Validate, ToSchema)]
 pub struct CreateCategory {
     /// Category name (1-100 characters, must be unique for the user)
     #[validate(length(min = 1, max = 100, message = "Category name must be 1-100 characters"))]
     pub name: String,
-    /// Optional hex color code for visual identification
+    /// Optional hex color code for visual identification (format `#RRGGBB`)
+    #[validate(regex(path = "HEX_COLOR_REGEX", message = "Color must be a hex code like #4ECDC4"))]
     pub color: Option<String>,
     /// Optional emoji or icon for visual identification
     pub icon: Option<String>,
+    /// Optional position in the list; defaults to after every existing category
+    pub sort_order: Option<i32>,
+}
+
+impl CreateCategory {
+    /// Strips surrounding whitespace from the name so `"  "` fails the length
+    /// check and `" Food "` can't sit next to `"Food"`. Call before `validate()`.
+    pub fn trim(&mut self) {
+        self.name = self.name.trim().to_string();
+    }
+}
+
+/// Query parameters for creating a category.
+///
+/// # Example URL
+/// ```text
+/// POST /api/categories?upsert=true
+/// ```
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct CreateCategoryQuery {
+    /// Return the existing category instead of failing when the name is taken
+    /// (defaults to false)
+    pub upsert: Option<bool>,
 }
 
 /// Request body for updating an existing category.
 ///
 /// All fields are optional - only provided fields will be updated.
-/// Demonstrates Rust's **Option type** for partial updates.
+/// Demonstrates Rust's **Option type** for partial updates: `color` and
+/// `icon` are `Option<Option<String>>`, so a missing field (`None`) leaves
+/// the column alone while an explicit `null` (`Some(None)`) clears it.
 ///
 /// # Example (only updating name)
 /// ```json
@@ -247,17 +647,130 @@ pub struct CreateCategory {
 ///   "name": "Grocery Shopping"
 /// }
 /// ```
-#[derive(Debug, Deserialize, Validate)]
+///
+/// # Example (removing the icon, keeping the color)
+/// ```json
+/// {
+///   "icon": null
+/// }
+/// ```
+#[derive(Debug, Deserialize, Validate, ToSchema)]
 pub struct UpdateCategory {
     /// New category name (optional)
     #[validate(length(min = 1, max = 100, message = "Category name must be 1-100 characters"))]
     pub name: Option<String>,
-    /// New color (optional)
+    /// New color (optional, format `#RRGGBB`; `null` removes it)
+    #[serde(default, deserialize_with = "double_option")]
+    #[schema(value_type = Option<String>, nullable)]
+    #[validate(regex(path = "HEX_COLOR_REGEX", message = "Color must be a hex code like #4ECDC4"))]
+    pub color: Option<Option<String>>,
+    /// New icon (optional; `null` removes it)
+    #[serde(default, deserialize_with = "double_option")]
+    #[schema(value_type = Option<String>, nullable)]
+    pub icon: Option<Option<String>>,
+    /// New position in the list (optional)
+    pub sort_order: Option<i32>,
+}
+
+impl UpdateCategory {
+    /// Strips surrounding whitespace from the name so `"  "` fails the length
+    /// check and `" Food "` can't sit next to `"Food"`. Call before `validate()`.
+    pub fn trim(&mut self) {
+        if let Some(name) = &mut self.name {
+            *name = name.trim().to_string();
+        }
+    }
+}
+
+/// Request body for merging one category into another.
+///
+/// All expenses, split parts, templates, budgets and alerts of the category in
+/// the URL move to `into`, then the source category is deleted. Where `into`
+/// already has a budget, or an alert at the same threshold, the target's is
+/// kept and the source's is dropped.
+///
+/// # Example
+/// ```json
+/// {
+///   "into": "123e4567-e89b-12d3-a456-426614174000"
+/// }
+/// ```
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct MergeCategory {
+    /// Category that receives the expenses and is kept
+    pub into: Uuid,
+}
+
+/// Request body for reordering the user's categories.
+///
+/// `ids` must list every category of the user exactly once; each gets its
+/// index in the list as its new `sort_order`.
+///
+/// # Example
+/// ```json
+/// {
+///   "ids": [
+///     "123e4567-e89b-12d3-a456-426614174000",
+///     "987fcdeb-51a2-43f1-b456-426614174000"
+///   ]
+/// }
+/// ```
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct ReorderCategories {
+    /// Category ids in their new order
+    pub ids: Vec<Uuid>,
+}
+
+/// A category without anything tied to one account, for copying a set of
+/// categories between users.
+///
+/// The export endpoint returns a list of these and the import endpoint takes
+/// the same list back.
+///
+/// # Example
+/// ```json
+/// {
+///   "name": "Groceries",
+///   "color": "#4ECDC4",
+///   "icon": "🛒"
+/// }
+/// ```
+#[derive(Debug, Serialize, Deserialize, FromRow, Validate, ToSchema)]
+pub struct CategoryTemplate {
+    /// Category name (1-100 characters)
+    #[validate(length(min = 1, max = 100, message = "Category name must be 1-100 characters"))]
+    pub name: String,
+    /// Optional hex color code (format `#RRGGBB`)
+    #[validate(regex(path = "HEX_COLOR_REGEX", message = "Color must be a hex code like #4ECDC4"))]
     pub color: Option<String>,
-    /// New icon (optional)
+    /// Optional emoji or icon
     pub icon: Option<String>,
 }
 
+impl CategoryTemplate {
+    /// Same trimming as [`CreateCategory::trim`]. Call before `validate()`.
+    pub fn trim(&mut self) {
+        self.name = self.name.trim().to_string();
+    }
+}
+
+/// Outcome of importing a list of categories.
+///
+/// # Example
+/// ```json
+/// {
+///   "created": 3,
+///   "skipped": 2
+/// }
+/// ```
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ImportCategoriesResult {
+    /// Categories added to the user's list
+    pub created: i64,
+    /// Entries whose name the user already had, or that repeated an earlier entry
+    pub skipped: i64,
+}
+
 // ============================================================================
 // Expense Models
 // ============================================================================
@@ -298,6 +811,8 @@ pub struct Expense {
     pub created_at: DateTime<Utc>,
     /// Timestamp when the expense record was last updated
     pub updated_at: DateTime<Utc>,
+    /// When the expense was moved to the trash (`None` for live expenses)
+    pub deleted_at: Option<DateTime<Utc>>,
 }
 
 /// Expense data joined with category information.
@@ -317,7 +832,7 @@ pub struct Expense {
 /// JOIN categories ON expenses.category_id = categories.id
 /// WHERE expenses.user_id = $1
 /// ```
-#[derive(Debug, Serialize, FromRow)]
+#[derive(Debug, Serialize, FromRow, ToSchema)]
 pub struct ExpenseWithCategory {
     /// Expense unique identifier
     pub id: Uuid,
@@ -343,83 +858,781 @@ pub struct ExpenseWithCategory {
     pub updated_at: DateTime<Utc>,
 }
 
-/// Request body for creating a new expense.
+/// Response of `POST /api/expenses`: the created expense plus any non-blocking
+/// warnings about it.
 ///
-/// # Example
+/// The expense fields are inlined, so clients that ignore `warnings` read it
+/// like any other expense.
+///
+/// # Example Response
 /// ```json
 /// {
-///   "category_id": "123e4567-e89b-12d3-a456-426614174000",
-///   "amount": 42.50,
-///   "description": "Lunch at restaurant",
-///   "expense_date": "2024-01-15"
+///   "id": "123e4567-e89b-12d3-a456-426614174000",
+///   "user_id": "223e4567-e89b-12d3-a456-426614174000",
+///   "category_id": "323e4567-e89b-12d3-a456-426614174000",
+///   "category_name": "Food & Dining",
+///   "category_color": "#FF6B6B",
+///   "category_icon": "🍔",
+///   "amount": "450.00",
+///   "description": "Lunch",
+///   "expense_date": "2024-01-15",
+///   "created_at": "2024-01-15T12:00:00Z",
+///   "updated_at": "2024-01-15T12:00:00Z",
+///   "warnings": ["Amount is more than 3x your average expense of 42.10 over the last 90 days"]
 /// }
 /// ```
-#[derive(Debug, Deserialize, Validate)]
-pub struct CreateExpense {
-    /// ID of the category for this expense (must belong to the user)
-    pub category_id: Uuid,
-
-    /// Amount spent (must be greater than 0)
-    /// Stored as f64 for JSON compatibility, converted to Decimal for database
-    #[validate(range(min = 0.01, message = "Amount must be greater than 0"))]
-    pub amount: f64,
-
-    /// Description of the expense (required, at least 1 character)
-    #[validate(length(min = 1, message = "Description is required"))]
-    pub description: String,
-
-    /// Date when the expense occurred (ISO 8601 format: YYYY-MM-DD)
-    pub expense_date: NaiveDate,
+#[derive(Debug, Serialize, ToSchema)]
+pub struct CreatedExpense {
+    #[serde(flatten)]
+    pub expense: ExpenseWithCategory,
+    /// Things worth a second look, such as an unusually large amount; empty when none
+    pub warnings: Vec<String>,
 }
 
-/// Request body for updating an existing expense.
+/// One page of expenses plus the total number of matches.
 ///
-/// All fields are optional for partial updates.
-/// Demonstrates Rust's **Option type** for flexible APIs.
+/// Clients keep requesting pages with `offset` advanced by the number of items
+/// received until they have `total` items, or jump to page `n` with
+/// `offset = (n - 1) * limit`.
 ///
-/// # Example (only updating amount)
+/// # Example Response
 /// ```json
 /// {
-///   "amount": 45.00
+///   "items": [ ... ],
+///   "total": 120,
+///   "limit": 50,
+///   "offset": 0,
+///   "page": 1,
+///   "total_pages": 3
 /// }
 /// ```
-#[derive(Debug, Deserialize, Validate)]
-pub struct UpdateExpense {
-    /// New category (optional)
-    pub category_id: Option<Uuid>,
-
-    /// New amount (optional, must be > 0 if provided)
-    #[validate(range(min = 0.01, message = "Amount must be greater than 0"))]
-    pub amount: Option<f64>,
+#[derive(Debug, Serialize, ToSchema)]
+pub struct PaginatedExpenses {
+    /// Expenses on this page, newest first
+    pub items: Vec<ExpenseWithCategory>,
+    /// Number of expenses matching the filters across all pages
+    pub total: i64,
+    /// Page size used
+    pub limit: i64,
+    /// Number of matching expenses skipped before this page
+    pub offset: i64,
+    /// 1-based number of this page; an `offset` between page boundaries
+    /// counts as the page it starts in
+    pub page: i64,
+    /// Number of pages of `limit` expenses needed for `total`; 0 when nothing matches
+    pub total_pages: i64,
+}
 
-    /// New description (optional)
-    pub description: Option<String>,
+impl PaginatedExpenses {
+    /// Wraps one page of results, deriving `page` and `total_pages`.
+    /// `limit` must be positive.
+    pub fn new(items: Vec<ExpenseWithCategory>, total: i64, limit: i64, offset: i64) -> Self {
+        Self {
+            items,
+            total,
+            limit,
+            offset,
+            page: offset / limit + 1,
+            total_pages: (total + limit - 1) / limit,
+        }
+    }
+}
 
-    /// New date (optional)
-    pub expense_date: Option<NaiveDate>,
+/// One page of categories plus the total number matching the search, shaped
+/// like [`PaginatedExpenses`].
+///
+/// # Example Response
+/// ```json
+/// {
+///   "items": [ ... ],
+///   "total": 73,
+///   "limit": 50,
+///   "offset": 0,
+///   "page": 1,
+///   "total_pages": 2
+/// }
+/// ```
+#[derive(Debug, Serialize, ToSchema)]
+pub struct PaginatedCategories {
+    /// Categories on this page, in the requested sort
+    pub items: Vec<Category>,
+    /// Number of categories matching the search across all pages
+    pub total: i64,
+    /// Page size used
+    pub limit: i64,
+    /// Number of matching categories skipped before this page
+    pub offset: i64,
+    /// 1-based number of this page
+    pub page: i64,
+    /// Number of pages of `limit` categories needed for `total`; 0 when nothing matches
+    pub total_pages: i64,
 }
 
-// ============================================================================
-// Query Models
-// ============================================================================
+impl PaginatedCategories {
+    /// Wraps one page of results, deriving `page` and `total_pages`.
+    /// `limit` must be positive.
+    pub fn new(items: Vec<Category>, total: i64, limit: i64, offset: i64) -> Self {
+        Self {
+            items,
+            total,
+            limit,
+            offset,
+            page: offset / limit + 1,
+            total_pages: (total + limit - 1) / limit,
+        }
+    }
+}
 
-/// Query parameters for filtering expenses.
+/// A deleted expense waiting in the trash.
 ///
-/// Used as URL query parameters, e.g.:
-/// `/api/expenses?start_date=2024-01-01&end_date=2024-01-31&category_id=...`
+/// It can be restored until `purge_at`, after which it is removed for good.
 ///
-/// All fields are optional, allowing flexible filtering:
-/// - No params: Return all expenses
+/// # Example Response
+/// ```json
+/// {
+///   "id": "123e4567-e89b-12d3-a456-426614174000",
+///   "category_id": "987fcdeb-51a2-43f1-b456-426614174000",
+///   "category_name": "Food",
+///   "category_color": "#FF5733",
+///   "category_icon": "🍔",
+///   "amount": "42.50",
+///   "description": "Lunch at restaurant",
+///   "expense_date": "2024-01-15",
+///   "deleted_at": "2024-01-20T09:00:00Z",
+///   "purge_at": "2024-02-19T09:00:00Z",
+///   "days_until_purge": 30
+/// }
+/// ```
+#[derive(Debug, Serialize, FromRow, ToSchema)]
+pub struct TrashedExpense {
+    /// Expense unique identifier
+    pub id: Uuid,
+    /// Category this expense belongs to
+    pub category_id: Uuid,
+    /// Category name (from joined table)
+    pub category_name: String,
+    /// Category color (from joined table)
+    pub category_color: Option<String>,
+    /// Category icon (from joined table)
+    pub category_icon: Option<String>,
+    /// Amount spent
+    pub amount: Decimal,
+    /// Description of the expense
+    pub description: String,
+    /// Date of the expense
+    pub expense_date: NaiveDate,
+    /// When the expense was deleted
+    pub deleted_at: DateTime<Utc>,
+    /// When the expense will be purged
+    pub purge_at: DateTime<Utc>,
+    /// Whole days left before the purge, rounded up
+    pub days_until_purge: i32,
+}
+
+/// A previous version of an expense, recorded when it was edited.
+///
+/// # Example Response
+/// ```json
+/// {
+///   "id": "5c4b3a29-1f0e-4d8c-9b7a-6e5d4c3b2a10",
+///   "category_id": "987fcdeb-51a2-43f1-b456-426614174000",
+///   "category_name": "Food",
+///   "amount": "40.00",
+///   "description": "Lunch",
+///   "expense_date": "2024-01-15",
+///   "valid_from": "2024-01-15T14:30:00Z",
+///   "replaced_at": "2024-01-16T09:00:00Z"
+/// }
+/// ```
+#[derive(Debug, Serialize, FromRow, ToSchema)]
+pub struct ExpenseVersion {
+    /// History entry identifier
+    pub id: Uuid,
+    /// Category at the time
+    pub category_id: Uuid,
+    /// Current name of that category (`None` if it has since been merged away)
+    pub category_name: Option<String>,
+    /// Amount at the time
+    pub amount: Decimal,
+    /// Description at the time
+    pub description: String,
+    /// Expense date at the time
+    pub expense_date: NaiveDate,
+    /// When this version became current
+    pub valid_from: DateTime<Utc>,
+    /// When an edit replaced it
+    pub replaced_at: DateTime<Utc>,
+}
+
+/// Request body for creating a new expense.
+///
+/// # Example
+/// ```json
+/// {
+///   "category_id": "123e4567-e89b-12d3-a456-426614174000",
+///   "amount": 42.50,
+///   "description": "Lunch at restaurant",
+///   "expense_date": "2024-01-15"
+/// }
+/// ```
+///
+/// # Example (one receipt across two categories)
+/// ```json
+/// {
+///   "category_id": "123e4567-e89b-12d3-a456-426614174000",
+///   "amount": 80.00,
+///   "description": "Supermarket",
+///   "expense_date": "2024-01-15",
+///   "splits": [
+///     { "category_id": "123e4567-e89b-12d3-a456-426614174000", "amount": 55.00 },
+///     { "category_id": "987fcdeb-51a2-43f1-b456-426614174000", "amount": 25.00 }
+///   ]
+/// }
+/// ```
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct CreateExpense {
+    /// ID of the category for this expense (must belong to the user)
+    pub category_id: Uuid,
+
+    /// Amount spent (must be greater than 0)
+    /// Stored as f64 for JSON compatibility, converted to Decimal for database
+    #[validate(range(min = 0.01, message = "Amount must be greater than 0"))]
+    pub amount: f64,
+
+    /// Description of the expense (required, 1-500 characters after trimming)
+    #[validate(length(min = 1, max = 500, message = "Description must be 1-500 characters"))]
+    pub description: String,
+
+    /// Date when the expense occurred (ISO 8601 format: YYYY-MM-DD)
+    pub expense_date: NaiveDate,
+
+    /// Optional allocation of the amount across categories; must add up to `amount`.
+    /// Summaries attribute each part to its own category instead of `category_id`.
+    pub splits: Option<Vec<ExpenseSplit>>,
+}
+
+/// Request body for creating an expense from one line of text.
+///
+/// The text holds an amount, a description, a `#category` and optionally a
+/// `YYYY-MM-DD` date (today if left out), in any order.
+///
+/// # Example
+/// ```json
+/// {
+///   "text": "12.50 lunch #food 2024-01-15"
+/// }
+/// ```
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct QuickExpense {
+    /// The line to parse (1-600 characters)
+    #[validate(length(min = 1, max = 600, message = "Text must be 1-600 characters"))]
+    pub text: String,
+}
+
+/// The part of a split expense attributed to one category.
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct ExpenseSplit {
+    /// Category this part belongs to (must belong to the user)
+    pub category_id: Uuid,
+    /// Amount attributed to the category (greater than 0, at most two decimals)
+    pub amount: f64,
+}
+
+impl CreateExpense {
+    /// Strips surrounding whitespace so `"  "` fails the length check.
+    /// Call before `validate()`.
+    pub fn trim(&mut self) {
+        self.description = self.description.trim().to_string();
+    }
+}
+
+/// Request body for updating an existing expense.
+///
+/// All fields are optional for partial updates.
+/// Demonstrates Rust's **Option type** for flexible APIs.
+///
+/// Send back the `updated_at` you last read as `expected_updated_at` to have
+/// the update rejected with `409 Conflict` if someone else changed the expense
+/// in the meantime. Without it, the last write wins.
+///
+/// # Example (only updating amount)
+/// ```json
+/// {
+///   "amount": 45.00,
+///   "expected_updated_at": "2024-01-15T14:30:00Z"
+/// }
+/// ```
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct UpdateExpense {
+    /// New category (optional)
+    pub category_id: Option<Uuid>,
+
+    /// New amount (optional, must be > 0 if provided)
+    #[validate(range(min = 0.01, message = "Amount must be greater than 0"))]
+    pub amount: Option<f64>,
+
+    /// New description (optional, 1-500 characters after trimming)
+    #[validate(length(min = 1, max = 500, message = "Description must be 1-500 characters"))]
+    pub description: Option<String>,
+
+    /// New date (optional)
+    pub expense_date: Option<NaiveDate>,
+
+    /// `updated_at` the client last saw (optional, enables the conflict check)
+    pub expected_updated_at: Option<DateTime<Utc>>,
+}
+
+impl UpdateExpense {
+    /// Strips surrounding whitespace so `"  "` fails the length check.
+    /// Call before `validate()`.
+    pub fn trim(&mut self) {
+        if let Some(description) = &mut self.description {
+            *description = description.trim().to_string();
+        }
+    }
+}
+
+/// Request body for copying one month's expenses into another month.
+///
+/// Months are written `YYYY-MM`. Copies keep their day of month, clamped to
+/// the last day when the target month is shorter (Jan 31 becomes Feb 28).
+///
+/// # Example
+/// ```json
+/// {
+///   "from_month": "2024-01",
+///   "to_month": "2024-02"
+/// }
+/// ```
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct CloneMonth {
+    /// Month to copy expenses from
+    pub from_month: String,
+    /// Month to create the copies in
+    pub to_month: String,
+}
+
+/// Request body for moving every expense matching a filter to one category.
+///
+/// `filter` takes the same date and category filters as the expense list;
+/// paging and sorting fields are ignored. An empty filter matches all of the
+/// user's expenses.
+///
+/// # Example
+/// ```json
+/// {
+///   "filter": {
+///     "start_date": "2024-01-01",
+///     "end_date": "2024-01-31",
+///     "category_ids": "123e4567-e89b-12d3-a456-426614174000"
+///   },
+///   "to_category": "223e4567-e89b-12d3-a456-426614174000"
+/// }
+/// ```
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct RecategorizeExpenses {
+    /// Which expenses to move
+    pub filter: ExpenseQuery,
+    /// Category to move them to (must belong to the user and not be archived)
+    pub to_category: Uuid,
+}
+
+/// Result of a bulk recategorization.
+///
+/// # Example
+/// ```json
+/// {
+///   "updated": 12
+/// }
+/// ```
+#[derive(Debug, Serialize, ToSchema)]
+pub struct RecategorizeResult {
+    /// Number of expenses moved; ones already in the target category aren't counted
+    pub updated: i64,
+}
+
+/// A named preset for an expense entered again and again, such as a monthly
+/// pass. Creating an expense from it only needs a date.
+///
+/// # Database Schema
+/// ```sql
+/// CREATE TABLE expense_templates (
+///     id UUID PRIMARY KEY,
+///     user_id UUID NOT NULL REFERENCES users(id),
+///     name VARCHAR(100) NOT NULL,
+///     category_id UUID NOT NULL REFERENCES categories(id),
+///     amount DECIMAL(12, 2) NOT NULL CHECK (amount > 0),
+///     description TEXT NOT NULL,
+///     created_at TIMESTAMPTZ NOT NULL,
+///     updated_at TIMESTAMPTZ NOT NULL,
+///     UNIQUE (user_id, name)
+/// );
+/// ```
+///
+/// # Example Response
+/// ```json
+/// {
+///   "id": "3c4d5e6f-7a8b-4c9d-8e0f-1a2b3c4d5e6f",
+///   "user_id": "123e4567-e89b-12d3-a456-426614174000",
+///   "name": "Metro pass",
+///   "category_id": "223e4567-e89b-12d3-a456-426614174000",
+///   "amount": "55.00",
+///   "description": "Monthly metro pass",
+///   "created_at": "2024-01-01T00:00:00Z",
+///   "updated_at": "2024-01-01T00:00:00Z"
+/// }
+/// ```
+#[derive(Debug, Clone, FromRow, Serialize, ToSchema)]
+pub struct ExpenseTemplate {
+    /// Unique identifier for the template
+    pub id: Uuid,
+    /// Owner of the template
+    pub user_id: Uuid,
+    /// Label shown on the button (unique per user)
+    pub name: String,
+    /// Category of the expenses created from it
+    pub category_id: Uuid,
+    /// Amount of the expenses created from it
+    pub amount: Decimal,
+    /// Description of the expenses created from it
+    pub description: String,
+    /// When the template was created
+    pub created_at: DateTime<Utc>,
+    /// When the template was last changed
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Request body for creating an expense template.
+///
+/// # Example
+/// ```json
+/// {
+///   "name": "Metro pass",
+///   "category_id": "223e4567-e89b-12d3-a456-426614174000",
+///   "amount": 55.00,
+///   "description": "Monthly metro pass"
+/// }
+/// ```
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct CreateExpenseTemplate {
+    /// Template name (1-100 characters after trimming, unique per user)
+    #[validate(length(min = 1, max = 100, message = "Template name must be 1-100 characters"))]
+    pub name: String,
+
+    /// Category of the expenses (must belong to the user and not be archived)
+    pub category_id: Uuid,
+
+    /// Amount of the expenses (must be greater than 0)
+    #[validate(range(min = 0.01, message = "Amount must be greater than 0"))]
+    pub amount: f64,
+
+    /// Description of the expenses (1-500 characters after trimming)
+    #[validate(length(min = 1, max = 500, message = "Description must be 1-500 characters"))]
+    pub description: String,
+}
+
+impl CreateExpenseTemplate {
+    /// Strips surrounding whitespace so `"  "` fails the length checks.
+    /// Call before `validate()`.
+    pub fn trim(&mut self) {
+        self.name = self.name.trim().to_string();
+        self.description = self.description.trim().to_string();
+    }
+}
+
+/// Request body for updating an expense template. Only provided fields change.
+///
+/// # Example
+/// ```json
+/// {
+///   "amount": 60.00
+/// }
+/// ```
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct UpdateExpenseTemplate {
+    /// New name (optional, 1-100 characters after trimming)
+    #[validate(length(min = 1, max = 100, message = "Template name must be 1-100 characters"))]
+    pub name: Option<String>,
+
+    /// New category (optional)
+    pub category_id: Option<Uuid>,
+
+    /// New amount (optional, must be > 0 if provided)
+    #[validate(range(min = 0.01, message = "Amount must be greater than 0"))]
+    pub amount: Option<f64>,
+
+    /// New description (optional, 1-500 characters after trimming)
+    #[validate(length(min = 1, max = 500, message = "Description must be 1-500 characters"))]
+    pub description: Option<String>,
+}
+
+impl UpdateExpenseTemplate {
+    /// Strips surrounding whitespace so `"  "` fails the length checks.
+    /// Call before `validate()`.
+    pub fn trim(&mut self) {
+        if let Some(name) = &mut self.name {
+            *name = name.trim().to_string();
+        }
+        if let Some(description) = &mut self.description {
+            *description = description.trim().to_string();
+        }
+    }
+}
+
+/// A file (e.g. a receipt) attached to an expense.
+///
+/// The file contents live in the configured [`Storage`](crate::storage::Storage)
+/// backend; this row only holds metadata and the key needed to retrieve them.
+///
+/// # Database Schema
+/// ```sql
+/// CREATE TABLE attachments (
+///     id UUID PRIMARY KEY,
+///     expense_id UUID NOT NULL REFERENCES expenses(id),
+///     filename VARCHAR(255) NOT NULL,
+///     content_type VARCHAR(100) NOT NULL,
+///     size BIGINT NOT NULL,
+///     storage_key VARCHAR(255) UNIQUE NOT NULL,
+///     created_at TIMESTAMPTZ NOT NULL
+/// );
+/// ```
+///
+/// # Example Response
+/// ```json
+/// {
+///   "id": "9b2e6f1c-3d4a-4b5c-8d7e-6f5a4b3c2d1e",
+///   "expense_id": "123e4567-e89b-12d3-a456-426614174000",
+///   "filename": "receipt.jpg",
+///   "content_type": "image/jpeg",
+///   "size": 184320,
+///   "created_at": "2024-01-15T10:30:00Z"
+/// }
+/// ```
+#[derive(Debug, Clone, FromRow, Serialize)]
+pub struct Attachment {
+    /// Unique identifier for the attachment
+    pub id: Uuid,
+    /// Expense this file is attached to
+    pub expense_id: Uuid,
+    /// Original filename as uploaded by the client
+    pub filename: String,
+    /// MIME type of the file (one of the allowed attachment types)
+    pub content_type: String,
+    /// File size in bytes
+    pub size: i64,
+    /// Key of the file in the storage backend (internal, never sent to clients)
+    #[serde(skip_serializing)]
+    pub storage_key: String,
+    /// Timestamp when the file was uploaded
+    pub created_at: DateTime<Utc>,
+}
+
+// ============================================================================
+// Budget Models
+// ============================================================================
+
+/// A monthly spending limit for one category.
+///
+/// Each category has at most one budget. Spending in the current calendar
+/// month is compared against `amount` in the category summary.
+///
+/// # Database Schema
+/// ```sql
+/// CREATE TABLE budgets (
+///     id UUID PRIMARY KEY,
+///     user_id UUID NOT NULL REFERENCES users(id),
+///     category_id UUID UNIQUE NOT NULL REFERENCES categories(id),
+///     amount DECIMAL(12, 2) NOT NULL CHECK (amount > 0),
+///     created_at TIMESTAMPTZ NOT NULL,
+///     updated_at TIMESTAMPTZ NOT NULL
+/// );
+/// ```
+///
+/// # Example Response
+/// ```json
+/// {
+///   "id": "7d1e2f3a-4b5c-6d7e-8f9a-0b1c2d3e4f5a",
+///   "user_id": "123e4567-e89b-12d3-a456-426614174000",
+///   "category_id": "223e4567-e89b-12d3-a456-426614174000",
+///   "amount": 400.00,
+///   "created_at": "2024-01-01T00:00:00Z",
+///   "updated_at": "2024-01-01T00:00:00Z"
+/// }
+/// ```
+#[derive(Debug, Clone, FromRow, Serialize, ToSchema)]
+pub struct Budget {
+    /// Unique identifier for the budget
+    pub id: Uuid,
+    /// Owner of the budget
+    pub user_id: Uuid,
+    /// Category the budget applies to
+    pub category_id: Uuid,
+    /// Monthly spending limit
+    pub amount: Decimal,
+    /// When the budget was created
+    pub created_at: DateTime<Utc>,
+    /// When the budget was last changed
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Request body for setting a category's monthly budget.
+///
+/// # Example
+/// ```json
+/// {
+///   "amount": 400.00
+/// }
+/// ```
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct SetBudget {
+    /// Monthly limit (must be greater than 0)
+    #[validate(range(min = 0.01, message = "Budget must be greater than 0"))]
+    pub amount: f64,
+}
+
+/// One day of a budget burn-down: spending so far this month against an even
+/// spread of the budget over the month.
+///
+/// # Example Response
+/// ```json
+/// {
+///   "day": "2024-01-10",
+///   "cumulative_spent": "150.00",
+///   "ideal_pace": "129.03"
+/// }
+/// ```
+#[derive(Debug, Serialize, ToSchema)]
+pub struct BurndownDay {
+    /// Date of this point
+    pub day: NaiveDate,
+    /// Spent in the category from the 1st of the month through `day`
+    pub cumulative_spent: Decimal,
+    /// Budget share for the days through `day` (budget × day / days in month)
+    pub ideal_pace: Decimal,
+}
+
+// ============================================================================
+// Alert Models
+// ============================================================================
+
+/// What an alert can watch: one category's budget, or the overall monthly
+/// limit from the settings.
+pub const ALERT_TYPES: &[&str] = &["budget", "monthly_limit"];
+
+/// A spending threshold checked in the background.
+///
+/// Fires once current-month spending reaches `threshold_percent` of the
+/// budget or limit, at most once per calendar month in the user's timezone.
+///
+/// # Database Schema
+/// ```sql
+/// CREATE TABLE alerts (
+///     id UUID PRIMARY KEY,
+///     user_id UUID NOT NULL REFERENCES users(id),
+///     alert_type VARCHAR(13) NOT NULL,
+///     category_id UUID REFERENCES categories(id),
+///     threshold_percent INTEGER NOT NULL,
+///     last_triggered_at TIMESTAMPTZ,
+///     created_at TIMESTAMPTZ NOT NULL
+/// );
+/// ```
+///
+/// # Example Response
+/// ```json
+/// {
+///   "id": "5a6b7c8d-9e0f-4a1b-8c2d-3e4f5a6b7c8d",
+///   "user_id": "123e4567-e89b-12d3-a456-426614174000",
+///   "alert_type": "budget",
+///   "category_id": "223e4567-e89b-12d3-a456-426614174000",
+///   "threshold_percent": 90,
+///   "last_triggered_at": null,
+///   "created_at": "2024-01-01T00:00:00Z"
+/// }
+/// ```
+#[derive(Debug, Clone, FromRow, Serialize, ToSchema)]
+pub struct Alert {
+    /// Unique identifier for the alert
+    pub id: Uuid,
+    /// Owner of the alert
+    pub user_id: Uuid,
+    /// One of [`ALERT_TYPES`]
+    pub alert_type: String,
+    /// Category whose budget is watched; `null` for `monthly_limit`
+    pub category_id: Option<Uuid>,
+    /// Percentage of the budget or limit that fires the alert
+    pub threshold_percent: i32,
+    /// When the alert last fired, `null` if never
+    pub last_triggered_at: Option<DateTime<Utc>>,
+    /// When the alert was created
+    pub created_at: DateTime<Utc>,
+}
+
+/// Request body for creating an alert.
+///
+/// # Example
+/// ```json
+/// {
+///   "alert_type": "budget",
+///   "category_id": "223e4567-e89b-12d3-a456-426614174000",
+///   "threshold_percent": 90
+/// }
+/// ```
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct CreateAlert {
+    /// One of [`ALERT_TYPES`]
+    pub alert_type: String,
+    /// Required for `budget`, must be omitted for `monthly_limit`
+    pub category_id: Option<Uuid>,
+    /// Percentage of the budget or limit (1-1000)
+    #[validate(range(min = 1, max = 1000, message = "Threshold must be between 1 and 1000 percent"))]
+    pub threshold_percent: i32,
+}
+
+/// Request body for changing an alert's threshold.
+///
+/// # Example
+/// ```json
+/// {
+///   "threshold_percent": 100
+/// }
+/// ```
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct UpdateAlert {
+    /// Percentage of the budget or limit (1-1000)
+    #[validate(range(min = 1, max = 1000, message = "Threshold must be between 1 and 1000 percent"))]
+    pub threshold_percent: i32,
+}
+
+// ============================================================================
+// Query Models
+// ============================================================================
+
+/// Query parameters for filtering expenses.
+///
+/// Used as URL query parameters, e.g.:
+/// `/api/expenses?start_date=2024-01-01&end_date=2024-01-31&category_id=...`
+///
+/// All fields are optional, allowing flexible filtering:
+/// - No params: Return all expenses
 /// - Only start_date: Expenses from that date onwards
 /// - start_date + end_date: Expenses in date range
 /// - category_id: Only expenses in that category
+/// - category_ids: Expenses in any of the listed categories (comma-separated)
 /// - Combine all: Expenses in category within date range
 ///
+/// Results are paginated with `limit` (default `DEFAULT_PAGE_SIZE`, max
+/// `MAX_PAGE_SIZE`) and `offset`, and
+/// sorted by `sort_by`/`sort_dir` (default: the user's sort settings, newest
+/// first unless changed).
+///
 /// # Example URL
-/// ```
+/// ```text
 /// GET /api/expenses?start_date=2024-01-01&end_date=2024-01-31&category_id=123e4567-e89b-12d3-a456-426614174000
+/// GET /api/expenses?limit=50&offset=50
+/// GET /api/expenses?sort_by=amount&sort_dir=asc
+/// GET /api/expenses?category_ids=123e4567-e89b-12d3-a456-426614174000,223e4567-e89b-12d3-a456-426614174000
 /// ```
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, IntoParams, ToSchema)]
 pub struct ExpenseQuery {
     /// Filter expenses from this date onwards (inclusive)
     pub start_date: Option<NaiveDate>,
@@ -427,6 +1640,326 @@ pub struct ExpenseQuery {
     pub end_date: Option<NaiveDate>,
     /// Filter expenses by category
     pub category_id: Option<Uuid>,
+    /// Filter expenses by any of several categories, comma-separated; combined
+    /// with `category_id` if both are given
+    #[serde(default, deserialize_with = "comma_separated_uuids")]
+    #[param(value_type = Option<String>)]
+    #[schema(value_type = Option<String>)]
+    pub category_ids: Vec<Uuid>,
+    /// Maximum number of expenses to return (1 to `MAX_PAGE_SIZE`, default `DEFAULT_PAGE_SIZE`)
+    pub limit: Option<i64>,
+    /// Number of expenses to skip (default 0)
+    pub offset: Option<i64>,
+    /// Column to sort by (default the user's `expense_sort_by` setting)
+    pub sort_by: Option<ExpenseSortField>,
+    /// Sort direction (default the user's `expense_sort_dir` setting)
+    pub sort_dir: Option<SortDirection>,
+}
+
+impl ExpenseQuery {
+    /// Every category asked for through `category_id` and `category_ids`;
+    /// empty when expenses shouldn't be filtered by category.
+    pub fn category_filter(&self) -> Vec<Uuid> {
+        self.category_id.iter().chain(&self.category_ids).copied().collect()
+    }
+}
+
+/// Tells a missing field apart from an explicit `null` in a partial update.
+///
+/// Used with `#[serde(default)]`: a missing field keeps the default `None`,
+/// `null` becomes `Some(None)` and a value becomes `Some(Some(value))`.
+fn double_option<'de, D, T>(deserializer: D) -> Result<Option<Option<T>>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    Option::<T>::deserialize(deserializer).map(Some)
+}
+
+/// Parses `a,b,c` into UUIDs, ignoring blanks so a trailing comma is harmless.
+fn comma_separated_uuids<'de, D>(deserializer: D) -> Result<Vec<Uuid>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let raw = String::deserialize(deserializer)?;
+    raw.split(',')
+        .map(str::trim)
+        .filter(|id| !id.is_empty())
+        .map(|id| {
+            Uuid::parse_str(id)
+                .map_err(|_| serde::de::Error::custom(format!("invalid category id: {}", id)))
+        })
+        .collect()
+}
+
+/// Columns the expense list can be sorted by.
+#[derive(Debug, Clone, Copy, Default, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ExpenseSortField {
+    #[default]
+    Date,
+    Amount,
+    Category,
+    Description,
+}
+
+impl ExpenseSortField {
+    /// SQL expression to order by. Never built from user input.
+    pub fn column(self) -> &'static str {
+        match self {
+            ExpenseSortField::Date => "expenses.expense_date",
+            ExpenseSortField::Amount => "expenses.amount",
+            ExpenseSortField::Category => "categories.name",
+            ExpenseSortField::Description => "expenses.description",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SortDirection {
+    Asc,
+    #[default]
+    Desc,
+}
+
+impl SortDirection {
+    pub fn as_sql(self) -> &'static str {
+        match self {
+            SortDirection::Asc => "ASC",
+            SortDirection::Desc => "DESC",
+        }
+    }
+}
+
+/// Orders the category list can be returned in.
+#[derive(Debug, Clone, Copy, Default, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum CategorySort {
+    /// The user's drag-and-drop order, ties alphabetically
+    #[default]
+    Manual,
+    /// Alphabetically, ignoring case
+    Name,
+    /// Most expenses first, ties in manual order
+    Usage,
+}
+
+/// Query parameters for listing categories.
+///
+/// The response is always a [`PaginatedCategories`]. Without `limit` every
+/// matching category is on the one page.
+///
+/// # Example URL
+/// ```text
+/// GET /api/categories?sort=usage
+/// GET /api/categories?search=food&limit=20&offset=20
+/// ```
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct CategoryListQuery {
+    /// Order to return categories in (defaults to the user's `category_sort` setting)
+    pub sort: Option<CategorySort>,
+    /// Only categories whose name contains this text, ignoring case
+    pub search: Option<String>,
+    /// Maximum number of categories to return (1 to `MAX_PAGE_SIZE`, default all)
+    pub limit: Option<i64>,
+    /// Number of categories to skip (default 0)
+    pub offset: Option<i64>,
+}
+
+/// Query parameters for description autocomplete.
+///
+/// # Example URL
+/// ```text
+/// GET /api/expenses/descriptions?q=cof&limit=10
+/// ```
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct DescriptionQuery {
+    /// Case-insensitive prefix to match (optional, all descriptions when omitted)
+    pub q: Option<String>,
+    /// Maximum number of descriptions to return (1-50, default 10)
+    pub limit: Option<i64>,
+}
+
+/// Query parameters for the year-over-year comparison.
+///
+/// # Example URL
+/// ```text
+/// GET /api/summaries/yearly-comparison?year=2024
+/// ```
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct YearlyComparisonQuery {
+    /// Year to compare against the one before it (defaults to the current year)
+    pub year: Option<i32>,
+}
+
+/// Query parameters for the yearly category summary.
+///
+/// # Example URL
+/// ```text
+/// GET /api/summaries/categories/yearly?year=2024
+/// ```
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct CategoryYearQuery {
+    /// Calendar year to total (defaults to the current year in the user's timezone)
+    pub year: Option<i32>,
+}
+
+/// Query parameters for the daily spending heatmap.
+///
+/// # Example URL
+/// ```text
+/// GET /api/summaries/heatmap?year=2024&fill=true
+/// ```
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct HeatmapQuery {
+    /// Calendar year to cover (defaults to the current year in the user's timezone)
+    pub year: Option<i32>,
+    /// Include every day of the year, with zero for days without expenses
+    /// (default false: only days with expenses)
+    pub fill: Option<bool>,
+}
+
+/// Date range for the most and least expensive days.
+///
+/// Missing dates default to the current month: the 1st through today.
+///
+/// # Example URL
+/// ```text
+/// GET /api/summaries/day-extremes?start_date=2024-01-01&end_date=2024-03-31
+/// ```
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct DayExtremesQuery {
+    /// First day of the range (inclusive)
+    pub start_date: Option<NaiveDate>,
+    /// Last day of the range (inclusive)
+    pub end_date: Option<NaiveDate>,
+}
+
+/// Query parameters for the monthly PDF report.
+///
+/// # Example URL
+/// ```text
+/// GET /api/reports/monthly.pdf?year=2024&month=3
+/// ```
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct MonthlyReportQuery {
+    /// Year of the report (defaults to the current year)
+    pub year: Option<i32>,
+    /// Month of the report, 1-12 (defaults to the current month)
+    pub month: Option<u32>,
+}
+
+/// Query parameters for a category's spending history.
+///
+/// # Example URL
+/// ```text
+/// GET /api/categories/123e4567-e89b-12d3-a456-426614174000/history?months=6
+/// ```
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct CategoryHistoryQuery {
+    /// Number of months to return, including the current one (1-60, defaults to 6)
+    pub months: Option<u32>,
+}
+
+/// Query parameters for the recently used categories.
+///
+/// # Example URL
+/// ```text
+/// GET /api/categories/recent?limit=5
+/// ```
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct RecentCategoriesQuery {
+    /// Maximum number of categories to return (1-20, defaults to 5)
+    pub limit: Option<i64>,
+}
+
+/// Text color that reads best on one category's color.
+///
+/// # Example Response
+/// ```json
+/// {
+///   "category_id": "223e4567-e89b-12d3-a456-426614174000",
+///   "color": "#FFEAA7",
+///   "text_color": "#000000",
+///   "contrast_ratio": 18.43
+/// }
+/// ```
+#[derive(Debug, Serialize, ToSchema)]
+pub struct CategoryContrast {
+    pub category_id: Uuid,
+    /// The category's color, `null` if it has none
+    pub color: Option<String>,
+    /// `#000000` or `#FFFFFF`, whichever contrasts more with `color`; `null` without a color
+    pub text_color: Option<String>,
+    /// WCAG contrast ratio of `text_color` on `color` (1-21, 2 decimals); `null` without a color
+    pub contrast_ratio: Option<f64>,
+}
+
+/// Query parameters for creating an expense from a template.
+///
+/// # Example URL
+/// ```text
+/// POST /api/expenses/from-template/3c4d5e6f-7a8b-4c9d-8e0f-1a2b3c4d5e6f?expense_date=2024-01-15
+/// ```
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct FromTemplateQuery {
+    /// Date of the new expense (defaults to today in the user's timezone)
+    pub expense_date: Option<NaiveDate>,
+}
+
+/// Query parameters for the category color palette.
+///
+/// # Example URL
+/// ```text
+/// GET /api/categories/palette?exclude_used=true
+/// ```
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct PaletteQuery {
+    /// Leave out colors already used by the user's categories (defaults to false)
+    pub exclude_used: Option<bool>,
+}
+
+/// Query parameters for category suggestions.
+///
+/// # Example URL
+/// ```text
+/// GET /api/categories/suggest?q=groc
+/// ```
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct SuggestCategoriesQuery {
+    /// Text typed so far; matched against category names
+    pub q: String,
+}
+
+/// Query parameters for the live event stream.
+///
+/// # Example URL
+/// ```text
+/// GET /api/events?ticket=9f86d081884c7d659a2feaa0c55ad015a3bf4f1b2b0b822cd15d6c15b0f00a08
+/// ```
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct EventsQuery {
+    /// Stream ticket for clients that can't send an `Authorization` header,
+    /// such as the browser's `EventSource`; the header wins when both are given
+    pub ticket: Option<String>,
+}
+
+/// A single-use ticket for opening the live event stream.
+///
+/// # Example Response
+/// ```json
+/// {
+///   "ticket": "9f86d081884c7d659a2feaa0c55ad015a3bf4f1b2b0b822cd15d6c15b0f00a08",
+///   "expires_in": 30
+/// }
+/// ```
+#[derive(Debug, Serialize, ToSchema)]
+pub struct StreamTicket {
+    /// Pass as `?ticket=` to `GET /api/events`; works once
+    pub ticket: String,
+    /// Seconds left to open the stream with it
+    pub expires_in: u64,
 }
 
 // ============================================================================
@@ -460,7 +1993,7 @@ pub struct ExpenseQuery {
 ///   "expense_count": 42
 /// }
 /// ```
-#[derive(Debug, Serialize, FromRow)]
+#[derive(Debug, Serialize, FromRow, ToSchema)]
 pub struct MonthlySummary {
     /// Month name (e.g., "January", "February")
     pub month: String,
@@ -485,12 +2018,15 @@ pub struct MonthlySummary {
 ///     categories.color as category_color,
 ///     categories.icon as category_icon,
 ///     COALESCE(SUM(expenses.amount), 0) as total_amount,
-///     COUNT(expenses.id)::BIGINT as expense_count
+///     COUNT(expenses.id)::BIGINT as expense_count,
+///     budgets.amount as budget_amount,
+///     (budgets.amount IS NOT NULL AND COALESCE(SUM(expenses.amount), 0) > budgets.amount) as over_budget
 /// FROM categories
 /// LEFT JOIN expenses ON categories.id = expenses.category_id
 ///     AND expenses.expense_date >= $2
+/// LEFT JOIN budgets ON budgets.category_id = categories.id
 /// WHERE categories.user_id = $1
-/// GROUP BY categories.id, categories.name, categories.color, categories.icon
+/// GROUP BY categories.id, categories.name, categories.color, categories.icon, budgets.amount
 /// ```
 ///
 /// # Example Response
@@ -501,10 +2037,12 @@ pub struct MonthlySummary {
 ///   "category_color": "#FF6B6B",
 ///   "category_icon": "🍔",
 ///   "total_amount": 450.25,
-///   "expense_count": 15
+///   "expense_count": 15,
+///   "budget_amount": 400.00,
+///   "over_budget": true
 /// }
 /// ```
-#[derive(Debug, Serialize, FromRow)]
+#[derive(Debug, Serialize, FromRow, ToSchema)]
 pub struct CategorySummary {
     /// Category unique identifier
     pub category_id: Uuid,
@@ -518,4 +2056,306 @@ pub struct CategorySummary {
     pub total_amount: Decimal,
     /// Number of expenses in this category
     pub expense_count: i64,
+    /// Monthly budget for this category, if one is set
+    pub budget_amount: Option<Decimal>,
+    /// Whether this month's spending exceeds the budget (false without a budget)
+    pub over_budget: bool,
+}
+
+/// Projected month-end spending based on the current month's pace.
+///
+/// Extrapolates the amount spent so far linearly across the whole month:
+/// `projected_total = spent_so_far / days_elapsed * days_in_month`.
+/// Today counts as an elapsed day, so on the 1st the projection is simply
+/// today's spending multiplied by the number of days in the month.
+///
+/// # Example Response
+/// ```json
+/// {
+///   "spent_so_far": "620.00",
+///   "projected_total": "1240.00",
+///   "days_elapsed": 15,
+///   "days_in_month": 30
+/// }
+/// ```
+#[derive(Debug, Serialize, ToSchema)]
+pub struct SpendingProjection {
+    /// Total spent in the current month up to and including today
+    pub spent_so_far: Decimal,
+    /// Estimated total for the whole month at the current pace
+    pub projected_total: Decimal,
+    /// Number of days of the month that have elapsed (including today)
+    pub days_elapsed: u32,
+    /// Total number of days in the current month
+    pub days_in_month: u32,
+}
+
+/// Runs of consecutive days with at least one recorded expense.
+///
+/// The current streak still counts when nothing has been recorded yet today,
+/// as long as yesterday had an expense. Both values are `0` without expenses.
+///
+/// # Example Response
+/// ```json
+/// {
+///   "current_streak_days": 4,
+///   "longest_streak_days": 9
+/// }
+/// ```
+#[derive(Debug, Serialize, ToSchema)]
+pub struct SpendingStreaks {
+    /// Consecutive days with expenses ending today (or yesterday)
+    pub current_streak_days: i64,
+    /// Longest run of consecutive days with expenses within the current month
+    pub longest_streak_days: i64,
+}
+
+/// Total spent on one day.
+#[derive(Debug, Serialize, FromRow, ToSchema)]
+pub struct DayTotal {
+    pub date: NaiveDate,
+    pub total_amount: Decimal,
+}
+
+/// The days with the highest and lowest spending in a date range.
+///
+/// Only days with expenses count, so `cheapest` is the lowest non-zero day.
+/// Both are `null` when the range has no expenses, and the same day when it
+/// has only one.
+///
+/// # Example Response
+/// ```json
+/// {
+///   "start_date": "2024-01-01",
+///   "end_date": "2024-01-31",
+///   "most_expensive": { "date": "2024-01-12", "total_amount": "245.80" },
+///   "cheapest": { "date": "2024-01-03", "total_amount": "3.50" }
+/// }
+/// ```
+#[derive(Debug, Serialize, ToSchema)]
+pub struct DayExtremes {
+    pub start_date: NaiveDate,
+    pub end_date: NaiveDate,
+    /// Day with the highest total (the most recent one on a tie)
+    pub most_expensive: Option<DayTotal>,
+    /// Day with the lowest non-zero total (the most recent one on a tie)
+    pub cheapest: Option<DayTotal>,
+}
+
+/// Width of the buckets in a spending time series.
+#[derive(Debug, Clone, Copy, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum TimeseriesInterval {
+    Day,
+    /// Weeks start on the user's `week_start` setting, Monday by default
+    Week,
+    Month,
+}
+
+impl TimeseriesInterval {
+    /// Field name for `date_trunc`. Never built from user input.
+    pub fn as_sql(self) -> &'static str {
+        match self {
+            TimeseriesInterval::Day => "day",
+            TimeseriesInterval::Week => "week",
+            TimeseriesInterval::Month => "month",
+        }
+    }
+}
+
+/// Query parameters for spending grouped into day, week or month buckets.
+///
+/// # Example URL
+/// ```text
+/// GET /api/summaries/timeseries?interval=week&start_date=2024-01-01&end_date=2024-03-31
+/// ```
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct TimeseriesQuery {
+    /// `day`, `week` or `month`
+    pub interval: TimeseriesInterval,
+    /// First day of the range (inclusive)
+    pub start_date: NaiveDate,
+    /// Last day of the range (inclusive)
+    pub end_date: NaiveDate,
+}
+
+/// Spending within one bucket of a time series.
+///
+/// Every bucket in the range is returned, with zeros where nothing was spent.
+/// The first bucket starts at the beginning of the week or month containing
+/// `start_date`, but only counts expenses from `start_date` on.
+///
+/// # Example Response
+/// ```json
+/// [
+///   { "bucket_start": "2024-01-01", "total_amount": "182.40", "expense_count": 6 },
+///   { "bucket_start": "2024-01-08", "total_amount": "0", "expense_count": 0 }
+/// ]
+/// ```
+#[derive(Debug, Serialize, FromRow, ToSchema)]
+pub struct TimeseriesBucket {
+    pub bucket_start: NaiveDate,
+    pub total_amount: Decimal,
+    pub expense_count: i64,
+}
+
+/// Monthly totals for a year side by side with the previous year.
+///
+/// The endpoint always returns twelve entries (January through December);
+/// months without expenses are filled with zeros so the two series line up.
+///
+/// # Example Response
+/// ```json
+/// {
+///   "month": 3,
+///   "current_total": "1320.50",
+///   "previous_total": "1180.00"
+/// }
+/// ```
+#[derive(Debug, Serialize, ToSchema)]
+pub struct YearlyComparison {
+    /// Month number (1 = January, 12 = December)
+    pub month: u32,
+    /// Total spent in this month of the requested year
+    pub current_total: Decimal,
+    /// Total spent in the same month of the previous year
+    pub previous_total: Decimal,
+}
+
+/// One month of spending in a single category.
+///
+/// The history endpoint returns one entry per month, oldest first; months
+/// without expenses are filled with zeros.
+///
+/// # Example Response
+/// ```json
+/// {
+///   "year": 2024,
+///   "month": 3,
+///   "total_amount": "212.40",
+///   "expense_count": 9
+/// }
+/// ```
+#[derive(Debug, Serialize, ToSchema)]
+pub struct CategoryMonthlyTotal {
+    /// Calendar year
+    pub year: i32,
+    /// Month number (1 = January, 12 = December)
+    pub month: u32,
+    /// Total spent in the category during this month
+    pub total_amount: Decimal,
+    /// Number of expenses in the category during this month
+    pub expense_count: i64,
+}
+
+/// An inclusive range of expense dates.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, ToSchema)]
+pub struct DateRange {
+    /// First day of the range
+    pub start: NaiveDate,
+    /// Last day of the range (inclusive)
+    pub end: NaiveDate,
+}
+
+/// Request body for comparing spending in two date ranges.
+///
+/// Deltas are reported as `b` minus `a`, so with last month as `a` and this
+/// month as `b` a negative delta means less was spent this month.
+///
+/// # Example
+/// ```json
+/// {
+///   "a": { "start": "2024-02-01", "end": "2024-02-29" },
+///   "b": { "start": "2024-03-01", "end": "2024-03-31" }
+/// }
+/// ```
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct CompareRanges {
+    /// Baseline range
+    pub a: DateRange,
+    /// Range compared against the baseline
+    pub b: DateRange,
+}
+
+/// Overall spending within one of the compared ranges.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct RangeTotals {
+    /// First day of the range
+    pub start: NaiveDate,
+    /// Last day of the range (inclusive)
+    pub end: NaiveDate,
+    /// Total spent in the range
+    pub total_amount: Decimal,
+    /// Number of expenses in the range
+    pub expense_count: i64,
+}
+
+/// One category's spending in both compared ranges.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct CategoryDelta {
+    pub category_id: Uuid,
+    pub category_name: String,
+    pub category_color: Option<String>,
+    pub category_icon: Option<String>,
+    /// Total spent in the category during range `a`
+    pub a_total: Decimal,
+    /// Total spent in the category during range `b`
+    pub b_total: Decimal,
+    /// `b_total - a_total`
+    pub delta: Decimal,
+    /// Number of expenses in the category during range `a`
+    pub a_count: i64,
+    /// Number of expenses in the category during range `b`
+    pub b_count: i64,
+}
+
+/// Spending in two date ranges side by side.
+///
+/// Categories with spending in either range are listed, largest change
+/// (in either direction) first.
+///
+/// # Example Response
+/// ```json
+/// {
+///   "a": { "start": "2024-02-01", "end": "2024-02-29", "total_amount": "1250.00", "expense_count": 41 },
+///   "b": { "start": "2024-03-01", "end": "2024-03-31", "total_amount": "1100.50", "expense_count": 38 },
+///   "total_delta": "-149.50",
+///   "categories": [
+///     {
+///       "category_id": "123e4567-e89b-12d3-a456-426614174000",
+///       "category_name": "Dining",
+///       "category_color": "#FF6B6B",
+///       "category_icon": "🍔",
+///       "a_total": "320.00",
+///       "b_total": "180.00",
+///       "delta": "-140.00",
+///       "a_count": 12,
+///       "b_count": 7
+///     }
+///   ]
+/// }
+/// ```
+#[derive(Debug, Serialize, ToSchema)]
+pub struct RangeComparison {
+    pub a: RangeTotals,
+    pub b: RangeTotals,
+    /// `b.total_amount - a.total_amount`
+    pub total_delta: Decimal,
+    pub categories: Vec<CategoryDelta>,
+}
+
+/// Everything the dashboard needs on load, fetched in one request.
+///
+/// `expenses` honours the same filter, sort and paging parameters as
+/// `GET /api/expenses`; the summaries ignore them.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct DashboardResponse {
+    /// All of the user's categories, in their `category_sort` order
+    pub categories: Vec<Category>,
+    /// First page of matching expenses
+    pub expenses: PaginatedExpenses,
+    /// Totals for the 12 most recent months with expenses
+    pub monthly_summary: Vec<MonthlySummary>,
+    /// Current-month totals per category
+    pub category_summary: Vec<CategorySummary>,
 }
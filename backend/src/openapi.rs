@@ -0,0 +1,233 @@
+//! OpenAPI description of the REST API.
+//!
+//! The spec is generated at compile time by [`utoipa`] from the
+//! `#[utoipa::path]` annotations on the handlers and the `ToSchema` /
+//! `IntoParams` derives on the models, so it stays in sync with the code.
+//!
+//! When `API_DOCS_ENABLED` is set, two public routes are registered:
+//!
+//! - `GET /api-docs/openapi.json` - the raw OpenAPI 3 document
+//! - `GET /swagger-ui` - an interactive Swagger UI pointed at that document
+
+use axum::{response::Html, Json};
+use utoipa::{
+    openapi::security::{Http, HttpAuthScheme, SecurityScheme},
+    Modify, OpenApi,
+};
+
+use crate::{
+    handlers::{
+        alerts, audit, budgets, categories, dashboard, demo, events, expenses, reports,
+        sessions, settings, summaries, templates, users,
+    },
+    models::{
+        Alert, AuditEvent, AuthResponse, Budget, BurndownDay, Category, CategoryContrast,
+        CategoryDelta, CategoryMonthlyTotal, CategorySort, CategorySummary,
+        CategoryTemplate, CloneMonth, CompareRanges, CreateAlert, CreateCategory, CreateExpense,
+        CreateExpenseTemplate, CreateUser, CreatedExpense, DashboardResponse, DateRange,
+        DayExtremes, DayTotal, ExpenseQuery, ExpenseSortField, ExpenseSplit, ExpenseTemplate,
+        ExpenseVersion, ExpenseWithCategory, ImportCategoriesResult, IntegrityReport, LoginRequest,
+        MergeCategory, MonthlySummary, PaginatedAuditEvents, PaginatedCategories, PaginatedExpenses,
+        QuickExpense, RangeComparison, RangeTotals, RecategorizeExpenses, RecategorizeResult,
+        ReorderCategories, SeedDemoResult, Session, SetBudget, SortDirection, SpendingProjection,
+        SpendingStreaks, StreamTicket, TimeseriesBucket, TimeseriesInterval, TrashedExpense,
+        UpdateAlert, UpdateCategory, UpdateExpense, UpdateExpenseTemplate, UpdateUser,
+        UpdateUserSettings, UserResponse, UserSettings, YearlyComparison,
+    },
+};
+
+#[derive(OpenApi)]
+#[openapi(
+    info(title = "Expense Tracker API"),
+    paths(
+        users::register,
+        users::login,
+        users::get_current_user,
+        users::update_current_user,
+        users::get_integrity_report,
+        settings::get_settings,
+        settings::update_settings,
+        sessions::list_sessions,
+        sessions::revoke_session,
+        sessions::revoke_other_sessions,
+        audit::list_audit_events,
+        demo::seed_demo_data,
+        categories::create_category,
+        categories::list_categories,
+        categories::get_category,
+        categories::update_category,
+        categories::delete_category,
+        categories::get_category_history,
+        categories::merge_category,
+        categories::archive_category,
+        categories::unarchive_category,
+        categories::reorder_categories,
+        categories::list_recent_categories,
+        categories::suggest_categories,
+        categories::get_category_palette,
+        categories::get_category_contrast,
+        categories::export_categories,
+        categories::import_categories,
+        expenses::create_expense,
+        expenses::create_quick_expense,
+        expenses::create_expense_from_template,
+        expenses::list_expenses,
+        expenses::get_expense,
+        expenses::update_expense,
+        expenses::delete_expense,
+        expenses::recategorize_expenses,
+        events::issue_stream_ticket,
+        events::stream_events,
+        expenses::clone_month,
+        expenses::list_descriptions,
+        expenses::get_expense_history,
+        expenses::list_trash,
+        expenses::restore_expense,
+        expenses::purge_expense,
+        budgets::list_budgets,
+        budgets::set_budget,
+        budgets::delete_budget,
+        budgets::get_budget_burndown,
+        templates::list_templates,
+        templates::create_template,
+        templates::update_template,
+        templates::delete_template,
+        alerts::list_alerts,
+        alerts::create_alert,
+        alerts::update_alert,
+        alerts::delete_alert,
+        summaries::get_monthly_summary,
+        summaries::export_monthly_summary_csv,
+        summaries::get_category_summary,
+        summaries::export_category_summary_csv,
+        summaries::get_all_time_category_summary,
+        summaries::get_yearly_category_summary,
+        summaries::get_spending_projection,
+        summaries::get_spending_streaks,
+        summaries::get_yearly_comparison,
+        summaries::compare_ranges,
+        summaries::get_day_extremes,
+        summaries::get_spending_timeseries,
+        summaries::get_spending_heatmap,
+        reports::export_monthly_report_pdf,
+        dashboard::get_dashboard,
+    ),
+    components(schemas(
+        CreateUser,
+        LoginRequest,
+        AuthResponse,
+        UserResponse,
+        UpdateUser,
+        UserSettings,
+        UpdateUserSettings,
+        IntegrityReport,
+        Session,
+        AuditEvent,
+        PaginatedAuditEvents,
+        SeedDemoResult,
+        StreamTicket,
+        Category,
+        PaginatedCategories,
+        CreateCategory,
+        UpdateCategory,
+        MergeCategory,
+        ReorderCategories,
+        CategoryTemplate,
+        CategoryContrast,
+        ImportCategoriesResult,
+        CategoryMonthlyTotal,
+        ExpenseWithCategory,
+        CreatedExpense,
+        PaginatedExpenses,
+        TrashedExpense,
+        ExpenseVersion,
+        ExpenseSortField,
+        CategorySort,
+        SortDirection,
+        CreateExpense,
+        ExpenseSplit,
+        QuickExpense,
+        UpdateExpense,
+        CloneMonth,
+        ExpenseQuery,
+        RecategorizeExpenses,
+        RecategorizeResult,
+        ExpenseTemplate,
+        CreateExpenseTemplate,
+        UpdateExpenseTemplate,
+        Budget,
+        SetBudget,
+        BurndownDay,
+        Alert,
+        CreateAlert,
+        UpdateAlert,
+        MonthlySummary,
+        CategorySummary,
+        SpendingProjection,
+        SpendingStreaks,
+        YearlyComparison,
+        DateRange,
+        CompareRanges,
+        RangeTotals,
+        CategoryDelta,
+        RangeComparison,
+        DayTotal,
+        DayExtremes,
+        TimeseriesInterval,
+        TimeseriesBucket,
+        DashboardResponse,
+    )),
+    modifiers(&BearerAuth),
+    tags(
+        (name = "auth", description = "Registration and login"),
+        (name = "users", description = "Current user, settings, active sessions and audit log"),
+        (name = "categories", description = "Expense categories"),
+        (name = "expenses", description = "Expense records"),
+        (name = "templates", description = "Saved presets for recurring expenses"),
+        (name = "budgets", description = "Monthly category budgets"),
+        (name = "alerts", description = "Spending alerts delivered by webhook"),
+        (name = "summaries", description = "Spending analytics"),
+        (name = "reports", description = "Printable reports"),
+    )
+)]
+pub struct ApiDoc;
+
+/// Registers the `bearer_auth` security scheme referenced by protected paths.
+struct BearerAuth;
+
+impl Modify for BearerAuth {
+    fn modify(&self, openapi: &mut utoipa::openapi::OpenApi) {
+        if let Some(components) = openapi.components.as_mut() {
+            components.add_security_scheme(
+                "bearer_auth",
+                SecurityScheme::Http(Http::new(HttpAuthScheme::Bearer)),
+            );
+        }
+    }
+}
+
+pub async fn openapi_json() -> Json<utoipa::openapi::OpenApi> {
+    Json(ApiDoc::openapi())
+}
+
+/// Serves a Swagger UI page that loads its assets from a CDN.
+pub async fn swagger_ui() -> Html<&'static str> {
+    Html(SWAGGER_UI_HTML)
+}
+
+const SWAGGER_UI_HTML: &str = r##"<!DOCTYPE html>
+<html lang="en">
+<head>
+    <meta charset="UTF-8">
+    <title>Expense Tracker API</title>
+    <link rel="stylesheet" href="https://unpkg.com/swagger-ui-dist@5/swagger-ui.css">
+</head>
+<body>
+    <div id="swagger-ui"></div>
+    <script src="https://unpkg.com/swagger-ui-dist@5/swagger-ui-bundle.js"></script>
+    <script>
+        window.ui = SwaggerUIBundle({ url: "/api-docs/openapi.json", dom_id: "#swagger-ui" });
+    </script>
+</body>
+</html>
+"##;
@@ -0,0 +1,157 @@
+//! Per-IP rate limiting for the public auth endpoints.
+//!
+//! Login and registration each get their own [`RateLimiter`], attached as a
+//! route layer, so hitting one limit never uses up the other's budget. Limits
+//! use a fixed window: an address may make `max_requests` requests, then gets
+//! `429 Too Many Requests` with a `Retry-After` header until its window ends.
+//!
+//! State is kept in memory, so limits are per process and reset on restart.
+
+use std::{
+    collections::HashMap,
+    net::IpAddr,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use axum::{
+    extract::{Request, State},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+
+use crate::{client_ip::ClientIp, config::RateLimit, error::AppError};
+
+/// Tracked addresses above which expired windows are swept on the next check.
+const PRUNE_THRESHOLD: usize = 10_000;
+
+struct Window {
+    started: Instant,
+    count: u32,
+}
+
+pub struct RateLimiter {
+    max_requests: u32,
+    window: Duration,
+    windows: Mutex<HashMap<IpAddr, Window>>,
+}
+
+impl RateLimiter {
+    pub fn new(limit: &RateLimit) -> Self {
+        Self {
+            max_requests: limit.max_requests,
+            window: Duration::from_secs(limit.window_secs),
+            windows: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Counts a request from `ip`, returning how long until it may retry if
+    /// the limit is already used up. A `max_requests` of 0 disables the limit.
+    pub fn check(&self, ip: IpAddr) -> Result<(), Duration> {
+        self.check_at(ip, Instant::now())
+    }
+
+    fn check_at(&self, ip: IpAddr, now: Instant) -> Result<(), Duration> {
+        if self.max_requests == 0 {
+            return Ok(());
+        }
+
+        let mut windows = self.windows.lock().expect("rate limiter lock poisoned");
+
+        if windows.len() > PRUNE_THRESHOLD {
+            windows.retain(|_, w| now.duration_since(w.started) < self.window);
+        }
+
+        let window = windows.entry(ip).or_insert(Window { started: now, count: 0 });
+        if now.duration_since(window.started) >= self.window {
+            *window = Window { started: now, count: 0 };
+        }
+
+        if window.count >= self.max_requests {
+            return Err(self.window - now.duration_since(window.started));
+        }
+
+        window.count += 1;
+        Ok(())
+    }
+}
+
+/// Middleware rejecting requests from addresses over the limiter's budget.
+///
+/// Addresses come from [`ClientIp`], so behind nginx each client is limited
+/// separately rather than everyone sharing the proxy's budget.
+///
+/// ```rust,ignore
+/// post(users::login).layer(middleware::from_fn_with_state(limiter, rate_limit))
+/// ```
+pub async fn rate_limit(
+    State(limiter): State<Arc<RateLimiter>>,
+    ClientIp(ip): ClientIp,
+    req: Request,
+    next: Next,
+) -> Response {
+    match limiter.check(ip) {
+        Ok(()) => next.run(req).await,
+        // Round up so clients never retry a moment too early
+        Err(retry_after) => AppError::TooManyRequests {
+            retry_after_secs: retry_after.as_secs() + u64::from(retry_after.subsec_nanos() > 0),
+        }
+        .into_response(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CLIENT: IpAddr = IpAddr::V4(std::net::Ipv4Addr::new(203, 0, 113, 7));
+    const OTHER_CLIENT: IpAddr = IpAddr::V4(std::net::Ipv4Addr::new(203, 0, 113, 8));
+
+    fn limiter(max_requests: u32) -> RateLimiter {
+        RateLimiter::new(&RateLimit { max_requests, window_secs: 60 })
+    }
+
+    #[test]
+    fn allows_max_requests_then_reports_the_rest_of_the_window() {
+        let limiter = limiter(2);
+        let start = Instant::now();
+
+        assert!(limiter.check_at(CLIENT, start).is_ok());
+        assert!(limiter.check_at(CLIENT, start + Duration::from_secs(10)).is_ok());
+        assert_eq!(
+            limiter.check_at(CLIENT, start + Duration::from_secs(15)),
+            Err(Duration::from_secs(45))
+        );
+    }
+
+    #[test]
+    fn window_resets_once_it_has_passed() {
+        let limiter = limiter(1);
+        let start = Instant::now();
+
+        assert!(limiter.check_at(CLIENT, start).is_ok());
+        assert!(limiter.check_at(CLIENT, start + Duration::from_secs(59)).is_err());
+        assert!(limiter.check_at(CLIENT, start + Duration::from_secs(60)).is_ok());
+        assert!(limiter.check_at(CLIENT, start + Duration::from_secs(61)).is_err());
+    }
+
+    #[test]
+    fn addresses_have_separate_budgets() {
+        let limiter = limiter(1);
+        let now = Instant::now();
+
+        assert!(limiter.check_at(CLIENT, now).is_ok());
+        assert!(limiter.check_at(CLIENT, now).is_err());
+        assert!(limiter.check_at(OTHER_CLIENT, now).is_ok());
+    }
+
+    #[test]
+    fn zero_max_requests_disables_the_limit() {
+        let limiter = limiter(0);
+        let now = Instant::now();
+
+        for _ in 0..100 {
+            assert!(limiter.check_at(CLIENT, now).is_ok());
+        }
+    }
+}
@@ -1,36 +1,174 @@
 use axum::{
+    extract::DefaultBodyLimit,
+    middleware,
     routing::{delete, get, post, put},
     Router,
 };
+use std::sync::Arc;
+use tower_http::{compression::CompressionLayer, limit::RequestBodyLimitLayer};
 
 use crate::{
-    handlers::{categories, expenses, summaries, users},
+    client_ip::resolve_client_ip,
+    handlers::{
+        alerts, attachments, audit, budgets, categories, dashboard, demo, events, expenses,
+        reports, sessions, settings, summaries, templates, users,
+    },
+    error::{json_method_not_allowed, route_not_found},
+    https::require_https,
+    metrics, openapi,
+    rate_limit::{rate_limit, RateLimiter},
     AppState,
 };
 
 pub fn create_router(state: AppState) -> Router {
-    Router::new()
+    // Separate limiters so failed logins can't block sign-ups and vice versa
+    let login_limiter = Arc::new(RateLimiter::new(&state.config.login_rate_limit));
+    let register_limiter = Arc::new(RateLimiter::new(&state.config.register_rate_limit));
+
+    // Auth routes (public, rate limited per client address)
+    let mut auth_routes = Router::new()
+        .route(
+            "/api/auth/register",
+            post(users::register)
+                .layer(middleware::from_fn_with_state(register_limiter, rate_limit)),
+        )
+        .route(
+            "/api/auth/login",
+            post(users::login).layer(middleware::from_fn_with_state(login_limiter, rate_limit)),
+        );
+    if state.config.require_https {
+        auth_routes = auth_routes.route_layer(middleware::from_fn(require_https));
+    }
+
+    let mut router = Router::new()
         // Health check
         .route("/health", get(|| async { "OK" }))
-        // Auth routes (public)
-        .route("/api/auth/register", post(users::register))
-        .route("/api/auth/login", post(users::login))
+        .merge(auth_routes)
         // User routes (protected)
         .route("/api/users/me", get(users::get_current_user))
+        .route("/api/users/me", put(users::update_current_user))
+        .route("/api/users/me/integrity", get(users::get_integrity_report))
+        .route("/api/users/me/settings", get(settings::get_settings))
+        .route("/api/users/me/settings", put(settings::update_settings))
+        .route("/api/users/me/sessions", get(sessions::list_sessions))
+        .route("/api/users/me/sessions", delete(sessions::revoke_other_sessions))
+        .route("/api/users/me/sessions/:id", delete(sessions::revoke_session))
+        .route("/api/users/me/audit", get(audit::list_audit_events))
+        .route("/api/users/me/seed-demo", post(demo::seed_demo_data))
         // Category routes (protected)
         .route("/api/categories", post(categories::create_category))
         .route("/api/categories", get(categories::list_categories))
+        .route("/api/categories/recent", get(categories::list_recent_categories))
+        .route("/api/categories/suggest", get(categories::suggest_categories))
+        .route("/api/categories/palette", get(categories::get_category_palette))
+        .route("/api/categories/contrast", get(categories::get_category_contrast))
+        .route("/api/categories/reorder", post(categories::reorder_categories))
+        .route("/api/categories/export", get(categories::export_categories))
+        .route("/api/categories/import", post(categories::import_categories))
         .route("/api/categories/:id", get(categories::get_category))
         .route("/api/categories/:id", put(categories::update_category))
         .route("/api/categories/:id", delete(categories::delete_category))
+        .route("/api/categories/:id/history", get(categories::get_category_history))
+        .route("/api/categories/:id/merge", post(categories::merge_category))
+        .route("/api/categories/:id/archive", post(categories::archive_category))
+        .route("/api/categories/:id/unarchive", post(categories::unarchive_category))
         // Expense routes (protected)
         .route("/api/expenses", post(expenses::create_expense))
         .route("/api/expenses", get(expenses::list_expenses))
+        .route("/api/expenses/quick", post(expenses::create_quick_expense))
+        .route(
+            "/api/expenses/from-template/:id",
+            post(expenses::create_expense_from_template),
+        )
+        .route("/api/expenses/recategorize", post(expenses::recategorize_expenses))
+        .route("/api/expenses/clone-month", post(expenses::clone_month))
+        .route("/api/expenses/descriptions", get(expenses::list_descriptions))
+        .route("/api/expenses/trash", get(expenses::list_trash))
         .route("/api/expenses/:id", get(expenses::get_expense))
         .route("/api/expenses/:id", put(expenses::update_expense))
         .route("/api/expenses/:id", delete(expenses::delete_expense))
+        .route("/api/expenses/:id/history", get(expenses::get_expense_history))
+        .route("/api/expenses/:id/restore", post(expenses::restore_expense))
+        .route("/api/expenses/:id/purge", delete(expenses::purge_expense))
+        // Live expense updates (protected; the stream takes a single-use ticket
+        // in the query, never the token)
+        .route("/api/events", get(events::stream_events))
+        .route("/api/events/ticket", post(events::issue_stream_ticket))
+        // Attachment downloads (protected)
+        .route(
+            "/api/expenses/:id/attachments/:attachment_id",
+            get(attachments::download_attachment),
+        )
+        // Expense template routes (protected)
+        .route("/api/expense-templates", get(templates::list_templates))
+        .route("/api/expense-templates", post(templates::create_template))
+        .route("/api/expense-templates/:id", put(templates::update_template))
+        .route("/api/expense-templates/:id", delete(templates::delete_template))
+        // Budget routes (protected)
+        .route("/api/budgets", get(budgets::list_budgets))
+        .route("/api/budgets/:category_id", put(budgets::set_budget))
+        .route("/api/budgets/:category_id", delete(budgets::delete_budget))
+        .route("/api/budgets/:category_id/burndown", get(budgets::get_budget_burndown))
+        // Alert routes (protected)
+        .route("/api/alerts", get(alerts::list_alerts))
+        .route("/api/alerts", post(alerts::create_alert))
+        .route("/api/alerts/:id", put(alerts::update_alert))
+        .route("/api/alerts/:id", delete(alerts::delete_alert))
         // Summary routes (protected)
+        .route("/api/dashboard", get(dashboard::get_dashboard))
         .route("/api/summaries/monthly", get(summaries::get_monthly_summary))
+        .route("/api/summaries/monthly.csv", get(summaries::export_monthly_summary_csv))
         .route("/api/summaries/categories", get(summaries::get_category_summary))
+        .route("/api/summaries/categories.csv", get(summaries::export_category_summary_csv))
+        .route(
+            "/api/summaries/categories/all-time",
+            get(summaries::get_all_time_category_summary),
+        )
+        .route(
+            "/api/summaries/categories/yearly",
+            get(summaries::get_yearly_category_summary),
+        )
+        .route("/api/summaries/projection", get(summaries::get_spending_projection))
+        .route("/api/summaries/streaks", get(summaries::get_spending_streaks))
+        .route("/api/summaries/yearly-comparison", get(summaries::get_yearly_comparison))
+        .route("/api/summaries/compare", post(summaries::compare_ranges))
+        .route("/api/summaries/day-extremes", get(summaries::get_day_extremes))
+        .route("/api/summaries/timeseries", get(summaries::get_spending_timeseries))
+        .route("/api/summaries/heatmap", get(summaries::get_spending_heatmap))
+        // Reports (protected)
+        .route("/api/reports/monthly.pdf", get(reports::export_monthly_report_pdf))
+        // Everything above gets the small JSON body limit (413 when exceeded)
+        .layer(RequestBodyLimitLayer::new(state.config.max_body_bytes))
+        // Attachment uploads (protected) carry files, so they get their own larger limit
+        .route(
+            "/api/expenses/:id/attachments",
+            post(attachments::upload_attachment).layer(DefaultBodyLimit::max(
+                state.config.max_attachment_bytes + attachments::MULTIPART_OVERHEAD_BYTES,
+            )),
+        );
+
+    if state.config.metrics_enabled {
+        // Metrics (public, outside the API namespace). The layer is added before
+        // the /metrics route so scrapes themselves are not recorded.
+        router = router
+            .route_layer(middleware::from_fn(metrics::track_metrics))
+            .route("/metrics", get(metrics::metrics_handler));
+    }
+
+    if state.config.api_docs_enabled {
+        // API documentation (public)
+        router = router
+            .route("/api-docs/openapi.json", get(openapi::openapi_json))
+            .route("/swagger-ui", get(openapi::swagger_ui));
+    }
+
+    // gzip or brotli, per Accept-Encoding. The default predicate skips images,
+    // event streams and tiny bodies; CSV exports are compressed like JSON.
+    router
+        .fallback(route_not_found)
+        .layer(middleware::map_response(json_method_not_allowed))
+        .layer(CompressionLayer::new())
+        // Outermost, so every route and route layer can extract `ClientIp`
+        .layer(middleware::from_fn_with_state(state.clone(), resolve_client_ip))
         .with_state(state)
 }
@@ -0,0 +1,89 @@
+//! Pluggable file storage for expense attachments.
+//!
+//! Handlers only talk to the [`Storage`] trait, so the backing store can be
+//! swapped (e.g. for S3) without touching the attachment endpoints. The
+//! default implementation, [`LocalStorage`], writes files under a directory on
+//! the local filesystem configured by `ATTACHMENTS_DIR`.
+//!
+//! Storage keys are generated by the server (`<user_id>/<uuid>`), never taken
+//! from client input, so they cannot be used for path traversal.
+
+use std::{io::ErrorKind, path::PathBuf};
+
+use axum::async_trait;
+
+use crate::error::{AppError, AppResult};
+
+/// Backend capable of persisting and retrieving attachment contents by key.
+#[async_trait]
+pub trait Storage: Send + Sync {
+    /// Stores `data` under `key`, overwriting any existing object.
+    async fn put(&self, key: &str, data: &[u8]) -> AppResult<()>;
+
+    /// Loads the object stored under `key`.
+    ///
+    /// Returns [`AppError::NotFound`] if nothing is stored under the key.
+    async fn get(&self, key: &str) -> AppResult<Vec<u8>>;
+
+    /// Removes the object stored under `key`. Deleting a missing key succeeds.
+    async fn delete(&self, key: &str) -> AppResult<()>;
+}
+
+/// Stores attachments as plain files below a root directory.
+pub struct LocalStorage {
+    root: PathBuf,
+}
+
+impl LocalStorage {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+}
+
+#[async_trait]
+impl Storage for LocalStorage {
+    async fn put(&self, key: &str, data: &[u8]) -> AppResult<()> {
+        let path = self.root.join(key);
+
+        if let Some(parent) = path.parent() {
+            tokio::fs::create_dir_all(parent)
+                .await
+                .map_err(anyhow::Error::from)?;
+        }
+
+        tokio::fs::write(&path, data)
+            .await
+            .map_err(anyhow::Error::from)?;
+
+        Ok(())
+    }
+
+    async fn get(&self, key: &str) -> AppResult<Vec<u8>> {
+        tokio::fs::read(self.root.join(key))
+            .await
+            .map_err(|e| match e.kind() {
+                ErrorKind::NotFound => AppError::NotFound("Attachment not found".to_string()),
+                _ => anyhow::Error::from(e).into(),
+            })
+    }
+
+    async fn delete(&self, key: &str) -> AppResult<()> {
+        match tokio::fs::remove_file(self.root.join(key)).await {
+            Err(e) if e.kind() != ErrorKind::NotFound => Err(anyhow::Error::from(e).into()),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Deletes the files stored under `keys`, e.g. after their attachment rows
+/// were removed.
+///
+/// The rows are already gone, so a file that can't be deleted is only logged
+/// rather than failing the request.
+pub async fn delete_all(storage: &dyn Storage, keys: &[String]) {
+    for key in keys {
+        if let Err(e) = storage.delete(key).await {
+            tracing::warn!("Failed to delete stored attachment {}: {:?}", key, e);
+        }
+    }
+}
@@ -0,0 +1,338 @@
+//! Outgoing webhooks for users who automate around their expenses.
+//!
+//! When a user has set `webhook_url` in their settings, every newly created
+//! expense (`expense.created`) and every spending alert that fires
+//! (`alert.triggered`) is POSTed there as JSON. Delivery runs in a background
+//! task after the response is sent: a slow or failing receiver never delays or
+//! fails the request, it is only logged.
+//!
+//! Each body is signed with the user's `webhook_secret` (HMAC-SHA256, hex) in
+//! the `X-Webhook-Signature` header as `sha256=<hex>`, so receivers can check
+//! the request came from this server:
+//!
+//! ```text
+//! expected = "sha256=" + hex(hmac_sha256(webhook_secret, raw_request_body))
+//! ```
+//!
+//! Webhook URLs are chosen by users, so they must not turn the server into a
+//! way to reach its own network. Unless `WEBHOOK_ALLOW_PRIVATE_NETWORKS` is
+//! on, URLs whose host is or resolves to a loopback, private, link-local or
+//! otherwise non-public address are refused when saved and again at delivery,
+//! the client's resolver drops such addresses so a name can't be re-pointed
+//! in between, and redirects are never followed.
+
+use std::{
+    io,
+    net::{IpAddr, SocketAddr},
+    sync::Arc,
+    time::Duration,
+};
+
+use chrono::{DateTime, Utc};
+use hmac::{Hmac, Mac};
+use rand::RngCore;
+use reqwest::{
+    dns::{Addrs, Name, Resolve, Resolving},
+    redirect, Url,
+};
+use serde::Serialize;
+use sha2::Sha256;
+use thiserror::Error;
+use uuid::Uuid;
+
+use crate::{alerts::TriggeredAlert, config::Config, models::ExpenseWithCategory, AppState};
+
+/// How long a receiver gets to answer before the delivery counts as failed.
+const DELIVERY_TIMEOUT: Duration = Duration::from_secs(10);
+
+const SIGNATURE_HEADER: &str = "x-webhook-signature";
+const EVENT_HEADER: &str = "x-webhook-event";
+
+const EXPENSE_CREATED: &str = "expense.created";
+const ALERT_TRIGGERED: &str = "alert.triggered";
+
+/// Body POSTed to the webhook URL for a new expense.
+#[derive(Serialize)]
+struct ExpensePayload<'a> {
+    event: &'static str,
+    sent_at: DateTime<Utc>,
+    expense: &'a ExpenseWithCategory,
+}
+
+/// Body POSTed to the webhook URL when an alert fires.
+#[derive(Serialize)]
+struct AlertPayload<'a> {
+    event: &'static str,
+    sent_at: DateTime<Utc>,
+    alert: &'a TriggeredAlert,
+}
+
+/// Why a webhook URL can't be used.
+#[derive(Debug, Error)]
+pub enum DestinationError {
+    #[error("Webhook URL must be an http or https URL")]
+    InvalidUrl,
+    #[error("Webhook URL host could not be resolved")]
+    Unresolvable,
+    #[error("Webhook URL must not point to a private or local address")]
+    NotPublic,
+}
+
+/// Whether `ip` is reachable on the public internet, as far as webhooks go.
+///
+/// Rejects loopback, private (including carrier-grade NAT and IPv6 unique
+/// local), link-local, unspecified, broadcast and multicast addresses.
+/// IPv4-mapped IPv6 addresses are judged by the IPv4 address.
+pub fn is_public_address(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(v4) => {
+            let [a, b, ..] = v4.octets();
+            let shared = a == 100 && (b & 0xc0) == 64;
+            !(v4.is_loopback()
+                || v4.is_private()
+                || shared
+                || v4.is_link_local()
+                || v4.is_unspecified()
+                || v4.is_broadcast()
+                || v4.is_multicast())
+        }
+        IpAddr::V6(v6) => {
+            if let Some(v4) = v6.to_ipv4_mapped() {
+                return is_public_address(IpAddr::V4(v4));
+            }
+            let first = v6.segments()[0];
+            let unique_local = (first & 0xfe00) == 0xfc00;
+            let link_local = (first & 0xffc0) == 0xfe80;
+            !(v6.is_loopback()
+                || v6.is_unspecified()
+                || v6.is_multicast()
+                || unique_local
+                || link_local)
+        }
+    }
+}
+
+/// Checks that `url` is an http(s) URL whose host is public, resolving names.
+///
+/// A name resolving to several addresses is refused if any of them isn't
+/// public. With `allow_private` only the URL itself is checked.
+pub async fn check_destination(url: &str, allow_private: bool) -> Result<(), DestinationError> {
+    let url = Url::parse(url).map_err(|_| DestinationError::InvalidUrl)?;
+    if !matches!(url.scheme(), "http" | "https") {
+        return Err(DestinationError::InvalidUrl);
+    }
+    let host = url.host_str().ok_or(DestinationError::InvalidUrl)?;
+    if allow_private {
+        return Ok(());
+    }
+
+    // IPv6 literals keep their brackets in `host_str`
+    let host = host.trim_start_matches('[').trim_end_matches(']');
+    let port = url.port_or_known_default().unwrap_or(80);
+    let addrs: Vec<SocketAddr> = match host.parse::<IpAddr>() {
+        Ok(ip) => vec![SocketAddr::new(ip, port)],
+        Err(_) => tokio::net::lookup_host((host, port))
+            .await
+            .map_err(|_| DestinationError::Unresolvable)?
+            .collect(),
+    };
+
+    if addrs.is_empty() {
+        return Err(DestinationError::Unresolvable);
+    }
+    if !addrs.iter().all(|addr| is_public_address(addr.ip())) {
+        return Err(DestinationError::NotPublic);
+    }
+    Ok(())
+}
+
+/// System resolver that leaves out non-public addresses, so a name checked by
+/// [`check_destination`] can't resolve somewhere private when connecting.
+struct PublicResolver;
+
+impl Resolve for PublicResolver {
+    fn resolve(&self, name: Name) -> Resolving {
+        Box::pin(async move {
+            let addrs: Vec<SocketAddr> = tokio::net::lookup_host((name.as_str(), 0))
+                .await?
+                .filter(|addr| is_public_address(addr.ip()))
+                .collect();
+            if addrs.is_empty() {
+                return Err(io::Error::new(
+                    io::ErrorKind::PermissionDenied,
+                    format!("{} has no public address", name.as_str()),
+                )
+                .into());
+            }
+            Ok(Box::new(addrs.into_iter()) as Addrs)
+        })
+    }
+}
+
+/// HTTP client shared by all deliveries, with the delivery timeout applied.
+///
+/// Redirects are not followed: a receiver could otherwise send deliveries on
+/// to an internal address.
+pub fn http_client(config: &Config) -> reqwest::Result<reqwest::Client> {
+    let builder = reqwest::Client::builder()
+        .timeout(DELIVERY_TIMEOUT)
+        .redirect(redirect::Policy::none());
+
+    if config.webhook_allow_private_networks {
+        builder.build()
+    } else {
+        builder.dns_resolver(Arc::new(PublicResolver)).build()
+    }
+}
+
+/// A new random signing secret, 32 bytes as hex.
+pub fn generate_secret() -> String {
+    let mut bytes = [0u8; 32];
+    rand::thread_rng().fill_bytes(&mut bytes);
+    hex::encode(bytes)
+}
+
+/// `sha256=<hex>` HMAC of `body` under `secret`.
+fn sign(secret: &str, body: &[u8]) -> String {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts keys of any length");
+    mac.update(body);
+    format!("sha256={}", hex::encode(mac.finalize().into_bytes()))
+}
+
+/// Sends `expense` to the user's webhook, if one is set, without waiting.
+///
+/// Call after the expense is committed; replays of an idempotent request
+/// shouldn't call it again.
+pub fn notify_expense_created(state: &AppState, user_id: Uuid, expense: &ExpenseWithCategory) {
+    let payload = ExpensePayload {
+        event: EXPENSE_CREATED,
+        sent_at: Utc::now(),
+        expense,
+    };
+    deliver(state, user_id, EXPENSE_CREATED, &payload);
+}
+
+/// Sends a fired `alert` to the user's webhook, if one is set, without waiting.
+pub fn notify_alert_triggered(state: &AppState, user_id: Uuid, alert: &TriggeredAlert) {
+    let payload = AlertPayload {
+        event: ALERT_TRIGGERED,
+        sent_at: Utc::now(),
+        alert,
+    };
+    deliver(state, user_id, ALERT_TRIGGERED, &payload);
+}
+
+/// POSTs `payload` to the user's webhook in a background task.
+fn deliver(state: &AppState, user_id: Uuid, event: &'static str, payload: &impl Serialize) {
+    let body = match serde_json::to_vec(payload) {
+        Ok(body) => body,
+        Err(e) => {
+            tracing::error!("Failed to serialize webhook payload: {:?}", e);
+            return;
+        }
+    };
+    let pool = state.pool.clone();
+    let client = state.webhook_client.clone();
+    let allow_private = state.config.webhook_allow_private_networks;
+
+    tokio::spawn(async move {
+        let webhook = sqlx::query_as::<_, (String, String)>(
+            r#"
+            SELECT webhook_url, webhook_secret
+            FROM user_settings
+            WHERE user_id = $1 AND webhook_url IS NOT NULL AND webhook_secret IS NOT NULL
+            "#,
+        )
+        .bind(user_id)
+        .fetch_optional(&pool)
+        .await;
+
+        let (url, secret) = match webhook {
+            Ok(Some(webhook)) => webhook,
+            Ok(None) => return,
+            Err(e) => {
+                tracing::warn!("Failed to load webhook for user {}: {:?}", user_id, e);
+                return;
+            }
+        };
+
+        // Re-checked in case the URL predates the check or its name moved
+        if let Err(e) = check_destination(&url, allow_private).await {
+            tracing::warn!("Skipping webhook delivery for user {}: {}", user_id, e);
+            return;
+        }
+
+        let result = client
+            .post(&url)
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .header(EVENT_HEADER, event)
+            .header(SIGNATURE_HEADER, sign(&secret, &body))
+            .body(body)
+            .send()
+            .await
+            .and_then(reqwest::Response::error_for_status);
+
+        if let Err(e) = result {
+            // The error names the URL
+            tracing::warn!("Webhook delivery for user {} failed: {}", user_id, e);
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn public(ip: &str) -> bool {
+        is_public_address(ip.parse().unwrap())
+    }
+
+    #[test]
+    fn internal_addresses_are_not_public() {
+        for ip in [
+            "127.0.0.1",
+            "10.1.2.3",
+            "172.16.0.1",
+            "192.168.1.1",
+            "100.64.0.1",
+            "169.254.169.254",
+            "0.0.0.0",
+            "255.255.255.255",
+            "224.0.0.1",
+            "::1",
+            "::",
+            "fd00::1",
+            "fe80::1",
+            "::ffff:127.0.0.1",
+            "::ffff:169.254.169.254",
+        ] {
+            assert!(!public(ip), "{} counted as public", ip);
+        }
+    }
+
+    #[test]
+    fn internet_addresses_are_public() {
+        for ip in ["93.184.216.34", "1.1.1.1", "100.128.0.1", "2606:4700::1111"] {
+            assert!(public(ip), "{} counted as internal", ip);
+        }
+    }
+
+    #[tokio::test]
+    async fn destinations_are_checked_by_address() {
+        let check = |url: &'static str| check_destination(url, false);
+
+        assert!(check("https://93.184.216.34/hook").await.is_ok());
+        assert!(matches!(check("http://127.0.0.1:3000/").await, Err(DestinationError::NotPublic)));
+        assert!(matches!(check("http://[::1]/").await, Err(DestinationError::NotPublic)));
+        assert!(matches!(check("http://localhost/").await, Err(DestinationError::NotPublic)));
+        assert!(matches!(check("ftp://93.184.216.34/").await, Err(DestinationError::InvalidUrl)));
+        assert!(matches!(check("not a url").await, Err(DestinationError::InvalidUrl)));
+    }
+
+    #[tokio::test]
+    async fn private_destinations_can_be_allowed() {
+        assert!(check_destination("http://127.0.0.1:3000/", true).await.is_ok());
+        assert!(check_destination("ftp://127.0.0.1/", true).await.is_err());
+    }
+}
@@ -0,0 +1,45 @@
+mod common;
+
+use axum::http::StatusCode;
+use chrono::{DateTime, Utc};
+use serde_json::json;
+use sqlx::PgPool;
+
+use expense_tracker_backend::alerts::check_alerts;
+
+#[sqlx::test]
+async fn crossing_a_threshold_fires_once_per_month(pool: PgPool) {
+    let app = common::test_app(pool);
+    let user = app.register_user("alice@example.com").await;
+    let shopping = user.category_id("Shopping").await;
+    let today = Utc::now().date_naive().to_string();
+
+    let response = user.put(&format!("/api/budgets/{}", shopping), json!({ "amount": 100 })).await;
+    assert!(response.status.is_success(), "{}", response.text());
+    let alert = json!({ "alert_type": "budget", "category_id": shopping, "threshold_percent": 90 });
+    let response = user.post("/api/alerts", alert).await;
+    assert_eq!(response.status, StatusCode::CREATED, "{}", response.text());
+
+    let last_triggered = || async {
+        sqlx::query_scalar::<_, Option<DateTime<Utc>>>("SELECT last_triggered_at FROM alerts")
+            .fetch_one(app.pool())
+            .await
+            .unwrap()
+    };
+
+    // 89% of the budget
+    user.create_expense(shopping, 89.0, &today).await;
+    check_alerts(&app.state).await.unwrap();
+    assert_eq!(last_triggered().await, None);
+
+    // 90% reaches the threshold
+    user.create_expense(shopping, 1.0, &today).await;
+    check_alerts(&app.state).await.unwrap();
+    let fired_at = last_triggered().await.expect("alert fired at the threshold");
+
+    // Later checks and further spending this month don't fire it again
+    check_alerts(&app.state).await.unwrap();
+    user.create_expense(shopping, 50.0, &today).await;
+    check_alerts(&app.state).await.unwrap();
+    assert_eq!(last_triggered().await, Some(fired_at));
+}
@@ -0,0 +1,126 @@
+mod common;
+
+use axum::http::StatusCode;
+use sqlx::PgPool;
+
+use common::TestUser;
+
+async fn expense_id(user: &TestUser) -> String {
+    let category_id = user.category_id("Shopping").await;
+    let expense = user.create_expense(category_id, 10.0, "2024-03-10").await;
+    expense["id"].as_str().unwrap().to_string()
+}
+
+#[sqlx::test]
+async fn uploaded_receipt_downloads_for_its_owner_only(pool: PgPool) {
+    let app = common::test_app(pool);
+    let alice = app.register_user("alice@example.com").await;
+    let bob = app.register_user("bob@example.com").await;
+    let expense_id = expense_id(&alice).await;
+
+    let response = alice
+        .upload_attachment(&expense_id, "Quittung Müller.png", "image/png", b"png")
+        .await;
+    assert_eq!(response.status, StatusCode::CREATED, "{}", response.text());
+    let attachment = response.json();
+    assert_eq!(attachment["filename"], "Quittung Müller.png");
+    assert_eq!(attachment["size"], 3);
+
+    let attachment_id = attachment["id"].as_str().unwrap();
+    let uri = format!("/api/expenses/{}/attachments/{}", expense_id, attachment_id);
+    let download = alice.get(&uri).await;
+    assert_eq!(download.status, StatusCode::OK);
+    assert_eq!(download.body.as_ref(), b"png");
+    assert_eq!(download.header("content-type"), Some("image/png"));
+    assert_eq!(
+        download.header("content-disposition"),
+        Some(
+            "attachment; filename=\"Quittung M_ller.png\"; \
+             filename*=UTF-8''Quittung%20M%C3%BCller.png"
+        )
+    );
+
+    assert_eq!(bob.get(&uri).await.status, StatusCode::NOT_FOUND);
+    let response = bob.upload_attachment(&expense_id, "x.png", "image/png", b"png").await;
+    assert_eq!(response.status, StatusCode::NOT_FOUND);
+}
+
+#[sqlx::test]
+async fn oversized_upload_is_rejected(pool: PgPool) {
+    let mut config = common::test_config();
+    config.max_attachment_bytes = 1024;
+    let app = common::test_app_with(pool, config);
+    let user = app.register_user("alice@example.com").await;
+    let expense_id = expense_id(&user).await;
+
+    let response = user
+        .upload_attachment(&expense_id, "big.pdf", "application/pdf", &[b'x'; 1025])
+        .await;
+    assert_eq!(response.status, StatusCode::PAYLOAD_TOO_LARGE);
+
+    let response = user
+        .upload_attachment(&expense_id, "ok.pdf", "application/pdf", &[b'x'; 1024])
+        .await;
+    assert_eq!(response.status, StatusCode::CREATED, "{}", response.text());
+
+    let stored: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM attachments")
+        .fetch_one(app.pool())
+        .await
+        .unwrap();
+    assert_eq!(stored, 1);
+}
+
+#[sqlx::test]
+async fn overlong_filename_is_rejected(pool: PgPool) {
+    let app = common::test_app(pool);
+    let user = app.register_user("alice@example.com").await;
+    let expense_id = expense_id(&user).await;
+    let png = b"\x89PNG\r\n\x1a\n";
+
+    let filename = format!("{}.png", "a".repeat(296));
+    let response = user.upload_attachment(&expense_id, &filename, "image/png", png).await;
+    assert_eq!(response.status, StatusCode::BAD_REQUEST);
+    assert_eq!(response.error(), "Filename can be at most 255 characters");
+
+    let filename = format!("{}.png", "a".repeat(251));
+    let response = user.upload_attachment(&expense_id, &filename, "image/png", png).await;
+    assert_eq!(response.status, StatusCode::CREATED, "{}", response.text());
+    assert_eq!(response.json()["filename"], filename);
+}
+
+#[sqlx::test]
+async fn unsupported_content_type_is_rejected(pool: PgPool) {
+    let app = common::test_app(pool);
+    let user = app.register_user("alice@example.com").await;
+    let expense_id = expense_id(&user).await;
+
+    let response = user.upload_attachment(&expense_id, "notes.txt", "text/plain", b"hello").await;
+    assert_eq!(response.status, StatusCode::BAD_REQUEST);
+    assert!(response.error().starts_with("Unsupported file type"));
+}
+
+#[sqlx::test]
+async fn body_limit_applies_to_json_but_not_to_uploads(pool: PgPool) {
+    let mut config = common::test_config();
+    config.max_body_bytes = 1024;
+    config.max_attachment_bytes = 8 * 1024;
+    let app = common::test_app_with(pool, config);
+    let user = app.register_user("alice@example.com").await;
+    let category_id = user.category_id("Shopping").await;
+
+    let oversized = serde_json::json!({
+        "category_id": category_id,
+        "amount": 10,
+        "description": "x".repeat(2000),
+        "expense_date": "2024-03-10",
+    });
+    let response = user.post("/api/expenses", oversized).await;
+    assert_eq!(response.status, StatusCode::PAYLOAD_TOO_LARGE);
+
+    // Uploads have their own, larger limit
+    let expense_id = expense_id(&user).await;
+    let response = user
+        .upload_attachment(&expense_id, "receipt.png", "image/png", &[0; 4096])
+        .await;
+    assert_eq!(response.status, StatusCode::CREATED, "{}", response.text());
+}
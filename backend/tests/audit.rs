@@ -0,0 +1,72 @@
+mod common;
+
+use axum::http::{Method, StatusCode};
+use serde_json::json;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use common::{json_request, TestClient, TestResponse};
+
+async fn login(client: &TestClient, email: &str, password: &str) -> TestResponse {
+    client
+        .post("/api/auth/login", json!({ "email": email, "password": password }))
+        .await
+}
+
+#[sqlx::test]
+async fn failed_logins_are_audited_without_revealing_the_account(pool: PgPool) {
+    let app = common::test_app(pool);
+    let user = app.register_user("alice@example.com").await;
+    let client = app.client();
+
+    let wrong_password = login(&client, "alice@example.com", "not-her-password").await;
+    let unknown_email = login(&client, "mallory@example.com", common::PASSWORD).await;
+    assert_eq!(wrong_password.status, StatusCode::UNAUTHORIZED);
+    assert_eq!(unknown_email.status, StatusCode::UNAUTHORIZED);
+    assert_eq!(wrong_password.body, unknown_email.body);
+
+    let rows: Vec<(Option<Uuid>, Option<String>, Option<String>)> = sqlx::query_as(
+        r#"
+        SELECT user_id, email, ip_address FROM audit_log
+        WHERE event_type = 'login_failed'
+        ORDER BY created_at, id
+        "#,
+    )
+    .fetch_all(app.pool())
+    .await
+    .unwrap();
+    let client_ip = Some("203.0.113.7".to_string());
+    assert_eq!(
+        rows,
+        [
+            (Some(user.id), Some("alice@example.com".to_string()), client_ip.clone()),
+            (None, Some("mallory@example.com".to_string()), client_ip),
+        ]
+    );
+
+    // Only the event tied to her account shows up for the user
+    let events = user.get("/api/users/me/audit").await;
+    assert_eq!(events.status, StatusCode::OK);
+    let events = events.json();
+    assert_eq!(events["total"], 1);
+    assert_eq!(events["items"][0]["event_type"], "login_failed");
+}
+
+#[sqlx::test]
+async fn audit_rows_record_the_forwarded_client_behind_a_trusted_proxy(pool: PgPool) {
+    let mut config = common::test_config();
+    config.trusted_proxies = vec!["203.0.113.7/32".parse().unwrap()];
+    let app = common::test_app_with(pool, config);
+    let user = app.register_user("alice@example.com").await;
+
+    let client = app.client();
+    let request = client
+        .request(Method::POST, "/api/auth/login")
+        .header("x-forwarded-for", "198.51.100.1");
+    let body = json!({ "email": user.email, "password": common::PASSWORD });
+    assert_eq!(client.send(json_request(request, &body)).await.status, StatusCode::OK);
+
+    let events = user.get("/api/users/me/audit").await.json();
+    assert_eq!(events["items"][0]["event_type"], "login");
+    assert_eq!(events["items"][0]["ip_address"], "198.51.100.1");
+}
@@ -0,0 +1,184 @@
+mod common;
+
+use axum::http::{Method, StatusCode};
+use serde_json::json;
+use sqlx::PgPool;
+
+use common::{json_request, TestApp, TestClient, TestResponse};
+use expense_tracker_backend::auth::decode_jwt;
+
+fn registration(email: &str) -> serde_json::Value {
+    json!({ "email": email, "password": common::PASSWORD, "full_name": "Test User" })
+}
+
+/// A login attempt claiming to be forwarded for `forwarded_for`.
+async fn login_forwarded_for(client: &TestClient, forwarded_for: &str) -> TestResponse {
+    let request = client
+        .request(Method::POST, "/api/auth/login")
+        .header("x-forwarded-for", forwarded_for);
+    let body = json!({ "email": "nobody@example.com", "password": common::PASSWORD });
+    client.send(json_request(request, &body)).await
+}
+
+/// Hours from now until the token from a login with `remember_me` expires.
+async fn login_lifetime_hours(app: &TestApp, remember_me: Option<bool>) -> i64 {
+    let mut body = json!({ "email": "alice@example.com", "password": common::PASSWORD });
+    if let Some(remember_me) = remember_me {
+        body["remember_me"] = json!(remember_me);
+    }
+    let response = app.client().post("/api/auth/login", body).await;
+    assert_eq!(response.status, StatusCode::OK, "{}", response.text());
+
+    let token = response.json()["token"].as_str().unwrap().to_string();
+    let claims = decode_jwt(&token, &app.state.jwt_keys).unwrap();
+    // Rounded, so a test straddling a second boundary still compares equal
+    (claims.exp - chrono::Utc::now().timestamp() + 1800) / 3600
+}
+
+#[sqlx::test]
+async fn remember_me_issues_a_longer_lived_token(pool: PgPool) {
+    let mut config = common::test_config();
+    config.jwt_expiration_hours = 24;
+    config.jwt_remember_hours = 720;
+    let app = common::test_app_with(pool, config);
+    app.register_user("alice@example.com").await;
+
+    assert_eq!(login_lifetime_hours(&app, None).await, 24);
+    assert_eq!(login_lifetime_hours(&app, Some(false)).await, 24);
+    assert_eq!(login_lifetime_hours(&app, Some(true)).await, 720);
+}
+
+#[sqlx::test]
+async fn registration_limit_returns_429_without_touching_the_login_budget(pool: PgPool) {
+    let mut config = common::test_config();
+    config.register_rate_limit.max_requests = 2;
+    config.register_rate_limit.window_secs = 3600;
+    config.login_rate_limit.max_requests = 2;
+    let app = common::test_app_with(pool, config);
+    let client = app.client();
+
+    for email in ["one@example.com", "two@example.com"] {
+        let response = client.post("/api/auth/register", registration(email)).await;
+        assert_eq!(response.status, StatusCode::CREATED, "{}", response.text());
+    }
+
+    let response = client.post("/api/auth/register", registration("three@example.com")).await;
+    assert_eq!(response.status, StatusCode::TOO_MANY_REQUESTS);
+    let retry_after: u64 = response.header("retry-after").unwrap().parse().unwrap();
+    assert!(retry_after > 3500 && retry_after <= 3600, "Retry-After: {}", retry_after);
+
+    // Blocked requests never reach the handler
+    let users: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM users")
+        .fetch_one(app.pool())
+        .await
+        .unwrap();
+    assert_eq!(users, 2);
+
+    let response = client
+        .post(
+            "/api/auth/login",
+            json!({ "email": "one@example.com", "password": common::PASSWORD }),
+        )
+        .await;
+    assert_eq!(response.status, StatusCode::OK);
+}
+
+#[sqlx::test]
+async fn trusted_proxy_clients_get_their_own_login_budget(pool: PgPool) {
+    let mut config = common::test_config();
+    config.login_rate_limit.max_requests = 1;
+    // The test requests come from CLIENT_ADDR, which plays nginx here
+    config.trusted_proxies = vec!["203.0.113.0/24".parse().unwrap()];
+    let app = common::test_app_with(pool, config);
+    let client = app.client();
+
+    let response = login_forwarded_for(&client, "198.51.100.1").await;
+    assert_eq!(response.status, StatusCode::UNAUTHORIZED);
+    let response = login_forwarded_for(&client, "198.51.100.1").await;
+    assert_eq!(response.status, StatusCode::TOO_MANY_REQUESTS);
+
+    let response = login_forwarded_for(&client, "198.51.100.2").await;
+    assert_eq!(response.status, StatusCode::UNAUTHORIZED);
+}
+
+#[sqlx::test]
+async fn forwarded_for_from_untrusted_peers_is_ignored(pool: PgPool) {
+    let mut config = common::test_config();
+    config.login_rate_limit.max_requests = 1;
+    let app = common::test_app_with(pool, config);
+    let client = app.client();
+
+    let response = login_forwarded_for(&client, "198.51.100.1").await;
+    assert_eq!(response.status, StatusCode::UNAUTHORIZED);
+
+    // A made-up address doesn't buy a fresh budget
+    let response = login_forwarded_for(&client, "198.51.100.2").await;
+    assert_eq!(response.status, StatusCode::TOO_MANY_REQUESTS);
+}
+
+#[sqlx::test]
+async fn require_https_rejects_plain_http_logins(pool: PgPool) {
+    let mut config = common::test_config();
+    config.require_https = true;
+    let app = common::test_app_with(pool, config);
+    let client = app.client();
+
+    let response = client.post("/api/auth/register", registration("alice@example.com")).await;
+    assert_eq!(response.status, StatusCode::BAD_REQUEST);
+    assert_eq!(response.error(), "HTTPS is required for this endpoint");
+
+    for proto in ["http", "https"] {
+        let request = client
+            .request(Method::POST, "/api/auth/register")
+            .header("x-forwarded-proto", proto);
+        let response = client.send(json_request(request, &registration("bob@example.com"))).await;
+        let expected = if proto == "https" { StatusCode::CREATED } else { StatusCode::BAD_REQUEST };
+        assert_eq!(response.status, expected, "{}: {}", proto, response.text());
+    }
+
+    // Only the endpoints issuing tokens are affected
+    let response = client.get("/api/expenses").await;
+    assert_eq!(response.status, StatusCode::UNAUTHORIZED);
+}
+
+#[sqlx::test]
+async fn concurrent_registrations_of_one_email_get_one_account(pool: PgPool) {
+    let app = common::test_app(pool);
+    let client = app.client();
+
+    let (first, second) = tokio::join!(
+        client.post("/api/auth/register", registration("alice@example.com")),
+        client.post("/api/auth/register", registration("Alice@Example.com")),
+    );
+    let mut statuses = [first.status, second.status];
+    statuses.sort();
+    assert_eq!(statuses, [StatusCode::CREATED, StatusCode::BAD_REQUEST]);
+    let rejected = if first.status == StatusCode::BAD_REQUEST { first } else { second };
+    assert_eq!(rejected.error(), "Email already registered");
+}
+
+#[sqlx::test]
+async fn emails_are_stored_lowercased_and_login_ignores_case(pool: PgPool) {
+    let app = common::test_app(pool);
+    let client = app.client();
+
+    let response = client.post("/api/auth/register", registration("  Alice@Example.COM ")).await;
+    assert_eq!(response.status, StatusCode::CREATED, "{}", response.text());
+    assert_eq!(response.json()["user"]["email"], "alice@example.com");
+
+    let stored: String = sqlx::query_scalar("SELECT email FROM users")
+        .fetch_one(app.pool())
+        .await
+        .unwrap();
+    assert_eq!(stored, "alice@example.com");
+
+    for email in ["alice@example.com", "ALICE@EXAMPLE.COM", " Alice@example.com"] {
+        let body = json!({ "email": email, "password": common::PASSWORD });
+        let response = client.post("/api/auth/login", body).await;
+        assert_eq!(response.status, StatusCode::OK, "{}: {}", email, response.text());
+    }
+
+    let response = client.post("/api/auth/register", registration("ALICE@example.com")).await;
+    assert_eq!(response.status, StatusCode::BAD_REQUEST);
+    assert_eq!(response.error(), "Email already registered");
+}
@@ -0,0 +1,509 @@
+mod common;
+
+use axum::http::StatusCode;
+use serde_json::{json, Value};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+/// Ids of all the user's categories, in list order.
+async fn category_ids(user: &common::TestUser) -> Vec<Value> {
+    let response = user.get("/api/categories").await;
+    assert_eq!(response.status, StatusCode::OK);
+    let items = response.json()["items"].as_array().unwrap().clone();
+    items.iter().map(|c| c["id"].clone()).collect()
+}
+
+/// Names on one page of `GET /api/categories?{query}`, plus the page's `total`.
+async fn category_page(user: &common::TestUser, query: &str) -> (Vec<String>, Value) {
+    let response = user.get(&format!("/api/categories?{}", query)).await;
+    assert_eq!(response.status, StatusCode::OK, "{}", response.text());
+    let page = response.json();
+    let names = page["items"].as_array().unwrap().iter();
+    let names = names.map(|c| c["name"].as_str().unwrap().to_string()).collect();
+    (names, page["total"].clone())
+}
+
+#[sqlx::test]
+async fn search_filters_by_name_and_pages_in_sql(pool: PgPool) {
+    let app = common::test_app(pool);
+    let alice = app.register_user("alice@example.com").await;
+    let bob = app.register_user("bob@example.com").await;
+    for name in ["Food Truck", "100% Organic", "Snack_Food"] {
+        let response = alice.post("/api/categories", json!({ "name": name })).await;
+        assert_eq!(response.status, StatusCode::CREATED, "{}", response.text());
+    }
+    let response = bob.post("/api/categories", json!({ "name": "Bob's Food" })).await;
+    assert_eq!(response.status, StatusCode::CREATED);
+
+    let (names, total) = category_page(&alice, "search=FOOD&sort=name").await;
+    assert_eq!(names, ["Food & Dining", "Food Truck", "Snack_Food"]);
+    assert_eq!(total, 3);
+
+    let (names, total) = category_page(&alice, "search=food&sort=name&limit=2&offset=1").await;
+    assert_eq!(names, ["Food Truck", "Snack_Food"]);
+    assert_eq!(total, 3);
+
+    // Past the end the total is still reported
+    let (names, total) = category_page(&alice, "search=food&limit=2&offset=10").await;
+    assert!(names.is_empty());
+    assert_eq!(total, 3);
+
+    // Wildcards in the search match literally
+    let (names, _) = category_page(&alice, "search=%25").await;
+    assert_eq!(names, ["100% Organic"]);
+    let (names, _) = category_page(&alice, "search=k_f").await;
+    assert_eq!(names, ["Snack_Food"]);
+
+    // Without parameters every category comes back on one page
+    let response = alice.get("/api/categories").await.json();
+    assert_eq!(response["total"], 10);
+    assert_eq!(response["items"].as_array().unwrap().len(), 10);
+    assert_eq!(response["total_pages"], 1);
+
+    let response = alice.get("/api/categories?limit=0").await;
+    assert_eq!(response.status, StatusCode::BAD_REQUEST);
+}
+
+#[sqlx::test]
+async fn reorder_accepts_exactly_max_batch_size_ids(pool: PgPool) {
+    let mut config = common::test_config();
+    // New users get seven default categories
+    config.max_batch_size = 7;
+    let app = common::test_app_with(pool, config);
+    let user = app.register_user("alice@example.com").await;
+
+    let mut ids = category_ids(&user).await;
+    assert_eq!(ids.len(), 7);
+    ids.reverse();
+
+    let response = user.post("/api/categories/reorder", json!({ "ids": ids })).await;
+    assert_eq!(response.status, StatusCode::OK, "{}", response.text());
+
+    ids.push(json!(Uuid::new_v4()));
+    let response = user.post("/api/categories/reorder", json!({ "ids": ids })).await;
+    assert_eq!(response.status, StatusCode::BAD_REQUEST);
+    assert_eq!(response.error(), "ids can have at most 7 entries, got 8");
+}
+
+#[sqlx::test]
+async fn update_skips_clears_or_sets_each_field(pool: PgPool) {
+    let app = common::test_app(pool);
+    let user = app.register_user("alice@example.com").await;
+    let uri = format!("/api/categories/{}", user.category_id("Shopping").await);
+    let before = user.get(&uri).await.json();
+    assert!(before["color"].is_string() && before["icon"].is_string());
+
+    // Omitted fields are left alone
+    let renamed = user.put(&uri, json!({ "name": "Kid's stuff" })).await;
+    assert_eq!(renamed.status, StatusCode::OK, "{}", renamed.text());
+    let renamed = renamed.json();
+    assert_eq!(renamed["name"], "Kid's stuff");
+    assert_eq!(renamed["color"], before["color"]);
+    assert_eq!(renamed["icon"], before["icon"]);
+    assert_eq!(renamed["sort_order"], before["sort_order"]);
+
+    // An explicit null clears
+    let cleared = user.put(&uri, json!({ "color": null, "icon": null })).await.json();
+    assert!(cleared["color"].is_null() && cleared["icon"].is_null());
+    assert_eq!(cleared["name"], "Kid's stuff");
+
+    let set = user.put(&uri, json!({ "color": "#123456", "icon": "🧸", "sort_order": 9 })).await;
+    let set = set.json();
+    assert_eq!(set["color"], "#123456");
+    assert_eq!(set["icon"], "🧸");
+    assert_eq!(set["sort_order"], 9);
+    assert_eq!(user.get(&uri).await.json(), set);
+}
+
+#[sqlx::test]
+async fn update_rejects_empty_duplicate_and_foreign_changes(pool: PgPool) {
+    let app = common::test_app(pool);
+    let alice = app.register_user("alice@example.com").await;
+    let bob = app.register_user("bob@example.com").await;
+    let uri = format!("/api/categories/{}", alice.category_id("Shopping").await);
+
+    let response = alice.put(&uri, json!({})).await;
+    assert_eq!(response.status, StatusCode::BAD_REQUEST);
+    assert_eq!(response.error(), "No fields to update");
+
+    let response = alice.put(&uri, json!({ "name": "Entertainment" })).await;
+    assert_eq!(response.status, StatusCode::BAD_REQUEST, "{}", response.text());
+
+    let response = bob.put(&uri, json!({ "name": "Mine now" })).await;
+    assert_eq!(response.status, StatusCode::NOT_FOUND);
+    assert_eq!(alice.get(&uri).await.json()["name"], "Shopping");
+}
+
+#[sqlx::test]
+async fn merge_moves_templates_budgets_and_alerts(pool: PgPool) {
+    let app = common::test_app(pool);
+    let user = app.register_user("alice@example.com").await;
+    let source = user.category_id("Shopping").await;
+    let target = user.category_id("Entertainment").await;
+
+    let template = user
+        .post(
+            "/api/expense-templates",
+            json!({ "name": "Gym", "category_id": source, "amount": 30, "description": "Gym" }),
+        )
+        .await;
+    assert_eq!(template.status, StatusCode::CREATED, "{}", template.text());
+    for (category, amount) in [(source, 100), (target, 250)] {
+        let response = user
+            .put(&format!("/api/budgets/{}", category), json!({ "amount": amount }))
+            .await;
+        assert!(response.status.is_success(), "{}", response.text());
+    }
+    // 80% exists on both sides, 50% only on the source
+    for (category, threshold) in [(source, 80), (source, 50), (target, 80)] {
+        let alert = json!({
+            "alert_type": "budget",
+            "category_id": category,
+            "threshold_percent": threshold,
+        });
+        let response = user.post("/api/alerts", alert).await;
+        assert_eq!(response.status, StatusCode::CREATED, "{}", response.text());
+    }
+
+    let uri = format!("/api/categories/{}/merge", source);
+    let response = user.post(&uri, json!({ "into": target })).await;
+    assert_eq!(response.status, StatusCode::OK, "{}", response.text());
+
+    let templates = user.get("/api/expense-templates").await.json();
+    assert_eq!(templates[0]["name"], "Gym");
+    assert_eq!(templates[0]["category_id"], json!(target));
+
+    let budgets: Vec<(Uuid, String)> =
+        sqlx::query_as("SELECT category_id, amount::TEXT FROM budgets")
+            .fetch_all(app.pool())
+            .await
+            .unwrap();
+    assert_eq!(budgets, [(target, "250.00".to_string())]);
+
+    let mut alerts: Vec<(Uuid, i32)> =
+        sqlx::query_as("SELECT category_id, threshold_percent FROM alerts")
+            .fetch_all(app.pool())
+            .await
+            .unwrap();
+    alerts.sort_by_key(|(_, threshold)| *threshold);
+    assert_eq!(alerts, [(target, 50), (target, 80)]);
+}
+
+#[sqlx::test]
+async fn merge_moves_a_budget_the_target_lacks(pool: PgPool) {
+    let app = common::test_app(pool);
+    let user = app.register_user("alice@example.com").await;
+    let source = user.category_id("Shopping").await;
+    let target = user.category_id("Entertainment").await;
+    let response = user.put(&format!("/api/budgets/{}", source), json!({ "amount": 100 })).await;
+    assert!(response.status.is_success(), "{}", response.text());
+
+    let uri = format!("/api/categories/{}/merge", source);
+    assert_eq!(user.post(&uri, json!({ "into": target })).await.status, StatusCode::OK);
+
+    let budgets: Vec<Uuid> = sqlx::query_scalar("SELECT category_id FROM budgets")
+        .fetch_all(app.pool())
+        .await
+        .unwrap();
+    assert_eq!(budgets, [target]);
+}
+
+#[sqlx::test]
+async fn merge_combines_expenses_and_split_parts(pool: PgPool) {
+    let app = common::test_app(pool);
+    let user = app.register_user("alice@example.com").await;
+    let source = user.category_id("Shopping").await;
+    let target = user.category_id("Entertainment").await;
+    let food = user.category_id("Food & Dining").await;
+
+    user.create_expense(source, 10.0, "2024-01-05").await;
+    user.create_expense(target, 20.0, "2024-01-06").await;
+    let split = user
+        .post(
+            "/api/expenses",
+            json!({
+                "category_id": food,
+                "amount": 30,
+                "description": "Mall",
+                "expense_date": "2024-01-07",
+                "splits": [
+                    { "category_id": food, "amount": 5 },
+                    { "category_id": source, "amount": 15 },
+                    { "category_id": target, "amount": 10 },
+                ],
+            }),
+        )
+        .await;
+    assert_eq!(split.status, StatusCode::CREATED, "{}", split.text());
+
+    let uri = format!("/api/categories/{}/merge", source);
+    let response = user.post(&uri, json!({ "into": target })).await;
+    assert_eq!(response.status, StatusCode::OK, "{}", response.text());
+    assert_eq!(response.json()["id"], json!(target));
+
+    let source_uri = format!("/api/categories/{}", source);
+    assert_eq!(user.get(&source_uri).await.status, StatusCode::NOT_FOUND);
+    let page = user.get(&format!("/api/expenses?category_id={}", target)).await.json();
+    assert_eq!(page["total"], 2);
+
+    let parts: Vec<(Uuid, String)> = sqlx::query_as(
+        "SELECT category_id, amount::TEXT FROM expense_splits ORDER BY amount::NUMERIC",
+    )
+    .fetch_all(app.pool())
+    .await
+    .unwrap();
+    assert_eq!(parts, [(food, "5.00".to_string()), (target, "25.00".to_string())]);
+}
+
+#[sqlx::test]
+async fn only_hex_colors_are_accepted(pool: PgPool) {
+    let app = common::test_app(pool);
+    let user = app.register_user("alice@example.com").await;
+
+    for color in ["red", "#FFF", "#12345G", "#123456\"><script>", ""] {
+        let response =
+            user.post("/api/categories", json!({ "name": "Pets", "color": color })).await;
+        assert_eq!(response.status, StatusCode::BAD_REQUEST, "{:?} was accepted", color);
+        assert!(response.error().contains("Color must be a hex code like #4ECDC4"));
+    }
+
+    let body = json!({ "name": "Pets", "color": "#a1B2c3" });
+    let response = user.post("/api/categories", body).await;
+    assert_eq!(response.status, StatusCode::CREATED, "{}", response.text());
+    assert_eq!(response.json()["color"], "#a1B2c3");
+
+    let uri = format!("/api/categories/{}", response.json()["id"].as_str().unwrap());
+    let response = user.put(&uri, json!({ "color": "blue" })).await;
+    assert_eq!(response.status, StatusCode::BAD_REQUEST);
+    assert!(response.error().contains("Color must be a hex code like #4ECDC4"));
+
+    let response = user.put(&uri, json!({ "color": "#000000" })).await;
+    assert_eq!(response.status, StatusCode::OK, "{}", response.text());
+    assert_eq!(response.json()["color"], "#000000");
+}
+
+#[sqlx::test]
+async fn new_users_start_with_the_default_categories(pool: PgPool) {
+    let app = common::test_app(pool);
+    let user = app.register_user("alice@example.com").await;
+
+    let response = user.get("/api/categories?sort=manual").await.json();
+    let categories: Vec<(&str, &str, &str)> = response["items"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|c| {
+            let field = |name: &str| c[name].as_str().unwrap();
+            (field("name"), field("color"), field("icon"))
+        })
+        .collect();
+    assert_eq!(
+        categories,
+        [
+            ("Food & Dining", "#FF6B6B", "🍔"),
+            ("Transportation", "#4ECDC4", "🚗"),
+            ("Shopping", "#45B7D1", "🛍️"),
+            ("Entertainment", "#96CEB4", "🎬"),
+            ("Bills & Utilities", "#FFEAA7", "💡"),
+            ("Healthcare", "#DFE6E9", "🏥"),
+            ("Other", "#B2BEC3", "📦"),
+        ]
+    );
+
+    // Each user gets their own copies
+    let other = app.register_user("bob@example.com").await;
+    assert_ne!(user.category_id("Other").await, other.category_id("Other").await);
+}
+
+#[sqlx::test]
+async fn concurrent_duplicate_names_get_one_category_and_a_400(pool: PgPool) {
+    let app = common::test_app(pool);
+    let user = app.register_user("alice@example.com").await;
+
+    let body = json!({ "name": "Pets" });
+    let (first, second) = tokio::join!(
+        user.post("/api/categories", body.clone()),
+        user.post("/api/categories", body.clone()),
+    );
+    let (created, rejected) =
+        if first.status == StatusCode::CREATED { (first, second) } else { (second, first) };
+    assert_eq!(created.status, StatusCode::CREATED, "{}", created.text());
+    assert_eq!(rejected.status, StatusCode::BAD_REQUEST, "{}", rejected.text());
+    assert_eq!(rejected.error(), "Category name already exists");
+
+    let count: i64 =
+        sqlx::query_scalar("SELECT COUNT(*) FROM categories WHERE user_id = $1 AND name = 'Pets'")
+            .bind(user.id)
+            .fetch_one(app.pool())
+            .await
+            .unwrap();
+    assert_eq!(count, 1);
+}
+
+#[sqlx::test]
+async fn category_names_are_trimmed(pool: PgPool) {
+    let app = common::test_app(pool);
+    let user = app.register_user("alice@example.com").await;
+
+    let response = user.post("/api/categories", json!({ "name": "  Pets  " })).await;
+    assert_eq!(response.status, StatusCode::CREATED);
+    assert_eq!(response.json()["name"], "Pets");
+
+    let response = user.post("/api/categories", json!({ "name": "   " })).await;
+    assert_eq!(response.status, StatusCode::BAD_REQUEST);
+}
+
+#[sqlx::test]
+async fn upsert_returns_the_existing_category_instead_of_a_400(pool: PgPool) {
+    let app = common::test_app(pool);
+    let user = app.register_user("alice@example.com").await;
+    let shopping = user.category_id("Shopping").await;
+    let before = category_ids(&user).await.len();
+
+    let response = user.post("/api/categories", json!({ "name": "Shopping" })).await;
+    assert_eq!(response.status, StatusCode::BAD_REQUEST);
+    assert_eq!(response.error(), "Category name already exists");
+
+    // The existing row comes back as it was, not with the new color
+    let body = json!({ "name": "Shopping", "color": "#000000" });
+    let response = user.post("/api/categories?upsert=true", body).await;
+    assert_eq!(response.status, StatusCode::OK, "{}", response.text());
+    let existing = response.json();
+    assert_eq!(existing["id"], shopping.to_string());
+    assert_eq!(existing["color"], "#45B7D1");
+
+    // A new name is still created
+    let response = user.post("/api/categories?upsert=true", json!({ "name": "Pets" })).await;
+    assert_eq!(response.status, StatusCode::CREATED, "{}", response.text());
+    let response = user.post("/api/categories?upsert=false", json!({ "name": "Pets" })).await;
+    assert_eq!(response.status, StatusCode::BAD_REQUEST);
+
+    assert_eq!(category_ids(&user).await.len(), before + 1);
+}
+
+#[sqlx::test]
+async fn cached_category_list_is_refreshed_after_each_change(pool: PgPool) {
+    let mut config = common::test_config();
+    config.category_cache_enabled = true;
+    let app = common::test_app_with(pool, config);
+    let user = app.register_user("alice@example.com").await;
+
+    let names = || async {
+        let response = user.get("/api/dashboard").await;
+        assert_eq!(response.status, StatusCode::OK, "{}", response.text());
+        let categories = response.json()["categories"].as_array().unwrap().clone();
+        categories.iter().map(|c| c["name"].as_str().unwrap().to_string()).collect::<Vec<_>>()
+    };
+    let before = names().await;
+
+    // Written behind the app's back, so only a cache miss would show it
+    sqlx::query("INSERT INTO categories (user_id, name, sort_order) VALUES ($1, 'Sneaky', 99)")
+        .bind(user.id)
+        .execute(app.pool())
+        .await
+        .unwrap();
+    assert_eq!(names().await, before);
+
+    let response = user.post("/api/categories", json!({ "name": "Pets" })).await;
+    assert_eq!(response.status, StatusCode::CREATED);
+    let pets = response.json()["id"].as_str().unwrap().to_string();
+    let after_create = names().await;
+    assert!(after_create.contains(&"Pets".to_string()), "{:?}", after_create);
+    assert!(after_create.contains(&"Sneaky".to_string()), "{:?}", after_create);
+
+    let uri = format!("/api/categories/{}", pets);
+    let response = user.put(&uri, json!({ "name": "Animals" })).await;
+    assert_eq!(response.status, StatusCode::OK, "{}", response.text());
+    let after_update = names().await;
+    assert!(after_update.contains(&"Animals".to_string()), "{:?}", after_update);
+    assert!(!after_update.contains(&"Pets".to_string()), "{:?}", after_update);
+
+    let response = user.delete(&uri).await;
+    assert!(response.status.is_success(), "{}", response.text());
+    assert!(!names().await.contains(&"Animals".to_string()));
+}
+
+#[sqlx::test]
+async fn active_categories_are_capped_at_the_configured_maximum(pool: PgPool) {
+    let mut config = common::test_config();
+    // The seven defaults plus one
+    config.max_categories_per_user = 8;
+    let app = common::test_app_with(pool, config);
+    let user = app.register_user("alice@example.com").await;
+    let limit_error =
+        "Category limit reached: at most 8 active categories; archive or delete one first";
+
+    let response = user.post("/api/categories", json!({ "name": "Pets" })).await;
+    assert_eq!(response.status, StatusCode::CREATED, "{}", response.text());
+
+    let response = user.post("/api/categories", json!({ "name": "Garden" })).await;
+    assert_eq!(response.status, StatusCode::BAD_REQUEST);
+    assert_eq!(response.error(), limit_error);
+    assert_eq!(category_ids(&user).await.len(), 8);
+
+    // Upserting an existing name adds nothing, so it still succeeds
+    let response = user.post("/api/categories?upsert=true", json!({ "name": "Pets" })).await;
+    assert_eq!(response.status, StatusCode::OK, "{}", response.text());
+
+    // Archived categories don't count, until they're unarchived
+    let other = user.category_id("Other").await;
+    let response = user.post(&format!("/api/categories/{}/archive", other), json!({})).await;
+    assert_eq!(response.status, StatusCode::OK, "{}", response.text());
+    let response = user.post("/api/categories", json!({ "name": "Garden" })).await;
+    assert_eq!(response.status, StatusCode::CREATED, "{}", response.text());
+
+    let response = user.post(&format!("/api/categories/{}/unarchive", other), json!({})).await;
+    assert_eq!(response.status, StatusCode::BAD_REQUEST);
+    assert_eq!(response.error(), limit_error);
+}
+
+#[sqlx::test]
+async fn reimporting_an_exported_set_is_a_no_op(pool: PgPool) {
+    let app = common::test_app(pool);
+    let alice = app.register_user("alice@example.com").await;
+    let bob = app.register_user("bob@example.com").await;
+    let response = alice.post("/api/categories", json!({ "name": "Pets", "icon": "🐶" })).await;
+    assert_eq!(response.status, StatusCode::CREATED);
+
+    let response = alice.get("/api/categories/export").await;
+    assert_eq!(response.status, StatusCode::OK);
+    let exported = response.json();
+    assert_eq!(exported.as_array().unwrap().len(), 8);
+
+    // Bob has the seven defaults already, so only Pets is new to him
+    let response = bob.post("/api/categories/import", exported.clone()).await;
+    assert_eq!(response.status, StatusCode::OK, "{}", response.text());
+    assert_eq!(response.json(), json!({ "created": 1, "skipped": 7 }));
+    assert_eq!(bob.get("/api/categories/export").await.json(), exported);
+
+    // Importing the same set again changes nothing for either of them
+    for user in [&alice, &bob] {
+        let before = category_ids(user).await;
+        let response = user.post("/api/categories/import", exported.clone()).await;
+        assert_eq!(response.status, StatusCode::OK, "{}", response.text());
+        assert_eq!(response.json(), json!({ "created": 0, "skipped": 8 }));
+        assert_eq!(category_ids(user).await, before);
+    }
+}
+
+#[sqlx::test]
+async fn saved_category_sort_applies_unless_the_query_names_one(pool: PgPool) {
+    let app = common::test_app(pool);
+    let user = app.register_user("alice@example.com").await;
+    let manual = category_page(&user, "").await.0;
+    assert_eq!(manual[0], "Food & Dining");
+    let mut alphabetical = manual.clone();
+    alphabetical.sort();
+
+    let response = user.put("/api/users/me/settings", json!({ "category_sort": "name" })).await;
+    assert_eq!(response.status, StatusCode::OK, "{}", response.text());
+    assert_eq!(category_page(&user, "").await.0, alphabetical);
+    assert_eq!(category_page(&user, "sort=manual").await.0, manual);
+
+    let other = user.category_id("Other").await;
+    user.create_expense(other, 5.0, "2024-03-01").await;
+    let response = user.put("/api/users/me/settings", json!({ "category_sort": "usage" })).await;
+    assert_eq!(response.status, StatusCode::OK, "{}", response.text());
+    assert_eq!(category_page(&user, "").await.0[0], "Other");
+    assert_eq!(category_page(&user, "sort=name").await.0, alphabetical);
+}
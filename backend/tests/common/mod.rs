@@ -0,0 +1,319 @@
+//! Shared setup for the integration tests.
+//!
+//! Every test gets a fresh database from `#[sqlx::test]` (migrations already
+//! applied) and builds the real router on top of it with [`test_app`]:
+//!
+//! ```rust,ignore
+//! #[sqlx::test]
+//! async fn lists_expenses(pool: PgPool) {
+//!     let app = common::test_app(pool);
+//!     let user = app.register_user("alice@example.com").await;
+//!     let response = user.get("/api/expenses").await;
+//!     assert_eq!(response.status, StatusCode::OK);
+//! }
+//! ```
+//!
+//! `DATABASE_URL` must point at a Postgres server the tests may create
+//! databases on; each test's database is dropped again when it passes.
+
+// Each test binary compiles this module separately and uses only part of it
+#![allow(dead_code)]
+
+use std::{
+    env,
+    net::SocketAddr,
+    ops::Deref,
+    sync::{Arc, Once},
+};
+
+use axum::{
+    body::{Body, Bytes},
+    extract::connect_info::MockConnectInfo,
+    http::{header, request, HeaderMap, Method, Request, StatusCode},
+    response::Response,
+    Router,
+};
+use http_body_util::BodyExt;
+use serde::de::DeserializeOwned;
+use serde_json::{json, Value};
+use sqlx::PgPool;
+use tower::ServiceExt;
+use uuid::Uuid;
+
+use expense_tracker_backend::{
+    auth::JwtKeys, category_cache::CategoryCache, config::Config, events::EventHub, routes,
+    storage::LocalStorage, webhook, AppState,
+};
+
+/// Password every test user registers with.
+pub const PASSWORD: &str = "correct-horse-9";
+
+/// Address requests appear to come from unless a test sets its own.
+pub const CLIENT_ADDR: ([u8; 4], u16) = ([203, 0, 113, 7], 50000);
+
+/// Configuration read from the environment like the server does, with the
+/// rate limits turned off and attachments written to a per-call temp dir.
+///
+/// Tests that need other settings change the returned value and pass it to
+/// [`test_app_with`].
+pub fn test_config() -> Config {
+    static DEFAULTS: Once = Once::new();
+    DEFAULTS.call_once(|| {
+        if env::var("JWT_SECRET").is_err() {
+            env::set_var("JWT_SECRET", "integration-test-secret-of-at-least-32-bytes");
+        }
+    });
+
+    let mut config = Config::from_env().expect("DATABASE_URL must be set to run the tests");
+    config.login_rate_limit.max_requests = 0;
+    config.register_rate_limit.max_requests = 0;
+    config.attachments_dir = env::temp_dir()
+        .join(format!("expense-tracker-test-{}", Uuid::new_v4()))
+        .to_string_lossy()
+        .into_owned();
+    config
+}
+
+/// The app with [`test_config`] on top of `pool`.
+pub fn test_app(pool: PgPool) -> TestApp {
+    test_app_with(pool, test_config())
+}
+
+/// The app with a custom configuration on top of `pool`.
+pub fn test_app_with(pool: PgPool, config: Config) -> TestApp {
+    let jwt_keys = JwtKeys::from_config(&config).expect("valid JWT configuration");
+    let state = AppState {
+        pool,
+        jwt_keys: Arc::new(jwt_keys),
+        metrics: None,
+        storage: Arc::new(LocalStorage::new(&config.attachments_dir)),
+        category_cache: config.category_cache_enabled.then(|| Arc::new(CategoryCache::new())),
+        webhook_client: webhook::http_client(&config).expect("webhook client"),
+        events: Arc::new(EventHub::new()),
+        config: Arc::new(config),
+    };
+
+    TestApp::new(state)
+}
+
+/// The router under test plus the state it was built from.
+pub struct TestApp {
+    pub state: AppState,
+    router: Router,
+}
+
+impl TestApp {
+    pub fn new(state: AppState) -> Self {
+        let router = routes::create_router(state.clone())
+            .layer(MockConnectInfo(SocketAddr::from(CLIENT_ADDR)));
+        Self { state, router }
+    }
+
+    pub fn pool(&self) -> &PgPool {
+        &self.state.pool
+    }
+
+    /// A client without a token, for the public endpoints.
+    pub fn client(&self) -> TestClient {
+        TestClient {
+            router: self.router.clone(),
+            token: None,
+        }
+    }
+
+    /// A client sending `token`, e.g. one from a second login.
+    pub fn client_with_token(&self, token: &str) -> TestClient {
+        TestClient {
+            router: self.router.clone(),
+            token: Some(token.to_string()),
+        }
+    }
+
+    /// Registers `email` through the API and returns a client logged in as it.
+    pub async fn register_user(&self, email: &str) -> TestUser {
+        let response = self
+            .client()
+            .post(
+                "/api/auth/register",
+                json!({ "email": email, "password": PASSWORD, "full_name": "Test User" }),
+            )
+            .await;
+        assert_eq!(response.status, StatusCode::CREATED, "register failed: {}", response.text());
+
+        let body = response.json();
+        let token = body["token"].as_str().expect("token in response").to_string();
+        TestUser {
+            id: body["user"]["id"].as_str().and_then(|id| id.parse().ok()).expect("user id"),
+            email: email.to_string(),
+            client: TestClient {
+                router: self.router.clone(),
+                token: Some(token.clone()),
+            },
+            token,
+        }
+    }
+}
+
+/// Sends requests to the router, with a bearer token when it has one.
+#[derive(Clone)]
+pub struct TestClient {
+    router: Router,
+    token: Option<String>,
+}
+
+impl TestClient {
+    /// Starts a request carrying this client's token, for tests that need
+    /// extra headers; finish it with [`json_request`] or a body and [`Self::send`].
+    pub fn request(&self, method: Method, uri: &str) -> request::Builder {
+        let builder = Request::builder().method(method).uri(uri);
+        match &self.token {
+            Some(token) => builder.header(header::AUTHORIZATION, format!("Bearer {}", token)),
+            None => builder,
+        }
+    }
+
+    /// Sends `request` without reading the body, for streaming responses.
+    pub async fn open(&self, request: Request<Body>) -> Response {
+        self.router.clone().oneshot(request).await.expect("infallible router")
+    }
+
+    pub async fn send(&self, request: Request<Body>) -> TestResponse {
+        let response = self.router.clone().oneshot(request).await.expect("infallible router");
+        let status = response.status();
+        let headers = response.headers().clone();
+        let body = response.into_body().collect().await.expect("response body").to_bytes();
+
+        TestResponse { status, headers, body }
+    }
+
+    pub async fn get(&self, uri: &str) -> TestResponse {
+        self.send(self.request(Method::GET, uri).body(Body::empty()).unwrap()).await
+    }
+
+    pub async fn post(&self, uri: &str, body: Value) -> TestResponse {
+        self.send(json_request(self.request(Method::POST, uri), &body)).await
+    }
+
+    pub async fn put(&self, uri: &str, body: Value) -> TestResponse {
+        self.send(json_request(self.request(Method::PUT, uri), &body)).await
+    }
+
+    pub async fn delete(&self, uri: &str) -> TestResponse {
+        self.send(self.request(Method::DELETE, uri).body(Body::empty()).unwrap()).await
+    }
+}
+
+/// Finishes `builder` with `body` as JSON.
+pub fn json_request(builder: request::Builder, body: &Value) -> Request<Body> {
+    builder
+        .header(header::CONTENT_TYPE, "application/json")
+        .body(Body::from(body.to_string()))
+        .unwrap()
+}
+
+/// A registered user; derefs to a [`TestClient`] sending its token.
+pub struct TestUser {
+    pub id: Uuid,
+    pub email: String,
+    pub token: String,
+    pub client: TestClient,
+}
+
+impl Deref for TestUser {
+    type Target = TestClient;
+
+    fn deref(&self) -> &TestClient {
+        &self.client
+    }
+}
+
+impl TestUser {
+    /// Id of one of the user's categories, e.g. a seeded default like "Shopping".
+    pub async fn category_id(&self, name: &str) -> Uuid {
+        let response = self.get("/api/categories").await;
+        assert_eq!(response.status, StatusCode::OK);
+
+        response.json()["items"]
+            .as_array()
+            .expect("category list")
+            .iter()
+            .find(|category| category["name"] == name)
+            .and_then(|category| category["id"].as_str())
+            .and_then(|id| id.parse().ok())
+            .unwrap_or_else(|| panic!("no category named {}", name))
+    }
+
+    /// Creates an expense and returns its JSON, failing the test if that doesn't work.
+    pub async fn create_expense(&self, category_id: Uuid, amount: f64, date: &str) -> Value {
+        let response = self
+            .post(
+                "/api/expenses",
+                json!({
+                    "category_id": category_id,
+                    "amount": amount,
+                    "description": "Test expense",
+                    "expense_date": date,
+                }),
+            )
+            .await;
+        assert_eq!(response.status, StatusCode::CREATED, "create failed: {}", response.text());
+        response.json()
+    }
+
+    /// Uploads `data` to an expense as a multipart file field.
+    pub async fn upload_attachment(
+        &self,
+        expense_id: &str,
+        filename: &str,
+        content_type: &str,
+        data: &[u8],
+    ) -> TestResponse {
+        const BOUNDARY: &str = "test-boundary";
+        let mut body = format!(
+            "--{BOUNDARY}\r\n\
+             Content-Disposition: form-data; name=\"file\"; filename=\"{filename}\"\r\n\
+             Content-Type: {content_type}\r\n\r\n"
+        )
+        .into_bytes();
+        body.extend_from_slice(data);
+        body.extend_from_slice(format!("\r\n--{BOUNDARY}--\r\n").as_bytes());
+
+        let request = self
+            .request(Method::POST, &format!("/api/expenses/{}/attachments", expense_id))
+            .header(header::CONTENT_TYPE, format!("multipart/form-data; boundary={BOUNDARY}"))
+            .body(Body::from(body))
+            .unwrap();
+        self.send(request).await
+    }
+}
+
+pub struct TestResponse {
+    pub status: StatusCode,
+    pub headers: HeaderMap,
+    pub body: Bytes,
+}
+
+impl TestResponse {
+    pub fn json(&self) -> Value {
+        serde_json::from_slice(&self.body)
+            .unwrap_or_else(|e| panic!("response is not JSON ({}): {}", e, self.text()))
+    }
+
+    pub fn json_as<T: DeserializeOwned>(&self) -> T {
+        serde_json::from_slice(&self.body)
+            .unwrap_or_else(|e| panic!("unexpected response ({}): {}", e, self.text()))
+    }
+
+    pub fn text(&self) -> String {
+        String::from_utf8_lossy(&self.body).into_owned()
+    }
+
+    /// The `error` message of an error response.
+    pub fn error(&self) -> String {
+        self.json()["error"].as_str().unwrap_or_default().to_string()
+    }
+
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers.get(name).and_then(|v| v.to_str().ok())
+    }
+}
@@ -0,0 +1,62 @@
+mod common;
+
+use axum::{
+    body::Body,
+    http::{header, Method, Request, StatusCode},
+    routing::get,
+    Router,
+};
+use tower::ServiceExt;
+
+use expense_tracker_backend::cors_layer;
+
+#[test]
+fn credentials_with_a_wildcard_origin_fail_at_startup() {
+    let mut config = common::test_config();
+    config.cors_allow_credentials = true;
+
+    for origins in [vec!["*"], vec!["https://app.example.com", "*"]] {
+        config.cors_allowed_origins = origins.iter().map(|origin| origin.to_string()).collect();
+        let error = cors_layer(&config).expect_err("credentials with * must be rejected");
+        assert_eq!(
+            error.to_string(),
+            "CORS_ALLOW_CREDENTIALS=true requires CORS_ALLOWED_ORIGINS to list origins, not *"
+        );
+    }
+
+    // Either setting on its own is fine
+    config.cors_allowed_origins = vec!["https://app.example.com".to_string()];
+    assert!(cors_layer(&config).is_ok());
+    config.cors_allow_credentials = false;
+    config.cors_allowed_origins = vec!["*".to_string()];
+    assert!(cors_layer(&config).is_ok());
+}
+
+#[tokio::test]
+async fn credentialed_preflight_echoes_the_origin_and_caches() {
+    let mut config = common::test_config();
+    config.cors_allow_credentials = true;
+    config.cors_allowed_origins = vec!["https://app.example.com".to_string()];
+    config.cors_max_age_secs = 600;
+    let router = Router::new()
+        .route("/api/expenses", get(|| async { "[]" }))
+        .layer(cors_layer(&config).unwrap());
+
+    let request = Request::builder()
+        .method(Method::OPTIONS)
+        .uri("/api/expenses")
+        .header(header::ORIGIN, "https://app.example.com")
+        .header(header::ACCESS_CONTROL_REQUEST_METHOD, "DELETE")
+        .header(header::ACCESS_CONTROL_REQUEST_HEADERS, "authorization")
+        .body(Body::empty())
+        .unwrap();
+    let response = router.oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let headers = response.headers();
+    assert_eq!(headers[header::ACCESS_CONTROL_ALLOW_ORIGIN], "https://app.example.com");
+    assert_eq!(headers[header::ACCESS_CONTROL_ALLOW_CREDENTIALS], "true");
+    assert_eq!(headers[header::ACCESS_CONTROL_ALLOW_METHODS], "DELETE");
+    assert_eq!(headers[header::ACCESS_CONTROL_ALLOW_HEADERS], "authorization");
+    assert_eq!(headers[header::ACCESS_CONTROL_MAX_AGE], "600");
+}
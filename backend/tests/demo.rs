@@ -0,0 +1,23 @@
+mod common;
+
+use axum::http::StatusCode;
+use serde_json::json;
+use sqlx::PgPool;
+
+#[sqlx::test]
+async fn seeding_again_is_a_no_op(pool: PgPool) {
+    let app = common::test_app(pool);
+    let user = app.register_user("alice@example.com").await;
+
+    let response = user.post("/api/users/me/seed-demo?seed=42", json!({})).await;
+    assert_eq!(response.status, StatusCode::OK, "{}", response.text());
+    let created = response.json()["created"].as_i64().unwrap();
+    assert!(created > 0);
+    let total = user.get("/api/expenses").await.json()["total"].clone();
+    assert_eq!(total, created);
+
+    let response = user.post("/api/users/me/seed-demo?seed=42", json!({})).await;
+    assert_eq!(response.status, StatusCode::OK, "{}", response.text());
+    assert_eq!(response.json(), json!({ "created": 0 }));
+    assert_eq!(user.get("/api/expenses").await.json()["total"], total);
+}
@@ -0,0 +1,104 @@
+mod common;
+
+use std::time::Duration;
+
+use axum::{
+    body::Body,
+    http::{Method, StatusCode},
+    response::Response,
+};
+use http_body_util::BodyExt;
+use sqlx::PgPool;
+
+use common::{TestClient, TestUser};
+
+/// Takes a stream ticket for `user`, failing the test if that doesn't work.
+async fn ticket(user: &TestUser) -> String {
+    let response = user.post("/api/events/ticket", serde_json::json!({})).await;
+    assert_eq!(response.status, StatusCode::OK, "{}", response.text());
+    let body = response.json();
+    assert_eq!(body["expires_in"], 30);
+    body["ticket"].as_str().unwrap().to_string()
+}
+
+/// Opens the event stream with `ticket` and no `Authorization` header.
+async fn open_stream(client: &TestClient, ticket: &str) -> Response {
+    let uri = format!("/api/events?ticket={}", ticket);
+    client.open(client.request(Method::GET, &uri).body(Body::empty()).unwrap()).await
+}
+
+/// Reads the stream until it has sent a `data:` line, and returns that line.
+async fn next_data(body: &mut Body) -> String {
+    let read = async {
+        loop {
+            let frame = body.frame().await.expect("stream ended").expect("stream error");
+            let Ok(chunk) = frame.into_data() else { continue };
+            let text = String::from_utf8(chunk.to_vec()).unwrap();
+            if let Some(line) = text.lines().find(|line| line.starts_with("data:")) {
+                return line.trim_start_matches("data:").trim().to_string();
+            }
+        }
+    };
+    tokio::time::timeout(Duration::from_secs(5), read).await.expect("no event within 5s")
+}
+
+#[sqlx::test]
+async fn ticket_opens_one_stream_that_hears_the_users_changes(pool: PgPool) {
+    let app = common::test_app(pool);
+    let user = app.register_user("alice@example.com").await;
+    let other = app.register_user("bob@example.com").await;
+    let anonymous = app.client();
+
+    let ticket = ticket(&user).await;
+    let response = open_stream(&anonymous, &ticket).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.headers()["content-type"], "text/event-stream");
+    let mut body = response.into_body();
+
+    // Someone else's change is not sent
+    let category = other.category_id("Shopping").await;
+    other.create_expense(category, 5.0, "2024-03-10").await;
+    let category = user.category_id("Shopping").await;
+    let created = user.create_expense(category, 12.0, "2024-03-10").await;
+
+    let event: serde_json::Value = serde_json::from_str(&next_data(&mut body).await).unwrap();
+    assert_eq!(event["type"], "expense.created");
+    assert_eq!(event["expense_id"], created["id"]);
+
+    // Tickets work once
+    let response = open_stream(&anonymous, &ticket).await;
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+}
+
+#[sqlx::test]
+async fn stream_rejects_jwts_in_the_query_and_unknown_tickets(pool: PgPool) {
+    let app = common::test_app(pool);
+    let user = app.register_user("alice@example.com").await;
+    let anonymous = app.client();
+
+    let uri = format!("/api/events?token={}", user.token);
+    let response = anonymous.get(&uri).await;
+    assert_eq!(response.status, StatusCode::UNAUTHORIZED);
+
+    let response = open_stream(&anonymous, "not-a-ticket").await;
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+    let response = anonymous.post("/api/events/ticket", serde_json::json!({})).await;
+    assert_eq!(response.status, StatusCode::UNAUTHORIZED);
+}
+
+#[sqlx::test]
+async fn ticket_of_a_revoked_session_is_refused(pool: PgPool) {
+    let app = common::test_app(pool);
+    let user = app.register_user("alice@example.com").await;
+
+    let ticket = ticket(&user).await;
+    sqlx::query("DELETE FROM sessions WHERE user_id = $1")
+        .bind(user.id)
+        .execute(app.pool())
+        .await
+        .unwrap();
+
+    let response = open_stream(&app.client(), &ticket).await;
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+}
@@ -0,0 +1,622 @@
+mod common;
+
+use axum::http::{Method, StatusCode};
+use serde_json::{json, Value};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use common::{json_request, TestResponse, TestUser};
+use expense_tracker_backend::error::{map_check_violation, AppError};
+
+/// Creates an expense sending `key` as its `Idempotency-Key`.
+async fn create_with_key(user: &TestUser, body: &Value, key: &str) -> TestResponse {
+    let request = user.request(Method::POST, "/api/expenses").header("idempotency-key", key);
+    user.send(json_request(request, body)).await
+}
+
+#[sqlx::test]
+async fn created_expense_is_listed_for_its_owner_only(pool: PgPool) {
+    let app = common::test_app(pool);
+    let alice = app.register_user("alice@example.com").await;
+    let bob = app.register_user("bob@example.com").await;
+    let shopping = alice.category_id("Shopping").await;
+
+    let created = alice.create_expense(shopping, 42.5, "2024-03-10").await;
+    assert_eq!(created["category_name"], "Shopping");
+    assert_eq!(created["amount"], "42.50");
+
+    let page = alice.get("/api/expenses").await;
+    assert_eq!(page.status, StatusCode::OK);
+    let page = page.json();
+    assert_eq!(page["total"], 1);
+    assert_eq!(page["items"][0]["id"], created["id"]);
+
+    let other = bob.get("/api/expenses").await.json();
+    assert_eq!(other["total"], 0);
+
+    let id = created["id"].as_str().unwrap();
+    let response = bob.get(&format!("/api/expenses/{}", id)).await;
+    assert_eq!(response.status, StatusCode::NOT_FOUND);
+}
+
+#[sqlx::test]
+async fn expenses_require_a_token(pool: PgPool) {
+    let app = common::test_app(pool);
+
+    let response = app.client().get("/api/expenses").await;
+    assert_eq!(response.status, StatusCode::UNAUTHORIZED);
+}
+
+#[sqlx::test]
+async fn amount_with_three_decimals_is_rejected(pool: PgPool) {
+    let app = common::test_app(pool);
+    let user = app.register_user("alice@example.com").await;
+    let category_id = user.category_id("Shopping").await;
+
+    let response = user
+        .post(
+            "/api/expenses",
+            json!({
+                "category_id": category_id,
+                "amount": 42.123,
+                "description": "Groceries",
+                "expense_date": "2024-03-10",
+            }),
+        )
+        .await;
+    assert_eq!(response.status, StatusCode::BAD_REQUEST);
+    assert_eq!(response.error(), "Amount must have at most two decimal places");
+
+    let created = user.create_expense(category_id, 42.12, "2024-03-10").await;
+    assert_eq!(created["amount"], "42.12");
+}
+
+#[sqlx::test]
+async fn idempotency_key_replays_the_original_until_it_is_deleted(pool: PgPool) {
+    let app = common::test_app(pool);
+    let user = app.register_user("alice@example.com").await;
+    let body = json!({
+        "category_id": user.category_id("Shopping").await,
+        "amount": 12,
+        "description": "Socks",
+        "expense_date": "2024-03-10",
+    });
+
+    let first = create_with_key(&user, &body, "retry-1").await;
+    assert_eq!(first.status, StatusCode::CREATED, "{}", first.text());
+    let id = first.json()["id"].clone();
+
+    let replay = create_with_key(&user, &body, "retry-1").await;
+    assert_eq!(replay.status, StatusCode::CREATED);
+    assert_eq!(replay.json()["id"], id);
+
+    let response = user.delete(&format!("/api/expenses/{}", id.as_str().unwrap())).await;
+    assert!(response.status.is_success(), "{}", response.text());
+
+    let response = create_with_key(&user, &body, "retry-1").await;
+    assert_eq!(response.status, StatusCode::CONFLICT);
+    assert_eq!(
+        response.error(),
+        "The expense created with this Idempotency-Key has been deleted; restore it from the \
+         trash or use a new key"
+    );
+    assert_eq!(user.get("/api/expenses").await.json()["total"], 0);
+
+    let response = create_with_key(&user, &body, "retry-2").await;
+    assert_eq!(response.status, StatusCode::CREATED);
+}
+
+#[sqlx::test]
+async fn descriptions_are_trimmed_and_limited_to_500_characters(pool: PgPool) {
+    let app = common::test_app(pool);
+    let user = app.register_user("alice@example.com").await;
+    let category_id = user.category_id("Shopping").await;
+    let expense = |description: String| {
+        json!({
+            "category_id": category_id,
+            "amount": 10,
+            "description": description,
+            "expense_date": "2024-03-10",
+        })
+    };
+
+    for description in ["   ".to_string(), "x".repeat(501)] {
+        let response = user.post("/api/expenses", expense(description)).await;
+        assert_eq!(response.status, StatusCode::BAD_REQUEST);
+        assert!(response.error().contains("Description must be 1-500 characters"));
+    }
+
+    // The limit counts characters, not bytes
+    let response = user.post("/api/expenses", expense("é".repeat(500))).await;
+    assert_eq!(response.status, StatusCode::CREATED, "{}", response.text());
+
+    let response = user.post("/api/expenses", expense("  Groceries \n".to_string())).await;
+    assert_eq!(response.status, StatusCode::CREATED);
+    let created = response.json();
+    assert_eq!(created["description"], "Groceries");
+
+    let uri = format!("/api/expenses/{}", created["id"].as_str().unwrap());
+    let response = user.put(&uri, json!({ "description": " \t " })).await;
+    assert_eq!(response.status, StatusCode::BAD_REQUEST);
+    let response = user.put(&uri, json!({ "description": "y".repeat(501) })).await;
+    assert_eq!(response.status, StatusCode::BAD_REQUEST);
+    let response = user.put(&uri, json!({ "description": " Food " })).await;
+    assert_eq!(response.json()["description"], "Food");
+}
+
+#[sqlx::test]
+async fn future_dates_follow_the_configured_policy(pool: PgPool) {
+    let mut config = common::test_config();
+    config.allow_future_expense_dates = false;
+    let app = common::test_app_with(pool.clone(), config);
+    let user = app.register_user("alice@example.com").await;
+    let category_id = user.category_id("Shopping").await;
+    let tomorrow = (chrono::Utc::now().date_naive() + chrono::Days::new(1)).to_string();
+    let today = chrono::Utc::now().date_naive().to_string();
+
+    let expense = |date: &str| {
+        json!({
+            "category_id": category_id,
+            "amount": 10,
+            "description": "Tickets",
+            "expense_date": date,
+        })
+    };
+
+    let response = user.post("/api/expenses", expense(&tomorrow)).await;
+    assert_eq!(response.status, StatusCode::BAD_REQUEST);
+    assert_eq!(response.error(), "Expense date cannot be in the future");
+
+    // Today and backdating are always fine
+    let created = user.create_expense(category_id, 10.0, &today).await;
+    user.create_expense(category_id, 10.0, "2020-02-29").await;
+
+    let uri = format!("/api/expenses/{}", created["id"].as_str().unwrap());
+    let response = user.put(&uri, json!({ "expense_date": tomorrow })).await;
+    assert_eq!(response.status, StatusCode::BAD_REQUEST);
+    assert_eq!(response.error(), "Expense date cannot be in the future");
+
+    let mut config = common::test_config();
+    config.allow_future_expense_dates = true;
+    let app = common::test_app_with(pool, config);
+    let user = app.client_with_token(&user.token);
+    let response = user.post("/api/expenses", expense(&tomorrow)).await;
+    assert_eq!(response.status, StatusCode::CREATED, "{}", response.text());
+    let response = user.put(&uri, json!({ "expense_date": tomorrow })).await;
+    assert_eq!(response.status, StatusCode::OK, "{}", response.text());
+}
+
+#[sqlx::test]
+async fn stale_expected_updated_at_is_rejected_with_409(pool: PgPool) {
+    let app = common::test_app(pool);
+    let user = app.register_user("alice@example.com").await;
+    let category_id = user.category_id("Shopping").await;
+    let created = user.create_expense(category_id, 10.0, "2024-03-10").await;
+    let uri = format!("/api/expenses/{}", created["id"].as_str().unwrap());
+    let loaded = created["updated_at"].clone();
+
+    // The first tab saves with the version it loaded
+    let response =
+        user.put(&uri, json!({ "amount": 11, "expected_updated_at": loaded })).await;
+    assert_eq!(response.status, StatusCode::OK, "{}", response.text());
+    assert_ne!(response.json()["updated_at"], loaded);
+
+    // The second tab still holds the old version
+    let response =
+        user.put(&uri, json!({ "amount": 12, "expected_updated_at": loaded })).await;
+    assert_eq!(response.status, StatusCode::CONFLICT);
+    assert_eq!(
+        response.error(),
+        "Expense was modified by another request; reload and try again"
+    );
+    assert_eq!(user.get(&uri).await.json()["amount"], "11.00");
+
+    // Only the successful edit left a history entry
+    let history = user.get(&format!("{}/history", uri)).await.json();
+    assert_eq!(history.as_array().unwrap().len(), 1);
+
+    // Without the field the last write wins as before
+    let response = user.put(&uri, json!({ "amount": 13 })).await;
+    assert_eq!(response.status, StatusCode::OK);
+    assert_eq!(response.json()["amount"], "13.00");
+}
+
+#[sqlx::test]
+async fn foreign_categories_are_listed_in_the_not_found_error(pool: PgPool) {
+    let app = common::test_app(pool);
+    let alice = app.register_user("alice@example.com").await;
+    let bob = app.register_user("bob@example.com").await;
+    let shopping = alice.category_id("Shopping").await;
+    let bobs_food = bob.category_id("Food & Dining").await;
+    let bobs_other = bob.category_id("Other").await;
+
+    let split = |category_ids: [Uuid; 3]| {
+        json!({
+            "category_id": category_ids[0],
+            "amount": 30,
+            "description": "Supermarket",
+            "expense_date": "2024-03-10",
+            "splits": [
+                { "category_id": category_ids[0], "amount": 10 },
+                { "category_id": category_ids[1], "amount": 10 },
+                { "category_id": category_ids[2], "amount": 10 },
+            ],
+        })
+    };
+
+    let response = alice.post("/api/expenses", split([shopping, bobs_food, bobs_other])).await;
+    assert_eq!(response.status, StatusCode::NOT_FOUND);
+    assert_eq!(
+        response.error(),
+        format!("Categories not found: {}, {}", bobs_food, bobs_other)
+    );
+
+    // A single offender is named on its own
+    let alices_food = alice.category_id("Food & Dining").await;
+    let response = alice.post("/api/expenses", split([shopping, alices_food, bobs_other])).await;
+    assert_eq!(response.status, StatusCode::NOT_FOUND);
+    assert_eq!(response.error(), format!("Category not found: {}", bobs_other));
+
+    assert_eq!(alice.get("/api/expenses").await.json()["total"], 0);
+    assert_eq!(bob.get("/api/expenses").await.json()["total"], 0);
+}
+
+#[sqlx::test]
+async fn list_filters_combine_in_every_combination(pool: PgPool) {
+    let app = common::test_app(pool);
+    let user = app.register_user("alice@example.com").await;
+    let food = user.category_id("Food & Dining").await;
+    let shopping = user.category_id("Shopping").await;
+    let other = user.category_id("Other").await;
+
+    let mut expenses = Vec::new();
+    for (category_id, date) in [
+        (food, "2024-01-05"),
+        (shopping, "2024-02-10"),
+        (other, "2024-02-20"),
+        (food, "2024-03-15"),
+        (shopping, "2024-04-01"),
+    ] {
+        let created = user.create_expense(category_id, 10.0, date).await;
+        expenses.push((created["id"].as_str().unwrap().to_string(), category_id, date));
+    }
+
+    let (start, end) = ("2024-02-10", "2024-03-15");
+    for combination in 0..16 {
+        let start_date = (combination & 1 != 0).then_some(start);
+        let end_date = (combination & 2 != 0).then_some(end);
+        let category_id = (combination & 4 != 0).then_some(food);
+        let category_ids = (combination & 8 != 0).then_some([shopping, other]);
+
+        let mut params = vec!["limit=100".to_string()];
+        if let Some(date) = start_date {
+            params.push(format!("start_date={}", date));
+        }
+        if let Some(date) = end_date {
+            params.push(format!("end_date={}", date));
+        }
+        if let Some(id) = category_id {
+            params.push(format!("category_id={}", id));
+        }
+        if let Some([a, b]) = category_ids {
+            params.push(format!("category_ids={},{}", a, b));
+        }
+
+        // Both category parameters together match either
+        let wanted: Vec<Uuid> =
+            category_id.into_iter().chain(category_ids.into_iter().flatten()).collect();
+        let mut expected: Vec<&str> = expenses
+            .iter()
+            .filter(|(_, _, date)| start_date.is_none_or(|start| *date >= start))
+            .filter(|(_, _, date)| end_date.is_none_or(|end| *date <= end))
+            .filter(|(_, category, _)| wanted.is_empty() || wanted.contains(category))
+            .map(|(id, ..)| id.as_str())
+            .collect();
+        expected.sort();
+
+        let uri = format!("/api/expenses?{}", params.join("&"));
+        let response = user.get(&uri).await;
+        assert_eq!(response.status, StatusCode::OK, "{}: {}", uri, response.text());
+        let page = response.json();
+        let mut ids: Vec<&str> = page["items"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|expense| expense["id"].as_str().unwrap())
+            .collect();
+        ids.sort();
+        assert_eq!(ids, expected, "{}", uri);
+        assert_eq!(page["total"], expected.len(), "{}", uri);
+    }
+}
+
+#[sqlx::test]
+async fn each_edit_records_the_version_it_replaced(pool: PgPool) {
+    let app = common::test_app(pool);
+    let alice = app.register_user("alice@example.com").await;
+    let bob = app.register_user("bob@example.com").await;
+    let shopping = alice.category_id("Shopping").await;
+    let food = alice.category_id("Food & Dining").await;
+    let created = alice.create_expense(shopping, 10.0, "2024-03-10").await;
+    let uri = format!("/api/expenses/{}", created["id"].as_str().unwrap());
+    let history_uri = format!("{}/history", uri);
+
+    let history = alice.get(&history_uri).await.json();
+    assert_eq!(history, json!([]));
+
+    let response = alice.put(&uri, json!({ "amount": 11, "description": "Socks" })).await;
+    assert_eq!(response.status, StatusCode::OK, "{}", response.text());
+    let response = alice.put(&uri, json!({ "category_id": food })).await;
+    assert_eq!(response.status, StatusCode::OK, "{}", response.text());
+
+    let response = alice.get(&history_uri).await;
+    assert_eq!(response.status, StatusCode::OK);
+    let history = response.json();
+    let versions = history.as_array().unwrap();
+    assert_eq!(versions.len(), 2);
+
+    // Newest first: the version the second edit replaced, then the original
+    assert_eq!(versions[0]["amount"], "11.00");
+    assert_eq!(versions[0]["description"], "Socks");
+    assert_eq!(versions[0]["category_name"], "Shopping");
+    assert_eq!(versions[1]["amount"], "10.00");
+    assert_eq!(versions[1]["description"], "Test expense");
+    assert_eq!(versions[1]["valid_from"], created["updated_at"]);
+    assert_eq!(versions[0]["valid_from"], versions[1]["replaced_at"]);
+
+    let response = bob.get(&history_uri).await;
+    assert_eq!(response.status, StatusCode::NOT_FOUND);
+}
+
+#[sqlx::test]
+async fn expenses_can_be_filtered_by_two_categories(pool: PgPool) {
+    let app = common::test_app(pool);
+    let user = app.register_user("alice@example.com").await;
+    let food = user.category_id("Food & Dining").await;
+    let shopping = user.category_id("Shopping").await;
+    let other = user.category_id("Other").await;
+    user.create_expense(food, 10.0, "2024-03-10").await;
+    user.create_expense(shopping, 20.0, "2024-03-11").await;
+    user.create_expense(other, 30.0, "2024-03-12").await;
+
+    let page = user.get(&format!("/api/expenses?category_ids={},{}", food, other)).await;
+    assert_eq!(page.status, StatusCode::OK, "{}", page.text());
+    let page = page.json();
+    assert_eq!(page["total"], 2);
+    let mut names: Vec<&str> = page["items"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|expense| expense["category_name"].as_str().unwrap())
+        .collect();
+    names.sort();
+    assert_eq!(names, ["Food & Dining", "Other"]);
+
+    // A single category still works on its own
+    let page = user.get(&format!("/api/expenses?category_id={}", shopping)).await.json();
+    assert_eq!(page["total"], 1);
+    assert_eq!(page["items"][0]["category_name"], "Shopping");
+
+    let response = user.get("/api/expenses?category_ids=not-a-uuid").await;
+    assert_eq!(response.status, StatusCode::BAD_REQUEST);
+}
+
+#[sqlx::test]
+async fn amounts_up_to_the_configured_maximum_are_accepted(pool: PgPool) {
+    let mut config = common::test_config();
+    config.max_expense_amount = "1000.00".parse().unwrap();
+    let app = common::test_app_with(pool, config);
+    let user = app.register_user("alice@example.com").await;
+    let category_id = user.category_id("Shopping").await;
+
+    let created = user.create_expense(category_id, 1000.0, "2024-03-10").await;
+    assert_eq!(created["amount"], "1000.00");
+
+    let body = json!({
+        "category_id": category_id,
+        "amount": 1000.01,
+        "description": "Typo",
+        "expense_date": "2024-03-10",
+    });
+    let response = user.post("/api/expenses", body).await;
+    assert_eq!(response.status, StatusCode::BAD_REQUEST);
+    assert_eq!(response.error(), "Amount cannot be more than 1000.00");
+
+    // Updates are held to the same limit
+    let uri = format!("/api/expenses/{}", created["id"].as_str().unwrap());
+    let response = user.put(&uri, json!({ "amount": 1000.01 })).await;
+    assert_eq!(response.status, StatusCode::BAD_REQUEST);
+    assert_eq!(response.error(), "Amount cannot be more than 1000.00");
+    let response = user.put(&uri, json!({ "amount": 999.99 })).await;
+    assert_eq!(response.status, StatusCode::OK, "{}", response.text());
+}
+
+#[sqlx::test]
+async fn pagination_reports_pages_at_the_boundaries(pool: PgPool) {
+    let mut config = common::test_config();
+    config.default_page_size = 2;
+    config.max_page_size = 4;
+    let app = common::test_app_with(pool, config);
+    let user = app.register_user("alice@example.com").await;
+    let category_id = user.category_id("Shopping").await;
+
+    let pages = |page: &Value| {
+        (page["items"].as_array().unwrap().len(), page["page"].clone(), page["total_pages"].clone())
+    };
+
+    let empty = user.get("/api/expenses").await.json();
+    assert_eq!(pages(&empty), (0, json!(1), json!(0)));
+
+    for day in 1..=5 {
+        user.create_expense(category_id, 10.0, &format!("2024-03-0{}", day)).await;
+    }
+
+    // 5 expenses, 2 per page by default: the last page holds the remainder
+    let first = user.get("/api/expenses").await.json();
+    assert_eq!(first["limit"], 2);
+    assert_eq!(pages(&first), (2, json!(1), json!(3)));
+    let last = user.get("/api/expenses?offset=4").await.json();
+    assert_eq!(pages(&last), (1, json!(3), json!(3)));
+    let past_the_end = user.get("/api/expenses?offset=6").await.json();
+    assert_eq!(pages(&past_the_end), (0, json!(4), json!(3)));
+
+    // One per page divides the total evenly; the largest page leaves one over
+    let five = user.get("/api/expenses?limit=1").await.json();
+    assert_eq!(pages(&five), (1, json!(1), json!(5)));
+    let max = user.get("/api/expenses?limit=4&offset=4").await.json();
+    assert_eq!(pages(&max), (1, json!(2), json!(2)));
+
+    for limit in [0, 5] {
+        let response = user.get(&format!("/api/expenses?limit={}", limit)).await;
+        assert_eq!(response.status, StatusCode::BAD_REQUEST);
+        assert_eq!(response.error(), "limit must be between 1 and 4");
+    }
+}
+
+#[sqlx::test]
+async fn dates_before_the_minimum_are_rejected(pool: PgPool) {
+    let app = common::test_app(pool);
+    let user = app.register_user("alice@example.com").await;
+    let category_id = user.category_id("Shopping").await;
+    let too_old = "Expense date cannot be before 1970-01-01";
+
+    let body = json!({
+        "category_id": category_id,
+        "amount": 10,
+        "description": "Typo",
+        "expense_date": "1800-01-01",
+    });
+    let response = user.post("/api/expenses", body).await;
+    assert_eq!(response.status, StatusCode::BAD_REQUEST);
+    assert_eq!(response.error(), too_old);
+
+    // The minimum itself is allowed
+    let created = user.create_expense(category_id, 10.0, "1970-01-01").await;
+
+    let uri = format!("/api/expenses/{}", created["id"].as_str().unwrap());
+    let response = user.put(&uri, json!({ "expense_date": "1800-01-01" })).await;
+    assert_eq!(response.status, StatusCode::BAD_REQUEST);
+    assert_eq!(response.error(), too_old);
+
+    let quick = json!({ "text": "10 socks #shopping 1800-01-01" });
+    let response = user.post("/api/expenses/quick", quick).await;
+    assert_eq!(response.status, StatusCode::BAD_REQUEST);
+    assert_eq!(response.error(), too_old);
+
+    assert_eq!(user.get("/api/expenses").await.json()["total"], 1);
+}
+
+#[sqlx::test]
+async fn saved_sort_applies_unless_the_query_names_one(pool: PgPool) {
+    let app = common::test_app(pool);
+    let user = app.register_user("alice@example.com").await;
+    let category_id = user.category_id("Shopping").await;
+    for (amount, date) in [(20.0, "2024-03-01"), (50.0, "2024-03-02"), (10.0, "2024-03-03")] {
+        user.create_expense(category_id, amount, date).await;
+    }
+
+    let amounts = |query: &'static str| {
+        let user = &user;
+        async move {
+            let response = user.get(&format!("/api/expenses?{}", query)).await;
+            assert_eq!(response.status, StatusCode::OK, "{}", response.text());
+            let page = response.json();
+            let items = page["items"].as_array().unwrap().iter();
+            items.map(|expense| expense["amount"].as_str().unwrap().to_string()).collect::<Vec<_>>()
+        }
+    };
+
+    // Newest first until the user picks something else
+    assert_eq!(amounts("").await, ["10.00", "50.00", "20.00"]);
+
+    let settings = json!({ "expense_sort_by": "amount", "expense_sort_dir": "desc" });
+    let response = user.put("/api/users/me/settings", settings).await;
+    assert_eq!(response.status, StatusCode::OK, "{}", response.text());
+
+    assert_eq!(amounts("").await, ["50.00", "20.00", "10.00"]);
+    assert_eq!(amounts("sort_by=date&sort_dir=asc").await, ["20.00", "50.00", "10.00"]);
+    // Each parameter overrides its own setting only
+    assert_eq!(amounts("sort_dir=asc").await, ["10.00", "20.00", "50.00"]);
+    assert_eq!(amounts("sort_by=date").await, ["10.00", "50.00", "20.00"]);
+}
+
+#[sqlx::test]
+async fn zero_amounts_are_rejected_by_validation_and_the_database(pool: PgPool) {
+    let app = common::test_app(pool);
+    let user = app.register_user("alice@example.com").await;
+    let category_id = user.category_id("Shopping").await;
+
+    let body = json!({
+        "category_id": category_id,
+        "amount": 0,
+        "description": "Nothing",
+        "expense_date": "2024-03-10",
+    });
+    let response = user.post("/api/expenses", body).await;
+    assert_eq!(response.status, StatusCode::BAD_REQUEST);
+
+    let created = user.create_expense(category_id, 10.0, "2024-03-10").await;
+    let id: Uuid = created["id"].as_str().unwrap().parse().unwrap();
+    let response = user.put(&format!("/api/expenses/{}", id), json!({ "amount": 0 })).await;
+    assert_eq!(response.status, StatusCode::BAD_REQUEST);
+
+    // Writes that skip the handlers still hit the CHECK constraint
+    let error = sqlx::query("UPDATE expenses SET amount = 0 WHERE id = $1")
+        .bind(id)
+        .execute(app.pool())
+        .await
+        .expect_err("amount = 0 must violate the CHECK constraint");
+    match map_check_violation(error) {
+        AppError::Validation(message) => assert_eq!(message, "Amount must be greater than 0"),
+        other => panic!("expected a validation error, got {:?}", other),
+    }
+    assert_eq!(user.get(&format!("/api/expenses/{}", id)).await.json()["amount"], "10.00");
+}
+
+#[sqlx::test]
+async fn unusually_large_amounts_are_saved_with_a_warning(pool: PgPool) {
+    let mut config = common::test_config();
+    config.large_expense_multiple = "3".parse().unwrap();
+    let app = common::test_app_with(pool, config);
+    let user = app.register_user("alice@example.com").await;
+    let category_id = user.category_id("Shopping").await;
+    let today = chrono::Utc::now().date_naive().to_string();
+    let warning = "Amount is more than 3x your average expense of 10.00 over the last 90 days";
+
+    // Saved, then trashed so it doesn't count towards the average
+    let create_and_trash = |amount: f64| {
+        let (user, today) = (&user, &today);
+        async move {
+            let created = user.create_expense(category_id, amount, today).await;
+            let uri = format!("/api/expenses/{}", created["id"].as_str().unwrap());
+            assert!(user.delete(&uri).await.status.is_success());
+            created["warnings"].clone()
+        }
+    };
+
+    // Too few recent expenses to compare against
+    for _ in 0..4 {
+        user.create_expense(category_id, 10.0, &today).await;
+    }
+    assert_eq!(create_and_trash(1000.0).await, json!([]));
+
+    user.create_expense(category_id, 10.0, &today).await;
+    assert_eq!(create_and_trash(30.01).await, json!([warning]));
+    // Exactly the multiple is not over it
+    assert_eq!(create_and_trash(30.0).await, json!([]));
+
+    // Off when the multiple is 0
+    let mut config = common::test_config();
+    config.large_expense_multiple = "0".parse().unwrap();
+    let app = common::test_app_with(app.pool().clone(), config);
+    let user = app.client_with_token(&user.token);
+    let body = json!({
+        "category_id": category_id,
+        "amount": 1000,
+        "description": "TV",
+        "expense_date": today,
+    });
+    let response = user.post("/api/expenses", body).await;
+    assert_eq!(response.status, StatusCode::CREATED);
+    assert_eq!(response.json()["warnings"], json!([]));
+}
@@ -0,0 +1,74 @@
+mod common;
+
+use axum::{
+    body::Body,
+    http::{header, Method, StatusCode},
+};
+use serde_json::json;
+use sqlx::PgPool;
+
+#[sqlx::test]
+async fn unknown_paths_get_a_json_404(pool: PgPool) {
+    let app = common::test_app(pool);
+    let user = app.register_user("alice@example.com").await;
+
+    for client in [app.client(), user.client.clone()] {
+        let response = client.get("/api/no-such-thing").await;
+        assert_eq!(response.status, StatusCode::NOT_FOUND);
+        assert_eq!(response.header("content-type"), Some("application/json"));
+        assert_eq!(response.error(), "Route not found");
+    }
+}
+
+#[sqlx::test]
+async fn wrong_methods_get_a_json_405_with_allow(pool: PgPool) {
+    let app = common::test_app(pool);
+    let user = app.register_user("alice@example.com").await;
+
+    let request = user.request(Method::PATCH, "/api/expenses").body(Body::empty()).unwrap();
+    let response = user.send(request).await;
+    assert_eq!(response.status, StatusCode::METHOD_NOT_ALLOWED);
+    assert_eq!(response.error(), "Method not allowed");
+    let allow = response.header(header::ALLOW.as_str()).unwrap_or_default();
+    assert!(allow.contains("GET") && allow.contains("POST"), "Allow: {}", allow);
+}
+
+#[sqlx::test]
+async fn bad_json_bodies_get_a_400_naming_the_problem(pool: PgPool) {
+    let app = common::test_app(pool);
+    let user = app.register_user("alice@example.com").await;
+    let category_id = user.category_id("Shopping").await;
+
+    let send = |body: String| {
+        let request = user
+            .request(Method::POST, "/api/expenses")
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(Body::from(body))
+            .unwrap();
+        user.send(request)
+    };
+
+    let response = send(r#"{"amount": 10,"#.to_string()).await;
+    assert_eq!(response.status, StatusCode::BAD_REQUEST);
+    assert!(response.error().starts_with("Malformed JSON: "), "{}", response.error());
+
+    let body = json!({
+        "category_id": category_id,
+        "amount": 10,
+        "description": "Socks",
+        "expense_date": "yesterday",
+    });
+    let response = send(body.to_string()).await;
+    assert_eq!(response.status, StatusCode::BAD_REQUEST);
+    let error = response.error();
+    assert!(error.starts_with("Invalid request body: expense_date: "), "{}", error);
+
+    let response = send(json!({ "amount": 10 }).to_string()).await;
+    assert_eq!(response.status, StatusCode::BAD_REQUEST);
+    assert!(response.error().contains("missing field"), "{}", response.error());
+
+    let request = user.request(Method::POST, "/api/expenses").body(Body::from("{}")).unwrap();
+    let response = user.send(request).await;
+    assert_eq!(response.status, StatusCode::BAD_REQUEST);
+    assert_eq!(response.error(), "Expected request with `Content-Type: application/json`");
+}
@@ -0,0 +1,122 @@
+mod common;
+
+use axum::http::{Method, StatusCode};
+use chrono::{DateTime, Duration, Utc};
+use serde_json::{json, Value};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use common::{json_request, TestApp};
+
+/// Logs `email` in again and returns the new session's token.
+async fn login(app: &TestApp, email: &str, forwarded_for: &str) -> String {
+    let client = app.client();
+    let request = client
+        .request(Method::POST, "/api/auth/login")
+        .header("x-forwarded-for", forwarded_for);
+    let body = json!({ "email": email, "password": common::PASSWORD });
+    let response = client.send(json_request(request, &body)).await;
+    assert_eq!(response.status, StatusCode::OK, "{}", response.text());
+    response.json()["token"].as_str().unwrap().to_string()
+}
+
+#[sqlx::test]
+async fn revoked_session_token_is_rejected(pool: PgPool) {
+    let app = common::test_app(pool);
+    let user = app.register_user("alice@example.com").await;
+    let laptop = app.client_with_token(&login(&app, &user.email, "198.51.100.1").await);
+
+    let sessions = laptop.get("/api/users/me/sessions").await;
+    assert_eq!(sessions.status, StatusCode::OK);
+    let sessions = sessions.json();
+    let sessions = sessions.as_array().unwrap();
+    assert_eq!(sessions.len(), 2);
+    let current: Vec<&Value> = sessions.iter().filter(|s| s["current"] == true).collect();
+    assert_eq!(current.len(), 1);
+    let registration = sessions.iter().find(|s| s["current"] == false).unwrap();
+
+    let uri = format!("/api/users/me/sessions/{}", registration["id"].as_str().unwrap());
+    assert_eq!(laptop.delete(&uri).await.status, StatusCode::NO_CONTENT);
+
+    assert_eq!(user.get("/api/expenses").await.status, StatusCode::UNAUTHORIZED);
+    assert_eq!(laptop.get("/api/expenses").await.status, StatusCode::OK);
+    assert_eq!(laptop.delete(&uri).await.status, StatusCode::NOT_FOUND);
+}
+
+#[sqlx::test]
+async fn revoking_other_sessions_keeps_the_current_one(pool: PgPool) {
+    let app = common::test_app(pool);
+    let user = app.register_user("alice@example.com").await;
+    let phone = app.client_with_token(&login(&app, &user.email, "198.51.100.1").await);
+
+    let response = user.delete("/api/users/me/sessions").await;
+    assert_eq!(response.status, StatusCode::NO_CONTENT);
+
+    assert_eq!(phone.get("/api/expenses").await.status, StatusCode::UNAUTHORIZED);
+    let sessions = user.get("/api/users/me/sessions").await.json();
+    assert_eq!(sessions.as_array().unwrap().len(), 1);
+}
+
+#[sqlx::test]
+async fn session_records_the_forwarded_client_behind_a_trusted_proxy(pool: PgPool) {
+    let mut config = common::test_config();
+    config.trusted_proxies = vec!["203.0.113.7/32".parse().unwrap()];
+    let app = common::test_app_with(pool, config);
+    let user = app.register_user("alice@example.com").await;
+    let token = login(&app, &user.email, "198.51.100.1").await;
+
+    let sessions = app.client_with_token(&token).get("/api/users/me/sessions").await.json();
+    let current = sessions.as_array().unwrap().iter().find(|s| s["current"] == true);
+    assert_eq!(current.unwrap()["ip_address"], "198.51.100.1");
+}
+
+#[sqlx::test]
+async fn last_used_at_is_written_at_most_once_a_minute(pool: PgPool) {
+    let app = common::test_app(pool);
+    let user = app.register_user("alice@example.com").await;
+
+    let last_used = || async {
+        sqlx::query_scalar::<_, DateTime<Utc>>("SELECT last_used_at FROM sessions")
+            .fetch_one(app.pool())
+            .await
+            .unwrap()
+    };
+    // Used 30 seconds ago: still active, but not written again
+    sqlx::query("UPDATE sessions SET last_used_at = NOW() - INTERVAL '30 seconds'")
+        .execute(app.pool())
+        .await
+        .unwrap();
+    let recent = last_used().await;
+    assert_eq!(user.get("/api/expenses").await.status, StatusCode::OK);
+    assert_eq!(last_used().await, recent);
+
+    sqlx::query("UPDATE sessions SET last_used_at = NOW() - INTERVAL '5 minutes'")
+        .execute(app.pool())
+        .await
+        .unwrap();
+    let stale = last_used().await;
+    assert_eq!(user.get("/api/expenses").await.status, StatusCode::OK);
+    assert!(last_used().await > stale + Duration::minutes(4));
+}
+
+#[sqlx::test]
+async fn expired_sessions_are_deleted_when_a_new_one_starts(pool: PgPool) {
+    let app = common::test_app(pool);
+    let alice = app.register_user("alice@example.com").await;
+    app.register_user("bob@example.com").await;
+
+    sqlx::query("UPDATE sessions SET expires_at = NOW() - INTERVAL '1 hour' WHERE user_id = $1")
+        .bind(alice.id)
+        .execute(app.pool())
+        .await
+        .unwrap();
+
+    login(&app, "bob@example.com", "198.51.100.1").await;
+
+    let owners: Vec<Uuid> = sqlx::query_scalar("SELECT user_id FROM sessions")
+        .fetch_all(app.pool())
+        .await
+        .unwrap();
+    assert_eq!(owners.len(), 2);
+    assert!(!owners.contains(&alice.id));
+}
@@ -0,0 +1,237 @@
+mod common;
+
+use axum::http::StatusCode;
+use chrono::{Duration, Utc};
+use rust_decimal::Decimal;
+use serde_json::{json, Value};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use common::{TestApp, TestUser};
+
+/// Creates a 100.00 expense split 60/40 between Food & Dining and Shopping.
+async fn split_expense(user: &TestUser, date: &str) -> (Value, Uuid, Uuid) {
+    let food = user.category_id("Food & Dining").await;
+    let shopping = user.category_id("Shopping").await;
+    let response = user
+        .post(
+            "/api/expenses",
+            json!({
+                "category_id": food,
+                "amount": 100,
+                "description": "Supermarket",
+                "expense_date": date,
+                "splits": [
+                    { "category_id": food, "amount": 60 },
+                    { "category_id": shopping, "amount": 40 },
+                ],
+            }),
+        )
+        .await;
+    assert_eq!(response.status, StatusCode::CREATED, "{}", response.text());
+    (response.json(), food, shopping)
+}
+
+async fn splits_of(app: &TestApp, expense_id: &str) -> Vec<(Uuid, Decimal)> {
+    sqlx::query_as(
+        "SELECT category_id, amount FROM expense_splits WHERE expense_id = $1 ORDER BY amount DESC",
+    )
+    .bind(expense_id.parse::<Uuid>().unwrap())
+    .fetch_all(app.pool())
+    .await
+    .unwrap()
+}
+
+#[sqlx::test]
+async fn splits_must_add_up_to_the_amount(pool: PgPool) {
+    let app = common::test_app(pool);
+    let user = app.register_user("alice@example.com").await;
+    let food = user.category_id("Food & Dining").await;
+    let shopping = user.category_id("Shopping").await;
+
+    let response = user
+        .post(
+            "/api/expenses",
+            json!({
+                "category_id": food,
+                "amount": 100,
+                "description": "Supermarket",
+                "expense_date": "2024-01-10",
+                "splits": [
+                    { "category_id": food, "amount": 60 },
+                    { "category_id": shopping, "amount": 30 },
+                ],
+            }),
+        )
+        .await;
+    assert_eq!(response.status, StatusCode::BAD_REQUEST);
+    assert_eq!(response.error(), "Split amounts add up to 90 but the expense amount is 100");
+    assert_eq!(user.get("/api/expenses").await.json()["total"], 0);
+}
+
+#[sqlx::test]
+async fn split_expense_amount_cannot_drift_from_its_parts(pool: PgPool) {
+    let app = common::test_app(pool);
+    let user = app.register_user("alice@example.com").await;
+    let (expense, ..) = split_expense(&user, "2024-01-10").await;
+    let uri = format!("/api/expenses/{}", expense["id"].as_str().unwrap());
+
+    let response = user.put(&uri, json!({ "amount": 120 })).await;
+    assert_eq!(response.status, StatusCode::BAD_REQUEST);
+    assert_eq!(
+        response.error(),
+        "Expense is split across categories; its amount can't change without new splits"
+    );
+    assert_eq!(user.get(&uri).await.json()["amount"], "100.00");
+
+    let response = user.put(&uri, json!({ "description": "Market" })).await;
+    assert_eq!(response.status, StatusCode::OK, "{}", response.text());
+}
+
+#[sqlx::test]
+async fn cloned_month_keeps_the_splits(pool: PgPool) {
+    let app = common::test_app(pool);
+    let user = app.register_user("alice@example.com").await;
+    let (_, food, shopping) = split_expense(&user, "2024-01-31").await;
+    user.create_expense(shopping, 5.0, "2024-01-15").await;
+
+    let months = json!({ "from_month": "2024-01", "to_month": "2024-02" });
+    let response = user.post("/api/expenses/clone-month", months).await;
+    assert_eq!(response.status, StatusCode::CREATED, "{}", response.text());
+    let copies = response.json();
+    let copies = copies.as_array().unwrap();
+    assert_eq!(copies.len(), 2);
+
+    let split_copy = copies.iter().find(|c| c["amount"] == "100.00").unwrap();
+    assert_eq!(split_copy["expense_date"], "2024-02-29");
+    assert_eq!(
+        splits_of(&app, split_copy["id"].as_str().unwrap()).await,
+        [(food, Decimal::new(6000, 2)), (shopping, Decimal::new(4000, 2))]
+    );
+
+    let plain_copy = copies.iter().find(|c| c["amount"] == "5.00").unwrap();
+    assert!(splits_of(&app, plain_copy["id"].as_str().unwrap()).await.is_empty());
+}
+
+#[sqlx::test]
+async fn cloning_refuses_a_split_into_an_archived_category(pool: PgPool) {
+    let app = common::test_app(pool);
+    let user = app.register_user("alice@example.com").await;
+    let (_, _, shopping) = split_expense(&user, "2024-01-31").await;
+
+    let response = user.post(&format!("/api/categories/{}/archive", shopping), json!({})).await;
+    assert_eq!(response.status, StatusCode::OK, "{}", response.text());
+
+    let months = json!({ "from_month": "2024-01", "to_month": "2024-02" });
+    let response = user.post("/api/expenses/clone-month", months).await;
+    assert_eq!(response.status, StatusCode::BAD_REQUEST);
+    assert_eq!(response.error(), "Category is archived: Shopping");
+
+    let page = user.get("/api/expenses?start_date=2024-02-01").await.json();
+    assert_eq!(page["total"], 0);
+}
+
+#[sqlx::test]
+async fn categories_used_only_by_splits_are_not_empty(pool: PgPool) {
+    let app = common::test_app(pool);
+    let user = app.register_user("alice@example.com").await;
+    let report = user.get("/api/users/me/integrity").await.json();
+    let all_empty = report["empty_categories"].as_i64().unwrap();
+
+    // Filed under Food & Dining but split into Shopping and Entertainment only
+    let food = user.category_id("Food & Dining").await;
+    let shopping = user.category_id("Shopping").await;
+    let entertainment = user.category_id("Entertainment").await;
+    let response = user
+        .post(
+            "/api/expenses",
+            json!({
+                "category_id": food,
+                "amount": 30,
+                "description": "Mall",
+                "expense_date": "2024-01-10",
+                "splits": [
+                    { "category_id": shopping, "amount": 20 },
+                    { "category_id": entertainment, "amount": 10 },
+                ],
+            }),
+        )
+        .await;
+    assert_eq!(response.status, StatusCode::CREATED, "{}", response.text());
+
+    let report = user.get("/api/users/me/integrity").await.json();
+    assert_eq!(report["empty_categories"], all_empty - 2);
+    assert_eq!(report["orphaned_expenses"], 0);
+    assert_eq!(report["non_positive_amounts"], 0);
+}
+
+#[sqlx::test]
+async fn recategorizing_moves_the_main_categorys_split_part(pool: PgPool) {
+    let app = common::test_app(pool);
+    let user = app.register_user("alice@example.com").await;
+    let (expense, food, shopping) = split_expense(&user, "2024-01-10").await;
+    let outside = user.create_expense(food, 15.0, "2024-02-10").await;
+    let entertainment = user.category_id("Entertainment").await;
+
+    let response = user
+        .post(
+            "/api/expenses/recategorize",
+            json!({
+                "filter": {
+                    "start_date": "2024-01-01",
+                    "end_date": "2024-01-31",
+                    "category_ids": food.to_string(),
+                },
+                "to_category": entertainment,
+            }),
+        )
+        .await;
+    assert_eq!(response.status, StatusCode::OK, "{}", response.text());
+    assert_eq!(response.json()["updated"], 1);
+
+    let id = expense["id"].as_str().unwrap();
+    let moved = user.get(&format!("/api/expenses/{}", id)).await.json();
+    assert_eq!(moved["category_id"], json!(entertainment));
+    assert_eq!(
+        splits_of(&app, id).await,
+        [(entertainment, Decimal::from(60)), (shopping, Decimal::from(40))]
+    );
+
+    let id = outside["id"].as_str().unwrap();
+    let untouched = user.get(&format!("/api/expenses/{}", id)).await.json();
+    assert_eq!(untouched["category_id"], json!(food));
+
+    // Moving onto a category that already has a part combines the two
+    let response = user
+        .post(
+            "/api/expenses/recategorize",
+            json!({
+                "filter": { "category_ids": entertainment.to_string() },
+                "to_category": shopping,
+            }),
+        )
+        .await;
+    assert_eq!(response.json()["updated"], 1);
+    let id = expense["id"].as_str().unwrap();
+    assert_eq!(splits_of(&app, id).await, [(shopping, Decimal::from(100))]);
+}
+
+#[sqlx::test]
+async fn categories_used_only_by_splits_count_as_recent(pool: PgPool) {
+    let app = common::test_app(pool);
+    let user = app.register_user("alice@example.com").await;
+    let yesterday = (Utc::now().date_naive() - Duration::days(1)).to_string();
+    let (_, food, shopping) = split_expense(&user, &yesterday).await;
+
+    let recent = user.get("/api/categories/recent").await.json();
+    let mut ids: Vec<Uuid> = recent
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|c| c["id"].as_str().unwrap().parse().unwrap())
+        .collect();
+    ids.sort();
+    let mut expected = vec![food, shopping];
+    expected.sort();
+    assert_eq!(ids, expected);
+}
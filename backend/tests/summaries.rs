@@ -0,0 +1,188 @@
+mod common;
+
+use axum::http::StatusCode;
+use chrono::Datelike;
+use serde_json::{json, Value};
+use sqlx::PgPool;
+
+use expense_tracker_backend::handlers::settings::local_date;
+
+/// The category summary entry named `name`.
+fn summary_of<'a>(summaries: &'a Value, name: &str) -> &'a Value {
+    summaries
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|summary| summary["category_name"] == name)
+        .unwrap_or_else(|| panic!("no summary for {}", name))
+}
+
+#[sqlx::test]
+async fn category_summary_flags_spending_over_the_budget(pool: PgPool) {
+    let app = common::test_app(pool);
+    let user = app.register_user("alice@example.com").await;
+    let shopping = user.category_id("Shopping").await;
+    let food = user.category_id("Food & Dining").await;
+    let today = chrono::Utc::now().date_naive().to_string();
+
+    for category in [shopping, food] {
+        let uri = format!("/api/budgets/{}", category);
+        let response = user.put(&uri, json!({ "amount": 100 })).await;
+        assert!(response.status.is_success(), "{}", response.text());
+    }
+    user.create_expense(shopping, 80.0, &today).await;
+    user.create_expense(shopping, 30.0, &today).await;
+    user.create_expense(food, 100.0, &today).await;
+    // Last month's spending doesn't count against this month's budget
+    user.create_expense(food, 500.0, "2020-01-15").await;
+
+    let response = user.get("/api/summaries/categories").await;
+    assert_eq!(response.status, StatusCode::OK, "{}", response.text());
+    let summaries = response.json();
+
+    let summary = summary_of(&summaries, "Shopping");
+    assert_eq!(summary["total_amount"], "110.00");
+    assert_eq!(summary["budget_amount"], "100.00");
+    assert_eq!(summary["over_budget"], true);
+
+    // Spending exactly the budget is not over it
+    let summary = summary_of(&summaries, "Food & Dining");
+    assert_eq!(summary["total_amount"], "100.00");
+    assert_eq!(summary["over_budget"], false);
+
+    let summary = summary_of(&summaries, "Other");
+    assert_eq!(summary["budget_amount"], Value::Null);
+    assert_eq!(summary["over_budget"], false);
+}
+
+#[sqlx::test]
+async fn category_summary_uses_the_month_in_the_users_timezone(pool: PgPool) {
+    let mut config = common::test_config();
+    // Kolkata's 1st can still be yesterday in UTC, which the date check uses
+    config.allow_future_expense_dates = true;
+    let app = common::test_app_with(pool, config);
+    let user = app.register_user("alice@example.com").await;
+    let shopping = user.category_id("Shopping").await;
+
+    let response =
+        user.put("/api/users/me/settings", json!({ "timezone": "Asia/Kolkata" })).await;
+    assert_eq!(response.status, StatusCode::OK, "{}", response.text());
+
+    let today = local_date(chrono::Utc::now(), Some("Asia/Kolkata"));
+    let first = today.with_day(1).unwrap();
+    user.create_expense(shopping, 10.0, &first.to_string()).await;
+    user.create_expense(shopping, 99.0, &first.pred_opt().unwrap().to_string()).await;
+
+    let summaries = user.get("/api/summaries/categories").await.json();
+    let summary = summary_of(&summaries, "Shopping");
+    assert_eq!(summary["total_amount"], "10.00");
+    assert_eq!(summary["expense_count"], 1);
+}
+
+#[sqlx::test]
+async fn weekly_buckets_follow_the_week_start_setting(pool: PgPool) {
+    let app = common::test_app(pool);
+    let user = app.register_user("alice@example.com").await;
+    let shopping = user.category_id("Shopping").await;
+    // Saturday, Sunday and Monday
+    for date in ["2024-01-06", "2024-01-07", "2024-01-08"] {
+        user.create_expense(shopping, 10.0, date).await;
+    }
+
+    let uri = "/api/summaries/timeseries?interval=week&start_date=2024-01-06&end_date=2024-01-14";
+    let buckets = |series: &Value| -> Vec<(String, i64)> {
+        series
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|bucket| {
+                let start = bucket["bucket_start"].as_str().unwrap().to_string();
+                (start, bucket["expense_count"].as_i64().unwrap())
+            })
+            .collect()
+    };
+
+    // Monday by default: the weekend closes one week, Monday opens the next
+    let response = user.get(uri).await;
+    assert_eq!(response.status, StatusCode::OK, "{}", response.text());
+    assert_eq!(
+        buckets(&response.json()),
+        [("2024-01-01".to_string(), 2), ("2024-01-08".to_string(), 1)]
+    );
+
+    let response = user.put("/api/users/me/settings", json!({ "week_start": "sunday" })).await;
+    assert_eq!(response.status, StatusCode::OK, "{}", response.text());
+
+    // Sunday: Saturday closes one week, Sunday and Monday share the next
+    let response = user.get(uri).await;
+    assert_eq!(response.status, StatusCode::OK, "{}", response.text());
+    assert_eq!(
+        buckets(&response.json()),
+        [
+            ("2023-12-31".to_string(), 1),
+            ("2024-01-07".to_string(), 2),
+            ("2024-01-14".to_string(), 0),
+        ]
+    );
+
+    // Months don't depend on it
+    let uri = "/api/summaries/timeseries?interval=month&start_date=2024-01-06&end_date=2024-01-14";
+    assert_eq!(buckets(&user.get(uri).await.json()), [("2024-01-01".to_string(), 3)]);
+}
+
+#[sqlx::test]
+async fn yearly_category_summary_covers_every_month_of_the_year(pool: PgPool) {
+    let app = common::test_app(pool);
+    let user = app.register_user("alice@example.com").await;
+    let shopping = user.category_id("Shopping").await;
+    let food = user.category_id("Food & Dining").await;
+
+    for (category, amount, date) in [
+        (shopping, 10.0, "2023-01-01"),
+        (shopping, 20.0, "2023-06-15"),
+        (shopping, 30.0, "2023-12-31"),
+        (food, 100.0, "2023-03-03"),
+        // Either side of the year
+        (food, 500.0, "2022-12-31"),
+        (shopping, 500.0, "2024-01-01"),
+    ] {
+        user.create_expense(category, amount, date).await;
+    }
+
+    let response = user.get("/api/summaries/categories/yearly?year=2023").await;
+    assert_eq!(response.status, StatusCode::OK, "{}", response.text());
+    let summaries = response.json();
+
+    // Highest total first
+    assert_eq!(summaries[0]["category_name"], "Food & Dining");
+    assert_eq!(summaries[0]["total_amount"], "100.00");
+    assert_eq!(summaries[0]["expense_count"], 1);
+    let summary = summary_of(&summaries, "Shopping");
+    assert_eq!(summary["total_amount"], "60.00");
+    assert_eq!(summary["expense_count"], 3);
+
+    // Every category appears, with zeros when nothing was spent
+    assert_eq!(summaries.as_array().unwrap().len(), 7);
+    let summary = summary_of(&summaries, "Other");
+    assert_eq!(summary["total_amount"], "0");
+    assert_eq!(summary["expense_count"], 0);
+
+    let response = user.get("/api/summaries/categories/yearly?year=300000").await;
+    assert_eq!(response.status, StatusCode::BAD_REQUEST);
+}
+
+#[sqlx::test]
+async fn yearly_comparison_rejects_years_out_of_range(pool: PgPool) {
+    let app = common::test_app(pool);
+    let user = app.register_user("alice@example.com").await;
+
+    for year in [i32::MIN, i32::MAX, 300000] {
+        let response = user.get(&format!("/api/summaries/yearly-comparison?year={}", year)).await;
+        assert_eq!(response.status, StatusCode::BAD_REQUEST, "{}", year);
+        assert_eq!(response.error(), "Invalid year");
+    }
+
+    let response = user.get("/api/summaries/yearly-comparison?year=2024").await;
+    assert_eq!(response.status, StatusCode::OK, "{}", response.text());
+    assert_eq!(response.json().as_array().unwrap().len(), 12);
+}
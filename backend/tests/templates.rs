@@ -0,0 +1,91 @@
+mod common;
+
+use axum::http::StatusCode;
+use serde_json::{json, Value};
+use sqlx::PgPool;
+
+use common::TestUser;
+
+async fn create_template(user: &TestUser, name: &str, category: &str) -> Value {
+    let category_id = user.category_id(category).await;
+    let response = user
+        .post(
+            "/api/expense-templates",
+            json!({
+                "name": name,
+                "category_id": category_id,
+                "amount": 49.9,
+                "description": "Monthly metro pass",
+            }),
+        )
+        .await;
+    assert_eq!(response.status, StatusCode::CREATED, "{}", response.text());
+    response.json()
+}
+
+#[sqlx::test]
+async fn template_creates_expenses_on_the_given_date(pool: PgPool) {
+    let app = common::test_app(pool);
+    let user = app.register_user("alice@example.com").await;
+    let template = create_template(&user, "Metro", "Transportation").await;
+    assert_eq!(template["amount"], "49.90");
+
+    let templates = user.get("/api/expense-templates").await.json();
+    assert_eq!(templates, json!([template.clone()]));
+
+    let uri = format!(
+        "/api/expenses/from-template/{}?expense_date=2024-02-01",
+        template["id"].as_str().unwrap()
+    );
+    let response = user.post(&uri, Value::Null).await;
+    assert_eq!(response.status, StatusCode::CREATED, "{}", response.text());
+    let expense = response.json();
+    assert_eq!(expense["category_id"], template["category_id"]);
+    assert_eq!(expense["amount"], "49.90");
+    assert_eq!(expense["description"], "Monthly metro pass");
+    assert_eq!(expense["expense_date"], "2024-02-01");
+
+    assert_eq!(user.get("/api/expenses").await.json()["total"], 1);
+}
+
+#[sqlx::test]
+async fn templates_are_private_and_names_unique(pool: PgPool) {
+    let app = common::test_app(pool);
+    let alice = app.register_user("alice@example.com").await;
+    let bob = app.register_user("bob@example.com").await;
+    let template = create_template(&alice, "Metro", "Transportation").await;
+
+    let duplicate = alice
+        .post(
+            "/api/expense-templates",
+            json!({
+                "name": "Metro",
+                "category_id": template["category_id"],
+                "amount": 10,
+                "description": "Again",
+            }),
+        )
+        .await;
+    assert_eq!(duplicate.status, StatusCode::BAD_REQUEST, "{}", duplicate.text());
+
+    let uri = format!("/api/expenses/from-template/{}", template["id"].as_str().unwrap());
+    let response = bob.post(&uri, Value::Null).await;
+    assert_eq!(response.status, StatusCode::NOT_FOUND);
+    assert_eq!(bob.get("/api/expense-templates").await.json(), json!([]));
+}
+
+#[sqlx::test]
+async fn category_used_by_a_template_cannot_be_deleted(pool: PgPool) {
+    let app = common::test_app(pool);
+    let user = app.register_user("alice@example.com").await;
+    let template = create_template(&user, "Metro", "Transportation").await;
+
+    let uri = format!("/api/categories/{}", template["category_id"].as_str().unwrap());
+    let response = user.delete(&uri).await;
+    assert_eq!(response.status, StatusCode::BAD_REQUEST);
+    assert_eq!(
+        response.error(),
+        "Cannot delete category used by expense templates; merge it or change the templates"
+    );
+    assert_eq!(user.get("/api/expense-templates").await.json().as_array().unwrap().len(), 1);
+}
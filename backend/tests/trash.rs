@@ -0,0 +1,104 @@
+mod common;
+
+use std::path::Path;
+
+use axum::http::StatusCode;
+use serde_json::{json, Value};
+use sqlx::PgPool;
+
+use common::{TestApp, TestUser};
+
+/// Creates an expense with one attachment and moves it to the trash.
+/// Returns the expense id and the attachment's file on disk.
+async fn trashed_expense_with_receipt(app: &TestApp, user: &TestUser) -> (String, String) {
+    let category_id = user.category_id("Shopping").await;
+    let expense = user.create_expense(category_id, 10.0, "2024-03-10").await;
+    let id = expense["id"].as_str().unwrap().to_string();
+
+    let response = user.upload_attachment(&id, "receipt.pdf", "application/pdf", b"%PDF").await;
+    assert_eq!(response.status, StatusCode::CREATED, "{}", response.text());
+    let storage_key: String = sqlx::query_scalar("SELECT storage_key FROM attachments")
+        .fetch_one(app.pool())
+        .await
+        .unwrap();
+    let file = Path::new(&app.state.config.attachments_dir).join(storage_key);
+    assert!(file.exists());
+
+    let response = user.delete(&format!("/api/expenses/{}", id)).await;
+    assert_eq!(response.status, StatusCode::NO_CONTENT);
+    (id, file.to_string_lossy().into_owned())
+}
+
+async fn count(app: &TestApp, table: &str) -> i64 {
+    sqlx::query_scalar(&format!("SELECT COUNT(*) FROM {}", table))
+        .fetch_one(app.pool())
+        .await
+        .unwrap()
+}
+
+#[sqlx::test]
+async fn restore_brings_a_trashed_expense_back(pool: PgPool) {
+    let app = common::test_app(pool);
+    let user = app.register_user("alice@example.com").await;
+    let (id, _) = trashed_expense_with_receipt(&app, &user).await;
+
+    assert_eq!(user.get("/api/expenses").await.json()["total"], 0);
+    let trash = user.get("/api/expenses/trash").await.json();
+    assert_eq!(trash.as_array().unwrap().len(), 1);
+    assert_eq!(trash[0]["id"], id.as_str());
+    assert_eq!(trash[0]["days_until_purge"], 30);
+
+    let response = user.post(&format!("/api/expenses/{}/restore", id), Value::Null).await;
+    assert_eq!(response.status, StatusCode::OK, "{}", response.text());
+    assert_eq!(response.json()["id"], id.as_str());
+
+    assert_eq!(user.get("/api/expenses").await.json()["total"], 1);
+    assert_eq!(user.get("/api/expenses/trash").await.json(), json!([]));
+    let response = user.post(&format!("/api/expenses/{}/restore", id), Value::Null).await;
+    assert_eq!(response.status, StatusCode::NOT_FOUND);
+}
+
+#[sqlx::test]
+async fn purge_hard_deletes_the_expense_and_its_files(pool: PgPool) {
+    let app = common::test_app(pool);
+    let user = app.register_user("alice@example.com").await;
+    let other = app.register_user("bob@example.com").await;
+    let (id, file) = trashed_expense_with_receipt(&app, &user).await;
+    let uri = format!("/api/expenses/{}/purge", id);
+
+    assert_eq!(other.delete(&uri).await.status, StatusCode::NOT_FOUND);
+
+    assert_eq!(user.delete(&uri).await.status, StatusCode::NO_CONTENT);
+    assert_eq!(count(&app, "expenses").await, 0);
+    assert_eq!(count(&app, "attachments").await, 0);
+    assert!(!Path::new(&file).exists(), "attachment file left behind");
+
+    assert_eq!(user.delete(&uri).await.status, StatusCode::NOT_FOUND);
+}
+
+#[sqlx::test]
+async fn live_expenses_cannot_be_purged(pool: PgPool) {
+    let app = common::test_app(pool);
+    let user = app.register_user("alice@example.com").await;
+    let category_id = user.category_id("Shopping").await;
+    let expense = user.create_expense(category_id, 10.0, "2024-03-10").await;
+
+    let uri = format!("/api/expenses/{}/purge", expense["id"].as_str().unwrap());
+    let response = user.delete(&uri).await;
+    assert_eq!(response.status, StatusCode::NOT_FOUND);
+    assert_eq!(response.error(), "Expense not in the trash");
+    assert_eq!(count(&app, "expenses").await, 1);
+}
+
+#[sqlx::test]
+async fn expired_trash_is_purged_with_its_files(pool: PgPool) {
+    let mut config = common::test_config();
+    config.trash_retention_days = 0;
+    let app = common::test_app_with(pool, config);
+    let user = app.register_user("alice@example.com").await;
+    let (_, file) = trashed_expense_with_receipt(&app, &user).await;
+
+    assert_eq!(user.get("/api/expenses/trash").await.json(), json!([]));
+    assert_eq!(count(&app, "expenses").await, 0);
+    assert!(!Path::new(&file).exists(), "attachment file left behind");
+}
@@ -0,0 +1,60 @@
+mod common;
+
+use axum::http::StatusCode;
+use serde_json::json;
+use sqlx::PgPool;
+
+#[sqlx::test]
+async fn changing_the_email_needs_the_current_password_and_is_audited(pool: PgPool) {
+    let app = common::test_app(pool);
+    let user = app.register_user("alice@example.com").await;
+
+    let response = user.put("/api/users/me", json!({ "email": "mallory@example.com" })).await;
+    assert_eq!(response.status, StatusCode::BAD_REQUEST);
+    assert_eq!(response.error(), "current_password is required to change the email");
+
+    let response = user
+        .put(
+            "/api/users/me",
+            json!({ "email": "mallory@example.com", "current_password": "guess-1234" }),
+        )
+        .await;
+    assert_eq!(response.status, StatusCode::BAD_REQUEST);
+    assert_eq!(response.error(), "Current password is incorrect");
+    assert_eq!(user.get("/api/users/me").await.json()["email"], "alice@example.com");
+
+    // Renaming alone, or "changing" to the same address, needs no password
+    let response = user
+        .put("/api/users/me", json!({ "full_name": "Alice", "email": "Alice@Example.com" }))
+        .await;
+    assert_eq!(response.status, StatusCode::OK, "{}", response.text());
+
+    let response = user
+        .put(
+            "/api/users/me",
+            json!({ "email": "alice@example.org", "current_password": common::PASSWORD }),
+        )
+        .await;
+    assert_eq!(response.status, StatusCode::OK, "{}", response.text());
+    assert_eq!(response.json()["email"], "alice@example.org");
+
+    let events = user.get("/api/users/me/audit").await.json();
+    let changes: Vec<_> = events["items"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .filter(|event| event["event_type"] == "email_changed")
+        .collect();
+    assert_eq!(changes.len(), 1);
+    assert_eq!(changes[0]["email"], "alice@example.com");
+    assert_eq!(changes[0]["ip_address"], "203.0.113.7");
+
+    let response = app
+        .client()
+        .post(
+            "/api/auth/login",
+            json!({ "email": "alice@example.org", "password": common::PASSWORD }),
+        )
+        .await;
+    assert_eq!(response.status, StatusCode::OK);
+}
@@ -0,0 +1,107 @@
+mod common;
+
+use std::time::Duration;
+
+use axum::{
+    http::{header, HeaderMap, StatusCode},
+    response::IntoResponse,
+    routing::post,
+    Router,
+};
+use hmac::{Hmac, Mac};
+use serde_json::{json, Value};
+use sha2::Sha256;
+use sqlx::PgPool;
+use tokio::{net::TcpListener, sync::mpsc};
+
+/// A request received by a [`receiver`].
+struct Delivery {
+    headers: HeaderMap,
+    body: Vec<u8>,
+}
+
+/// Serves `response` for every POST on a local port, passing each request on.
+async fn receiver(
+    response: impl IntoResponse + Clone + Send + Sync + 'static,
+) -> (String, mpsc::UnboundedReceiver<Delivery>) {
+    let (tx, rx) = mpsc::unbounded_channel();
+    let app = Router::new().route(
+        "/hook",
+        post(move |headers: HeaderMap, body: axum::body::Bytes| async move {
+            let _ = tx.send(Delivery { headers, body: body.to_vec() });
+            response.into_response()
+        }),
+    );
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}/hook", listener.local_addr().unwrap());
+    tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+    (url, rx)
+}
+
+async fn next_delivery(rx: &mut mpsc::UnboundedReceiver<Delivery>) -> Delivery {
+    tokio::time::timeout(Duration::from_secs(5), rx.recv())
+        .await
+        .expect("no webhook delivery within 5s")
+        .unwrap()
+}
+
+#[sqlx::test]
+async fn local_webhook_urls_are_rejected(pool: PgPool) {
+    let app = common::test_app(pool);
+    let user = app.register_user("alice@example.com").await;
+
+    for url in ["http://127.0.0.1:3000/hook", "http://localhost/hook", "http://169.254.169.254/"] {
+        let response = user.put("/api/users/me/settings", json!({ "webhook_url": url })).await;
+        assert_eq!(response.status, StatusCode::BAD_REQUEST, "{}", url);
+        assert_eq!(response.error(), "Webhook URL must not point to a private or local address");
+    }
+}
+
+#[sqlx::test]
+async fn new_expense_is_delivered_signed(pool: PgPool) {
+    let mut config = common::test_config();
+    config.webhook_allow_private_networks = true;
+    let app = common::test_app_with(pool, config);
+    let user = app.register_user("alice@example.com").await;
+    let (url, mut deliveries) = receiver(StatusCode::NO_CONTENT).await;
+
+    let settings = user.put("/api/users/me/settings", json!({ "webhook_url": url })).await;
+    assert_eq!(settings.status, StatusCode::OK, "{}", settings.text());
+    let secret = settings.json()["webhook_secret"].as_str().unwrap().to_string();
+
+    let category_id = user.category_id("Shopping").await;
+    let expense = user.create_expense(category_id, 12.5, "2024-03-10").await;
+
+    let delivery = next_delivery(&mut deliveries).await;
+    assert_eq!(delivery.headers["x-webhook-event"], "expense.created");
+    assert_eq!(delivery.headers[header::CONTENT_TYPE], "application/json");
+
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes()).unwrap();
+    mac.update(&delivery.body);
+    let expected = format!("sha256={}", hex::encode(mac.finalize().into_bytes()));
+    assert_eq!(delivery.headers["x-webhook-signature"], expected.as_str());
+
+    let body: Value = serde_json::from_slice(&delivery.body).unwrap();
+    assert_eq!(body["event"], "expense.created");
+    assert_eq!(body["expense"]["id"], expense["id"]);
+}
+
+#[sqlx::test]
+async fn redirects_are_not_followed(pool: PgPool) {
+    let mut config = common::test_config();
+    config.webhook_allow_private_networks = true;
+    let app = common::test_app_with(pool, config);
+    let user = app.register_user("alice@example.com").await;
+    let (target, mut redirected) = receiver(StatusCode::NO_CONTENT).await;
+    let redirect = (StatusCode::TEMPORARY_REDIRECT, [(header::LOCATION, target)]);
+    let (url, mut deliveries) = receiver(redirect).await;
+
+    let settings = user.put("/api/users/me/settings", json!({ "webhook_url": url })).await;
+    assert_eq!(settings.status, StatusCode::OK, "{}", settings.text());
+    let category_id = user.category_id("Shopping").await;
+    user.create_expense(category_id, 12.5, "2024-03-10").await;
+
+    next_delivery(&mut deliveries).await;
+    tokio::time::sleep(Duration::from_millis(300)).await;
+    assert!(redirected.try_recv().is_err(), "redirect was followed");
+}
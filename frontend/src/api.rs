@@ -1,11 +1,30 @@
-use gloo_net::http::Request;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use chrono::NaiveDate;
+use gloo_net::http::{Method, Request, RequestBuilder, Response};
 use serde::Deserialize;
 use uuid::Uuid;
+use wasm_bindgen_futures::JsFuture;
 use web_sys::window;
 
 use crate::models::*;
 
-const API_BASE: &str = "http://localhost:3000/api";
+/// Backend base URL, baked in at compile time from `API_BASE_URL` so the
+/// same source can be built for different deployments.
+const API_BASE: &str = match option_env!("API_BASE_URL") {
+    Some(url) => url,
+    None => "http://localhost:3000/api",
+};
+
+/// How many times an idempotent GET is retried after the first attempt.
+const MAX_GET_RETRIES: u32 = 2;
+/// Delay before the first retry; doubled for every further attempt.
+const RETRY_BASE_DELAY_MS: i32 = 300;
+
+thread_local! {
+    static UNAUTHORIZED_HANDLER: RefCell<Option<Rc<dyn Fn()>>> = RefCell::new(None);
+}
 
 #[derive(Debug, Clone, Deserialize)]
 pub struct ErrorResponse {
@@ -38,6 +57,98 @@ pub fn clear_token() {
     }
 }
 
+/// Registers the callback invoked when an authenticated request gets a 401,
+/// typically used by the app to switch back to the login screen.
+pub fn set_unauthorized_handler(handler: impl Fn() + 'static) {
+    UNAUTHORIZED_HANDLER.with(|h| *h.borrow_mut() = Some(Rc::new(handler)));
+}
+
+/// Clears the stored token and tells the app to show the login screen.
+fn signal_logout() {
+    clear_token();
+    let handler = UNAUTHORIZED_HANDLER.with(|h| h.borrow().clone());
+    if let Some(handler) = handler {
+        handler();
+    }
+}
+
+/// Starts a request with the stored token in the `Authorization` header.
+///
+/// Every call to a protected endpoint goes through here. Without a token the
+/// app is sent back to the login screen, the same as for a 401 (see
+/// [`reject_unauthorized`]), and no request is made.
+fn authed_request(method: Method, url: &str) -> Result<RequestBuilder, String> {
+    let Some(token) = get_token() else {
+        signal_logout();
+        return Err("You are not logged in".to_string());
+    };
+
+    Ok(RequestBuilder::new(url)
+        .method(method)
+        .header("Authorization", &format!("Bearer {}", token)))
+}
+
+/// Logs out when the server rejects our token, so an expired session ends up
+/// on the login screen.
+fn reject_unauthorized(response: Response) -> Result<Response, String> {
+    if response.status() != 401 {
+        return Ok(response);
+    }
+
+    signal_logout();
+    Err("Your session has expired, please log in again".to_string())
+}
+
+pub async fn sleep(ms: i32) {
+    let promise = js_sys::Promise::new(&mut |resolve, _| {
+        if let Some(window) = window() {
+            let _ = window.set_timeout_with_callback_and_timeout_and_arguments_0(&resolve, ms);
+        }
+    });
+    let _ = JsFuture::from(promise).await;
+}
+
+/// URL of the live event stream. `EventSource` can't send headers, so this
+/// trades the token for a single-use ticket that goes in the query string
+/// instead; tickets are hex and expire after 30 seconds.
+pub async fn events_url() -> Result<String, String> {
+    let url = format!("{}/events/ticket", API_BASE);
+    let response = authed_request(Method::POST, &url)?
+        .send()
+        .await
+        .map_err(|e| e.to_string())?;
+    let response = reject_unauthorized(response)?;
+
+    if response.ok() {
+        let ticket = response.json::<StreamTicket>().await.map_err(|e| e.to_string())?;
+        Ok(format!("{}/events?ticket={}", API_BASE, ticket.ticket))
+    } else {
+        let error = response.json::<ErrorResponse>().await
+            .map_err(|e| e.to_string())?;
+        Err(error.error)
+    }
+}
+
+/// Sends an authenticated GET, retrying network failures and 5xx responses
+/// with exponential backoff. Only used for GETs since they are idempotent.
+async fn get_with_retry(url: &str) -> Result<Response, String> {
+    let mut attempt = 0;
+
+    loop {
+        let result = authed_request(Method::GET, url)?.send().await;
+
+        match result {
+            Ok(response) if response.status() >= 500 && attempt < MAX_GET_RETRIES => {}
+            Ok(response) => return reject_unauthorized(response),
+            Err(_) if attempt < MAX_GET_RETRIES => {}
+            Err(e) => return Err(e.to_string()),
+        }
+
+        sleep(RETRY_BASE_DELAY_MS * 2_i32.pow(attempt)).await;
+        attempt += 1;
+    }
+}
+
 pub async fn register(req: RegisterRequest) -> Result<AuthResponse, String> {
     let response = Request::post(&format!("{}/auth/register", API_BASE))
         .json(&req)
@@ -79,13 +190,25 @@ pub async fn login(req: LoginRequest) -> Result<AuthResponse, String> {
 }
 
 pub async fn get_current_user() -> Result<User, String> {
-    let token = get_token().ok_or("No token found")?;
+    let response = get_with_retry(&format!("{}/users/me", API_BASE)).await?;
+
+    if response.ok() {
+        response.json::<User>().await.map_err(|e| e.to_string())
+    } else {
+        let error = response.json::<ErrorResponse>().await
+            .map_err(|e| e.to_string())?;
+        Err(error.error)
+    }
+}
 
-    let response = Request::get(&format!("{}/users/me", API_BASE))
-        .header("Authorization", &format!("Bearer {}", token))
+pub async fn update_profile(req: UpdateUser) -> Result<User, String> {
+    let response = authed_request(Method::PUT, &format!("{}/users/me", API_BASE))?
+        .json(&req)
+        .map_err(|e| e.to_string())?
         .send()
         .await
         .map_err(|e| e.to_string())?;
+    let response = reject_unauthorized(response)?;
 
     if response.ok() {
         response.json::<User>().await.map_err(|e| e.to_string())
@@ -96,14 +219,104 @@ pub async fn get_current_user() -> Result<User, String> {
     }
 }
 
-pub async fn list_categories() -> Result<Vec<Category>, String> {
-    let token = get_token().ok_or("No token found")?;
+pub async fn get_settings() -> Result<UserSettings, String> {
+    let response = get_with_retry(&format!("{}/users/me/settings", API_BASE)).await?;
+
+    if response.ok() {
+        response.json::<UserSettings>().await.map_err(|e| e.to_string())
+    } else {
+        let error = response.json::<ErrorResponse>().await
+            .map_err(|e| e.to_string())?;
+        Err(error.error)
+    }
+}
+
+pub async fn update_settings(req: UpdateUserSettings) -> Result<UserSettings, String> {
+    let response = authed_request(Method::PUT, &format!("{}/users/me/settings", API_BASE))?
+        .json(&req)
+        .map_err(|e| e.to_string())?
+        .send()
+        .await
+        .map_err(|e| e.to_string())?;
+    let response = reject_unauthorized(response)?;
+
+    if response.ok() {
+        response.json::<UserSettings>().await.map_err(|e| e.to_string())
+    } else {
+        let error = response.json::<ErrorResponse>().await
+            .map_err(|e| e.to_string())?;
+        Err(error.error)
+    }
+}
+
+pub async fn list_sessions() -> Result<Vec<Session>, String> {
+    let response = get_with_retry(&format!("{}/users/me/sessions", API_BASE)).await?;
 
-    let response = Request::get(&format!("{}/categories", API_BASE))
-        .header("Authorization", &format!("Bearer {}", token))
+    if response.ok() {
+        response.json::<Vec<Session>>().await.map_err(|e| e.to_string())
+    } else {
+        let error = response.json::<ErrorResponse>().await
+            .map_err(|e| e.to_string())?;
+        Err(error.error)
+    }
+}
+
+pub async fn revoke_session(id: Uuid) -> Result<(), String> {
+    let url = format!("{}/users/me/sessions/{}", API_BASE, id);
+    let response = authed_request(Method::DELETE, &url)?
         .send()
         .await
         .map_err(|e| e.to_string())?;
+    let response = reject_unauthorized(response)?;
+
+    if response.ok() {
+        Ok(())
+    } else {
+        let error = response.json::<ErrorResponse>().await
+            .map_err(|e| e.to_string())?;
+        Err(error.error)
+    }
+}
+
+pub async fn revoke_other_sessions() -> Result<(), String> {
+    let response = authed_request(Method::DELETE, &format!("{}/users/me/sessions", API_BASE))?
+        .send()
+        .await
+        .map_err(|e| e.to_string())?;
+    let response = reject_unauthorized(response)?;
+
+    if response.ok() {
+        Ok(())
+    } else {
+        let error = response.json::<ErrorResponse>().await
+            .map_err(|e| e.to_string())?;
+        Err(error.error)
+    }
+}
+
+/// Fills an account without expenses with a few months of sample data;
+/// returns how many expenses were created (0 if it already had some).
+pub async fn seed_demo_data() -> Result<i64, String> {
+    let response = authed_request(Method::POST, &format!("{}/users/me/seed-demo", API_BASE))?
+        .send()
+        .await
+        .map_err(|e| e.to_string())?;
+    let response = reject_unauthorized(response)?;
+
+    if response.ok() {
+        response.json::<SeedDemoResult>().await
+            .map(|result| result.created)
+            .map_err(|e| e.to_string())
+    } else {
+        let error = response.json::<ErrorResponse>().await
+            .map_err(|e| e.to_string())?;
+        Err(error.error)
+    }
+}
+
+pub async fn list_recent_categories(limit: u32) -> Result<Vec<Category>, String> {
+    let url = format!("{}/categories/recent?limit={}", API_BASE, limit);
+    let response = get_with_retry(&url).await?;
 
     if response.ok() {
         response.json::<Vec<Category>>().await.map_err(|e| e.to_string())
@@ -114,16 +327,64 @@ pub async fn list_categories() -> Result<Vec<Category>, String> {
     }
 }
 
-pub async fn create_category(req: CreateCategory) -> Result<Category, String> {
-    let token = get_token().ok_or("No token found")?;
+pub async fn get_category_palette() -> Result<Vec<String>, String> {
+    let url = format!("{}/categories/palette?exclude_used=true", API_BASE);
+    let response = get_with_retry(&url).await?;
 
-    let response = Request::post(&format!("{}/categories", API_BASE))
-        .header("Authorization", &format!("Bearer {}", token))
+    if response.ok() {
+        response.json::<Vec<String>>().await.map_err(|e| e.to_string())
+    } else {
+        let error = response.json::<ErrorResponse>().await
+            .map_err(|e| e.to_string())?;
+        Err(error.error)
+    }
+}
+
+pub async fn suggest_categories(q: &str) -> Result<Vec<Category>, String> {
+    let url = format!(
+        "{}/categories/suggest?q={}",
+        API_BASE,
+        String::from(js_sys::encode_uri_component(q))
+    );
+    let response = get_with_retry(&url).await?;
+
+    if response.ok() {
+        response.json::<Vec<Category>>().await.map_err(|e| e.to_string())
+    } else {
+        let error = response.json::<ErrorResponse>().await
+            .map_err(|e| e.to_string())?;
+        Err(error.error)
+    }
+}
+
+/// Categories whose name contains `q`, in the user's category order.
+pub async fn search_categories(q: &str, limit: i64, offset: i64) -> Result<PaginatedCategories, String> {
+    let url = format!(
+        "{}/categories?search={}&limit={}&offset={}",
+        API_BASE,
+        String::from(js_sys::encode_uri_component(q)),
+        limit,
+        offset
+    );
+    let response = get_with_retry(&url).await?;
+
+    if response.ok() {
+        response.json::<PaginatedCategories>().await.map_err(|e| e.to_string())
+    } else {
+        let error = response.json::<ErrorResponse>().await
+            .map_err(|e| e.to_string())?;
+        Err(error.error)
+    }
+}
+
+pub async fn create_category(req: CreateCategory) -> Result<Category, String> {
+    let response = authed_request(Method::POST, &format!("{}/categories", API_BASE))?
         .json(&req)
         .map_err(|e| e.to_string())?
         .send()
         .await
         .map_err(|e| e.to_string())?;
+    let response = reject_unauthorized(response)?;
 
     if response.ok() {
         response.json::<Category>().await.map_err(|e| e.to_string())
@@ -134,15 +395,114 @@ pub async fn create_category(req: CreateCategory) -> Result<Category, String> {
     }
 }
 
-pub async fn list_expenses(
+pub async fn archive_category(id: Uuid) -> Result<Category, String> {
+    set_category_archived(id, "archive").await
+}
+
+pub async fn unarchive_category(id: Uuid) -> Result<Category, String> {
+    set_category_archived(id, "unarchive").await
+}
+
+/// Sends `POST /categories/{id}/{action}` for `archive` or `unarchive`.
+async fn set_category_archived(id: Uuid, action: &str) -> Result<Category, String> {
+    let url = format!("{}/categories/{}/{}", API_BASE, id, action);
+    let response = authed_request(Method::POST, &url)?
+        .send()
+        .await
+        .map_err(|e| e.to_string())?;
+    let response = reject_unauthorized(response)?;
+
+    if response.ok() {
+        response.json::<Category>().await.map_err(|e| e.to_string())
+    } else {
+        let error = response.json::<ErrorResponse>().await
+            .map_err(|e| e.to_string())?;
+        Err(error.error)
+    }
+}
+
+pub async fn reorder_categories(ids: Vec<Uuid>) -> Result<Vec<Category>, String> {
+    let url = format!("{}/categories/reorder", API_BASE);
+    let response = authed_request(Method::POST, &url)?
+        .json(&ReorderCategories { ids })
+        .map_err(|e| e.to_string())?
+        .send()
+        .await
+        .map_err(|e| e.to_string())?;
+    let response = reject_unauthorized(response)?;
+
+    if response.ok() {
+        response.json::<Vec<Category>>().await.map_err(|e| e.to_string())
+    } else {
+        let error = response.json::<ErrorResponse>().await
+            .map_err(|e| e.to_string())?;
+        Err(error.error)
+    }
+}
+
+pub async fn export_categories() -> Result<Vec<CategoryTemplate>, String> {
+    let response = get_with_retry(&format!("{}/categories/export", API_BASE)).await?;
+
+    if response.ok() {
+        response.json::<Vec<CategoryTemplate>>().await.map_err(|e| e.to_string())
+    } else {
+        let error = response.json::<ErrorResponse>().await
+            .map_err(|e| e.to_string())?;
+        Err(error.error)
+    }
+}
+
+pub async fn import_categories(
+    templates: &[CategoryTemplate],
+) -> Result<ImportCategoriesResult, String> {
+    let url = format!("{}/categories/import", API_BASE);
+    let response = authed_request(Method::POST, &url)?
+        .json(templates)
+        .map_err(|e| e.to_string())?
+        .send()
+        .await
+        .map_err(|e| e.to_string())?;
+    let response = reject_unauthorized(response)?;
+
+    if response.ok() {
+        response.json::<ImportCategoriesResult>().await.map_err(|e| e.to_string())
+    } else {
+        let error = response.json::<ErrorResponse>().await
+            .map_err(|e| e.to_string())?;
+        Err(error.error)
+    }
+}
+
+pub async fn get_category_history(id: Uuid, months: u32) -> Result<Vec<CategoryMonthlyTotal>, String> {
+    let url = format!("{}/categories/{}/history?months={}", API_BASE, id, months);
+    let response = get_with_retry(&url).await?;
+
+    if response.ok() {
+        response.json::<Vec<CategoryMonthlyTotal>>().await.map_err(|e| e.to_string())
+    } else {
+        let error = response.json::<ErrorResponse>().await
+            .map_err(|e| e.to_string())?;
+        Err(error.error)
+    }
+}
+
+/// Query string for the expense filter, sort and paging parameters shared by
+/// `GET /expenses` and `GET /dashboard`.
+fn expense_query_string(
     start_date: Option<String>,
     end_date: Option<String>,
-    category_id: Option<Uuid>,
-) -> Result<Vec<Expense>, String> {
-    let token = get_token().ok_or("No token found")?;
-
-    let mut url = format!("{}/expenses", API_BASE);
-    let mut params = Vec::new();
+    category_ids: Vec<Uuid>,
+    sort_by: &str,
+    sort_dir: &str,
+    limit: i64,
+    offset: i64,
+) -> String {
+    let mut params = vec![
+        format!("sort_by={}", sort_by),
+        format!("sort_dir={}", sort_dir),
+        format!("limit={}", limit),
+        format!("offset={}", offset),
+    ];
 
     if let Some(start) = start_date {
         params.push(format!("start_date={}", start));
@@ -150,23 +510,78 @@ pub async fn list_expenses(
     if let Some(end) = end_date {
         params.push(format!("end_date={}", end));
     }
-    if let Some(cat_id) = category_id {
-        params.push(format!("category_id={}", cat_id));
+    if !category_ids.is_empty() {
+        let ids: Vec<String> = category_ids.iter().map(Uuid::to_string).collect();
+        params.push(format!("category_ids={}", ids.join(",")));
     }
 
-    if !params.is_empty() {
-        url.push('?');
-        url.push_str(&params.join("&"));
+    params.join("&")
+}
+
+pub async fn list_expenses(
+    start_date: Option<String>,
+    end_date: Option<String>,
+    category_ids: Vec<Uuid>,
+    sort_by: &str,
+    sort_dir: &str,
+    limit: i64,
+    offset: i64,
+) -> Result<PaginatedExpenses, String> {
+    let url = format!(
+        "{}/expenses?{}",
+        API_BASE,
+        expense_query_string(start_date, end_date, category_ids, sort_by, sort_dir, limit, offset)
+    );
+    let response = get_with_retry(&url).await?;
+
+    if response.ok() {
+        response.json::<PaginatedExpenses>().await.map_err(|e| e.to_string())
+    } else {
+        let error = response.json::<ErrorResponse>().await
+            .map_err(|e| e.to_string())?;
+        Err(error.error)
     }
+}
+
+/// Categories, the first page of expenses and the summaries in one request.
+pub async fn get_dashboard(
+    start_date: Option<String>,
+    end_date: Option<String>,
+    category_ids: Vec<Uuid>,
+    sort_by: &str,
+    sort_dir: &str,
+    limit: i64,
+) -> Result<DashboardResponse, String> {
+    let url = format!(
+        "{}/dashboard?{}",
+        API_BASE,
+        expense_query_string(start_date, end_date, category_ids, sort_by, sort_dir, limit, 0)
+    );
+    let response = get_with_retry(&url).await?;
 
-    let response = Request::get(&url)
-        .header("Authorization", &format!("Bearer {}", token))
+    if response.ok() {
+        response.json::<DashboardResponse>().await.map_err(|e| e.to_string())
+    } else {
+        let error = response.json::<ErrorResponse>().await
+            .map_err(|e| e.to_string())?;
+        Err(error.error)
+    }
+}
+
+/// `idempotency_key` should stay the same for every retry of one submit so the
+/// backend returns the original expense instead of creating a duplicate.
+pub async fn create_expense(req: CreateExpense, idempotency_key: Uuid) -> Result<CreatedExpense, String> {
+    let response = authed_request(Method::POST, &format!("{}/expenses", API_BASE))?
+        .header("Idempotency-Key", &idempotency_key.to_string())
+        .json(&req)
+        .map_err(|e| e.to_string())?
         .send()
         .await
         .map_err(|e| e.to_string())?;
+    let response = reject_unauthorized(response)?;
 
     if response.ok() {
-        response.json::<Vec<Expense>>().await.map_err(|e| e.to_string())
+        response.json::<CreatedExpense>().await.map_err(|e| e.to_string())
     } else {
         let error = response.json::<ErrorResponse>().await
             .map_err(|e| e.to_string())?;
@@ -174,16 +589,79 @@ pub async fn list_expenses(
     }
 }
 
-pub async fn create_expense(req: CreateExpense) -> Result<Expense, String> {
-    let token = get_token().ok_or("No token found")?;
+pub async fn create_quick_expense(text: String) -> Result<Expense, String> {
+    let response = authed_request(Method::POST, &format!("{}/expenses/quick", API_BASE))?
+        .json(&QuickExpense { text })
+        .map_err(|e| e.to_string())?
+        .send()
+        .await
+        .map_err(|e| e.to_string())?;
+    let response = reject_unauthorized(response)?;
 
-    let response = Request::post(&format!("{}/expenses", API_BASE))
-        .header("Authorization", &format!("Bearer {}", token))
+    if response.ok() {
+        response.json::<Expense>().await.map_err(|e| e.to_string())
+    } else {
+        let error = response.json::<ErrorResponse>().await
+            .map_err(|e| e.to_string())?;
+        Err(error.error)
+    }
+}
+
+pub async fn list_expense_templates() -> Result<Vec<ExpenseTemplate>, String> {
+    let response = get_with_retry(&format!("{}/expense-templates", API_BASE)).await?;
+
+    if response.ok() {
+        response.json::<Vec<ExpenseTemplate>>().await.map_err(|e| e.to_string())
+    } else {
+        let error = response.json::<ErrorResponse>().await
+            .map_err(|e| e.to_string())?;
+        Err(error.error)
+    }
+}
+
+pub async fn create_expense_template(req: CreateExpenseTemplate) -> Result<ExpenseTemplate, String> {
+    let response = authed_request(Method::POST, &format!("{}/expense-templates", API_BASE))?
         .json(&req)
         .map_err(|e| e.to_string())?
         .send()
         .await
         .map_err(|e| e.to_string())?;
+    let response = reject_unauthorized(response)?;
+
+    if response.ok() {
+        response.json::<ExpenseTemplate>().await.map_err(|e| e.to_string())
+    } else {
+        let error = response.json::<ErrorResponse>().await
+            .map_err(|e| e.to_string())?;
+        Err(error.error)
+    }
+}
+
+pub async fn delete_expense_template(id: Uuid) -> Result<(), String> {
+    let url = format!("{}/expense-templates/{}", API_BASE, id);
+    let response = authed_request(Method::DELETE, &url)?
+        .send()
+        .await
+        .map_err(|e| e.to_string())?;
+    let response = reject_unauthorized(response)?;
+
+    if response.ok() {
+        Ok(())
+    } else {
+        let error = response.json::<ErrorResponse>().await
+            .map_err(|e| e.to_string())?;
+        Err(error.error)
+    }
+}
+
+/// Creates an expense dated today (in the user's timezone) from a template.
+pub async fn create_expense_from_template(id: Uuid) -> Result<Expense, String> {
+    let url = format!("{}/expenses/from-template/{}", API_BASE, id);
+    let response = authed_request(Method::POST, &url)?
+        .send()
+        .await
+        .map_err(|e| e.to_string())?;
+    let response = reject_unauthorized(response)?;
 
     if response.ok() {
         response.json::<Expense>().await.map_err(|e| e.to_string())
@@ -195,13 +673,11 @@ pub async fn create_expense(req: CreateExpense) -> Result<Expense, String> {
 }
 
 pub async fn delete_expense(id: Uuid) -> Result<(), String> {
-    let token = get_token().ok_or("No token found")?;
-
-    let response = Request::delete(&format!("{}/expenses/{}", API_BASE, id))
-        .header("Authorization", &format!("Bearer {}", token))
+    let response = authed_request(Method::DELETE, &format!("{}/expenses/{}", API_BASE, id))?
         .send()
         .await
         .map_err(|e| e.to_string())?;
+    let response = reject_unauthorized(response)?;
 
     if response.ok() {
         Ok(())
@@ -212,17 +688,28 @@ pub async fn delete_expense(id: Uuid) -> Result<(), String> {
     }
 }
 
-pub async fn get_monthly_summary() -> Result<Vec<MonthlySummary>, String> {
-    let token = get_token().ok_or("No token found")?;
+pub async fn list_trash() -> Result<Vec<TrashedExpense>, String> {
+    let response = get_with_retry(&format!("{}/expenses/trash", API_BASE)).await?;
 
-    let response = Request::get(&format!("{}/summaries/monthly", API_BASE))
-        .header("Authorization", &format!("Bearer {}", token))
+    if response.ok() {
+        response.json::<Vec<TrashedExpense>>().await.map_err(|e| e.to_string())
+    } else {
+        let error = response.json::<ErrorResponse>().await
+            .map_err(|e| e.to_string())?;
+        Err(error.error)
+    }
+}
+
+pub async fn restore_expense(id: Uuid) -> Result<Expense, String> {
+    let url = format!("{}/expenses/{}/restore", API_BASE, id);
+    let response = authed_request(Method::POST, &url)?
         .send()
         .await
         .map_err(|e| e.to_string())?;
+    let response = reject_unauthorized(response)?;
 
     if response.ok() {
-        response.json::<Vec<MonthlySummary>>().await.map_err(|e| e.to_string())
+        response.json::<Expense>().await.map_err(|e| e.to_string())
     } else {
         let error = response.json::<ErrorResponse>().await
             .map_err(|e| e.to_string())?;
@@ -230,14 +717,127 @@ pub async fn get_monthly_summary() -> Result<Vec<MonthlySummary>, String> {
     }
 }
 
-pub async fn get_category_summary() -> Result<Vec<CategorySummary>, String> {
-    let token = get_token().ok_or("No token found")?;
+pub async fn purge_expense(id: Uuid) -> Result<(), String> {
+    let url = format!("{}/expenses/{}/purge", API_BASE, id);
+    let response = authed_request(Method::DELETE, &url)?
+        .send()
+        .await
+        .map_err(|e| e.to_string())?;
+    let response = reject_unauthorized(response)?;
+
+    if response.ok() {
+        Ok(())
+    } else {
+        let error = response.json::<ErrorResponse>().await
+            .map_err(|e| e.to_string())?;
+        Err(error.error)
+    }
+}
+
+pub async fn compare_ranges(req: CompareRanges) -> Result<RangeComparison, String> {
+    let response = authed_request(Method::POST, &format!("{}/summaries/compare", API_BASE))?
+        .json(&req)
+        .map_err(|e| e.to_string())?
+        .send()
+        .await
+        .map_err(|e| e.to_string())?;
+    let response = reject_unauthorized(response)?;
+
+    if response.ok() {
+        response.json::<RangeComparison>().await.map_err(|e| e.to_string())
+    } else {
+        let error = response.json::<ErrorResponse>().await
+            .map_err(|e| e.to_string())?;
+        Err(error.error)
+    }
+}
 
-    let response = Request::get(&format!("{}/summaries/categories", API_BASE))
-        .header("Authorization", &format!("Bearer {}", token))
+pub async fn clone_month(req: CloneMonth) -> Result<Vec<Expense>, String> {
+    let response = authed_request(Method::POST, &format!("{}/expenses/clone-month", API_BASE))?
+        .json(&req)
+        .map_err(|e| e.to_string())?
         .send()
         .await
         .map_err(|e| e.to_string())?;
+    let response = reject_unauthorized(response)?;
+
+    if response.ok() {
+        response.json::<Vec<Expense>>().await.map_err(|e| e.to_string())
+    } else {
+        let error = response.json::<ErrorResponse>().await
+            .map_err(|e| e.to_string())?;
+        Err(error.error)
+    }
+}
+
+pub async fn list_descriptions(q: &str, limit: u32) -> Result<Vec<String>, String> {
+    let url = format!(
+        "{}/expenses/descriptions?q={}&limit={}",
+        API_BASE,
+        String::from(js_sys::encode_uri_component(q)),
+        limit
+    );
+    let response = get_with_retry(&url).await?;
+
+    if response.ok() {
+        response.json::<Vec<String>>().await.map_err(|e| e.to_string())
+    } else {
+        let error = response.json::<ErrorResponse>().await
+            .map_err(|e| e.to_string())?;
+        Err(error.error)
+    }
+}
+
+/// Returns the CSV text of `GET /summaries/{name}.csv`.
+async fn get_summary_csv(name: &str) -> Result<String, String> {
+    let url = format!("{}/summaries/{}.csv", API_BASE, name);
+    let response = get_with_retry(&url).await?;
+
+    if response.ok() {
+        response.text().await.map_err(|e| e.to_string())
+    } else {
+        let error = response.json::<ErrorResponse>().await
+            .map_err(|e| e.to_string())?;
+        Err(error.error)
+    }
+}
+
+pub async fn export_monthly_summary_csv() -> Result<String, String> {
+    get_summary_csv("monthly").await
+}
+
+pub async fn export_category_summary_csv() -> Result<String, String> {
+    get_summary_csv("categories").await
+}
+
+pub async fn export_monthly_report_pdf(year: i32, month: u32) -> Result<Vec<u8>, String> {
+    let url = format!("{}/reports/monthly.pdf?year={}&month={}", API_BASE, year, month);
+    let response = get_with_retry(&url).await?;
+
+    if response.ok() {
+        response.binary().await.map_err(|e| e.to_string())
+    } else {
+        let error = response.json::<ErrorResponse>().await
+            .map_err(|e| e.to_string())?;
+        Err(error.error)
+    }
+}
+
+pub async fn get_category_summary() -> Result<Vec<CategorySummary>, String> {
+    let response = get_with_retry(&format!("{}/summaries/categories", API_BASE)).await?;
+
+    if response.ok() {
+        response.json::<Vec<CategorySummary>>().await.map_err(|e| e.to_string())
+    } else {
+        let error = response.json::<ErrorResponse>().await
+            .map_err(|e| e.to_string())?;
+        Err(error.error)
+    }
+}
+
+pub async fn get_all_time_category_summary() -> Result<Vec<CategorySummary>, String> {
+    let response =
+        get_with_retry(&format!("{}/summaries/categories/all-time", API_BASE)).await?;
 
     if response.ok() {
         response.json::<Vec<CategorySummary>>().await.map_err(|e| e.to_string())
@@ -247,3 +847,132 @@ pub async fn get_category_summary() -> Result<Vec<CategorySummary>, String> {
         Err(error.error)
     }
 }
+
+pub async fn get_yearly_category_summary(year: i32) -> Result<Vec<CategorySummary>, String> {
+    let url = format!("{}/summaries/categories/yearly?year={}", API_BASE, year);
+    let response = get_with_retry(&url).await?;
+
+    if response.ok() {
+        response.json::<Vec<CategorySummary>>().await.map_err(|e| e.to_string())
+    } else {
+        let error = response.json::<ErrorResponse>().await
+            .map_err(|e| e.to_string())?;
+        Err(error.error)
+    }
+}
+
+pub async fn get_spending_projection() -> Result<SpendingProjection, String> {
+    let response = get_with_retry(&format!("{}/summaries/projection", API_BASE)).await?;
+
+    if response.ok() {
+        response.json::<SpendingProjection>().await.map_err(|e| e.to_string())
+    } else {
+        let error = response.json::<ErrorResponse>().await
+            .map_err(|e| e.to_string())?;
+        Err(error.error)
+    }
+}
+
+pub async fn get_spending_streaks() -> Result<SpendingStreaks, String> {
+    let response = get_with_retry(&format!("{}/summaries/streaks", API_BASE)).await?;
+
+    if response.ok() {
+        response.json::<SpendingStreaks>().await.map_err(|e| e.to_string())
+    } else {
+        let error = response.json::<ErrorResponse>().await
+            .map_err(|e| e.to_string())?;
+        Err(error.error)
+    }
+}
+
+/// Most and least expensive days of the current month so far.
+pub async fn get_day_extremes() -> Result<DayExtremes, String> {
+    let response = get_with_retry(&format!("{}/summaries/day-extremes", API_BASE)).await?;
+
+    if response.ok() {
+        response.json::<DayExtremes>().await.map_err(|e| e.to_string())
+    } else {
+        let error = response.json::<ErrorResponse>().await
+            .map_err(|e| e.to_string())?;
+        Err(error.error)
+    }
+}
+
+/// Spending per `interval` (`day`, `week` or `month`) bucket between the two
+/// dates, with empty buckets included.
+pub async fn get_spending_timeseries(
+    interval: &str,
+    start_date: NaiveDate,
+    end_date: NaiveDate,
+) -> Result<Vec<TimeseriesBucket>, String> {
+    let url = format!(
+        "{}/summaries/timeseries?interval={}&start_date={}&end_date={}",
+        API_BASE, interval, start_date, end_date
+    );
+    let response = get_with_retry(&url).await?;
+
+    if response.ok() {
+        response.json::<Vec<TimeseriesBucket>>().await.map_err(|e| e.to_string())
+    } else {
+        let error = response.json::<ErrorResponse>().await
+            .map_err(|e| e.to_string())?;
+        Err(error.error)
+    }
+}
+
+/// Spending per day of `year`; with `fill` every day of the year is listed,
+/// zero where nothing was spent.
+pub async fn get_spending_heatmap(year: i32, fill: bool) -> Result<Vec<DayTotal>, String> {
+    let url = format!("{}/summaries/heatmap?year={}&fill={}", API_BASE, year, fill);
+    let response = get_with_retry(&url).await?;
+
+    if response.ok() {
+        response.json::<Vec<DayTotal>>().await.map_err(|e| e.to_string())
+    } else {
+        let error = response.json::<ErrorResponse>().await
+            .map_err(|e| e.to_string())?;
+        Err(error.error)
+    }
+}
+
+pub async fn list_budgets() -> Result<Vec<Budget>, String> {
+    let response = get_with_retry(&format!("{}/budgets", API_BASE)).await?;
+
+    if response.ok() {
+        response.json::<Vec<Budget>>().await.map_err(|e| e.to_string())
+    } else {
+        let error = response.json::<ErrorResponse>().await
+            .map_err(|e| e.to_string())?;
+        Err(error.error)
+    }
+}
+
+pub async fn get_budget_burndown(category_id: Uuid) -> Result<Vec<BurndownDay>, String> {
+    let url = format!("{}/budgets/{}/burndown", API_BASE, category_id);
+    let response = get_with_retry(&url).await?;
+
+    if response.ok() {
+        response.json::<Vec<BurndownDay>>().await.map_err(|e| e.to_string())
+    } else {
+        let error = response.json::<ErrorResponse>().await
+            .map_err(|e| e.to_string())?;
+        Err(error.error)
+    }
+}
+
+pub async fn get_yearly_comparison(year: Option<i32>) -> Result<Vec<YearlyComparison>, String> {
+    let mut url = format!("{}/summaries/yearly-comparison", API_BASE);
+    if let Some(year) = year {
+        url.push_str(&format!("?year={}", year));
+    }
+
+    let response = get_with_retry(&url).await?;
+
+    if response.ok() {
+        response.json::<Vec<YearlyComparison>>().await.map_err(|e| e.to_string())
+    } else {
+        let error = response.json::<ErrorResponse>().await
+            .map_err(|e| e.to_string())?;
+        Err(error.error)
+    }
+}
@@ -1,6 +1,7 @@
 use leptos::*;
 
 use crate::api::{login, register};
+use crate::components::toast::use_toasts;
 use crate::models::{LoginRequest, RegisterRequest};
 
 #[component]
@@ -12,24 +13,26 @@ where
     let (email, set_email) = create_signal(String::new());
     let (password, set_password) = create_signal(String::new());
     let (full_name, set_full_name) = create_signal(String::new());
-    let (error, set_error) = create_signal(None::<String>);
+    let (remember_me, set_remember_me) = create_signal(false);
     let (loading, set_loading) = create_signal(false);
+    let toasts = use_toasts();
 
     let handle_submit = move |ev: leptos::ev::SubmitEvent| {
         ev.prevent_default();
-        set_error.set(None);
         set_loading.set(true);
 
         let email_val = email.get();
         let password_val = password.get();
         let full_name_val = full_name.get();
         let is_login_val = is_login.get();
+        let remember_me_val = remember_me.get();
 
         spawn_local(async move {
             let result = if is_login_val {
                 login(LoginRequest {
                     email: email_val,
                     password: password_val,
+                    remember_me: remember_me_val,
                 })
                 .await
             } else {
@@ -48,7 +51,7 @@ where
                     on_auth();
                 }
                 Err(e) => {
-                    set_error.set(Some(e));
+                    toasts.error(e);
                 }
             }
         });
@@ -61,10 +64,6 @@ where
                     {move || if is_login.get() { "Login" } else { "Register" }}
                 </h1>
 
-                {move || error.get().map(|e| view! {
-                    <div class="error">{e}</div>
-                })}
-
                 <form on:submit=handle_submit>
                     <div class="form-group">
                         <label>"Email"</label>
@@ -101,6 +100,19 @@ where
                         </div>
                     })}
 
+                    {move || is_login.get().then(|| view! {
+                        <div class="form-group remember-me">
+                            <label>
+                                <input
+                                    type="checkbox"
+                                    prop:checked=remember_me
+                                    on:change=move |ev| set_remember_me.set(event_target_checked(&ev))
+                                />
+                                " Remember me"
+                            </label>
+                        </div>
+                    })}
+
                     <div class="form-group">
                         <button
                             type="submit"
@@ -123,7 +135,6 @@ where
                         on:click=move |ev| {
                             ev.prevent_default();
                             set_is_login.update(|v| *v = !*v);
-                            set_error.set(None);
                         }
                         style="color: #667eea; font-weight: 600;"
                     >
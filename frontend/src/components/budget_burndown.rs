@@ -0,0 +1,116 @@
+use charming::{
+    component::{Axis, Legend},
+    element::{AxisType, Tooltip, Trigger},
+    series::Line,
+    Chart, WasmRenderer,
+};
+use chrono::Datelike;
+use leptos::*;
+use uuid::Uuid;
+
+use crate::api::{get_budget_burndown, list_budgets};
+use crate::components::toast::use_toasts;
+use crate::models::{Budget, BurndownDay, Category};
+
+const CHART_ID: &str = "budget-burndown-chart";
+
+/// This month's cumulative spending in one budgeted category against the
+/// straight line that would use up the budget exactly on the last day.
+/// Hidden while the user has no budgets.
+#[component]
+pub fn BudgetBurndownChart(
+    categories: ReadSignal<Vec<Category>>,
+    reload: RwSignal<i32>,
+) -> impl IntoView {
+    let (budgets, set_budgets) = create_signal(Vec::<Budget>::new());
+    let (selected, set_selected) = create_signal(None::<Uuid>);
+    let (burndown, set_burndown) = create_signal(Vec::<BurndownDay>::new());
+    let toasts = use_toasts();
+
+    spawn_local(async move {
+        match list_budgets().await {
+            Ok(loaded) => {
+                set_selected.set(loaded.first().map(|b| b.category_id));
+                set_budgets.set(loaded);
+            }
+            Err(e) => toasts.error(e),
+        }
+    });
+
+    // New or deleted expenses move the spent line, so follow dashboard reloads too
+    create_effect(move |_| {
+        reload.get();
+        let Some(category_id) = selected.get() else {
+            return;
+        };
+
+        spawn_local(async move {
+            match get_budget_burndown(category_id).await {
+                Ok(days) => set_burndown.set(days),
+                Err(e) => toasts.error(e),
+            }
+        });
+    });
+
+    create_effect(move |_| {
+        let days = burndown.get();
+        if days.is_empty() {
+            return;
+        }
+
+        let chart = Chart::new()
+            .tooltip(Tooltip::new().trigger(Trigger::Axis))
+            .legend(Legend::new().bottom(0))
+            .x_axis(
+                Axis::new()
+                    .type_(AxisType::Category)
+                    .data(days.iter().map(|d| d.day.day().to_string()).collect()),
+            )
+            .y_axis(Axis::new().type_(AxisType::Value))
+            .series(
+                Line::new()
+                    .name("Spent")
+                    .data(days.iter().map(|d| d.cumulative_spent).collect()),
+            )
+            .series(
+                Line::new()
+                    .name("Ideal pace")
+                    .data(days.iter().map(|d| d.ideal_pace).collect()),
+            );
+
+        if let Err(e) = WasmRenderer::new(800, 360).render(CHART_ID, &chart) {
+            logging::error!("Failed to render budget burn-down chart: {:?}", e);
+        }
+    });
+
+    let category_name = move |id: Uuid| {
+        categories.with(|cats| {
+            cats.iter()
+                .find(|c| c.id == id)
+                .map(|c| c.name.clone())
+                .unwrap_or_else(|| "Unknown category".to_string())
+        })
+    };
+
+    // The chart container always stays in the DOM so the render effect can find it
+    view! {
+        <div class="card" style:display=move || if budgets.with(Vec::is_empty) { "none" } else { "block" }>
+            <div class="card-header">
+                <h2 style="color: #333;">"Budget Burn-down"</h2>
+                <select
+                    prop:value=move || selected.get().map(|id| id.to_string()).unwrap_or_default()
+                    on:change=move |ev| set_selected.set(Uuid::parse_str(&event_target_value(&ev)).ok())
+                >
+                    {move || budgets.get().into_iter().map(|budget| {
+                        view! {
+                            <option value=budget.category_id.to_string()>
+                                {category_name(budget.category_id)}
+                            </option>
+                        }
+                    }).collect::<Vec<_>>()}
+                </select>
+            </div>
+            <div id=CHART_ID class="chart-container"></div>
+        </div>
+    }
+}
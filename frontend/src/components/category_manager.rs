@@ -0,0 +1,463 @@
+use leptos::*;
+use uuid::Uuid;
+use wasm_bindgen_futures::JsFuture;
+
+use crate::api::{
+    archive_category, create_category, export_categories, get_category_history,
+    get_category_palette, import_categories, reorder_categories, search_categories,
+    unarchive_category,
+};
+use crate::components::toast::use_toasts;
+use crate::download::save_file;
+use crate::models::{Category, CategoryTemplate, CreateCategory};
+
+const DEFAULT_COLOR: &str = "#667eea";
+/// Number of months shown in each category's sparkline.
+const HISTORY_MONTHS: u32 = 6;
+const SPARKLINE_WIDTH: f64 = 60.0;
+const SPARKLINE_HEIGHT: f64 = 18.0;
+/// Number of search results fetched at a time.
+const SEARCH_PAGE_SIZE: i64 = 50;
+
+/// Mirrors the backend check: only `#RRGGBB` hex codes are accepted.
+fn is_valid_hex_color(value: &str) -> bool {
+    value.len() == 7
+        && value.starts_with('#')
+        && value[1..].chars().all(|c| c.is_ascii_hexdigit())
+}
+
+/// `reorderable` is false while categories are shown sorted by name or usage,
+/// where dragging a chip wouldn't visibly move it.
+#[component]
+pub fn CategoryManager<F>(
+    categories: ReadSignal<Vec<Category>>,
+    reorderable: Signal<bool>,
+    on_changed: F,
+) -> impl IntoView
+where
+    F: Fn() + Copy + 'static,
+{
+    let (name, set_name) = create_signal(String::new());
+    let (color, set_color) = create_signal(DEFAULT_COLOR.to_string());
+    let (icon, set_icon) = create_signal(String::new());
+    let (loading, set_loading) = create_signal(false);
+    let (dragged, set_dragged) = create_signal(None::<Uuid>);
+    let (palette, set_palette) = create_signal(Vec::<String>::new());
+    let (search, set_search) = create_signal(String::new());
+    let (results, set_results) = create_signal(Vec::<Category>::new());
+    let (results_total, set_results_total) = create_signal(0i64);
+    let file_input = create_node_ref::<html::Input>();
+    let toasts = use_toasts();
+
+    // Dragging within a filtered list would reorder against hidden categories
+    let can_reorder = Signal::derive(move || reorderable.get() && search.with(|q| q.trim().is_empty()));
+
+    // Also follows `categories` so an archive or a new category shows up in the results
+    create_effect(move |_| {
+        categories.track();
+        let q = search.get().trim().to_string();
+        if q.is_empty() {
+            return;
+        }
+
+        spawn_local(async move {
+            match search_categories(&q, SEARCH_PAGE_SIZE, 0).await {
+                // Drop responses for text the user has already typed past
+                Ok(page) if search.get_untracked().trim() == q => {
+                    set_results_total.set(page.total);
+                    set_results.set(page.items);
+                }
+                Ok(_) => {}
+                Err(e) => toasts.error(e),
+            }
+        });
+    });
+
+    let load_more_results = move |_| {
+        let q = search.get_untracked().trim().to_string();
+        let offset = results.with_untracked(|r| r.len() as i64);
+        spawn_local(async move {
+            match search_categories(&q, SEARCH_PAGE_SIZE, offset).await {
+                Ok(page) => {
+                    set_results_total.set(page.total);
+                    set_results.update(|r| r.extend(page.items));
+                }
+                Err(e) => toasts.error(e),
+            }
+        });
+    };
+
+    let shown = move || {
+        if search.with(|q| q.trim().is_empty()) {
+            categories.get()
+        } else {
+            results.get()
+        }
+    };
+
+    // Refetched whenever categories change so colors just taken drop out
+    create_effect(move |_| {
+        categories.track();
+        spawn_local(async move {
+            if let Ok(colors) = get_category_palette().await {
+                set_palette.set(colors);
+            }
+        });
+    });
+
+    let handle_toggle_archived = move |id: Uuid, archived: bool| {
+        spawn_local(async move {
+            let result = if archived {
+                unarchive_category(id).await
+            } else {
+                archive_category(id).await
+            };
+
+            match result {
+                Ok(cat) => {
+                    toasts.success(if cat.archived { "Category archived" } else { "Category unarchived" });
+                    on_changed();
+                }
+                Err(e) => toasts.error(e),
+            }
+        });
+    };
+
+    let handle_export = move |_| {
+        spawn_local(async move {
+            let result = export_categories().await.and_then(|templates| {
+                serde_json::to_string_pretty(&templates).map_err(|e| e.to_string())
+            });
+
+            match result {
+                Ok(json) => {
+                    if let Err(e) = save_file("categories.json", &json, "application/json") {
+                        toasts.error(e);
+                    }
+                }
+                Err(e) => toasts.error(e),
+            }
+        });
+    };
+
+    // Reads the chosen file (an earlier export) and adds the categories in it
+    let handle_import = move |_| {
+        let Some(input) = file_input.get() else { return };
+        let Some(file) = input.files().and_then(|files| files.get(0)) else { return };
+        // Cleared so picking the same file again still fires a change
+        input.set_value("");
+
+        spawn_local(async move {
+            let text = JsFuture::from(file.text())
+                .await
+                .ok()
+                .and_then(|text| text.as_string())
+                .ok_or_else(|| "Could not read file".to_string());
+            let templates = text.and_then(|text| {
+                serde_json::from_str::<Vec<CategoryTemplate>>(&text)
+                    .map_err(|_| "Not a category export file".to_string())
+            });
+            let result = match templates {
+                Ok(templates) => import_categories(&templates).await,
+                Err(e) => Err(e),
+            };
+
+            match result {
+                Ok(summary) => {
+                    toasts.success(format!(
+                        "Imported {} categories, {} already existed",
+                        summary.created, summary.skipped
+                    ));
+                    if summary.created > 0 {
+                        on_changed();
+                    }
+                }
+                Err(e) => toasts.error(e),
+            }
+        });
+    };
+
+    // Moves the dragged chip to the position of the chip it was dropped on
+    let handle_drop = move |target: Uuid| {
+        let Some(source) = dragged.get() else { return };
+        set_dragged.set(None);
+        if source == target || !can_reorder.get_untracked() {
+            return;
+        }
+
+        let mut ids: Vec<Uuid> = categories.get().iter().map(|c| c.id).collect();
+        let (Some(from), Some(to)) = (
+            ids.iter().position(|id| *id == source),
+            ids.iter().position(|id| *id == target),
+        ) else {
+            return;
+        };
+        let moved = ids.remove(from);
+        ids.insert(to, moved);
+
+        spawn_local(async move {
+            match reorder_categories(ids).await {
+                Ok(_) => on_changed(),
+                Err(e) => toasts.error(e),
+            }
+        });
+    };
+
+    let handle_submit = move |ev: leptos::ev::SubmitEvent| {
+        ev.prevent_default();
+
+        let name_val = name.get();
+        if name_val.trim().is_empty() {
+            toasts.error("Please enter a category name");
+            return;
+        }
+
+        let color_val = color.get();
+        if !is_valid_hex_color(&color_val) {
+            toasts.error("Color must be a hex code like #4ECDC4");
+            return;
+        }
+
+        let icon_val = icon.get();
+        let icon_val = if icon_val.trim().is_empty() { None } else { Some(icon_val) };
+
+        set_loading.set(true);
+
+        spawn_local(async move {
+            let result = create_category(CreateCategory {
+                name: name_val.trim().to_string(),
+                color: Some(color_val),
+                icon: icon_val,
+            })
+            .await;
+
+            set_loading.set(false);
+
+            match result {
+                Ok(_) => {
+                    set_name.set(String::new());
+                    set_color.set(DEFAULT_COLOR.to_string());
+                    set_icon.set(String::new());
+                    toasts.success("Category added");
+                    on_changed();
+                }
+                Err(e) => {
+                    toasts.error(e);
+                }
+            }
+        });
+    };
+
+    view! {
+        <div class="card">
+            <div class="card-header">
+                <h2 style="color: #333;">"Categories"</h2>
+                <div class="header-actions">
+                    <button type="button" class="btn-secondary" on:click=handle_export>
+                        "Export"
+                    </button>
+                    <button
+                        type="button"
+                        class="btn-secondary"
+                        on:click=move |_| {
+                            if let Some(input) = file_input.get() {
+                                input.click();
+                            }
+                        }
+                    >
+                        "Import"
+                    </button>
+                    <input
+                        type="file"
+                        accept="application/json,.json"
+                        style="display: none;"
+                        node_ref=file_input
+                        on:change=handle_import
+                    />
+                </div>
+            </div>
+
+            <input
+                type="search"
+                class="category-search"
+                prop:value=search
+                on:input=move |ev| set_search.set(event_target_value(&ev))
+                placeholder="Search categories..."
+            />
+
+            <div class="category-list">
+                {move || shown().into_iter().map(|cat| {
+                    let color = cat.color.clone().unwrap_or_else(|| DEFAULT_COLOR.to_string());
+                    let id = cat.id;
+                    let archived = cat.archived;
+                    view! {
+                        <span
+                            class="category-chip"
+                            class:archived=archived
+                            class:dragging=move || dragged.get() == Some(id)
+                            style:border-color=color.clone()
+                            draggable=move || if can_reorder.get() { "true" } else { "false" }
+                            title=move || can_reorder.get().then_some("Drag to reorder")
+                            on:dragstart=move |ev: ev::DragEvent| {
+                                // Firefox won't start a drag without data
+                                if let Some(transfer) = ev.data_transfer() {
+                                    let _ = transfer.set_data("text/plain", &id.to_string());
+                                }
+                                set_dragged.set(Some(id));
+                            }
+                            on:dragend=move |_| set_dragged.set(None)
+                            on:dragover=move |ev: ev::DragEvent| ev.prevent_default()
+                            on:drop=move |ev: ev::DragEvent| {
+                                ev.prevent_default();
+                                handle_drop(id);
+                            }
+                        >
+                            {cat.icon.as_ref().map(|i| format!("{} ", i)).unwrap_or_default()}
+                            {&cat.name}
+                            <CategorySparkline category_id=cat.id color=color />
+                            <button
+                                type="button"
+                                class="chip-action"
+                                on:click=move |_| handle_toggle_archived(id, archived)
+                            >
+                                {if archived { "Unarchive" } else { "Archive" }}
+                            </button>
+                        </span>
+                    }
+                }).collect::<Vec<_>>()}
+            </div>
+
+            {move || {
+                let showing = results.with(Vec::len) as i64;
+                let total = results_total.get();
+                (!search.with(|q| q.trim().is_empty())).then(|| view! {
+                    <p style="color: #6c757d; margin-bottom: 12px;">
+                        {format!("Showing {} of {} matching categories", showing, total)}
+                        {(showing < total).then(|| view! {
+                            " "
+                            <button type="button" class="btn-secondary" on:click=load_more_results>
+                                "Load more"
+                            </button>
+                        })}
+                    </p>
+                })
+            }}
+
+            <form on:submit=handle_submit class="filters">
+                <div class="form-group">
+                    <label>"Name"</label>
+                    <input
+                        type="text"
+                        required
+                        maxlength="100"
+                        prop:value=name
+                        on:input=move |ev| set_name.set(event_target_value(&ev))
+                        placeholder="e.g. Groceries"
+                    />
+                </div>
+
+                <div class="form-group">
+                    <label>"Color"</label>
+                    <div class="color-input">
+                        <input
+                            type="text"
+                            maxlength="7"
+                            prop:value=color
+                            class:invalid=move || !is_valid_hex_color(&color.get())
+                            on:input=move |ev| set_color.set(event_target_value(&ev))
+                            placeholder="#4ECDC4"
+                        />
+                        <span
+                            class="color-swatch"
+                            style:background-color=move || {
+                                let c = color.get();
+                                if is_valid_hex_color(&c) { c } else { "transparent".to_string() }
+                            }
+                        ></span>
+                    </div>
+                    <div class="palette">
+                        {move || palette.get().into_iter().map(|c| {
+                            let value = c.clone();
+                            let selected = c.clone();
+                            view! {
+                                <button
+                                    type="button"
+                                    class="palette-swatch"
+                                    class:selected=move || color.get().eq_ignore_ascii_case(&selected)
+                                    style:background-color=c.clone()
+                                    title=c.clone()
+                                    on:click=move |_| set_color.set(value.clone())
+                                ></button>
+                            }
+                        }).collect::<Vec<_>>()}
+                    </div>
+                </div>
+
+                <div class="form-group">
+                    <label>"Icon"</label>
+                    <input
+                        type="text"
+                        prop:value=icon
+                        on:input=move |ev| set_icon.set(event_target_value(&ev))
+                        placeholder="🛒"
+                    />
+                </div>
+
+                <button type="submit" disabled=loading>
+                    {move || if loading.get() { "Adding..." } else { "Add Category" }}
+                </button>
+            </form>
+        </div>
+    }
+}
+
+/// Tiny line chart of a category's monthly totals over the last
+/// [`HISTORY_MONTHS`] months, oldest on the left.
+#[component]
+fn CategorySparkline(category_id: Uuid, color: String) -> impl IntoView {
+    let (points, set_points) = create_signal(String::new());
+
+    spawn_local(async move {
+        // A missing sparkline isn't worth a toast; the chip still renders
+        if let Ok(history) = get_category_history(category_id, HISTORY_MONTHS).await {
+            set_points.set(sparkline_points(
+                &history.iter().map(|m| m.total_amount).collect::<Vec<_>>(),
+            ));
+        }
+    });
+
+    view! {
+        <svg
+            class="sparkline"
+            width=SPARKLINE_WIDTH
+            height=SPARKLINE_HEIGHT
+            viewBox=format!("0 0 {} {}", SPARKLINE_WIDTH, SPARKLINE_HEIGHT)
+        >
+            <polyline points=points fill="none" stroke=color stroke-width="1.5" />
+        </svg>
+    }
+}
+
+/// Scales values into SVG `x,y` pairs; an all-zero series is a flat line at the bottom.
+fn sparkline_points(values: &[f64]) -> String {
+    let max = values.iter().cloned().fold(0.0, f64::max);
+    let step = if values.len() > 1 {
+        SPARKLINE_WIDTH / (values.len() - 1) as f64
+    } else {
+        0.0
+    };
+
+    values
+        .iter()
+        .enumerate()
+        .map(|(i, v)| {
+            let y = if max > 0.0 {
+                SPARKLINE_HEIGHT - 1.0 - (v / max) * (SPARKLINE_HEIGHT - 2.0)
+            } else {
+                SPARKLINE_HEIGHT - 1.0
+            };
+            format!("{:.1},{:.1}", i as f64 * step, y)
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
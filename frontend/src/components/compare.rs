@@ -0,0 +1,176 @@
+use chrono::{Datelike, Local, Months, NaiveDate};
+use leptos::*;
+
+use crate::api::compare_ranges;
+use crate::components::toast::use_toasts;
+use crate::format::format_money;
+use crate::models::{CompareRanges, DateRange, RangeComparison};
+
+/// Last month (`a`) against this month (`b`), the comparison asked for most.
+fn default_ranges() -> (DateRange, DateRange) {
+    let today = Local::now().date_naive();
+    let this_month = today.with_day(1).expect("Valid date");
+    let last_month_end = this_month.pred_opt().expect("Valid date");
+    let last_month = last_month_end.with_day(1).expect("Valid date");
+    let this_month_end = this_month
+        .checked_add_months(Months::new(1))
+        .and_then(|d| d.pred_opt())
+        .expect("Valid date");
+
+    (
+        DateRange { start: last_month, end: last_month_end },
+        DateRange { start: this_month, end: this_month_end },
+    )
+}
+
+/// "▲ $12.00" for more spent in `b`, "▼ $12.00" for less, with the matching class.
+fn delta_view(delta: f64, currency: &str) -> impl IntoView {
+    let (class, arrow) = if delta > 0.005 {
+        ("delta-up", "▲ ")
+    } else if delta < -0.005 {
+        ("delta-down", "▼ ")
+    } else {
+        ("", "")
+    };
+    view! { <span class=class>{arrow}{format_money(delta.abs(), currency)}</span> }
+}
+
+/// Two date range pickers and the change in spending between them, overall
+/// and per category.
+#[component]
+pub fn RangeCompare(currency: Memo<String>) -> impl IntoView {
+    let (default_a, default_b) = default_ranges();
+    let (a_start, set_a_start) = create_signal(default_a.start.to_string());
+    let (a_end, set_a_end) = create_signal(default_a.end.to_string());
+    let (b_start, set_b_start) = create_signal(default_b.start.to_string());
+    let (b_end, set_b_end) = create_signal(default_b.end.to_string());
+    let (result, set_result) = create_signal(None::<RangeComparison>);
+    let (loading, set_loading) = create_signal(false);
+    let toasts = use_toasts();
+
+    let handle_compare = move |ev: leptos::ev::SubmitEvent| {
+        ev.prevent_default();
+
+        let parse = |value: String| NaiveDate::parse_from_str(&value, "%Y-%m-%d").ok();
+        let (Some(a_start), Some(a_end), Some(b_start), Some(b_end)) = (
+            parse(a_start.get()),
+            parse(a_end.get()),
+            parse(b_start.get()),
+            parse(b_end.get()),
+        ) else {
+            toasts.error("Please pick a start and end date for both ranges");
+            return;
+        };
+
+        set_loading.set(true);
+        spawn_local(async move {
+            let request = CompareRanges {
+                a: DateRange { start: a_start, end: a_end },
+                b: DateRange { start: b_start, end: b_end },
+            };
+            match compare_ranges(request).await {
+                Ok(comparison) => set_result.set(Some(comparison)),
+                Err(e) => toasts.error(e),
+            }
+            set_loading.set(false);
+        });
+    };
+
+    let range_inputs = move |label: &'static str,
+                             start: ReadSignal<String>,
+                             set_start: WriteSignal<String>,
+                             end: ReadSignal<String>,
+                             set_end: WriteSignal<String>| {
+        view! {
+            <div class="form-group">
+                <label>{label}</label>
+                <div class="range-inputs">
+                    <input
+                        type="date"
+                        prop:value=start
+                        on:change=move |ev| set_start.set(event_target_value(&ev))
+                    />
+                    <input
+                        type="date"
+                        prop:value=end
+                        on:change=move |ev| set_end.set(event_target_value(&ev))
+                    />
+                </div>
+            </div>
+        }
+    };
+
+    view! {
+        <div class="card">
+            <h2 style="margin-bottom: 20px; color: #333;">"Compare Periods"</h2>
+            <form on:submit=handle_compare>
+                <div class="compare-ranges">
+                    {range_inputs("Period A", a_start, set_a_start, a_end, set_a_end)}
+                    {range_inputs("Period B", b_start, set_b_start, b_end, set_b_end)}
+                </div>
+                <button type="submit" disabled=loading>
+                    {move || if loading.get() { "Comparing..." } else { "Compare" }}
+                </button>
+            </form>
+        </div>
+
+        {move || result.get().map(|comparison| {
+            let currency = currency.get();
+            view! {
+                <div class="summary-grid">
+                    <div class="summary-card">
+                        <h3>"Period A (" {comparison.a.start.to_string()} " – " {comparison.a.end.to_string()} ")"</h3>
+                        <div class="value">{format_money(comparison.a.total_amount, &currency)}</div>
+                        <p>{comparison.a.expense_count} " expenses"</p>
+                    </div>
+                    <div class="summary-card">
+                        <h3>"Period B (" {comparison.b.start.to_string()} " – " {comparison.b.end.to_string()} ")"</h3>
+                        <div class="value">{format_money(comparison.b.total_amount, &currency)}</div>
+                        <p>{comparison.b.expense_count} " expenses"</p>
+                    </div>
+                </div>
+
+                <div class="card">
+                    <h2 style="margin-bottom: 20px; color: #333;">
+                        "Change: " {delta_view(comparison.total_delta, &currency)}
+                    </h2>
+                    {if comparison.categories.is_empty() {
+                        view! {
+                            <p style="text-align: center; color: #6c757d; padding: 20px;">
+                                "No expenses in either period."
+                            </p>
+                        }.into_view()
+                    } else {
+                        view! {
+                            <div class="expense-list">
+                                {comparison.categories.into_iter().map(|category| {
+                                    let color = category.category_color.clone()
+                                        .unwrap_or_else(|| "#667eea".to_string());
+                                    view! {
+                                        <div class="expense-item" style:border-left-color=color>
+                                            <div class="expense-icon">
+                                                {category.category_icon.unwrap_or_else(|| "📦".to_string())}
+                                            </div>
+                                            <div class="expense-details">
+                                                <h3>{category.category_name}</h3>
+                                                <p>
+                                                    {format_money(category.a_total, &currency)}
+                                                    " → "
+                                                    {format_money(category.b_total, &currency)}
+                                                </p>
+                                            </div>
+                                            <div class="expense-amount">
+                                                {delta_view(category.delta, &currency)}
+                                            </div>
+                                            <div></div>
+                                        </div>
+                                    }
+                                }).collect::<Vec<_>>()}
+                            </div>
+                        }.into_view()
+                    }}
+                </div>
+            }
+        })}
+    }
+}
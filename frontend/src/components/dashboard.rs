@@ -1,12 +1,136 @@
-use chrono::{Datelike, Local};
+use chrono::{Datelike, Duration, Local, Month, NaiveDate};
 use leptos::*;
+use serde::{Deserialize, Serialize};
 use uuid::Uuid;
+use web_sys::window;
 
 use crate::api::{
-    clear_token, delete_expense, get_category_summary, get_monthly_summary, list_categories,
-    list_expenses,
+    clear_token, clone_month, delete_expense, export_category_summary_csv,
+    export_monthly_report_pdf, export_monthly_summary_csv, get_all_time_category_summary, get_category_summary,
+    get_dashboard, get_day_extremes, get_settings, get_spending_projection, get_spending_streaks,
+    get_yearly_category_summary, get_yearly_comparison, list_expenses, restore_expense,
+    seed_demo_data,
 };
-use crate::models::{Category, CategorySummary, Expense, MonthlySummary};
+use crate::components::toast::{use_toasts, ToastKind};
+use crate::download::{save_bytes, save_file};
+use crate::live::LiveUpdates;
+use crate::format::{
+    format_date, format_money, DEFAULT_CURRENCY, DEFAULT_DATE_FORMAT, DEFAULT_WEEK_START,
+};
+use crate::models::{
+    Category, CategorySummary, CloneMonth, DayExtremes, Expense, MonthlySummary, SpendingProjection,
+    SpendingStreaks, UserSettings, YearlyComparison,
+};
+use crate::storage;
+
+/// Number of expenses shown per page of the expense table.
+const PAGE_SIZE: i64 = 50;
+
+/// Share of the monthly limit at which the dashboard starts warning.
+const LIMIT_WARNING_RATIO: f64 = 0.8;
+
+/// Sortable columns of the expense table as (`sort_by` value, header label).
+pub const SORT_COLUMNS: &[(&str, &str)] = &[
+    ("date", "Date"),
+    ("category", "Category"),
+    ("amount", "Amount"),
+    ("description", "Description"),
+];
+
+const SORT_STORAGE_KEY: &str = "expense_sort";
+
+/// `localStorage` key of the expense list filters.
+const FILTERS_KEY: &str = "dashboard_filters";
+
+/// Filters restored on the next visit, so returning users keep their view.
+#[derive(Default, Serialize, Deserialize)]
+struct SavedFilters {
+    categories: Vec<Uuid>,
+    start_date: Option<String>,
+    end_date: Option<String>,
+}
+
+/// Period covered by the category summary card.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum SummaryRange {
+    Month,
+    Year,
+    AllTime,
+}
+
+/// Category summary for `range`; `year` is used for [`SummaryRange::Year`].
+async fn load_category_summary(range: SummaryRange, year: i32) -> Result<Vec<CategorySummary>, String> {
+    match range {
+        SummaryRange::Month => get_category_summary().await,
+        SummaryRange::Year => get_yearly_category_summary(year).await,
+        SummaryRange::AllTime => get_all_time_category_summary().await,
+    }
+}
+
+/// Forgets the saved filters, e.g. on logout since category ids belong to one user.
+fn clear_filters() {
+    storage::remove(FILTERS_KEY);
+}
+
+/// Minutes without any input after which the dashboard logs out, so a session
+/// left open on a shared computer doesn't stay usable.
+const INACTIVITY_TIMEOUT_MINUTES: f64 = 15.0;
+/// How often the time since the last input is checked.
+const INACTIVITY_CHECK_INTERVAL: std::time::Duration = std::time::Duration::from_secs(15);
+
+fn sort_column(value: &str) -> Option<&'static str> {
+    SORT_COLUMNS.iter().find(|(column, _)| *column == value).map(|(column, _)| *column)
+}
+
+/// Restores the sort chosen earlier in this browser session, stored as
+/// `"<column>:<asc|desc>"`. `None` until one is chosen, so the default sort
+/// from the settings applies.
+fn load_sort() -> Option<(&'static str, bool)> {
+    let saved = window()
+        .and_then(|w| w.session_storage().ok())
+        .flatten()
+        .and_then(|s| s.get_item(SORT_STORAGE_KEY).ok())
+        .flatten()
+        .unwrap_or_default();
+
+    let (column, dir) = saved.split_once(':')?;
+    sort_column(column).map(|column| (column, dir != "asc"))
+}
+
+fn save_sort(column: &str, descending: bool) {
+    if let Some(storage) = window()
+        .and_then(|w| w.session_storage().ok())
+        .flatten()
+    {
+        let dir = if descending { "desc" } else { "asc" };
+        let _ = storage.set_item(SORT_STORAGE_KEY, &format!("{}:{}", column, dir));
+    }
+}
+
+/// What the dashboard shows below its header.
+#[derive(Clone, Copy, PartialEq)]
+enum Page {
+    Overview,
+    Compare,
+    Trash,
+    Settings,
+}
+
+/// First day of the week containing `date`, for a `week_start` setting of
+/// `monday` (ISO weeks) or `sunday`.
+fn start_of_week(date: NaiveDate, week_start: &str) -> NaiveDate {
+    let days_in = if week_start == "sunday" {
+        date.weekday().num_days_from_sunday()
+    } else {
+        date.weekday().num_days_from_monday()
+    };
+    date - Duration::days(days_in as i64)
+}
+
+/// "1 day" / "5 days".
+fn days_label(days: i64) -> String {
+    if days == 1 { "1 day".to_string() } else { format!("{} days", days) }
+}
 
 #[component]
 pub fn Dashboard<F>(on_logout: F) -> impl IntoView
@@ -15,114 +139,539 @@ where
 {
     let (categories, set_categories) = create_signal(Vec::<Category>::new());
     let (expenses, set_expenses) = create_signal(Vec::<Expense>::new());
+    let (total_expenses, set_total_expenses) = create_signal(0i64);
+    let (expense_page, set_expense_page) = create_signal(1i64);
+    let (total_pages, set_total_pages) = create_signal(0i64);
+    let (loading_page, set_loading_page) = create_signal(false);
     let (monthly_summary, set_monthly_summary) = create_signal(Vec::<MonthlySummary>::new());
     let (category_summary, set_category_summary) = create_signal(Vec::<CategorySummary>::new());
+    let (summary_range, set_summary_range) = create_signal(SummaryRange::Month);
+    let (projection, set_projection) = create_signal(None::<SpendingProjection>);
+    let (streaks, set_streaks) = create_signal(None::<SpendingStreaks>);
+    let (day_extremes, set_day_extremes) = create_signal(None::<DayExtremes>);
+    let (yearly_comparison, set_yearly_comparison) = create_signal(Vec::<YearlyComparison>::new());
+    let current_year = Local::now().year();
+    let settings = create_rw_signal(None::<UserSettings>);
+    let currency = create_memo(move |_| {
+        settings.with(|s| s.as_ref().map(|s| s.currency.clone()))
+            .unwrap_or_else(|| DEFAULT_CURRENCY.to_string())
+    });
+    let date_format = create_memo(move |_| {
+        settings.with(|s| s.as_ref().map(|s| s.date_format.clone()))
+            .unwrap_or_else(|| DEFAULT_DATE_FORMAT.to_string())
+    });
+    let week_start = create_memo(move |_| {
+        settings.with(|s| s.as_ref().map(|s| s.week_start.clone()))
+            .unwrap_or_else(|| DEFAULT_WEEK_START.to_string())
+    });
+    let monthly_limit = create_memo(move |_| {
+        settings.with(|s| s.as_ref().and_then(|s| s.monthly_limit))
+    });
+    let (page, set_page) = create_signal(Page::Overview);
     let (loading, set_loading) = create_signal(true);
-    let (error, set_error) = create_signal(None::<String>);
+    let toasts = use_toasts();
 
-    let (filter_category, set_filter_category) = create_signal(None::<Uuid>);
-    let (filter_start_date, set_filter_start_date) = create_signal(None::<String>);
-    let (filter_end_date, set_filter_end_date) = create_signal(None::<String>);
+    let saved_filters = storage::load::<SavedFilters>(FILTERS_KEY).unwrap_or_default();
+    let (filter_categories, set_filter_categories) = create_signal(saved_filters.categories);
+    let (filter_start_date, set_filter_start_date) = create_signal(saved_filters.start_date);
+    let (filter_end_date, set_filter_end_date) = create_signal(saved_filters.end_date);
+
+    // Only writes storage; the filter handlers bump `reload_data` themselves
+    create_effect(move |_| {
+        let filters = SavedFilters {
+            categories: filter_categories.get(),
+            start_date: filter_start_date.get(),
+            end_date: filter_end_date.get(),
+        };
+        if filters.categories.is_empty()
+            && filters.start_date.is_none()
+            && filters.end_date.is_none()
+        {
+            storage::remove(FILTERS_KEY);
+        } else {
+            storage::save(FILTERS_KEY, &filters);
+        }
+    });
+
+    let saved_sort = load_sort();
+    // Until a column header is clicked, the table follows the settings
+    let sort_chosen = store_value(saved_sort.is_some());
+    let (initial_sort_by, initial_sort_desc) = saved_sort.unwrap_or(("date", true));
+    let (sort_by, set_sort_by) = create_signal(initial_sort_by);
+    let (sort_desc, set_sort_desc) = create_signal(initial_sort_desc);
 
     let reload_data = create_rw_signal(0);
 
+    // Changes made on another device show up without a manual refresh
+    let live_updates = LiveUpdates::start(move || reload_data.update(|v| *v += 1));
+    on_cleanup(move || live_updates.stop());
+
     create_effect(move |_| {
         reload_data.get();
         set_loading.set(true);
-        set_error.set(None);
 
         spawn_local(async move {
-            match list_categories().await {
-                Ok(cats) => set_categories.set(cats),
-                Err(e) => set_error.set(Some(e)),
+            match get_settings().await {
+                Ok(loaded) => {
+                    if !sort_chosen.get_value() {
+                        if let Some(column) = sort_column(&loaded.expense_sort_by) {
+                            set_sort_by.set(column);
+                        }
+                        set_sort_desc.set(loaded.expense_sort_dir != "asc");
+                    }
+                    settings.set(Some(loaded));
+                }
+                Err(e) => toasts.error(e),
             }
 
             let start = filter_start_date.get();
             let end = filter_end_date.get();
-            let cat = filter_category.get();
+            let cats = filter_categories.get();
+            let dir = if sort_desc.get() { "desc" } else { "asc" };
 
-            match list_expenses(start, end, cat).await {
-                Ok(exps) => set_expenses.set(exps),
-                Err(e) => set_error.set(Some(e)),
+            // Any reload (filter or sort change, create, delete) starts over at the first page
+            match get_dashboard(start, end, cats, sort_by.get(), dir, PAGE_SIZE).await {
+                Ok(dashboard) => {
+                    set_categories.set(dashboard.categories);
+                    set_expenses.set(dashboard.expenses.items);
+                    set_total_expenses.set(dashboard.expenses.total);
+                    set_expense_page.set(dashboard.expenses.page);
+                    set_total_pages.set(dashboard.expenses.total_pages);
+                    set_monthly_summary.set(dashboard.monthly_summary);
+                    set_category_summary.set(dashboard.category_summary);
+                }
+                Err(e) => toasts.error(e),
             }
 
-            match get_monthly_summary().await {
-                Ok(summary) => set_monthly_summary.set(summary),
-                Err(e) => set_error.set(Some(e)),
+            // The dashboard response only has the current month's category summary
+            let range = summary_range.get_untracked();
+            if range != SummaryRange::Month {
+                match load_category_summary(range, current_year).await {
+                    Ok(summary) => set_category_summary.set(summary),
+                    Err(e) => toasts.error(e),
+                }
             }
 
-            match get_category_summary().await {
-                Ok(summary) => set_category_summary.set(summary),
-                Err(e) => set_error.set(Some(e)),
+            match get_spending_projection().await {
+                Ok(p) => set_projection.set(Some(p)),
+                Err(e) => toasts.error(e),
+            }
+
+            match get_spending_streaks().await {
+                Ok(s) => set_streaks.set(Some(s)),
+                Err(e) => toasts.error(e),
+            }
+
+            match get_day_extremes().await {
+                Ok(extremes) => set_day_extremes.set(Some(extremes)),
+                Err(e) => toasts.error(e),
+            }
+
+            match get_yearly_comparison(Some(current_year)).await {
+                Ok(comparison) => set_yearly_comparison.set(comparison),
+                Err(e) => toasts.error(e),
             }
 
             set_loading.set(false);
         });
     });
 
-    let handle_logout = move |_| {
+    // Saving a different default sort in the settings applies it right away
+    create_effect(move |previous: Option<Option<(String, String, String)>>| {
+        let current = settings.with(|s| {
+            s.as_ref().map(|s| {
+                (s.expense_sort_by.clone(), s.expense_sort_dir.clone(), s.category_sort.clone())
+            })
+        });
+        if let (Some(Some(previous)), Some(current)) = (&previous, &current) {
+            if previous != current {
+                reload_data.update(|v| *v += 1);
+            }
+        }
+        current
+    });
+
+    let logout = move || {
         clear_token();
+        crate::components::expense_form::clear_draft();
+        clear_filters();
         on_logout();
     };
+    let handle_logout = move |_| logout();
+
+    // Input only records the time; the interval compares against it, rather
+    // than every mouse move restarting a timer. Checking elapsed time also
+    // catches up correctly after the computer wakes from sleep.
+    let last_activity = store_value(js_sys::Date::now());
+    let touch = move || last_activity.set_value(js_sys::Date::now());
+    let activity_listeners = [
+        window_event_listener(ev::mousemove, move |_| touch()),
+        window_event_listener(ev::mousedown, move |_| touch()),
+        window_event_listener(ev::keydown, move |_| touch()),
+        window_event_listener(ev::scroll, move |_| touch()),
+        window_event_listener(ev::touchstart, move |_| touch()),
+    ];
+    let inactivity_timer = set_interval_with_handle(
+        move || {
+            let idle_minutes = (js_sys::Date::now() - last_activity.get_value()) / 60_000.0;
+            if idle_minutes >= INACTIVITY_TIMEOUT_MINUTES {
+                toasts.error("Session expired due to inactivity");
+                logout();
+            }
+        },
+        INACTIVITY_CHECK_INTERVAL,
+    )
+    .ok();
+    on_cleanup(move || {
+        if let Some(timer) = inactivity_timer {
+            timer.clear();
+        }
+        for listener in activity_listeners {
+            listener.remove();
+        }
+    });
+
+    let go_to_page = move |page: i64| {
+        let offset = (page - 1) * PAGE_SIZE;
+        let dir = if sort_desc.get_untracked() { "desc" } else { "asc" };
+        set_loading_page.set(true);
+
+        spawn_local(async move {
+            let result = list_expenses(
+                filter_start_date.get_untracked(),
+                filter_end_date.get_untracked(),
+                filter_categories.get_untracked(),
+                sort_by.get_untracked(),
+                dir,
+                PAGE_SIZE,
+                offset,
+            )
+            .await;
+
+            set_loading_page.set(false);
+
+            match result {
+                Ok(page) => {
+                    set_expenses.set(page.items);
+                    set_total_expenses.set(page.total);
+                    set_expense_page.set(page.page);
+                    set_total_pages.set(page.total_pages);
+                }
+                Err(e) => toasts.error(e),
+            }
+        });
+    };
+
+    // Clicking the active column flips the direction; a new column starts
+    // descending for date/amount and ascending for text
+    let handle_sort = move |column: &'static str| {
+        let descending = if sort_by.get_untracked() == column {
+            !sort_desc.get_untracked()
+        } else {
+            matches!(column, "date" | "amount")
+        };
+        set_sort_by.set(column);
+        set_sort_desc.set(descending);
+        sort_chosen.set_value(true);
+        save_sort(column, descending);
+        reload_data.update(|v| *v += 1);
+    };
+
+    // Only the category summary changes, so skip the full reload
+    let handle_summary_range = move |range: SummaryRange| {
+        if summary_range.get_untracked() == range {
+            return;
+        }
+        set_summary_range.set(range);
+
+        spawn_local(async move {
+            match load_category_summary(range, current_year).await {
+                Ok(summary) => set_category_summary.set(summary),
+                Err(e) => toasts.error(e),
+            }
+        });
+    };
+
+    let handle_export_monthly = move |_| {
+        spawn_local(async move {
+            match export_monthly_summary_csv().await {
+                Ok(csv) => {
+                    if let Err(e) = save_file("monthly-summary.csv", &csv, "text/csv") {
+                        toasts.error(e);
+                    }
+                }
+                Err(e) => toasts.error(e),
+            }
+        });
+    };
+
+    let handle_export_categories = move |_| {
+        spawn_local(async move {
+            match export_category_summary_csv().await {
+                Ok(csv) => {
+                    if let Err(e) = save_file("category-summary.csv", &csv, "text/csv") {
+                        toasts.error(e);
+                    }
+                }
+                Err(e) => toasts.error(e),
+            }
+        });
+    };
+
+    let handle_export_report = move |year: i32, month: u32| {
+        spawn_local(async move {
+            match export_monthly_report_pdf(year, month).await {
+                Ok(pdf) => {
+                    let filename = format!("expenses-{}-{:02}.pdf", year, month);
+                    if let Err(e) = save_bytes(&filename, &pdf, "application/pdf") {
+                        toasts.error(e);
+                    }
+                }
+                Err(e) => toasts.error(e),
+            }
+        });
+    };
 
     let handle_delete = move |id: Uuid| {
         spawn_local(async move {
             match delete_expense(id).await {
-                Ok(_) => reload_data.update(|v| *v += 1),
-                Err(e) => set_error.set(Some(e)),
+                Ok(_) => {
+                    // Each delete gets its own toast, so undoing one doesn't
+                    // bring back an expense deleted right after it
+                    let undo = move || {
+                        spawn_local(async move {
+                            match restore_expense(id).await {
+                                Ok(_) => {
+                                    toasts.success("Expense restored");
+                                    reload_data.update(|v| *v += 1);
+                                }
+                                Err(e) => toasts.error(e),
+                            }
+                        });
+                    };
+                    toasts.with_action(ToastKind::Success, "Expense moved to trash", "Undo", undo);
+                    reload_data.update(|v| *v += 1);
+                }
+                Err(e) => toasts.error(e),
             }
         });
     };
 
-    let total_this_month = move || {
-        let now = Local::now();
-        expenses
-            .get()
-            .iter()
-            .filter(|e| {
-                e.expense_date.year() == now.year() && e.expense_date.month() == now.month()
+    // Starting point for people whose spending barely changes month to month
+    let handle_copy_last_month = move |_| {
+        let this_month = Local::now().date_naive().with_day(1).expect("Valid date");
+        let last_month = this_month.pred_opt().expect("Valid date");
+        let confirmed = window()
+            .and_then(|w| {
+                w.confirm_with_message("Copy all of last month's expenses into this month?").ok()
+            })
+            .unwrap_or(false);
+        if !confirmed {
+            return;
+        }
+
+        spawn_local(async move {
+            let result = clone_month(CloneMonth {
+                from_month: last_month.format("%Y-%m").to_string(),
+                to_month: this_month.format("%Y-%m").to_string(),
             })
-            .map(|e| e.amount)
-            .sum::<f64>()
+            .await;
+
+            match result {
+                Ok(copied) if copied.is_empty() => toasts.error("Last month has no expenses to copy"),
+                Ok(copied) => {
+                    toasts.success(format!("Copied {} expenses", copied.len()));
+                    reload_data.update(|v| *v += 1);
+                }
+                Err(e) => toasts.error(e),
+            }
+        });
+    };
+
+    // Lets someone trying the app see a populated dashboard without typing
+    let (seeding, set_seeding) = create_signal(false);
+    let handle_seed_demo = move |_| {
+        set_seeding.set(true);
+        spawn_local(async move {
+            match seed_demo_data().await {
+                Ok(0) => toasts.error("Demo data can only be loaded into an account without expenses"),
+                Ok(created) => {
+                    toasts.success(format!("Loaded {} demo expenses", created));
+                    reload_data.update(|v| *v += 1);
+                }
+                Err(e) => toasts.error(e),
+            }
+            set_seeding.set(false);
+        });
+    };
+
+    // The expense list is paginated, so month totals come from the summaries
+    // rather than from whatever pages happen to be loaded
+    let total_this_month = move || projection.get().map(|p| p.spent_so_far).unwrap_or(0.0);
+
+    // Today and this week are summed from the loaded pages, which with the
+    // default newest-first sort are the most recent expenses
+    let total_today = move || {
+        let today = Local::now().date_naive();
+        expenses.with(|exps| {
+            exps.iter()
+                .filter(|e| e.expense_date == today)
+                .map(|e| e.amount)
+                .sum::<f64>()
+        })
+    };
+
+    let total_this_week = move || {
+        let today = Local::now().date_naive();
+        let week = start_of_week(today, &week_start.get());
+        expenses.with(|exps| {
+            exps.iter()
+                .filter(|e| start_of_week(e.expense_date, &week_start.get()) == week)
+                .map(|e| e.amount)
+                .sum::<f64>()
+        })
     };
 
     let expense_count_this_month = move || {
         let now = Local::now();
-        expenses
+        let month_name = now.format("%B").to_string();
+        monthly_summary
             .get()
             .iter()
-            .filter(|e| {
-                e.expense_date.year() == now.year() && e.expense_date.month() == now.month()
-            })
-            .count()
+            // Postgres pads `TO_CHAR(..., 'Month')` with spaces
+            .find(|m| m.year == now.year() && m.month.trim() == month_name)
+            .map(|m| m.expense_count)
+            .unwrap_or(0)
+    };
+
+    // None when no limit is set or spending is still under the warning threshold
+    let limit_banner = move || {
+        let limit = monthly_limit.get().filter(|l| *l > 0.0)?;
+        let spent = total_this_month();
+        let (class, message) = if spent > limit {
+            ("limit-banner limit-over", "You're over your monthly limit")
+        } else if spent >= limit * LIMIT_WARNING_RATIO {
+            ("limit-banner limit-warning", "You're approaching your monthly limit")
+        } else {
+            return None;
+        };
+
+        Some(view! {
+            <div class=class>
+                {message} ": " {format_money(spent, &currency.get())} " of " {format_money(limit, &currency.get())}
+                {format!(" ({:.0}%)", spent / limit * 100.0)}
+            </div>
+        })
     };
 
     view! {
         <div class="container">
             <div class="header">
                 <h1>"Expense Tracker"</h1>
-                <button on:click=handle_logout class="btn-secondary">
-                    "Logout"
-                </button>
+                <div class="header-actions">
+                    {[
+                        (Page::Compare, "Compare"),
+                        (Page::Trash, "Trash"),
+                        (Page::Settings, "Settings"),
+                    ].into_iter().map(|(target, label)| {
+                        view! {
+                            <button
+                                class="btn-secondary"
+                                on:click=move |_| set_page.update(|p| {
+                                    *p = if *p == target { Page::Overview } else { target }
+                                })
+                            >
+                                {move || if page.get() == target { "Back to Dashboard" } else { label }}
+                            </button>
+                        }
+                    }).collect::<Vec<_>>()}
+                    <button on:click=handle_logout class="btn-secondary">
+                        "Logout"
+                    </button>
+                </div>
             </div>
 
-            {move || error.get().map(|e| view! {
-                <div class="error">{e}</div>
-            })}
-
             {move || if loading.get() {
                 view! { <div class="loading">"Loading..."</div> }.into_view()
+            } else if page.get() == Page::Compare {
+                view! {
+                    <crate::components::compare::RangeCompare currency=currency />
+                }.into_view()
+            } else if page.get() == Page::Trash {
+                view! {
+                    <crate::components::trash::Trash
+                        currency=currency
+                        date_format=date_format
+                        on_restored=move || reload_data.update(|v| *v += 1)
+                    />
+                }.into_view()
+            } else if page.get() == Page::Settings {
+                view! {
+                    <div>
+                        <crate::components::profile::ProfileForm />
+
+                        <crate::components::settings::SettingsForm settings=settings />
+
+                        <crate::components::sessions::ActiveSessions />
+                    </div>
+                }.into_view()
             } else {
                 view! {
                     <div>
+                        {limit_banner}
+
                         <div class="summary-grid">
                             <div class="summary-card">
                                 <h3>"This Month"</h3>
-                                <div class="value">"$"{format!("{:.2}", total_this_month())}</div>
+                                <div class="value">{move || format_money(total_this_month(), &currency.get())}</div>
+                            </div>
+                            <div class="summary-card">
+                                <h3>"This Week"</h3>
+                                <div class="value">{move || format_money(total_this_week(), &currency.get())}</div>
+                            </div>
+                            <div class="summary-card">
+                                <h3>"Today"</h3>
+                                <div class="value">{move || format_money(total_today(), &currency.get())}</div>
                             </div>
                             <div class="summary-card">
                                 <h3>"Expenses Count"</h3>
-                                <div class="value">{expense_count_this_month()}</div>
+                                <div class="value">{move || expense_count_this_month()}</div>
+                            </div>
+                            <div class="summary-card">
+                                <h3>"Projected"</h3>
+                                <div class="value">
+                                    {move || format_money(projection.get().map(|p| p.projected_total).unwrap_or(0.0), &currency.get())}
+                                </div>
+                            </div>
+                            <div class="summary-card">
+                                <h3>"Streak"</h3>
+                                <div class="value">
+                                    {move || streaks.get().map(|s| days_label(s.current_streak_days)).unwrap_or_default()}
+                                </div>
+                                <div class="subvalue">
+                                    {move || streaks.get().map(|s| format!("Best this month: {}", days_label(s.longest_streak_days))).unwrap_or_default()}
+                                </div>
+                            </div>
+                            <div class="summary-card">
+                                <h3>"Biggest Day"</h3>
+                                <div class="value">
+                                    {move || day_extremes.get()
+                                        .and_then(|d| d.most_expensive)
+                                        .map(|day| format_money(day.total_amount, &currency.get()))
+                                        .unwrap_or_else(|| "-".to_string())}
+                                </div>
+                                <div class="subvalue">
+                                    {move || day_extremes.get().and_then(|d| {
+                                        let most = d.most_expensive?;
+                                        let cheapest = d.cheapest?;
+                                        Some(format!(
+                                            "{} · Lowest: {} on {}",
+                                            format_date(most.date, &date_format.get()),
+                                            format_money(cheapest.total_amount, &currency.get()),
+                                            format_date(cheapest.date, &date_format.get()),
+                                        ))
+                                    }).unwrap_or_else(|| "No expenses this month".to_string())}
+                                </div>
                             </div>
                             <div class="summary-card">
                                 <h3>"Categories"</h3>
@@ -130,41 +679,93 @@ where
                             </div>
                         </div>
 
+                        <crate::components::quick_add::QuickAdd
+                            on_created=move || reload_data.update(|v| *v += 1)
+                        />
+
+                        <crate::components::templates::ExpenseTemplates
+                            categories=categories
+                            on_created=move || reload_data.update(|v| *v += 1)
+                        />
+
                         <crate::components::expense_form::ExpenseForm
                             categories=categories
                             on_created=move || reload_data.update(|v| *v += 1)
                         />
 
+                        <crate::components::category_manager::CategoryManager
+                            categories=categories
+                            reorderable=Signal::derive(move || settings.with(|s| {
+                                s.as_ref().is_none_or(|s| s.category_sort == "manual")
+                            }))
+                            on_changed=move || reload_data.update(|v| *v += 1)
+                        />
+
                         <div class="card">
-                            <h2 style="margin-bottom: 20px; color: #333;">"Filters"</h2>
+                            <div class="card-header">
+                                <h2 style="color: #333;">"Filters"</h2>
+                                <button
+                                    class="btn-secondary"
+                                    disabled=move || filter_categories.with(|c| c.is_empty())
+                                        && filter_start_date.with(Option::is_none)
+                                        && filter_end_date.with(Option::is_none)
+                                    on:click=move |_| {
+                                        set_filter_categories.set(Vec::new());
+                                        set_filter_start_date.set(None);
+                                        set_filter_end_date.set(None);
+                                        clear_filters();
+                                        reload_data.update(|v| *v += 1);
+                                    }
+                                >
+                                    "Clear Filters"
+                                </button>
+                            </div>
                             <div class="filters">
                                 <div class="form-group">
-                                    <label>"Category"</label>
-                                    <select on:change=move |ev| {
-                                        let value = event_target_value(&ev);
-                                        if value.is_empty() {
-                                            set_filter_category.set(None);
-                                        } else if let Ok(uuid) = Uuid::parse_str(&value) {
-                                            set_filter_category.set(Some(uuid));
-                                        }
-                                        reload_data.update(|v| *v += 1);
-                                    }>
-                                        <option value="">"All Categories"</option>
+                                    <label>"Categories"</label>
+                                    <div class="quick-picks">
+                                        <button
+                                            type="button"
+                                            class="quick-pick"
+                                            class:active=move || filter_categories.with(|c| c.is_empty())
+                                            on:click=move |_| {
+                                                set_filter_categories.set(Vec::new());
+                                                reload_data.update(|v| *v += 1);
+                                            }
+                                        >
+                                            "All"
+                                        </button>
                                         {move || categories.get().into_iter().map(|cat| {
+                                            let id = cat.id;
                                             view! {
-                                                <option value={cat.id.to_string()}>
+                                                <button
+                                                    type="button"
+                                                    class="quick-pick"
+                                                    class:active=move || filter_categories.with(|c| c.contains(&id))
+                                                    on:click=move |_| {
+                                                        set_filter_categories.update(|c| {
+                                                            if let Some(pos) = c.iter().position(|x| *x == id) {
+                                                                c.remove(pos);
+                                                            } else {
+                                                                c.push(id);
+                                                            }
+                                                        });
+                                                        reload_data.update(|v| *v += 1);
+                                                    }
+                                                >
                                                     {cat.icon.as_ref().map(|i| format!("{} ", i)).unwrap_or_default()}
-                                                    {&cat.name}
-                                                </option>
+                                                    {cat.name}
+                                                </button>
                                             }
                                         }).collect::<Vec<_>>()}
-                                    </select>
+                                    </div>
                                 </div>
 
                                 <div class="form-group">
                                     <label>"Start Date"</label>
                                     <input
                                         type="date"
+                                        prop:value=move || filter_start_date.get().unwrap_or_default()
                                         on:change=move |ev| {
                                             let value = event_target_value(&ev);
                                             set_filter_start_date.set(if value.is_empty() { None } else { Some(value) });
@@ -177,6 +778,7 @@ where
                                     <label>"End Date"</label>
                                     <input
                                         type="date"
+                                        prop:value=move || filter_end_date.get().unwrap_or_default()
                                         on:change=move |ev| {
                                             let value = event_target_value(&ev);
                                             set_filter_end_date.set(if value.is_empty() { None } else { Some(value) });
@@ -188,57 +790,165 @@ where
                         </div>
 
                         <div class="card">
-                            <h2 style="margin-bottom: 20px; color: #333;">"Recent Expenses"</h2>
-                            <div class="expense-list">
-                                {move || {
-                                    let exps = expenses.get();
-                                    if exps.is_empty() {
-                                        view! {
-                                            <p style="text-align: center; color: #6c757d; padding: 20px;">
-                                                "No expenses found. Add your first expense above!"
-                                            </p>
-                                        }.into_view()
-                                    } else {
-                                        exps.into_iter().map(|expense| {
-                                            let color = expense.category_color.clone().unwrap_or_else(|| "#667eea".to_string());
-                                            let exp_id = expense.id;
-                                            view! {
-                                                <div class="expense-item" style:border-left-color=color>
-                                                    <div class="expense-icon">
-                                                        {expense.category_icon.unwrap_or_else(|| "📦".to_string())}
-                                                    </div>
-                                                    <div class="expense-details">
-                                                        <h3>{&expense.description}</h3>
-                                                        <p>{expense.category_name.clone()} " • " {expense.expense_date.format("%b %d, %Y").to_string()}</p>
-                                                    </div>
-                                                    <div class="expense-amount">
-                                                        "$"{format!("{:.2}", expense.amount)}
-                                                    </div>
-                                                    <div class="expense-actions">
-                                                        <button
-                                                            class="btn-danger"
-                                                            on:click=move |_| {
-                                                                if web_sys::window()
-                                                                    .and_then(|w| w.confirm_with_message("Delete this expense?").ok())
-                                                                    .unwrap_or(false)
-                                                                {
-                                                                    handle_delete(exp_id);
-                                                                }
-                                                            }
-                                                        >
-                                                            "Delete"
-                                                        </button>
-                                                    </div>
-                                                </div>
-                                            }
-                                        }).collect::<Vec<_>>().into_view()
-                                    }
-                                }}
+                            <div class="card-header">
+                                <h2 style="color: #333;">"Recent Expenses"</h2>
+                                <button class="btn-secondary" on:click=handle_copy_last_month>
+                                    "Copy Last Month"
+                                </button>
                             </div>
+                            {move || {
+                                let exps = expenses.get();
+                                let unfiltered = filter_categories.with(|c| c.is_empty())
+                                    && filter_start_date.with(Option::is_none)
+                                    && filter_end_date.with(Option::is_none);
+                                if exps.is_empty() {
+                                    view! {
+                                        <p style="text-align: center; color: #6c757d; padding: 20px;">
+                                            "No expenses found. Add your first expense above!"
+                                        </p>
+                                        {unfiltered.then(|| view! {
+                                            <div style="text-align: center; padding-bottom: 20px;">
+                                                <button
+                                                    class="btn-secondary"
+                                                    on:click=handle_seed_demo
+                                                    disabled=seeding
+                                                >
+                                                    {move || if seeding.get() { "Loading..." } else { "Load demo data" }}
+                                                </button>
+                                            </div>
+                                        })}
+                                    }.into_view()
+                                } else {
+                                    view! {
+                                        <table class="expense-table">
+                                            <thead>
+                                                <tr>
+                                                    {SORT_COLUMNS.iter().map(|&(column, label)| {
+                                                        view! {
+                                                            <th
+                                                                class="sortable"
+                                                                class:active=move || sort_by.get() == column
+                                                                on:click=move |_| handle_sort(column)
+                                                            >
+                                                                {label}
+                                                                {move || (sort_by.get() == column).then(|| {
+                                                                    if sort_desc.get() { " ▼" } else { " ▲" }
+                                                                })}
+                                                            </th>
+                                                        }
+                                                    }).collect::<Vec<_>>()}
+                                                    <th></th>
+                                                </tr>
+                                            </thead>
+                                            <tbody>
+                                                {exps.into_iter().map(|expense| {
+                                                    let color = expense.category_color.clone().unwrap_or_else(|| "#667eea".to_string());
+                                                    let exp_id = expense.id;
+                                                    view! {
+                                                        <tr>
+                                                            <td style:border-left-color=color class="expense-date">
+                                                                {format_date(expense.expense_date, &date_format.get())}
+                                                            </td>
+                                                            <td>
+                                                                {expense.category_icon.unwrap_or_else(|| "📦".to_string())}
+                                                                " "
+                                                                {expense.category_name.clone()}
+                                                            </td>
+                                                            <td class="expense-amount">
+                                                                {format_money(expense.amount, &currency.get())}
+                                                            </td>
+                                                            <td>{&expense.description}</td>
+                                                            <td class="expense-actions">
+                                                                <button
+                                                                    class="btn-danger"
+                                                                    on:click=move |_| {
+                                                                        if web_sys::window()
+                                                                            .and_then(|w| w.confirm_with_message("Delete this expense?").ok())
+                                                                            .unwrap_or(false)
+                                                                        {
+                                                                            handle_delete(exp_id);
+                                                                        }
+                                                                    }
+                                                                >
+                                                                    "Delete"
+                                                                </button>
+                                                            </td>
+                                                        </tr>
+                                                    }
+                                                }).collect::<Vec<_>>()}
+                                            </tbody>
+                                        </table>
+                                    }.into_view()
+                                }
+                            }}
+                            {move || (total_pages.get() > 1).then(|| view! {
+                                <div class="pagination">
+                                    <button
+                                        class="btn-secondary"
+                                        disabled=move || loading_page.get() || (expense_page.get() <= 1)
+                                        on:click=move |_| go_to_page(expense_page.get_untracked() - 1)
+                                    >
+                                        "Previous"
+                                    </button>
+                                    <span>
+                                        {move || if loading_page.get() {
+                                            view! { <span class="spinner"></span>"Loading..." }.into_view()
+                                        } else {
+                                            format!(
+                                                "Page {} of {} ({} expenses)",
+                                                expense_page.get(),
+                                                total_pages.get(),
+                                                total_expenses.get()
+                                            )
+                                            .into_view()
+                                        }}
+                                    </span>
+                                    <button
+                                        class="btn-secondary"
+                                        disabled=move || loading_page.get() || (expense_page.get() >= total_pages.get())
+                                        on:click=move |_| go_to_page(expense_page.get_untracked() + 1)
+                                    >
+                                        "Next"
+                                    </button>
+                                </div>
+                            })}
                         </div>
 
                         <div class="card">
-                            <h2 style="margin-bottom: 20px; color: #333;">"Category Summary (This Month)"</h2>
+                            <div class="card-header">
+                                <h2 style="color: #333;">"Category Summary"</h2>
+                                // The CSV export covers the current month only
+                                <button
+                                    class="btn-secondary"
+                                    style:visibility=move || if summary_range.get() == SummaryRange::Month { "visible" } else { "hidden" }
+                                    on:click=handle_export_categories
+                                >
+                                    "Export CSV"
+                                </button>
+                            </div>
+                            <div class="tab-buttons">
+                                <button
+                                    class="tab-button"
+                                    class:active=move || summary_range.get() == SummaryRange::Month
+                                    on:click=move |_| handle_summary_range(SummaryRange::Month)
+                                >
+                                    "This Month"
+                                </button>
+                                <button
+                                    class="tab-button"
+                                    class:active=move || summary_range.get() == SummaryRange::Year
+                                    on:click=move |_| handle_summary_range(SummaryRange::Year)
+                                >
+                                    "This Year"
+                                </button>
+                                <button
+                                    class="tab-button"
+                                    class:active=move || summary_range.get() == SummaryRange::AllTime
+                                    on:click=move |_| handle_summary_range(SummaryRange::AllTime)
+                                >
+                                    "All Time"
+                                </button>
+                            </div>
                             <div class="expense-list">
                                 {move || category_summary.get().into_iter().map(|summary| {
                                     let color = summary.category_color.clone().unwrap_or_else(|| "#667eea".to_string());
@@ -248,11 +958,19 @@ where
                                                 {summary.category_icon.unwrap_or_else(|| "📦".to_string())}
                                             </div>
                                             <div class="expense-details">
-                                                <h3>{&summary.category_name}</h3>
-                                                <p>{summary.expense_count} " expenses"</p>
+                                                <h3>
+                                                    {&summary.category_name}
+                                                    {summary.over_budget.then(|| view! {
+                                                        <span class="badge badge-warning">"Over budget"</span>
+                                                    })}
+                                                </h3>
+                                                <p>
+                                                    {summary.expense_count} " expenses"
+                                                    {summary.budget_amount.map(|b| format!(" • Budget {}", format_money(b, &currency.get())))}
+                                                </p>
                                             </div>
                                             <div class="expense-amount">
-                                                "$"{format!("{:.2}", summary.total_amount)}
+                                                {format_money(summary.total_amount, &currency.get())}
                                             </div>
                                             <div></div>
                                         </div>
@@ -260,6 +978,76 @@ where
                                 }).collect::<Vec<_>>()}
                             </div>
                         </div>
+
+                        <div class="card">
+                            <div class="card-header">
+                                <h2 style="color: #333;">"Monthly Totals"</h2>
+                                <div>
+                                    <button
+                                        class="btn-secondary"
+                                        on:click=move |_| {
+                                            let today = Local::now().date_naive();
+                                            handle_export_report(today.year(), today.month());
+                                        }
+                                    >
+                                        "Download PDF"
+                                    </button>
+                                    <button class="btn-secondary" on:click=handle_export_monthly>
+                                        "Export CSV"
+                                    </button>
+                                </div>
+                            </div>
+                            <div class="expense-list">
+                                {move || monthly_summary.get().into_iter().map(|summary| {
+                                    // The summary names its month, e.g. "March"
+                                    let month = summary.month.trim().parse::<Month>().ok();
+                                    let year = summary.year;
+                                    view! {
+                                        <div class="expense-item">
+                                            <div></div>
+                                            <div class="expense-details">
+                                                <h3>{summary.month.trim().to_string()} " " {summary.year}</h3>
+                                                <p>{summary.expense_count} " expenses"</p>
+                                            </div>
+                                            <div class="expense-amount">
+                                                {format_money(summary.total_amount, &currency.get())}
+                                            </div>
+                                            <div class="expense-actions">
+                                                {month.map(|month| view! {
+                                                    <button
+                                                        class="btn-secondary"
+                                                        on:click=move |_| handle_export_report(year, month.number_from_month())
+                                                    >
+                                                        "PDF"
+                                                    </button>
+                                                })}
+                                            </div>
+                                        </div>
+                                    }
+                                }).collect::<Vec<_>>()}
+                            </div>
+                        </div>
+
+                        <crate::components::yearly_comparison::YearlyComparisonChart
+                            year=current_year
+                            comparison=yearly_comparison
+                        />
+
+                        <crate::components::heatmap::SpendingHeatmap
+                            year=current_year
+                            currency=currency
+                            reload=reload_data
+                        />
+
+                        <crate::components::spending_timeseries::SpendingTimeseriesChart
+                            reload=reload_data
+                        />
+
+                        <crate::components::budget_burndown::BudgetBurndownChart
+                            categories=categories
+                            reload=reload_data
+                        />
+
                     </div>
                 }.into_view()
             }}
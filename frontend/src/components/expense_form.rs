@@ -1,9 +1,35 @@
 use chrono::Local;
 use leptos::*;
+use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
-use crate::api::create_expense;
+use crate::api::{create_expense, list_descriptions, list_recent_categories, suggest_categories};
+use crate::components::toast::use_toasts;
+use crate::format::{parse_amount, MIN_EXPENSE_DATE};
 use crate::models::{Category, CreateExpense};
+use crate::storage;
+
+/// Number of quick-pick buttons shown above the category dropdown.
+const RECENT_CATEGORIES: u32 = 5;
+/// Number of past descriptions offered while typing.
+const DESCRIPTION_SUGGESTIONS: u32 = 10;
+/// `localStorage` key of the half-filled form.
+const DRAFT_KEY: &str = "expense_draft";
+
+/// Form input saved while typing, so a refresh or accidental navigation
+/// doesn't lose it.
+#[derive(Serialize, Deserialize)]
+struct ExpenseDraft {
+    category_id: Option<Uuid>,
+    amount: String,
+    description: String,
+    expense_date: String,
+}
+
+/// Forgets any saved draft, e.g. on logout so the next user doesn't see it.
+pub fn clear_draft() {
+    storage::remove(DRAFT_KEY);
+}
 
 #[component]
 pub fn ExpenseForm<F>(
@@ -13,35 +39,135 @@ pub fn ExpenseForm<F>(
 where
     F: Fn() + Copy + 'static,
 {
-    let (category_id, set_category_id) = create_signal(None::<Uuid>);
-    let (amount, set_amount) = create_signal(String::new());
-    let (description, set_description) = create_signal(String::new());
+    let draft = storage::load::<ExpenseDraft>(DRAFT_KEY);
+    let (category_id, set_category_id) = create_signal(draft.as_ref().and_then(|d| d.category_id));
+    let (amount, set_amount) = create_signal(
+        draft.as_ref().map(|d| d.amount.clone()).unwrap_or_default()
+    );
+    let (description, set_description) = create_signal(
+        draft.as_ref().map(|d| d.description.clone()).unwrap_or_default()
+    );
     let (expense_date, set_expense_date) = create_signal(
-        Local::now().format("%Y-%m-%d").to_string()
+        draft
+            .map(|d| d.expense_date)
+            .unwrap_or_else(|| Local::now().format("%Y-%m-%d").to_string())
     );
-    let (error, set_error) = create_signal(None::<String>);
     let (loading, set_loading) = create_signal(false);
+    // Reused until the expense is created, so a double-click or a retry after a
+    // network error can't insert the same expense twice.
+    let idempotency_key = store_value(Uuid::new_v4());
+    let (recent, set_recent) = create_signal(Vec::<Category>::new());
+    let (search, set_search) = create_signal(String::new());
+    let (suggestions, set_suggestions) = create_signal(Vec::<Category>::new());
+    let (past_descriptions, set_past_descriptions) = create_signal(Vec::<String>::new());
+    let toasts = use_toasts();
+
+    // The date always has a value, so it alone doesn't count as unsaved input
+    let has_unsaved = create_memo(move |_| {
+        category_id.get().is_some()
+            || !amount.get().trim().is_empty()
+            || !description.get().trim().is_empty()
+    });
+
+    // Also clears the draft once a successful submit has emptied the form
+    create_effect(move |_| {
+        if has_unsaved.get() {
+            storage::save(DRAFT_KEY, &ExpenseDraft {
+                category_id: category_id.get(),
+                amount: amount.get(),
+                description: description.get(),
+                expense_date: expense_date.get(),
+            });
+        } else {
+            storage::remove(DRAFT_KEY);
+        }
+    });
+
+    // Browsers show their own generic prompt; the message is ignored by most
+    let unload_guard = window_event_listener(ev::beforeunload, move |ev| {
+        if has_unsaved.get_untracked() {
+            ev.prevent_default();
+            ev.set_return_value("You have an unsaved expense.");
+        }
+    });
+    on_cleanup(move || unload_guard.remove());
+
+    // Quick picks are a shortcut; the dropdown still works without them
+    let load_recent = move || {
+        spawn_local(async move {
+            if let Ok(cats) = list_recent_categories(RECENT_CATEGORIES).await {
+                set_recent.set(cats);
+            }
+        });
+    };
+    load_recent();
+
+    let handle_search = move |ev: leptos::ev::Event| {
+        let q = event_target_value(&ev);
+        set_search.set(q.clone());
+        if q.trim().is_empty() {
+            set_suggestions.set(Vec::new());
+            return;
+        }
+
+        spawn_local(async move {
+            if let Ok(cats) = suggest_categories(q.trim()).await {
+                // Drop responses for text the user has already typed past
+                if search.get_untracked() == q {
+                    set_suggestions.set(cats);
+                }
+            }
+        });
+    };
+
+    let handle_description = move |ev: leptos::ev::Event| {
+        let q = event_target_value(&ev);
+        set_description.set(q.clone());
+        if q.trim().is_empty() {
+            set_past_descriptions.set(Vec::new());
+            return;
+        }
+
+        spawn_local(async move {
+            if let Ok(found) = list_descriptions(q.trim(), DESCRIPTION_SUGGESTIONS).await {
+                if description.get_untracked() == q {
+                    set_past_descriptions.set(found);
+                }
+            }
+        });
+    };
+
+    // Shown under the field while typing; nothing until the user starts
+    let amount_error = move || {
+        let value = amount.get();
+        if value.is_empty() { None } else { parse_amount(&value).err() }
+    };
+
+    let pick_suggestion = move |id: Uuid| {
+        set_category_id.set(Some(id));
+        set_search.set(String::new());
+        set_suggestions.set(Vec::new());
+    };
 
     let handle_submit = move |ev: leptos::ev::SubmitEvent| {
         ev.prevent_default();
-        set_error.set(None);
 
         let Some(cat_id) = category_id.get() else {
-            set_error.set(Some("Please select a category".to_string()));
+            toasts.error("Please select a category");
             return;
         };
 
-        let amount_val = match amount.get().parse::<f64>() {
-            Ok(v) if v > 0.0 => v,
-            _ => {
-                set_error.set(Some("Please enter a valid amount".to_string()));
+        let amount_val = match parse_amount(&amount.get()) {
+            Ok(v) => v,
+            Err(e) => {
+                toasts.error(e);
                 return;
             }
         };
 
         let desc = description.get();
         if desc.trim().is_empty() {
-            set_error.set(Some("Please enter a description".to_string()));
+            toasts.error("Please enter a description");
             return;
         }
 
@@ -49,33 +175,44 @@ where
         let date = match chrono::NaiveDate::parse_from_str(&date_str, "%Y-%m-%d") {
             Ok(d) => d,
             Err(_) => {
-                set_error.set(Some("Invalid date format".to_string()));
+                toasts.error("Invalid date format");
                 return;
             }
         };
 
         set_loading.set(true);
 
+        let key = idempotency_key.get_value();
         spawn_local(async move {
-            let result = create_expense(CreateExpense {
-                category_id: cat_id,
-                amount: amount_val,
-                description: desc.clone(),
-                expense_date: date,
-            })
+            let result = create_expense(
+                CreateExpense {
+                    category_id: cat_id,
+                    amount: amount_val,
+                    description: desc.clone(),
+                    expense_date: date,
+                },
+                key,
+            )
             .await;
 
             set_loading.set(false);
 
             match result {
-                Ok(_) => {
+                Ok(created) => {
                     set_amount.set(String::new());
                     set_description.set(String::new());
                     set_category_id.set(None);
+                    idempotency_key.set_value(Uuid::new_v4());
+                    load_recent();
+                    toasts.success("Expense added");
+                    // Saved anyway; just a nudge to double-check, e.g. for an extra zero
+                    for warning in created.warnings {
+                        toasts.info(warning);
+                    }
                     on_created();
                 }
                 Err(e) => {
-                    set_error.set(Some(e));
+                    toasts.error(e);
                 }
             }
         });
@@ -87,15 +224,60 @@ where
                 "Add New Expense"
             </h2>
 
-            {move || error.get().map(|e| view! {
-                <div class="error">{e}</div>
-            })}
-
             <form on:submit=handle_submit>
                 <div class="form-group">
                     <label>"Category"</label>
+                    {move || {
+                        let cats = recent.get();
+                        (!cats.is_empty()).then(|| view! {
+                            <div class="quick-picks">
+                                {cats.into_iter().map(|cat| {
+                                    let id = cat.id;
+                                    view! {
+                                        <button
+                                            type="button"
+                                            class="quick-pick"
+                                            class:active=move || category_id.get() == Some(id)
+                                            on:click=move |_| set_category_id.set(Some(id))
+                                        >
+                                            {cat.icon.as_ref().map(|i| format!("{} ", i)).unwrap_or_default()}
+                                            {&cat.name}
+                                        </button>
+                                    }
+                                }).collect::<Vec<_>>()}
+                            </div>
+                        })
+                    }}
+                    <input
+                        type="search"
+                        class="category-search"
+                        prop:value=search
+                        on:input=handle_search
+                        placeholder="Search categories..."
+                    />
+                    {move || {
+                        let cats = suggestions.get();
+                        (!cats.is_empty()).then(|| view! {
+                            <div class="quick-picks">
+                                {cats.into_iter().map(|cat| {
+                                    let id = cat.id;
+                                    view! {
+                                        <button
+                                            type="button"
+                                            class="quick-pick"
+                                            on:click=move |_| pick_suggestion(id)
+                                        >
+                                            {cat.icon.as_ref().map(|i| format!("{} ", i)).unwrap_or_default()}
+                                            {&cat.name}
+                                        </button>
+                                    }
+                                }).collect::<Vec<_>>()}
+                            </div>
+                        })
+                    }}
                     <select
                         required
+                        prop:value=move || category_id.get().map(|id| id.to_string()).unwrap_or_default()
                         on:change=move |ev| {
                             let value = event_target_value(&ev);
                             if let Ok(uuid) = Uuid::parse_str(&value) {
@@ -104,7 +286,7 @@ where
                         }
                     >
                         <option value="">"Select a category"</option>
-                        {move || categories.get().into_iter().map(|cat| {
+                        {move || categories.get().into_iter().filter(|cat| !cat.archived).map(|cat| {
                             view! {
                                 <option value={cat.id.to_string()}>
                                     {cat.icon.as_ref().map(|i| format!("{} ", i)).unwrap_or_default()}
@@ -117,15 +299,23 @@ where
 
                 <div class="form-group">
                     <label>"Amount ($)"</label>
+                    // Plain text so typos like "1e5" reach our own validation
+                    // instead of the browser silently clearing the value
                     <input
-                        type="number"
-                        step="0.01"
-                        min="0.01"
+                        type="text"
+                        inputmode="decimal"
                         required
                         prop:value=amount
+                        class:invalid=move || amount_error().is_some()
                         on:input=move |ev| set_amount.set(event_target_value(&ev))
+                        on:blur=move |_| {
+                            if let Ok(v) = parse_amount(&amount.get_untracked()) {
+                                set_amount.set(format!("{:.2}", v));
+                            }
+                        }
                         placeholder="0.00"
                     />
+                    {move || amount_error().map(|e| view! { <p class="field-error">{e}</p> })}
                 </div>
 
                 <div class="form-group">
@@ -133,10 +323,17 @@ where
                     <input
                         type="text"
                         required
+                        maxlength="500"
+                        list="past-descriptions"
                         prop:value=description
-                        on:input=move |ev| set_description.set(event_target_value(&ev))
+                        on:input=handle_description
                         placeholder="What did you spend on?"
                     />
+                    <datalist id="past-descriptions">
+                        {move || past_descriptions.get().into_iter().map(|d| {
+                            view! { <option value=d></option> }
+                        }).collect::<Vec<_>>()}
+                    </datalist>
                 </div>
 
                 <div class="form-group">
@@ -144,6 +341,7 @@ where
                     <input
                         type="date"
                         required
+                        min=MIN_EXPENSE_DATE
                         prop:value=expense_date
                         on:input=move |ev| set_expense_date.set(event_target_value(&ev))
                     />
@@ -0,0 +1,74 @@
+use chrono::{Datelike, NaiveDate};
+use leptos::*;
+
+use crate::api::get_spending_heatmap;
+use crate::components::toast::use_toasts;
+use crate::format::format_money;
+use crate::models::DayTotal;
+
+/// Number of colored shades; days without spending use the blank level 0.
+const LEVELS: u32 = 4;
+
+/// Shade of a day relative to the most expensive day of the year.
+fn intensity(amount: f64, max: f64) -> u32 {
+    if amount <= 0.0 || max <= 0.0 {
+        return 0;
+    }
+    ((amount / max * LEVELS as f64).ceil() as u32).clamp(1, LEVELS)
+}
+
+/// GitHub-style calendar of daily spending: one column per week (Monday on
+/// top), darker cells for more expensive days.
+#[component]
+pub fn SpendingHeatmap(
+    year: i32,
+    currency: Memo<String>,
+    reload: RwSignal<i32>,
+) -> impl IntoView {
+    let (days, set_days) = create_signal(Vec::<DayTotal>::new());
+    let toasts = use_toasts();
+
+    create_effect(move |_| {
+        reload.get();
+        spawn_local(async move {
+            match get_spending_heatmap(year, true).await {
+                Ok(loaded) => set_days.set(loaded),
+                Err(e) => toasts.error(e),
+            }
+        });
+    });
+
+    // Blank cells so January 1st lands on its weekday row
+    let leading_blanks = NaiveDate::from_ymd_opt(year, 1, 1)
+        .map(|first| first.weekday().num_days_from_monday())
+        .unwrap_or(0);
+
+    view! {
+        <div class="card">
+            <div class="card-header">
+                <h2 style="color: #333;">{format!("Daily Spending in {}", year)}</h2>
+            </div>
+            <div class="heatmap">
+                {(0..leading_blanks).map(|_| view! {
+                    <span class="heatmap-cell heatmap-blank"></span>
+                }).collect::<Vec<_>>()}
+                {move || {
+                    let data = days.get();
+                    let max = data.iter().map(|d| d.total_amount).fold(0.0, f64::max);
+                    data.into_iter().map(|day| {
+                        let class = format!(
+                            "heatmap-cell heatmap-level-{}",
+                            intensity(day.total_amount, max)
+                        );
+                        let title = format!(
+                            "{}: {}",
+                            day.date.format("%a %b %-d"),
+                            format_money(day.total_amount, &currency.get())
+                        );
+                        view! { <span class=class title=title></span> }
+                    }).collect::<Vec<_>>()
+                }}
+            </div>
+        </div>
+    }
+}
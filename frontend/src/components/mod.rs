@@ -1,3 +1,16 @@
 pub mod auth;
+pub mod budget_burndown;
+pub mod category_manager;
+pub mod compare;
 pub mod dashboard;
 pub mod expense_form;
+pub mod heatmap;
+pub mod profile;
+pub mod quick_add;
+pub mod sessions;
+pub mod settings;
+pub mod spending_timeseries;
+pub mod templates;
+pub mod toast;
+pub mod trash;
+pub mod yearly_comparison;
@@ -0,0 +1,132 @@
+use leptos::*;
+
+use crate::api::{get_current_user, update_profile};
+use crate::components::toast::use_toasts;
+use crate::models::UpdateUser;
+
+#[component]
+pub fn ProfileForm() -> impl IntoView {
+    let (full_name, set_full_name) = create_signal(String::new());
+    let (email, set_email) = create_signal(String::new());
+    // The address last loaded or saved, to tell when the email is being changed
+    let (saved_email, set_saved_email) = create_signal(String::new());
+    let (current_password, set_current_password) = create_signal(String::new());
+    let (loading, set_loading) = create_signal(false);
+    let toasts = use_toasts();
+
+    spawn_local(async move {
+        match get_current_user().await {
+            Ok(user) => {
+                set_full_name.set(user.full_name);
+                set_saved_email.set(user.email.clone());
+                set_email.set(user.email);
+            }
+            Err(e) => toasts.error(e),
+        }
+    });
+
+    // The server compares trimmed, lowercased addresses too
+    let email_changed =
+        move || email.get().trim().to_lowercase() != saved_email.get().to_lowercase();
+
+    let handle_submit = move |ev: leptos::ev::SubmitEvent| {
+        ev.prevent_default();
+
+        let name_val = full_name.get();
+        if name_val.trim().is_empty() {
+            toasts.error("Please enter your name");
+            return;
+        }
+
+        let email_val = email.get();
+        if email_val.trim().is_empty() {
+            toasts.error("Please enter your email");
+            return;
+        }
+
+        let password_val = if email_changed() {
+            let password = current_password.get();
+            if password.is_empty() {
+                toasts.error("Please enter your current password to change your email");
+                return;
+            }
+            Some(password)
+        } else {
+            None
+        };
+
+        set_loading.set(true);
+
+        spawn_local(async move {
+            let result = update_profile(UpdateUser {
+                full_name: Some(name_val.trim().to_string()),
+                email: Some(email_val.trim().to_string()),
+                current_password: password_val,
+            })
+            .await;
+
+            set_loading.set(false);
+
+            match result {
+                Ok(user) => {
+                    set_full_name.set(user.full_name);
+                    set_saved_email.set(user.email.clone());
+                    set_email.set(user.email);
+                    set_current_password.set(String::new());
+                    toasts.success("Profile updated");
+                }
+                Err(e) => {
+                    toasts.error(e);
+                }
+            }
+        });
+    };
+
+    view! {
+        <div class="card">
+            <h2 style="margin-bottom: 20px; color: #333;">
+                "Profile"
+            </h2>
+
+            <form on:submit=handle_submit class="filters">
+                <div class="form-group">
+                    <label>"Full Name"</label>
+                    <input
+                        type="text"
+                        required
+                        prop:value=full_name
+                        on:input=move |ev| set_full_name.set(event_target_value(&ev))
+                    />
+                </div>
+
+                <div class="form-group">
+                    <label>"Email"</label>
+                    <input
+                        type="email"
+                        required
+                        prop:value=email
+                        on:input=move |ev| set_email.set(event_target_value(&ev))
+                    />
+                </div>
+
+                <Show when=email_changed>
+                    <div class="form-group">
+                        <label>"Current Password"</label>
+                        <input
+                            type="password"
+                            required
+                            autocomplete="current-password"
+                            prop:value=current_password
+                            on:input=move |ev| set_current_password.set(event_target_value(&ev))
+                            placeholder="Needed to change your email"
+                        />
+                    </div>
+                </Show>
+
+                <button type="submit" disabled=loading>
+                    {move || if loading.get() { "Saving..." } else { "Save Profile" }}
+                </button>
+            </form>
+        </div>
+    }
+}
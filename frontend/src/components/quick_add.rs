@@ -0,0 +1,60 @@
+use leptos::*;
+
+use crate::api::create_quick_expense;
+use crate::components::toast::use_toasts;
+
+/// One-line expense entry such as `12.50 lunch #food 2024-01-15`; the backend
+/// parses the text and reports which part it couldn't read.
+#[component]
+pub fn QuickAdd<F>(on_created: F) -> impl IntoView
+where
+    F: Fn() + Copy + 'static,
+{
+    let (text, set_text) = create_signal(String::new());
+    let (loading, set_loading) = create_signal(false);
+    let toasts = use_toasts();
+
+    let handle_submit = move |ev: leptos::ev::SubmitEvent| {
+        ev.prevent_default();
+
+        let line = text.get().trim().to_string();
+        if line.is_empty() {
+            return;
+        }
+
+        set_loading.set(true);
+        spawn_local(async move {
+            match create_quick_expense(line).await {
+                Ok(expense) => {
+                    set_text.set(String::new());
+                    toasts.success(format!(
+                        "Added \"{}\" to {}",
+                        expense.description, expense.category_name
+                    ));
+                    on_created();
+                }
+                // Keep the text so the reported token can be fixed in place
+                Err(e) => toasts.error(e),
+            }
+            set_loading.set(false);
+        });
+    };
+
+    view! {
+        <div class="card">
+            <h2 style="margin-bottom: 20px; color: #333;">"Quick Add"</h2>
+            <form class="quick-add" on:submit=handle_submit>
+                <input
+                    type="text"
+                    prop:value=text
+                    on:input=move |ev| set_text.set(event_target_value(&ev))
+                    placeholder="12.50 lunch #food 2024-01-15"
+                    maxlength="600"
+                />
+                <button type="submit" disabled=loading>
+                    {move || if loading.get() { "Adding..." } else { "Add" }}
+                </button>
+            </form>
+        </div>
+    }
+}
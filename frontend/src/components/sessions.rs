@@ -0,0 +1,96 @@
+use leptos::*;
+use uuid::Uuid;
+
+use crate::api::{list_sessions, revoke_other_sessions, revoke_session};
+use crate::components::toast::use_toasts;
+use crate::models::Session;
+
+/// Lists the devices the user is logged in on and lets them revoke any of
+/// them (or every device except this one).
+#[component]
+pub fn ActiveSessions() -> impl IntoView {
+    let (sessions, set_sessions) = create_signal(Vec::<Session>::new());
+    let reload = create_rw_signal(0);
+    let toasts = use_toasts();
+
+    create_effect(move |_| {
+        reload.get();
+        spawn_local(async move {
+            match list_sessions().await {
+                Ok(list) => set_sessions.set(list),
+                Err(e) => toasts.error(e),
+            }
+        });
+    });
+
+    let handle_revoke = move |id: Uuid| {
+        spawn_local(async move {
+            match revoke_session(id).await {
+                Ok(_) => {
+                    toasts.success("Session revoked");
+                    reload.update(|v| *v += 1);
+                }
+                Err(e) => toasts.error(e),
+            }
+        });
+    };
+
+    let handle_revoke_others = move |_| {
+        spawn_local(async move {
+            match revoke_other_sessions().await {
+                Ok(_) => {
+                    toasts.success("Logged out of all other devices");
+                    reload.update(|v| *v += 1);
+                }
+                Err(e) => toasts.error(e),
+            }
+        });
+    };
+
+    view! {
+        <div class="card">
+            <div class="card-header">
+                <h2 style="color: #333;">"Active Sessions"</h2>
+                <button
+                    class="btn-secondary"
+                    disabled=move || sessions.get().iter().all(|s| s.current)
+                    on:click=handle_revoke_others
+                >
+                    "Log out other devices"
+                </button>
+            </div>
+            <div class="expense-list">
+                {move || sessions.get().into_iter().map(|session| {
+                    let id = session.id;
+                    view! {
+                        <div class="expense-item">
+                            <div class="expense-icon">"💻"</div>
+                            <div class="expense-details">
+                                <h3>
+                                    {session.user_agent.unwrap_or_else(|| "Unknown device".to_string())}
+                                </h3>
+                                <p>
+                                    {session.ip_address.unwrap_or_default()}
+                                    " • Last active "
+                                    {session.last_used_at.format("%b %d, %Y %H:%M").to_string()}
+                                </p>
+                            </div>
+                            <div></div>
+                            <div class="expense-actions">
+                                {if session.current {
+                                    view! { <span class="session-current">"This device"</span> }.into_view()
+                                } else {
+                                    view! {
+                                        <button class="btn-danger" on:click=move |_| handle_revoke(id)>
+                                            "Revoke"
+                                        </button>
+                                    }.into_view()
+                                }}
+                            </div>
+                        </div>
+                    }
+                }).collect::<Vec<_>>()}
+            </div>
+        </div>
+    }
+}
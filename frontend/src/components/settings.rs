@@ -0,0 +1,268 @@
+use leptos::*;
+
+use crate::api::update_settings;
+use crate::components::toast::use_toasts;
+use crate::components::dashboard::SORT_COLUMNS;
+use crate::format::{
+    CATEGORY_SORTS, CURRENCIES, DATE_FORMATS, DEFAULT_CATEGORY_SORT, DEFAULT_CURRENCY,
+    DEFAULT_DATE_FORMAT, DEFAULT_EXPENSE_SORT_BY, DEFAULT_EXPENSE_SORT_DIR, DEFAULT_WEEK_START,
+    SORT_DIRECTIONS, WEEK_STARTS,
+};
+use crate::models::{UpdateUserSettings, UserSettings};
+
+/// Edits the server-side preferences. `settings` is owned by the dashboard,
+/// which loads it on startup; a successful save replaces it so amounts, dates
+/// and the limit banner follow immediately.
+#[component]
+pub fn SettingsForm(settings: RwSignal<Option<UserSettings>>) -> impl IntoView {
+    let initial = settings.get_untracked();
+    let (timezone, set_timezone) = create_signal(
+        initial.as_ref().and_then(|s| s.timezone.clone()).unwrap_or_default()
+    );
+    let (currency, set_currency) = create_signal(
+        initial
+            .as_ref()
+            .map(|s| s.currency.clone())
+            .unwrap_or_else(|| DEFAULT_CURRENCY.to_string())
+    );
+    let (limit, set_limit) = create_signal(
+        initial
+            .as_ref()
+            .and_then(|s| s.monthly_limit)
+            .map(|l| l.to_string())
+            .unwrap_or_default()
+    );
+    let (date_format, set_date_format) = create_signal(
+        initial
+            .as_ref()
+            .map(|s| s.date_format.clone())
+            .unwrap_or_else(|| DEFAULT_DATE_FORMAT.to_string())
+    );
+    let (week_start, set_week_start) = create_signal(
+        initial
+            .as_ref()
+            .map(|s| s.week_start.clone())
+            .unwrap_or_else(|| DEFAULT_WEEK_START.to_string())
+    );
+    let (expense_sort_by, set_expense_sort_by) = create_signal(
+        initial
+            .as_ref()
+            .map(|s| s.expense_sort_by.clone())
+            .unwrap_or_else(|| DEFAULT_EXPENSE_SORT_BY.to_string())
+    );
+    let (expense_sort_dir, set_expense_sort_dir) = create_signal(
+        initial
+            .as_ref()
+            .map(|s| s.expense_sort_dir.clone())
+            .unwrap_or_else(|| DEFAULT_EXPENSE_SORT_DIR.to_string())
+    );
+    let (category_sort, set_category_sort) = create_signal(
+        initial
+            .as_ref()
+            .map(|s| s.category_sort.clone())
+            .unwrap_or_else(|| DEFAULT_CATEGORY_SORT.to_string())
+    );
+    let (webhook_url, set_webhook_url) = create_signal(
+        initial.as_ref().and_then(|s| s.webhook_url.clone()).unwrap_or_default()
+    );
+    let (loading, set_loading) = create_signal(false);
+    let toasts = use_toasts();
+
+    let handle_submit = move |ev: leptos::ev::SubmitEvent| {
+        ev.prevent_default();
+
+        // An empty field means no limit, which the backend expects as 0
+        let limit_val = limit.get();
+        let limit_val = if limit_val.trim().is_empty() {
+            0.0
+        } else {
+            match limit_val.trim().parse::<f64>() {
+                Ok(v) if v >= 0.0 => v,
+                _ => {
+                    toasts.error("Please enter a valid monthly limit");
+                    return;
+                }
+            }
+        };
+
+        let req = UpdateUserSettings {
+            timezone: Some(timezone.get().trim().to_string()),
+            currency: Some(currency.get()),
+            monthly_limit: Some(limit_val),
+            date_format: Some(date_format.get()),
+            week_start: Some(week_start.get()),
+            expense_sort_by: Some(expense_sort_by.get()),
+            expense_sort_dir: Some(expense_sort_dir.get()),
+            category_sort: Some(category_sort.get()),
+            webhook_url: Some(webhook_url.get().trim().to_string()),
+        };
+
+        set_loading.set(true);
+
+        spawn_local(async move {
+            let result = update_settings(req).await;
+
+            set_loading.set(false);
+
+            match result {
+                Ok(saved) => {
+                    set_timezone.set(saved.timezone.clone().unwrap_or_default());
+                    set_limit.set(saved.monthly_limit.map(|l| l.to_string()).unwrap_or_default());
+                    set_webhook_url.set(saved.webhook_url.clone().unwrap_or_default());
+                    settings.set(Some(saved));
+                    toasts.success("Settings saved");
+                }
+                Err(e) => {
+                    toasts.error(e);
+                }
+            }
+        });
+    };
+
+    view! {
+        <div class="card">
+            <h2 style="margin-bottom: 20px; color: #333;">
+                "Settings"
+            </h2>
+
+            <form on:submit=handle_submit class="filters">
+                <div class="form-group">
+                    <label>"Timezone"</label>
+                    <div class="input-with-button">
+                        <input
+                            type="text"
+                            prop:value=timezone
+                            on:input=move |ev| set_timezone.set(event_target_value(&ev))
+                            placeholder="UTC"
+                        />
+                        {browser_timezone().map(|tz| view! {
+                            <button
+                                type="button"
+                                class="btn-secondary"
+                                title="Use this browser's timezone"
+                                on:click=move |_| set_timezone.set(tz.clone())
+                            >
+                                "Detect"
+                            </button>
+                        })}
+                    </div>
+                </div>
+
+                <div class="form-group">
+                    <label>"Currency"</label>
+                    <select
+                        prop:value=currency
+                        on:change=move |ev| set_currency.set(event_target_value(&ev))
+                    >
+                        {CURRENCIES.iter().map(|code| {
+                            view! { <option value=*code>{*code}</option> }
+                        }).collect::<Vec<_>>()}
+                    </select>
+                </div>
+
+                <div class="form-group">
+                    <label>"Monthly Limit"</label>
+                    <input
+                        type="number"
+                        step="0.01"
+                        min="0"
+                        prop:value=limit
+                        on:input=move |ev| set_limit.set(event_target_value(&ev))
+                        placeholder="No limit"
+                    />
+                </div>
+
+                <div class="form-group">
+                    <label>"Date Format"</label>
+                    <select
+                        prop:value=date_format
+                        on:change=move |ev| set_date_format.set(event_target_value(&ev))
+                    >
+                        {DATE_FORMATS.iter().map(|format| {
+                            view! { <option value=*format>{*format}</option> }
+                        }).collect::<Vec<_>>()}
+                    </select>
+                </div>
+
+                <div class="form-group">
+                    <label>"Week Starts On"</label>
+                    <select
+                        prop:value=week_start
+                        on:change=move |ev| set_week_start.set(event_target_value(&ev))
+                    >
+                        {WEEK_STARTS.iter().map(|&(value, label)| {
+                            view! { <option value=value>{label}</option> }
+                        }).collect::<Vec<_>>()}
+                    </select>
+                </div>
+
+                <div class="form-group">
+                    <label>"Sort Expenses By"</label>
+                    <select
+                        prop:value=expense_sort_by
+                        on:change=move |ev| set_expense_sort_by.set(event_target_value(&ev))
+                    >
+                        {SORT_COLUMNS.iter().map(|&(value, label)| {
+                            view! { <option value=value>{label}</option> }
+                        }).collect::<Vec<_>>()}
+                    </select>
+                </div>
+
+                <div class="form-group">
+                    <label>"Sort Direction"</label>
+                    <select
+                        prop:value=expense_sort_dir
+                        on:change=move |ev| set_expense_sort_dir.set(event_target_value(&ev))
+                    >
+                        {SORT_DIRECTIONS.iter().map(|&(value, label)| {
+                            view! { <option value=value>{label}</option> }
+                        }).collect::<Vec<_>>()}
+                    </select>
+                </div>
+
+                <div class="form-group">
+                    <label>"Category Order"</label>
+                    <select
+                        prop:value=category_sort
+                        on:change=move |ev| set_category_sort.set(event_target_value(&ev))
+                    >
+                        {CATEGORY_SORTS.iter().map(|&(value, label)| {
+                            view! { <option value=value>{label}</option> }
+                        }).collect::<Vec<_>>()}
+                    </select>
+                </div>
+
+                <div class="form-group">
+                    <label>"Webhook URL"</label>
+                    <input
+                        type="url"
+                        prop:value=webhook_url
+                        on:input=move |ev| set_webhook_url.set(event_target_value(&ev))
+                        placeholder="Not sent"
+                    />
+                </div>
+
+                // Receivers need the secret to check X-Webhook-Signature
+                {move || settings.with(|s| s.as_ref().and_then(|s| s.webhook_secret.clone())).map(|secret| view! {
+                    <div class="form-group">
+                        <label>"Webhook Secret"</label>
+                        <input type="text" readonly prop:value=secret />
+                    </div>
+                })}
+
+                <button type="submit" disabled=loading>
+                    {move || if loading.get() { "Saving..." } else { "Save Settings" }}
+                </button>
+            </form>
+        </div>
+    }
+}
+
+/// IANA name of the browser's timezone, e.g. "Asia/Kolkata".
+fn browser_timezone() -> Option<String> {
+    let options = js_sys::Intl::DateTimeFormat::new(&js_sys::Array::new(), &js_sys::Object::new())
+        .resolved_options();
+    js_sys::Reflect::get(&options, &"timeZone".into())
+        .ok()?
+        .as_string()
+}
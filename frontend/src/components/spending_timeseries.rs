@@ -0,0 +1,103 @@
+use charming::{
+    component::Axis,
+    element::{AxisType, Tooltip, Trigger},
+    series::Bar,
+    Chart, WasmRenderer,
+};
+use chrono::{Datelike, Duration, Local, Months, NaiveDate};
+use leptos::*;
+
+use crate::api::get_spending_timeseries;
+use crate::components::toast::use_toasts;
+use crate::models::TimeseriesBucket;
+
+const CHART_ID: &str = "spending-timeseries-chart";
+
+/// Interval value, its label in the picker and how far back the chart reaches.
+const INTERVALS: [(&str, &str); 3] = [
+    ("day", "Last 30 days"),
+    ("week", "Last 12 weeks"),
+    ("month", "Last 12 months"),
+];
+
+/// First day shown for `interval`, ending at `today`.
+fn range_start(interval: &str, today: NaiveDate) -> NaiveDate {
+    match interval {
+        "day" => today - Duration::days(29),
+        "week" => today - Duration::weeks(11),
+        _ => today
+            .with_day(1)
+            .and_then(|first| first.checked_sub_months(Months::new(11)))
+            .expect("Valid date"),
+    }
+}
+
+/// Spending per day, week or month, with the interval picked in the card.
+#[component]
+pub fn SpendingTimeseriesChart(reload: RwSignal<i32>) -> impl IntoView {
+    let (interval, set_interval) = create_signal("month");
+    let (buckets, set_buckets) = create_signal(Vec::<TimeseriesBucket>::new());
+    let toasts = use_toasts();
+
+    create_effect(move |_| {
+        reload.get();
+        let interval = interval.get();
+        let today = Local::now().date_naive();
+
+        spawn_local(async move {
+            match get_spending_timeseries(interval, range_start(interval, today), today).await {
+                Ok(loaded) => set_buckets.set(loaded),
+                Err(e) => toasts.error(e),
+            }
+        });
+    });
+
+    create_effect(move |_| {
+        let data = buckets.get();
+        let label_format = if interval.get_untracked() == "month" { "%b %Y" } else { "%b %-d" };
+        let labels = data
+            .iter()
+            .map(|b| b.bucket_start.format(label_format).to_string())
+            .collect();
+
+        let chart = Chart::new()
+            .tooltip(Tooltip::new().trigger(Trigger::Axis))
+            .x_axis(
+                Axis::new()
+                    .type_(AxisType::Category)
+                    .data(labels),
+            )
+            .y_axis(Axis::new().type_(AxisType::Value))
+            .series(
+                Bar::new()
+                    .name("Spent")
+                    .data(data.iter().map(|b| b.total_amount).collect()),
+            );
+
+        if let Err(e) = WasmRenderer::new(800, 360).render(CHART_ID, &chart) {
+            logging::error!("Failed to render spending chart: {:?}", e);
+        }
+    });
+
+    view! {
+        <div class="card">
+            <div class="card-header">
+                <h2 style="color: #333;">"Spending Over Time"</h2>
+                <select
+                    prop:value=move || interval.get()
+                    on:change=move |ev| {
+                        let value = event_target_value(&ev);
+                        if let Some((name, _)) = INTERVALS.iter().find(|(name, _)| *name == value) {
+                            set_interval.set(name);
+                        }
+                    }
+                >
+                    {INTERVALS.iter().map(|(name, label)| view! {
+                        <option value=*name>{*label}</option>
+                    }).collect::<Vec<_>>()}
+                </select>
+            </div>
+            <div id=CHART_ID class="chart-container"></div>
+        </div>
+    }
+}
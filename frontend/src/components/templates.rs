@@ -0,0 +1,208 @@
+use leptos::*;
+use uuid::Uuid;
+
+use crate::api::{
+    create_expense_from_template, create_expense_template, delete_expense_template,
+    list_expense_templates,
+};
+use crate::components::toast::use_toasts;
+use crate::format::parse_amount;
+use crate::models::{Category, CreateExpenseTemplate, ExpenseTemplate};
+
+/// Saved expenses such as a monthly pass, one button each. A click adds the
+/// expense dated today; the small form below saves a new template.
+#[component]
+pub fn ExpenseTemplates<F>(
+    categories: ReadSignal<Vec<Category>>,
+    on_created: F,
+) -> impl IntoView
+where
+    F: Fn() + Copy + 'static,
+{
+    let (templates, set_templates) = create_signal(Vec::<ExpenseTemplate>::new());
+    let (adding, set_adding) = create_signal(None::<Uuid>);
+    let (show_form, set_show_form) = create_signal(false);
+    let (name, set_name) = create_signal(String::new());
+    let (category_id, set_category_id) = create_signal(None::<Uuid>);
+    let (amount, set_amount) = create_signal(String::new());
+    let (description, set_description) = create_signal(String::new());
+    let (saving, set_saving) = create_signal(false);
+    let toasts = use_toasts();
+
+    let load_templates = move || {
+        spawn_local(async move {
+            match list_expense_templates().await {
+                Ok(loaded) => set_templates.set(loaded),
+                Err(e) => toasts.error(e),
+            }
+        });
+    };
+    load_templates();
+
+    let use_template = move |template: ExpenseTemplate| {
+        set_adding.set(Some(template.id));
+        spawn_local(async move {
+            match create_expense_from_template(template.id).await {
+                Ok(expense) => {
+                    toasts.success(format!(
+                        "Added \"{}\" to {}",
+                        expense.description, expense.category_name
+                    ));
+                    on_created();
+                }
+                Err(e) => toasts.error(e),
+            }
+            set_adding.set(None);
+        });
+    };
+
+    let remove_template = move |id: Uuid| {
+        spawn_local(async move {
+            match delete_expense_template(id).await {
+                Ok(()) => set_templates.update(|t| t.retain(|t| t.id != id)),
+                Err(e) => toasts.error(e),
+            }
+        });
+    };
+
+    let handle_submit = move |ev: leptos::ev::SubmitEvent| {
+        ev.prevent_default();
+
+        let Some(cat_id) = category_id.get() else {
+            toasts.error("Please select a category");
+            return;
+        };
+        let amount_val = match parse_amount(&amount.get()) {
+            Ok(v) => v,
+            Err(e) => {
+                toasts.error(e);
+                return;
+            }
+        };
+
+        set_saving.set(true);
+        let req = CreateExpenseTemplate {
+            name: name.get(),
+            category_id: cat_id,
+            amount: amount_val,
+            description: description.get(),
+        };
+        spawn_local(async move {
+            match create_expense_template(req).await {
+                Ok(_) => {
+                    set_name.set(String::new());
+                    set_category_id.set(None);
+                    set_amount.set(String::new());
+                    set_description.set(String::new());
+                    set_show_form.set(false);
+                    load_templates();
+                }
+                Err(e) => toasts.error(e),
+            }
+            set_saving.set(false);
+        });
+    };
+
+    view! {
+        <div class="card">
+            <div class="card-header">
+                <h2 style="color: #333;">"Templates"</h2>
+                <button
+                    class="btn-secondary"
+                    on:click=move |_| set_show_form.update(|v| *v = !*v)
+                >
+                    {move || if show_form.get() { "Cancel" } else { "New Template" }}
+                </button>
+            </div>
+
+            {move || {
+                let list = templates.get();
+                if list.is_empty() {
+                    view! {
+                        <p style="color: #6c757d;">"Save an expense you enter often to add it with one click."</p>
+                    }.into_view()
+                } else {
+                    view! {
+                        <div class="quick-picks">
+                            {list.into_iter().map(|template| {
+                                let id = template.id;
+                                let title = format!("{} ({:.2})", template.description, template.amount);
+                                let label = template.name.clone();
+                                view! {
+                                    <span class="template-pick">
+                                        <button
+                                            type="button"
+                                            class="quick-pick"
+                                            title=title
+                                            disabled=move || adding.get().is_some()
+                                            on:click=move |_| use_template(template.clone())
+                                        >
+                                            {label}
+                                        </button>
+                                        <button
+                                            type="button"
+                                            class="template-remove"
+                                            title="Remove template"
+                                            on:click=move |_| remove_template(id)
+                                        >
+                                            "×"
+                                        </button>
+                                    </span>
+                                }
+                            }).collect::<Vec<_>>()}
+                        </div>
+                    }.into_view()
+                }
+            }}
+
+            {move || show_form.get().then(|| view! {
+                <form class="template-form" on:submit=handle_submit>
+                    <input
+                        type="text"
+                        required
+                        maxlength="100"
+                        prop:value=name
+                        on:input=move |ev| set_name.set(event_target_value(&ev))
+                        placeholder="Name, e.g. Metro pass"
+                    />
+                    <select
+                        required
+                        prop:value=move || category_id.get().map(|id| id.to_string()).unwrap_or_default()
+                        on:change=move |ev| {
+                            set_category_id.set(Uuid::parse_str(&event_target_value(&ev)).ok());
+                        }
+                    >
+                        <option value="">"Category"</option>
+                        {move || categories.get().into_iter().filter(|cat| !cat.archived).map(|cat| {
+                            view! {
+                                <option value={cat.id.to_string()}>
+                                    {cat.icon.as_ref().map(|i| format!("{} ", i)).unwrap_or_default()}
+                                    {&cat.name}
+                                </option>
+                            }
+                        }).collect::<Vec<_>>()}
+                    </select>
+                    <input
+                        type="text"
+                        inputmode="decimal"
+                        required
+                        prop:value=amount
+                        on:input=move |ev| set_amount.set(event_target_value(&ev))
+                        placeholder="0.00"
+                    />
+                    <input
+                        type="text"
+                        required
+                        maxlength="500"
+                        prop:value=description
+                        on:input=move |ev| set_description.set(event_target_value(&ev))
+                        placeholder="Description"
+                    />
+                    <button type="submit" disabled=saving>
+                        {move || if saving.get() { "Saving..." } else { "Save" }}
+                    </button>
+                </form>
+            })}
+        </div>
+    }
+}
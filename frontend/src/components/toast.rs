@@ -0,0 +1,145 @@
+use std::time::Duration;
+
+use leptos::*;
+
+/// How long a toast stays on screen before it is dismissed automatically.
+const TOAST_DURATION: Duration = Duration::from_secs(4);
+
+/// Toasts with a button stay a little longer so there's time to click it.
+const ACTION_TOAST_DURATION: Duration = Duration::from_secs(6);
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ToastKind {
+    Success,
+    Info,
+    Error,
+}
+
+impl ToastKind {
+    fn class(&self) -> &'static str {
+        match self {
+            ToastKind::Success => "toast toast-success",
+            ToastKind::Info => "toast toast-info",
+            ToastKind::Error => "toast toast-error",
+        }
+    }
+}
+
+/// Button shown on a toast, such as "Undo" after a delete.
+#[derive(Debug, Clone, Copy)]
+pub struct ToastAction {
+    pub label: &'static str,
+    pub on_click: Callback<()>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Toast {
+    pub id: u64,
+    pub kind: ToastKind,
+    pub message: String,
+    pub action: Option<ToastAction>,
+}
+
+/// Handle to the toast queue, provided as context by [`ToastProvider`].
+#[derive(Clone, Copy)]
+pub struct Toasts {
+    items: RwSignal<Vec<Toast>>,
+    next_id: StoredValue<u64>,
+}
+
+impl Toasts {
+    fn new() -> Self {
+        Self {
+            items: create_rw_signal(Vec::new()),
+            next_id: store_value(0),
+        }
+    }
+
+    pub fn push(&self, kind: ToastKind, message: impl Into<String>) {
+        self.show(kind, message.into(), None);
+    }
+
+    /// Shows a toast with a button; clicking it runs `on_click` and closes the
+    /// toast. Each call gets its own toast, so several can be pending at once.
+    pub fn with_action(
+        &self,
+        kind: ToastKind,
+        message: impl Into<String>,
+        label: &'static str,
+        on_click: impl Fn() + 'static,
+    ) {
+        let action = ToastAction {
+            label,
+            on_click: Callback::new(move |_| on_click()),
+        };
+        self.show(kind, message.into(), Some(action));
+    }
+
+    fn show(&self, kind: ToastKind, message: String, action: Option<ToastAction>) {
+        let id = self.next_id.get_value();
+        self.next_id.set_value(id + 1);
+
+        let duration = if action.is_some() { ACTION_TOAST_DURATION } else { TOAST_DURATION };
+        self.items.update(|items| items.push(Toast { id, kind, message, action }));
+
+        let toasts = *self;
+        set_timeout(move || toasts.dismiss(id), duration);
+    }
+
+    pub fn success(&self, message: impl Into<String>) {
+        self.push(ToastKind::Success, message);
+    }
+
+    pub fn info(&self, message: impl Into<String>) {
+        self.push(ToastKind::Info, message);
+    }
+
+    pub fn error(&self, message: impl Into<String>) {
+        self.push(ToastKind::Error, message);
+    }
+
+    pub fn dismiss(&self, id: u64) {
+        self.items.update(|items| items.retain(|toast| toast.id != id));
+    }
+}
+
+/// Returns the toast queue provided by the enclosing [`ToastProvider`].
+pub fn use_toasts() -> Toasts {
+    expect_context::<Toasts>()
+}
+
+#[component]
+pub fn ToastProvider(children: Children) -> impl IntoView {
+    let toasts = Toasts::new();
+    provide_context(toasts);
+
+    view! {
+        {children()}
+        <div class="toast-container">
+            <For
+                each=move || toasts.items.get()
+                key=|toast| toast.id
+                children=move |toast| {
+                    let id = toast.id;
+                    view! {
+                        <div class=toast.kind.class() on:click=move |_| toasts.dismiss(id)>
+                            {toast.message}
+                            {toast.action.map(|action| view! {
+                                <button
+                                    class="toast-action"
+                                    on:click=move |ev| {
+                                        ev.stop_propagation();
+                                        toasts.dismiss(id);
+                                        action.on_click.call(());
+                                    }
+                                >
+                                    {action.label}
+                                </button>
+                            })}
+                        </div>
+                    }
+                }
+            />
+        </div>
+    }
+}
@@ -0,0 +1,122 @@
+use leptos::*;
+use uuid::Uuid;
+use web_sys::window;
+
+use crate::api::{list_trash, purge_expense, restore_expense};
+use crate::components::toast::use_toasts;
+use crate::format::{format_date, format_money};
+use crate::models::TrashedExpense;
+
+/// Lists deleted expenses with the days left before they are purged, and lets
+/// the user restore them or delete them for good.
+#[component]
+pub fn Trash<F>(
+    currency: Memo<String>,
+    date_format: Memo<String>,
+    on_restored: F,
+) -> impl IntoView
+where
+    F: Fn() + Copy + 'static,
+{
+    let (trash, set_trash) = create_signal(Vec::<TrashedExpense>::new());
+    let reload = create_rw_signal(0);
+    let toasts = use_toasts();
+
+    create_effect(move |_| {
+        reload.get();
+        spawn_local(async move {
+            match list_trash().await {
+                Ok(list) => set_trash.set(list),
+                Err(e) => toasts.error(e),
+            }
+        });
+    });
+
+    let handle_restore = move |id: Uuid| {
+        spawn_local(async move {
+            match restore_expense(id).await {
+                Ok(_) => {
+                    toasts.success("Expense restored");
+                    reload.update(|v| *v += 1);
+                    on_restored();
+                }
+                Err(e) => toasts.error(e),
+            }
+        });
+    };
+
+    let handle_purge = move |id: Uuid| {
+        let confirmed = window()
+            .and_then(|w| {
+                w.confirm_with_message("Permanently delete this expense? This can't be undone.").ok()
+            })
+            .unwrap_or(false);
+        if !confirmed {
+            return;
+        }
+
+        spawn_local(async move {
+            match purge_expense(id).await {
+                Ok(_) => {
+                    toasts.success("Expense permanently deleted");
+                    reload.update(|v| *v += 1);
+                }
+                Err(e) => toasts.error(e),
+            }
+        });
+    };
+
+    view! {
+        <div class="card">
+            <h2 style="color: #333;">"Trash"</h2>
+            {move || if trash.with(|t| t.is_empty()) {
+                view! {
+                    <p style="text-align: center; color: #6c757d; padding: 20px;">
+                        "The trash is empty."
+                    </p>
+                }.into_view()
+            } else {
+                view! {
+                    <div class="expense-list">
+                        {trash.get().into_iter().map(|expense| {
+                            let color = expense.category_color.clone().unwrap_or_else(|| "#667eea".to_string());
+                            let id = expense.id;
+                            let purge_label = match expense.days_until_purge {
+                                0 | 1 => "Purged within a day".to_string(),
+                                days => format!("Purged in {} days", days),
+                            };
+                            view! {
+                                <div class="expense-item" style:border-left-color=color>
+                                    <div class="expense-icon">
+                                        {expense.category_icon.unwrap_or_else(|| "📦".to_string())}
+                                    </div>
+                                    <div class="expense-details">
+                                        <h3>{&expense.description}</h3>
+                                        <p>
+                                            {expense.category_name.clone()}
+                                            " • "
+                                            {format_date(expense.expense_date, &date_format.get())}
+                                            " • "
+                                            <span class="purge-countdown">{purge_label}</span>
+                                        </p>
+                                    </div>
+                                    <div class="expense-amount">
+                                        {format_money(expense.amount, &currency.get())}
+                                    </div>
+                                    <div class="expense-actions">
+                                        <button class="btn-secondary" on:click=move |_| handle_restore(id)>
+                                            "Restore"
+                                        </button>
+                                        <button class="btn-danger" on:click=move |_| handle_purge(id)>
+                                            "Delete forever"
+                                        </button>
+                                    </div>
+                                </div>
+                            }
+                        }).collect::<Vec<_>>()}
+                    </div>
+                }.into_view()
+            }}
+        </div>
+    }
+}
@@ -0,0 +1,57 @@
+use charming::{
+    component::{Axis, Legend},
+    element::{AxisType, Tooltip, Trigger},
+    series::Bar,
+    Chart, WasmRenderer,
+};
+use leptos::*;
+
+use crate::models::YearlyComparison;
+
+const CHART_ID: &str = "yearly-comparison-chart";
+const MONTH_NAMES: [&str; 12] = [
+    "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+];
+
+#[component]
+pub fn YearlyComparisonChart(
+    year: i32,
+    comparison: ReadSignal<Vec<YearlyComparison>>,
+) -> impl IntoView {
+    create_effect(move |_| {
+        let data = comparison.get();
+
+        let chart = Chart::new()
+            .tooltip(Tooltip::new().trigger(Trigger::Axis))
+            .legend(Legend::new().bottom(0))
+            .x_axis(
+                Axis::new()
+                    .type_(AxisType::Category)
+                    .data(MONTH_NAMES.to_vec()),
+            )
+            .y_axis(Axis::new().type_(AxisType::Value))
+            .series(
+                Bar::new()
+                    .name((year - 1).to_string())
+                    .data(data.iter().map(|m| m.previous_total).collect()),
+            )
+            .series(
+                Bar::new()
+                    .name(year.to_string())
+                    .data(data.iter().map(|m| m.current_total).collect()),
+            );
+
+        if let Err(e) = WasmRenderer::new(800, 360).render(CHART_ID, &chart) {
+            logging::error!("Failed to render yearly comparison chart: {:?}", e);
+        }
+    });
+
+    view! {
+        <div class="card">
+            <h2 style="margin-bottom: 20px; color: #333;">
+                "Year over Year (" {year - 1} " vs " {year} ")"
+            </h2>
+            <div id=CHART_ID class="chart-container"></div>
+        </div>
+    }
+}
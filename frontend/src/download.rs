@@ -0,0 +1,40 @@
+//! Saving generated files from the browser.
+
+use wasm_bindgen::{JsCast, JsValue};
+use web_sys::{window, Blob, BlobPropertyBag, HtmlAnchorElement, Url};
+
+/// Offers `contents` to the user as a download named `filename`.
+///
+/// Files behind authentication can't be linked to directly (the token travels
+/// in a header), so they're fetched first and handed over as a blob URL.
+pub fn save_file(filename: &str, contents: &str, mime: &str) -> Result<(), String> {
+    save_blob(filename, &contents.into(), mime)
+}
+
+/// Like [`save_file`], for binary contents such as a PDF.
+pub fn save_bytes(filename: &str, contents: &[u8], mime: &str) -> Result<(), String> {
+    save_blob(filename, &js_sys::Uint8Array::from(contents).into(), mime)
+}
+
+fn save_blob(filename: &str, contents: &JsValue, mime: &str) -> Result<(), String> {
+    let parts = js_sys::Array::of1(contents);
+    let options = BlobPropertyBag::new();
+    options.set_type(mime);
+    let blob = Blob::new_with_buffer_source_sequence_and_options(&parts, &options)
+        .map_err(|_| "Could not create file".to_string())?;
+
+    let url = Url::create_object_url_with_blob(&blob)
+        .map_err(|_| "Could not create file".to_string())?;
+
+    let anchor = window()
+        .and_then(|w| w.document())
+        .and_then(|d| d.create_element("a").ok())
+        .and_then(|el| el.dyn_into::<HtmlAnchorElement>().ok())
+        .ok_or("Could not start download")?;
+    anchor.set_href(&url);
+    anchor.set_download(filename);
+    anchor.click();
+
+    let _ = Url::revoke_object_url(&url);
+    Ok(())
+}
@@ -0,0 +1,113 @@
+//! Display formatting shared by the components.
+
+use chrono::NaiveDate;
+
+/// Currency used until the user's settings have loaded.
+pub const DEFAULT_CURRENCY: &str = "USD";
+
+/// Currencies offered in the settings, as ISO 4217 codes.
+pub const CURRENCIES: &[&str] = &["USD", "EUR", "GBP", "INR"];
+
+/// Date format used until the user's settings have loaded.
+pub const DEFAULT_DATE_FORMAT: &str = "MMM D, YYYY";
+
+/// Date formats offered in the settings; must match the backend's list.
+pub const DATE_FORMATS: &[&str] = &["MMM D, YYYY", "YYYY-MM-DD", "DD/MM/YYYY", "MM/DD/YYYY"];
+
+/// First day of the week used until the user's settings have loaded.
+pub const DEFAULT_WEEK_START: &str = "monday";
+
+/// Week starts offered in the settings as (value, label); values match the backend.
+pub const WEEK_STARTS: &[(&str, &str)] = &[("monday", "Monday"), ("sunday", "Sunday")];
+
+/// Expense sort used until the user's settings have loaded.
+pub const DEFAULT_EXPENSE_SORT_BY: &str = "date";
+pub const DEFAULT_EXPENSE_SORT_DIR: &str = "desc";
+
+/// Sort directions offered in the settings as (value, label).
+pub const SORT_DIRECTIONS: &[(&str, &str)] = &[("desc", "Descending"), ("asc", "Ascending")];
+
+/// Category orders offered in the settings as (value, label); values match the backend.
+pub const CATEGORY_SORTS: &[(&str, &str)] =
+    &[("manual", "Manual"), ("name", "Name"), ("usage", "Most used")];
+
+pub const DEFAULT_CATEGORY_SORT: &str = "manual";
+
+fn currency_symbol(currency: &str) -> Option<&'static str> {
+    match currency {
+        "USD" => Some("$"),
+        "EUR" => Some("€"),
+        "GBP" => Some("£"),
+        "INR" => Some("₹"),
+        _ => None,
+    }
+}
+
+/// Formats an amount with two decimals, thousands separators and the
+/// currency's symbol, e.g. `$1,234.50`. Currencies without a known symbol
+/// are prefixed with their code instead: `CHF 1,234.50`.
+pub fn format_money(amount: f64, currency: &str) -> String {
+    let formatted = format!("{:.2}", amount.abs());
+    let (whole, cents) = formatted.split_once('.').unwrap_or((&formatted, "00"));
+
+    let mut grouped = String::new();
+    for (i, digit) in whole.chars().enumerate() {
+        if i > 0 && (whole.len() - i) % 3 == 0 {
+            grouped.push(',');
+        }
+        grouped.push(digit);
+    }
+
+    // `{:.2}` rounds, so check the rounded value to avoid printing "-$0.00"
+    let sign = if amount < 0.0 && formatted != "0.00" { "-" } else { "" };
+    match currency_symbol(currency) {
+        Some(symbol) => format!("{}{}{}.{}", sign, symbol, grouped, cents),
+        None => format!("{}{} {}.{}", sign, currency, grouped, cents),
+    }
+}
+
+/// Largest amount accepted for one expense; matches the backend's default
+/// `MAX_EXPENSE_AMOUNT`.
+pub const MAX_EXPENSE_AMOUNT: f64 = 1_000_000.0;
+
+/// Earliest expense date the date picker offers; matches the backend's default
+/// `MIN_EXPENSE_DATE`.
+pub const MIN_EXPENSE_DATE: &str = "1970-01-01";
+
+/// Parses an amount typed by the user, mirroring the backend's rules: plain
+/// digits with an optional decimal point and at most two decimals, greater
+/// than zero and at most [`MAX_EXPENSE_AMOUNT`]. Rejects forms
+/// `f64::from_str` would accept, like `1e5` or `inf`.
+pub fn parse_amount(input: &str) -> Result<f64, &'static str> {
+    let input = input.trim();
+    if input.is_empty() {
+        return Err("Please enter an amount");
+    }
+
+    let (whole, decimals) = input.split_once('.').unwrap_or((input, ""));
+    let digits_only = |s: &str| s.chars().all(|c| c.is_ascii_digit());
+    if (whole.is_empty() && decimals.is_empty()) || !digits_only(whole) || !digits_only(decimals) {
+        return Err("Amount must be a number like 12.50");
+    }
+    if decimals.len() > 2 {
+        return Err("Amount can have at most two decimal places");
+    }
+
+    match input.parse::<f64>() {
+        Ok(value) if value > MAX_EXPENSE_AMOUNT => Err("Amount cannot be more than 1,000,000"),
+        Ok(value) if value.is_finite() && value > 0.0 => Ok(value),
+        _ => Err("Amount must be greater than 0"),
+    }
+}
+
+/// Formats a date in one of the [`DATE_FORMATS`] chosen in the settings,
+/// falling back to [`DEFAULT_DATE_FORMAT`] for anything unrecognised.
+pub fn format_date(date: NaiveDate, date_format: &str) -> String {
+    let pattern = match date_format {
+        "YYYY-MM-DD" => "%Y-%m-%d",
+        "DD/MM/YYYY" => "%d/%m/%Y",
+        "MM/DD/YYYY" => "%m/%d/%Y",
+        _ => "%b %-d, %Y",
+    };
+    date.format(pattern).to_string()
+}
@@ -1,24 +1,33 @@
 mod api;
 mod components;
+mod download;
+mod format;
+mod live;
 mod models;
+mod storage;
 
 use leptos::*;
 
 use crate::components::auth::Auth;
 use crate::components::dashboard::Dashboard;
+use crate::components::toast::ToastProvider;
 
 #[component]
 fn App() -> impl IntoView {
     let (is_authenticated, set_is_authenticated) = create_signal(api::get_token().is_some());
 
+    api::set_unauthorized_handler(move || set_is_authenticated.set(false));
+
     view! {
-        <div>
-            {move || if is_authenticated.get() {
-                view! { <Dashboard on_logout=move || set_is_authenticated.set(false) /> }.into_view()
-            } else {
-                view! { <Auth on_auth=move || set_is_authenticated.set(true) /> }.into_view()
-            }}
-        </div>
+        <ToastProvider>
+            <div>
+                {move || if is_authenticated.get() {
+                    view! { <Dashboard on_logout=move || set_is_authenticated.set(false) /> }.into_view()
+                } else {
+                    view! { <Auth on_auth=move || set_is_authenticated.set(true) /> }.into_view()
+                }}
+            </div>
+        </ToastProvider>
     }
 }
 
@@ -0,0 +1,110 @@
+//! Live updates from `GET /api/events`, so a dashboard left open reloads when
+//! expenses change on another device.
+//!
+//! Each connection is opened with a fresh single-use stream ticket, since
+//! `EventSource` can't send the token itself. Tickets don't survive a
+//! reconnect, so when the browser's own retry of a dropped connection is
+//! refused, a new ticket is fetched and a new `EventSource` opened after
+//! [`RECONNECT_DELAY_MS`], until the user logs out.
+
+use std::{
+    cell::{Cell, RefCell},
+    rc::Rc,
+};
+
+use js_sys::{Function, Promise};
+use wasm_bindgen::{closure::Closure, JsCast, JsValue};
+use wasm_bindgen_futures::{spawn_local, JsFuture};
+use web_sys::EventSource;
+
+use crate::api::{events_url, get_token, sleep};
+
+const RECONNECT_DELAY_MS: i32 = 10_000;
+
+/// Handle to a running subscription; call [`stop`](Self::stop) when the
+/// component that started it goes away.
+#[derive(Clone, Default)]
+pub struct LiveUpdates {
+    stopped: Rc<Cell<bool>>,
+    /// Resolves the wait on the current connection
+    wake: Rc<RefCell<Option<Function>>>,
+}
+
+impl LiveUpdates {
+    /// Calls `on_change` for every change to the user's expenses, and after
+    /// each reconnect since events sent in between were missed.
+    pub fn start(on_change: impl Fn() + 'static) -> Self {
+        let live = Self::default();
+        let on_change: Rc<dyn Fn()> = Rc::new(on_change);
+        let opened_before = Rc::new(Cell::new(false));
+
+        let this = live.clone();
+        spawn_local(async move {
+            while !this.stopped.get() {
+                if get_token().is_none() {
+                    break;
+                }
+                // A failed ticket request waits like a dropped connection
+                if let Ok(url) = events_url().await {
+                    this.listen(&url, on_change.clone(), opened_before.clone()).await;
+                }
+
+                if this.stopped.get() {
+                    break;
+                }
+                sleep(RECONNECT_DELAY_MS).await;
+            }
+        });
+
+        live
+    }
+
+    pub fn stop(&self) {
+        self.stopped.set(true);
+        self.wake_up();
+    }
+
+    fn wake_up(&self) {
+        if let Some(wake) = self.wake.borrow_mut().take() {
+            let _ = wake.call0(&JsValue::NULL);
+        }
+    }
+
+    /// Runs one `EventSource` until the browser closes it for good or
+    /// [`stop`](Self::stop) is called.
+    async fn listen(&self, url: &str, on_change: Rc<dyn Fn()>, opened_before: Rc<Cell<bool>>) {
+        let Ok(source) = EventSource::new(url) else { return };
+
+        let on_message = {
+            let on_change = on_change.clone();
+            Closure::<dyn FnMut()>::new(move || on_change())
+        };
+        let on_open = Closure::<dyn FnMut()>::new(move || {
+            if opened_before.replace(true) {
+                on_change();
+            }
+        });
+        // Errors while the browser is still retrying leave it CONNECTING
+        let on_error = {
+            let this = self.clone();
+            let source = source.clone();
+            Closure::<dyn FnMut()>::new(move || {
+                if source.ready_state() == EventSource::CLOSED {
+                    this.wake_up();
+                }
+            })
+        };
+
+        let closed = Promise::new(&mut |resolve, _| *self.wake.borrow_mut() = Some(resolve));
+        source.set_onmessage(Some(on_message.as_ref().unchecked_ref()));
+        source.set_onopen(Some(on_open.as_ref().unchecked_ref()));
+        source.set_onerror(Some(on_error.as_ref().unchecked_ref()));
+
+        let _ = JsFuture::from(closed).await;
+
+        source.close();
+        source.set_onmessage(None);
+        source.set_onopen(None);
+        source.set_onerror(None);
+    }
+}
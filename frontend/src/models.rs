@@ -10,6 +10,45 @@ pub struct User {
     pub created_at: DateTime<Utc>,
 }
 
+#[derive(Debug, Clone, Serialize)]
+pub struct UpdateUser {
+    pub full_name: Option<String>,
+    pub email: Option<String>,
+    /// Required by the server when `email` changes
+    pub current_password: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UserSettings {
+    pub timezone: Option<String>,
+    pub currency: String,
+    pub monthly_limit: Option<f64>,
+    pub date_format: String,
+    pub week_start: String,
+    pub expense_sort_by: String,
+    pub expense_sort_dir: String,
+    pub category_sort: String,
+    pub webhook_url: Option<String>,
+    pub webhook_secret: Option<String>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct UpdateUserSettings {
+    /// `Some("")` resets to UTC
+    pub timezone: Option<String>,
+    pub currency: Option<String>,
+    /// `Some(0.0)` removes the limit
+    pub monthly_limit: Option<f64>,
+    pub date_format: Option<String>,
+    pub week_start: Option<String>,
+    pub expense_sort_by: Option<String>,
+    pub expense_sort_dir: Option<String>,
+    pub category_sort: Option<String>,
+    /// `Some("")` stops webhook deliveries
+    pub webhook_url: Option<String>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AuthResponse {
     pub token: String,
@@ -27,6 +66,18 @@ pub struct RegisterRequest {
 pub struct LoginRequest {
     pub email: String,
     pub password: String,
+    pub remember_me: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Session {
+    pub id: Uuid,
+    pub user_agent: Option<String>,
+    pub ip_address: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub last_used_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+    pub current: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -37,6 +88,18 @@ pub struct Category {
     pub color: Option<String>,
     pub icon: Option<String>,
     pub created_at: DateTime<Utc>,
+    pub sort_order: i32,
+    pub archived: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PaginatedCategories {
+    pub items: Vec<Category>,
+    pub total: i64,
+    pub limit: i64,
+    pub offset: i64,
+    pub page: i64,
+    pub total_pages: i64,
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -46,6 +109,25 @@ pub struct CreateCategory {
     pub icon: Option<String>,
 }
 
+#[derive(Debug, Clone, Serialize)]
+pub struct ReorderCategories {
+    pub ids: Vec<Uuid>,
+}
+
+/// Name, color and icon of a category, as exported and imported.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CategoryTemplate {
+    pub name: String,
+    pub color: Option<String>,
+    pub icon: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImportCategoriesResult {
+    pub created: i64,
+    pub skipped: i64,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Expense {
     pub id: Uuid,
@@ -61,6 +143,41 @@ pub struct Expense {
     pub updated_at: DateTime<Utc>,
 }
 
+/// A newly created expense plus warnings worth showing, such as an unusually
+/// large amount.
+#[derive(Debug, Clone, Deserialize)]
+pub struct CreatedExpense {
+    #[serde(flatten)]
+    pub expense: Expense,
+    #[serde(default)]
+    pub warnings: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TrashedExpense {
+    pub id: Uuid,
+    pub category_id: Uuid,
+    pub category_name: String,
+    pub category_color: Option<String>,
+    pub category_icon: Option<String>,
+    pub amount: f64,
+    pub description: String,
+    pub expense_date: NaiveDate,
+    pub deleted_at: DateTime<Utc>,
+    pub purge_at: DateTime<Utc>,
+    pub days_until_purge: i32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PaginatedExpenses {
+    pub items: Vec<Expense>,
+    pub total: i64,
+    pub limit: i64,
+    pub offset: i64,
+    pub page: i64,
+    pub total_pages: i64,
+}
+
 #[derive(Debug, Clone, Serialize)]
 pub struct CreateExpense {
     pub category_id: Uuid,
@@ -69,12 +186,49 @@ pub struct CreateExpense {
     pub expense_date: NaiveDate,
 }
 
+/// Saved category, amount and description for an expense entered often.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExpenseTemplate {
+    pub id: Uuid,
+    pub name: String,
+    pub category_id: Uuid,
+    pub amount: f64,
+    pub description: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CreateExpenseTemplate {
+    pub name: String,
+    pub category_id: Uuid,
+    pub amount: f64,
+    pub description: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct SeedDemoResult {
+    pub created: i64,
+}
+
+/// One line such as `12.50 lunch #food 2024-01-15`, parsed by the backend.
+#[derive(Debug, Clone, Serialize)]
+pub struct QuickExpense {
+    pub text: String,
+}
+
 #[derive(Debug, Clone, Serialize)]
 pub struct UpdateExpense {
     pub category_id: Option<Uuid>,
     pub amount: Option<f64>,
     pub description: Option<String>,
     pub expense_date: Option<NaiveDate>,
+    pub expected_updated_at: Option<DateTime<Utc>>,
+}
+
+/// Months are `YYYY-MM`.
+#[derive(Debug, Clone, Serialize)]
+pub struct CloneMonth {
+    pub from_month: String,
+    pub to_month: String,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -93,4 +247,125 @@ pub struct CategorySummary {
     pub category_icon: Option<String>,
     pub total_amount: f64,
     pub expense_count: i64,
+    pub budget_amount: Option<f64>,
+    pub over_budget: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CategoryMonthlyTotal {
+    pub year: i32,
+    pub month: u32,
+    pub total_amount: f64,
+    pub expense_count: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SpendingProjection {
+    pub spent_so_far: f64,
+    pub projected_total: f64,
+    pub days_elapsed: u32,
+    pub days_in_month: u32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DayTotal {
+    pub date: NaiveDate,
+    pub total_amount: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Budget {
+    pub id: Uuid,
+    pub category_id: Uuid,
+    pub amount: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TimeseriesBucket {
+    pub bucket_start: NaiveDate,
+    pub total_amount: f64,
+    pub expense_count: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BurndownDay {
+    pub day: NaiveDate,
+    pub cumulative_spent: f64,
+    pub ideal_pace: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DayExtremes {
+    pub start_date: NaiveDate,
+    pub end_date: NaiveDate,
+    pub most_expensive: Option<DayTotal>,
+    pub cheapest: Option<DayTotal>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SpendingStreaks {
+    pub current_streak_days: i64,
+    pub longest_streak_days: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct YearlyComparison {
+    pub month: u32,
+    pub current_total: f64,
+    pub previous_total: f64,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct DateRange {
+    pub start: NaiveDate,
+    pub end: NaiveDate,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CompareRanges {
+    pub a: DateRange,
+    pub b: DateRange,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RangeTotals {
+    pub start: NaiveDate,
+    pub end: NaiveDate,
+    pub total_amount: f64,
+    pub expense_count: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CategoryDelta {
+    pub category_id: Uuid,
+    pub category_name: String,
+    pub category_color: Option<String>,
+    pub category_icon: Option<String>,
+    pub a_total: f64,
+    pub b_total: f64,
+    pub delta: f64,
+    pub a_count: i64,
+    pub b_count: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RangeComparison {
+    pub a: RangeTotals,
+    pub b: RangeTotals,
+    pub total_delta: f64,
+    pub categories: Vec<CategoryDelta>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DashboardResponse {
+    pub categories: Vec<Category>,
+    pub expenses: PaginatedExpenses,
+    pub monthly_summary: Vec<MonthlySummary>,
+    pub category_summary: Vec<CategorySummary>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StreamTicket {
+    pub ticket: String,
+    pub expires_in: u64,
 }
@@ -0,0 +1,29 @@
+//! JSON values kept in the browser's `localStorage`.
+//!
+//! Storage can be unavailable (private browsing, quota exceeded), so every
+//! helper fails quietly: loads return `None` and saves are dropped.
+
+use serde::{de::DeserializeOwned, Serialize};
+use web_sys::{window, Storage};
+
+fn local_storage() -> Option<Storage> {
+    window()?.local_storage().ok()?
+}
+
+/// Reads and parses the value under `key`; a missing or unparsable value is `None`.
+pub fn load<T: DeserializeOwned>(key: &str) -> Option<T> {
+    let raw = local_storage()?.get_item(key).ok()??;
+    serde_json::from_str(&raw).ok()
+}
+
+pub fn save<T: Serialize>(key: &str, value: &T) {
+    if let (Some(storage), Ok(raw)) = (local_storage(), serde_json::to_string(value)) {
+        let _ = storage.set_item(key, &raw);
+    }
+}
+
+pub fn remove(key: &str) {
+    if let Some(storage) = local_storage() {
+        let _ = storage.remove_item(key);
+    }
+}
@@ -0,0 +1,26 @@
+#!/bin/sh
+# Puts the ECharts build the charts render with into vendor/, so the page
+# loads it from its own origin rather than a CDN. npm checks the package
+# against the registry's integrity hash. Bump VERSION together with the
+# <script> tag in index.html.
+set -e
+
+VERSION=5.4.3
+TARGET="vendor/echarts-$VERSION.min.js"
+
+cd "$(dirname "$0")"
+if [ -f "$TARGET" ]; then
+    exit 0
+fi
+
+if ! command -v npm >/dev/null 2>&1; then
+    echo "npm is needed once to fetch ECharts $VERSION into frontend/vendor/" >&2
+    exit 1
+fi
+
+WORK=$(mktemp -d)
+trap 'rm -rf "$WORK"' EXIT
+npm pack "echarts@$VERSION" --pack-destination "$WORK" --silent >/dev/null
+tar -xzf "$WORK/echarts-$VERSION.tgz" -C "$WORK" package/dist/echarts.min.js
+mkdir -p vendor
+mv "$WORK/package/dist/echarts.min.js" "$TARGET"